@@ -0,0 +1,83 @@
+use basalt::input::Qwerty;
+use basalt::interface::{BinStyle, Color};
+use basalt::render::Renderer;
+use basalt::window::WindowOptions;
+use basalt::{Basalt, BasaltOptions};
+
+fn main() {
+    Basalt::initialize(BasaltOptions::default(), move |basalt_res| {
+        let basalt = basalt_res.unwrap();
+
+        let window = basalt
+            .window_manager_ref()
+            .create(WindowOptions {
+                title: String::from("atlas-dump"),
+                inner_size: Some([400; 2]),
+                ..WindowOptions::default()
+            })
+            .unwrap();
+
+        // Press F12 to write each atlas to a PNG in the current directory.
+        window.on_press(Qwerty::F12, move |target, _, _| {
+            let window = target.into_window().unwrap();
+            let mut index = 0;
+
+            loop {
+                let snapshot = match window.debug_atlas_snapshot(index) {
+                    Ok(Some(some)) => some,
+                    Ok(None) => {
+                        index += 1;
+                        continue;
+                    },
+                    Err(e) => {
+                        println!("Stopped at image backing {}: {}", index, e);
+                        break;
+                    },
+                };
+
+                let path = format!("atlas-{}.png", index);
+
+                match image::save_buffer(
+                    &path,
+                    &snapshot.data,
+                    snapshot.width,
+                    snapshot.height,
+                    image::ColorType::Rgba8,
+                ) {
+                    Ok(_) => println!("Saved {}", path),
+                    Err(e) => println!("Failed to save {}: {}", path, e),
+                }
+
+                index += 1;
+            }
+
+            Default::default()
+        });
+
+        let background = window.new_bin();
+
+        background
+            .style_update(BinStyle {
+                pos_from_t: Some(0.0),
+                pos_from_b: Some(0.0),
+                pos_from_l: Some(0.0),
+                pos_from_r: Some(0.0),
+                back_color: Some(Color::shex("f0f0f0")),
+                pad_t: Some(10.0),
+                pad_l: Some(10.0),
+                text: String::from("Press F12 to dump the atlases."),
+                text_height: Some(16.0),
+                text_color: Some(Color::shex("303030")),
+                ..BinStyle::default()
+            })
+            .expect_valid();
+
+        Renderer::new(window)
+            .unwrap()
+            .with_interface_only()
+            .run()
+            .unwrap();
+
+        basalt.exit();
+    });
+}
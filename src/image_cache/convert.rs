@@ -2,6 +2,122 @@ use vulkano::format::Format as VkFormat;
 
 use crate::image_cache::{ImageData, ImageFormat};
 
+/// Write image data in the provided vulkan format directly into `dst`.
+///
+/// Data that is already in the layout of the vulkan format is copied as is and data that only
+/// differs in channel order is remapped. Anything else is converted with
+/// `image_data_to_vulkan_format`.
+pub fn write_image_data_to_vulkan_format(
+    image_format: ImageFormat,
+    image_data: &ImageData,
+    vulkan_format: VkFormat,
+    dst: &mut [u8],
+) {
+    let channel_order = match vulkan_format {
+        VkFormat::R8G8B8A8_UINT | VkFormat::R8G8B8A8_UNORM => Some([0, 1, 2, 3]),
+        VkFormat::B8G8R8A8_UINT | VkFormat::B8G8R8A8_UNORM => Some([2, 1, 0, 3]),
+        VkFormat::A8B8G8R8_UINT_PACK32 | VkFormat::A8B8G8R8_UNORM_PACK32 => Some([3, 2, 1, 0]),
+        _ => None,
+    };
+
+    match (image_data, channel_order) {
+        (ImageData::D8(data), Some(channel_order)) => {
+            match image_format {
+                ImageFormat::LRGBA => {
+                    if channel_order == [0, 1, 2, 3] {
+                        dst.copy_from_slice(data);
+                    } else {
+                        for (src, dst) in data.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+                            remap_channels(channel_order, [src[0], src[1], src[2], src[3]], dst);
+                        }
+                    }
+                },
+                ImageFormat::LRGB => {
+                    for (src, dst) in data.chunks_exact(3).zip(dst.chunks_exact_mut(4)) {
+                        remap_channels(channel_order, [src[0], src[1], src[2], 255], dst);
+                    }
+                },
+                ImageFormat::LMono => {
+                    for (src, dst) in data.iter().zip(dst.chunks_exact_mut(4)) {
+                        remap_channels(channel_order, [*src, *src, *src, 255], dst);
+                    }
+                },
+                ImageFormat::LMonoA => {
+                    for (src, dst) in data.chunks_exact(2).zip(dst.chunks_exact_mut(4)) {
+                        remap_channels(channel_order, [src[0], src[0], src[0], src[1]], dst);
+                    }
+                },
+                _ => {
+                    dst.copy_from_slice(&image_data_to_vulkan_format(
+                        image_format,
+                        image_data,
+                        vulkan_format,
+                    ));
+                },
+            }
+        },
+        (ImageData::D16(data), None)
+            if image_format == ImageFormat::LRGBA
+                && matches!(
+                    vulkan_format,
+                    VkFormat::R16G16B16A16_UINT | VkFormat::R16G16B16A16_UNORM
+                ) =>
+        {
+            for (src, dst) in data.iter().zip(dst.chunks_exact_mut(2)) {
+                dst.copy_from_slice(&src.to_ne_bytes());
+            }
+        },
+        _ => {
+            dst.copy_from_slice(&image_data_to_vulkan_format(
+                image_format,
+                image_data,
+                vulkan_format,
+            ));
+        },
+    }
+}
+
+/// Convert data in the provided vulkan format into 8-bit RGBA.
+///
+/// ***Note:** Values are not converted between color spaces.*
+pub fn vulkan_format_to_rgba8(vulkan_format: VkFormat, src: &[u8]) -> Result<Vec<u8>, String> {
+    let channel_order = match vulkan_format {
+        VkFormat::R8G8B8A8_UINT | VkFormat::R8G8B8A8_UNORM | VkFormat::R8G8B8A8_SRGB => {
+            [0, 1, 2, 3]
+        },
+        VkFormat::B8G8R8A8_UINT | VkFormat::B8G8R8A8_UNORM | VkFormat::B8G8R8A8_SRGB => {
+            [2, 1, 0, 3]
+        },
+        VkFormat::A8B8G8R8_UINT_PACK32
+        | VkFormat::A8B8G8R8_UNORM_PACK32
+        | VkFormat::A8B8G8R8_SRGB_PACK32 => [3, 2, 1, 0],
+        VkFormat::R16G16B16A16_UINT | VkFormat::R16G16B16A16_UNORM => {
+            return Ok(src
+                .chunks_exact(2)
+                .map(|bytes| (u16::from_ne_bytes([bytes[0], bytes[1]]) >> 8) as u8)
+                .collect());
+        },
+        _ => return Err(format!("Unable to convert from {:?}.", vulkan_format)),
+    };
+
+    let mut dst = vec![0; src.len()];
+
+    for (src, dst) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+        for (i, j) in channel_order.into_iter().enumerate() {
+            dst[j] = src[i];
+        }
+    }
+
+    Ok(dst)
+}
+
+#[inline(always)]
+fn remap_channels(channel_order: [usize; 4], src: [u8; 4], dst: &mut [u8]) {
+    for (i, j) in channel_order.into_iter().enumerate() {
+        dst[i] = src[j];
+    }
+}
+
 pub fn image_data_to_vulkan_format(
     image_format: ImageFormat,
     image_data: &ImageData,
@@ -1164,31 +1280,31 @@ pub fn image_data_to_vulkan_format(
 
 #[inline(always)]
 pub(crate) fn u8f32(v: u8) -> f32 {
-    v as f32 / u8::max_value() as f32
+    v as f32 / u8::MAX as f32
 }
 
 #[inline(always)]
 pub(crate) fn f32u8(v: f32) -> u8 {
-    (v * u8::max_value() as f32)
-        .clamp(0.0, u8::max_value() as f32)
-        .trunc() as u8
+    (v * u8::MAX as f32).clamp(0.0, u8::MAX as f32).trunc() as u8
 }
 
 #[inline(always)]
 pub(crate) fn u16f32(v: u16) -> f32 {
-    v as f32 / u16::max_value() as f32
+    v as f32 / u16::MAX as f32
 }
 
 #[inline(always)]
 pub(crate) fn f32u16(v: f32) -> u16 {
-    (v * u16::max_value() as f32)
-        .clamp(0.0, u16::max_value() as f32)
-        .trunc() as u16
+    (v * u16::MAX as f32).clamp(0.0, u16::MAX as f32).trunc() as u16
 }
 
 #[inline(always)]
 pub(crate) fn lts(v: f32) -> f32 {
-    (v.powf(1.0 / 2.4) * 1.005) - 0.055
+    if v < 0.0031308 {
+        v * 12.92
+    } else {
+        (v.powf(1.0 / 2.4) * 1.055) - 0.055
+    }
 }
 
 #[inline(always)]
@@ -1199,3 +1315,63 @@ pub(crate) fn stl(v: f32) -> f32 {
         ((v + 0.055) / 1.055).powf(2.4)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use vulkano::format::Format as VkFormat;
+
+    use super::{image_data_to_vulkan_format, write_image_data_to_vulkan_format};
+    use crate::image_cache::{ImageData, ImageFormat};
+
+    fn assert_write_matches(
+        image_format: ImageFormat,
+        image_data: ImageData,
+        vulkan_format: VkFormat,
+    ) {
+        let expected = image_data_to_vulkan_format(image_format, &image_data, vulkan_format);
+        let mut dst = vec![0; expected.len()];
+        write_image_data_to_vulkan_format(image_format, &image_data, vulkan_format, &mut dst);
+
+        assert_eq!(dst, expected, "{:?} to {:?}", image_format, vulkan_format);
+    }
+
+    #[test]
+    fn write_matches_convert_8bit() {
+        for vulkan_format in [
+            VkFormat::R8G8B8A8_UNORM,
+            VkFormat::B8G8R8A8_UNORM,
+            VkFormat::A8B8G8R8_UNORM_PACK32,
+            VkFormat::R16G16B16A16_UNORM,
+        ] {
+            for image_format in [
+                ImageFormat::LRGBA,
+                ImageFormat::LRGB,
+                ImageFormat::LMono,
+                ImageFormat::LMonoA,
+                ImageFormat::SRGBA,
+                ImageFormat::SRGB,
+                ImageFormat::SMono,
+                ImageFormat::SMonoA,
+            ] {
+                let data = (0..(image_format.components() * 4))
+                    .map(|i| (i * 37) as u8)
+                    .collect();
+
+                assert_write_matches(image_format, ImageData::D8(data), vulkan_format);
+            }
+        }
+    }
+
+    #[test]
+    fn write_matches_convert_16bit() {
+        for vulkan_format in [VkFormat::R8G8B8A8_UNORM, VkFormat::R16G16B16A16_UNORM] {
+            for image_format in [ImageFormat::LRGBA, ImageFormat::LRGB, ImageFormat::SRGBA] {
+                let data = (0..(image_format.components() * 4))
+                    .map(|i| (i * 4099) as u16)
+                    .collect();
+
+                assert_write_matches(image_format, ImageData::D16(data), vulkan_format);
+            }
+        }
+    }
+}
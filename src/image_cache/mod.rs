@@ -10,13 +10,24 @@ use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use cosmic_text::CacheKey as GlyphCacheKey;
 use parking_lot::Mutex;
 use url::Url;
 use vulkano::format::Format as VkFormat;
 
+/// The amount of threads used to load images in the background.
+const LOADER_THREADS: usize = 4;
+
+// NOTE: Sizes are rounded up to a step of an eighth of their next power of two, so that a SVG
+//       isn't rasterized again for every size while a bin is resized.
+pub(crate) fn svg_raster_size(size: f32) -> u32 {
+    let size = size.ceil().max(1.0) as u32;
+    let step = (size.next_power_of_two() / 8).max(8);
+    size.div_ceil(step) * step
+}
+
 /// `ImageCacheKey` is a value used to refrence an image within the cache.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ImageCacheKey {
@@ -24,6 +35,16 @@ pub enum ImageCacheKey {
     Path(PathBuf),
     Glyph(GlyphCacheKey),
     User(TypeId, u64),
+    /// Encoded image data identified by the 128-bit hash of its content.
+    Encoded(u128),
+    /// A SVG image rasterized at a specific size.
+    ///
+    /// `source` should be either `ImageCacheKey::Path` or `ImageCacheKey::Url`.
+    Svg {
+        source: Box<ImageCacheKey>,
+        width: u32,
+        height: u32,
+    },
 }
 
 impl ImageCacheKey {
@@ -45,6 +66,34 @@ impl ImageCacheKey {
         key.hash(&mut hasher);
         Self::User(key.type_id(), hasher.finish())
     }
+
+    /// Create an `ImageCacheKey` for a SVG rasterized at the provided size. This will not load
+    /// the image.
+    pub fn svg(source: Self, width: u32, height: u32) -> Self {
+        Self::Svg {
+            source: Box::new(source),
+            width,
+            height,
+        }
+    }
+
+    /// Check if this key references a SVG image.
+    ///
+    /// ***Note:** For `Path` & `Url` this is determined by the extension.*
+    pub fn is_svg(&self) -> bool {
+        match self {
+            Self::Path(path) => {
+                path.extension()
+                    .map(|ext| ext.eq_ignore_ascii_case("svg"))
+                    .unwrap_or(false)
+            },
+            Self::Url(url) => url.path().to_ascii_lowercase().ends_with(".svg"),
+            Self::Svg {
+                ..
+            } => true,
+            _ => false,
+        }
+    }
 }
 
 /// Specifies how long an image should remain in the cache after it isn't used.
@@ -59,6 +108,18 @@ pub enum ImageCacheLifetime {
     Seconds(u64),
 }
 
+impl ImageCacheLifetime {
+    #[cfg(feature = "image_decode")]
+    fn longest(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Indefinite, _) | (_, Self::Indefinite) => Self::Indefinite,
+            (Self::Seconds(a), Self::Seconds(b)) => Self::Seconds(a.max(b)),
+            (Self::Seconds(a), _) | (_, Self::Seconds(a)) => Self::Seconds(a),
+            (Self::Immeditate, Self::Immeditate) => Self::Immeditate,
+        }
+    }
+}
+
 /// Specifies the layout and colorspace of the image data.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ImageFormat {
@@ -101,17 +162,83 @@ pub enum ImageData {
     D16(Vec<u16>),
 }
 
+impl ImageData {
+    fn size_in_bytes(&self) -> usize {
+        match self {
+            Self::D8(data) => data.len(),
+            Self::D16(data) => data.len() * 2,
+        }
+    }
+}
+
 pub(crate) struct ObtainedImage {
     pub width: u32,
     pub height: u32,
-    pub data: Vec<u8>,
+    format: ImageFormat,
+    data: Arc<ImageData>,
+    target_format: VkFormat,
+}
+
+impl ObtainedImage {
+    /// The length in bytes of the data once in the target format.
+    pub fn data_len(&self) -> usize {
+        self.target_format.block_size() as usize * self.width as usize * self.height as usize
+    }
+
+    /// Write the data in the target format into `dst`.
+    ///
+    /// ***Note:** `dst` must be the same length as `data_len()`.*
+    pub fn write_data(&self, dst: &mut [u8]) {
+        convert::write_image_data_to_vulkan_format(
+            self.format,
+            &self.data,
+            self.target_format,
+            dst,
+        );
+    }
 }
 
 struct Image {
     format: ImageFormat,
     width: u32,
     height: u32,
-    data: ImageData,
+    data: Arc<ImageData>,
+    animation: Option<Arc<ImageAnimation>>,
+}
+
+/// Information about the frames of an animated image.
+///
+/// The frames of an animated image are stored in a grid within a single image. Frames are
+/// ordered left to right, then top to bottom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageAnimation {
+    /// The width of a single frame.
+    pub frame_width: u32,
+    /// The height of a single frame.
+    pub frame_height: u32,
+    /// The number of frames per row.
+    pub columns: u32,
+    /// How long each frame should be displayed for.
+    pub delays: Vec<Duration>,
+}
+
+impl ImageAnimation {
+    /// The number of frames in the animation.
+    pub fn frame_count(&self) -> usize {
+        self.delays.len()
+    }
+
+    /// The coordinates of a frame in the format of `BinStyle.back_image_coords`.
+    pub fn frame_coords(&self, frame: usize) -> [f32; 4] {
+        let frame = frame as u32;
+
+        [
+            ((frame / self.columns) * self.frame_height) as f32,
+            ((frame % self.columns) * self.frame_width) as f32,
+            self.frame_width as f32,
+            self.frame_height as f32,
+        ]
+    }
 }
 
 struct ImageEntry {
@@ -122,6 +249,22 @@ struct ImageEntry {
     associated_data: Arc<dyn Any + Send + Sync>,
 }
 
+impl ImageEntry {
+    fn image_info(&self) -> ImageInfo {
+        ImageInfo {
+            width: self.image.width,
+            height: self.image.height,
+            format: self.image.format,
+            depth: match *self.image.data {
+                ImageData::D8(_) => ImageDepth::D8,
+                ImageData::D16(_) => ImageDepth::D16,
+            },
+            animation: self.image.animation.clone(),
+            associated_data: self.associated_data.clone(),
+        }
+    }
+}
+
 /// Information about an image including width, height, format and depth.
 #[derive(Debug, Clone)]
 pub struct ImageInfo {
@@ -129,6 +272,8 @@ pub struct ImageInfo {
     pub height: u32,
     pub format: ImageFormat,
     pub depth: ImageDepth,
+    /// Present if the image is animated.
+    pub animation: Option<Arc<ImageAnimation>>,
     pub associated_data: Arc<dyn Any + Send + Sync>,
 }
 
@@ -138,16 +283,121 @@ impl ImageInfo {
     }
 }
 
+/// Statistics about the usage of an `ImageCache`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageCacheStats {
+    /// The number of images stored.
+    pub images: usize,
+    /// The size in bytes of all images stored.
+    pub bytes: usize,
+    /// The number of images currently used by the renderer.
+    pub used_images: usize,
+    /// The size in bytes of images currently used by the renderer.
+    pub used_bytes: usize,
+    /// The budget set with `ImageCache::set_budget`.
+    pub budget: Option<usize>,
+}
+
 /// System for storing images used within the UI.
 pub struct ImageCache {
     images: Mutex<HashMap<ImageCacheKey, ImageEntry>>,
+    pending_loads: Mutex<HashMap<ImageCacheKey, Vec<ImageLoadCallback>>>,
+    failed_loads: Mutex<HashMap<ImageCacheKey, String>>,
+    loader_send: Mutex<Option<flume::Sender<ImageLoadJob>>>,
+    budget: Mutex<Option<usize>>,
+}
+
+type ImageLoadCallback = Box<dyn FnOnce(Result<ImageInfo, String>) + Send + 'static>;
+type ImageLoadJob = Box<dyn FnOnce() + Send + 'static>;
+
+/// A handle to an image being loaded in the background.
+///
+/// Returned from `ImageCache::load_from_key_async`.
+pub struct ImageLoadHandle {
+    cache_key: ImageCacheKey,
+    result_recv: flume::Receiver<Result<ImageInfo, String>>,
+}
+
+impl ImageLoadHandle {
+    /// The `ImageCacheKey` of the image being loaded.
+    pub fn cache_key(&self) -> &ImageCacheKey {
+        &self.cache_key
+    }
+
+    /// Check if the load has completed without blocking.
+    ///
+    /// ***Note:** Once the result has been returned, this will return `None`.*
+    pub fn try_result(&self) -> Option<Result<ImageInfo, String>> {
+        match self.result_recv.try_recv() {
+            Ok(result) => Some(result),
+            Err(flume::TryRecvError::Empty) => None,
+            Err(flume::TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Block until the load has completed.
+    pub fn wait(self) -> Result<ImageInfo, String> {
+        self.result_recv
+            .recv()
+            .unwrap_or_else(|_| Err(String::from("Image load was cancelled.")))
+    }
 }
 
 impl ImageCache {
     pub(crate) fn new() -> Self {
         Self {
             images: Mutex::new(HashMap::new()),
+            pending_loads: Mutex::new(HashMap::new()),
+            failed_loads: Mutex::new(HashMap::new()),
+            loader_send: Mutex::new(None),
+            budget: Mutex::new(None),
+        }
+    }
+
+    /// Set the maximum size in bytes of images stored. `None` is unlimited.
+    ///
+    /// When the budget is exceeded, images that are no longer used are evicted starting with
+    /// the least recently used.
+    ///
+    /// ***Note:** Images that are in use, have never been used, or have an
+    /// `ImageCacheLifetime::Indefinite` lifetime are never evicted. The budget may be exceeded
+    /// by these images.*
+    pub fn set_budget(&self, budget: Option<usize>) {
+        *self.budget.lock() = budget;
+
+        if let Some(budget) = budget {
+            evict_to_budget(&mut self.images.lock(), budget);
+        }
+    }
+
+    /// Retrieve the budget set with `set_budget`.
+    pub fn budget(&self) -> Option<usize> {
+        *self.budget.lock()
+    }
+
+    /// Retrieve statistics about the usage of the cache.
+    pub fn stats(&self) -> ImageCacheStats {
+        let images = self.images.lock();
+
+        let mut stats = ImageCacheStats {
+            images: images.len(),
+            bytes: 0,
+            used_images: 0,
+            used_bytes: 0,
+            budget: *self.budget.lock(),
+        };
+
+        for entry in images.values() {
+            let size = entry.image.data.size_in_bytes();
+            stats.bytes += size;
+
+            if entry.refs > 0 {
+                stats.used_images += 1;
+                stats.used_bytes += size;
+            }
         }
+
+        stats
     }
 
     /// Load an image from raw data. This is not an encoded format like PNG (See `load_from_bytes`).
@@ -163,55 +413,50 @@ impl ImageCache {
     ) -> Result<ImageInfo, String> {
         let expected_data_len = width as usize * height as usize * format.components();
 
-        let (data_len, depth) = match &data {
-            ImageData::D8(data) => (data.len(), ImageDepth::D8),
-            ImageData::D16(data) => (data.len(), ImageDepth::D16),
+        let data_len = match &data {
+            ImageData::D8(data) => data.len(),
+            ImageData::D16(data) => data.len(),
         };
 
         if expected_data_len != data_len {
             return Err(String::from("data invalid length"));
         }
 
-        let associated_data = Arc::new(associated_data);
+        Ok(self.insert_image(
+            cache_key,
+            lifetime,
+            associated_data,
+            Image {
+                format,
+                width,
+                height,
+                data: Arc::new(data),
+                animation: None,
+            },
+        ))
+    }
 
+    fn insert_image<D: Any + Send + Sync>(
+        &self,
+        cache_key: ImageCacheKey,
+        lifetime: ImageCacheLifetime,
+        associated_data: D,
+        image: Image,
+    ) -> ImageInfo {
         match self.images.lock().entry(cache_key) {
             HashMapEntry::Vacant(entry) => {
-                entry.insert(ImageEntry {
-                    image: Image {
-                        format,
-                        width,
-                        height,
-                        data,
-                    },
-                    refs: 0,
-                    unused_since: None,
-                    lifetime,
-                    associated_data: associated_data.clone(),
-                });
-            },
-            HashMapEntry::Occupied(occupied_entry) => {
-                let entry = occupied_entry.get();
-
-                return Ok(ImageInfo {
-                    width: entry.image.width,
-                    height: entry.image.height,
-                    format: entry.image.format,
-                    depth: match entry.image.data {
-                        ImageData::D8(_) => ImageDepth::D8,
-                        ImageData::D16(_) => ImageDepth::D16,
-                    },
-                    associated_data: entry.associated_data.clone(),
-                });
+                entry
+                    .insert(ImageEntry {
+                        image,
+                        refs: 0,
+                        unused_since: None,
+                        lifetime,
+                        associated_data: Arc::new(associated_data),
+                    })
+                    .image_info()
             },
+            HashMapEntry::Occupied(entry) => entry.get().image_info(),
         }
-
-        Ok(ImageInfo {
-            width,
-            height,
-            format,
-            depth,
-            associated_data,
-        })
     }
 
     /// Load an image from bytes that are encoded format such as PNG.
@@ -223,89 +468,50 @@ impl ImageCache {
         associated_data: D,
         bytes: B,
     ) -> Result<ImageInfo, String> {
-        let format = image::guess_format(bytes.as_ref())
-            .map_err(|e| format!("Failed to guess image format type: {}", e))?;
-        let image = image::load_from_memory_with_format(bytes.as_ref(), format)
-            .map_err(|e| format!("Failed to load iamge: {}", e))?;
+        let image = decode_bytes(bytes.as_ref())?;
+        Ok(self.insert_image(cache_key, lifetime, associated_data, image))
+    }
 
-        let width = image.width();
-        let height = image.height();
+    /// Load an image from bytes that are encoded format such as PNG without providing a key.
+    ///
+    /// The returned key is derived from the bytes, so identical bytes reuse the existing entry.
+    ///
+    /// ***Note:** When an existing entry is reused, the longer of the two lifetimes is kept.*
+    #[cfg(feature = "image_decode")]
+    pub fn load_raw_encoded<B: AsRef<[u8]>>(
+        &self,
+        lifetime: ImageCacheLifetime,
+        bytes: B,
+        format_hint: Option<image::ImageFormat>,
+    ) -> Result<ImageCacheKey, String> {
+        let bytes = bytes.as_ref();
+        let cache_key = ImageCacheKey::Encoded(content_hash(bytes));
 
-        let (mut image_format, image_data) = match image {
-            image::DynamicImage::ImageLuma8(img) => {
-                (ImageFormat::LMono, ImageData::D8(img.into_vec()))
-            },
-            image::DynamicImage::ImageLumaA8(img) => {
-                (ImageFormat::LMonoA, ImageData::D8(img.into_vec()))
-            },
-            image::DynamicImage::ImageRgb8(img) => {
-                (ImageFormat::LRGB, ImageData::D8(img.into_vec()))
-            },
-            image::DynamicImage::ImageRgba8(img) => {
-                (ImageFormat::LRGBA, ImageData::D8(img.into_vec()))
-            },
-            image::DynamicImage::ImageLuma16(img) => {
-                (ImageFormat::LMono, ImageData::D16(img.into_vec()))
-            },
-            image::DynamicImage::ImageLumaA16(img) => {
-                (ImageFormat::LMonoA, ImageData::D16(img.into_vec()))
-            },
-            image::DynamicImage::ImageRgb16(img) => {
-                (ImageFormat::LRGB, ImageData::D16(img.into_vec()))
-            },
-            image::DynamicImage::ImageRgba16(img) => {
-                (ImageFormat::LRGBA, ImageData::D16(img.into_vec()))
-            },
-            image::DynamicImage::ImageRgb32F(img) => {
-                (
-                    ImageFormat::LRGB,
-                    ImageData::D16(
-                        img.into_vec()
-                            .into_iter()
-                            .map(|val| {
-                                (val.clamp(0.0, 1.0) * u16::max_value() as f32).trunc() as u16
-                            })
-                            .collect(),
-                    ),
-                )
-            },
-            image::DynamicImage::ImageRgba32F(img) => {
-                (
-                    ImageFormat::LRGBA,
-                    ImageData::D16(
-                        img.into_vec()
-                            .into_iter()
-                            .map(|val| {
-                                (val.clamp(0.0, 1.0) * u16::max_value() as f32).trunc() as u16
-                            })
-                            .collect(),
-                    ),
-                )
-            },
-            _ => return Err(String::from("Image format not supported.")),
-        };
+        if let Some(entry) = self.images.lock().get_mut(&cache_key) {
+            entry.lifetime = entry.lifetime.longest(lifetime);
+            return Ok(cache_key);
+        }
 
-        let is_linear = !matches!(format, image::ImageFormat::Jpeg);
+        let image = decode_bytes_with_format(bytes, format_hint)?;
+        let mut images = self.images.lock();
 
-        if !is_linear {
-            image_format = match image_format {
-                ImageFormat::LMono => ImageFormat::SMono,
-                ImageFormat::LMonoA => ImageFormat::SMonoA,
-                ImageFormat::LRGB => ImageFormat::SRGB,
-                ImageFormat::LRGBA => ImageFormat::SRGBA,
-                _ => unreachable!(),
-            };
+        match images.entry(cache_key.clone()) {
+            HashMapEntry::Vacant(entry) => {
+                entry.insert(ImageEntry {
+                    image,
+                    refs: 0,
+                    unused_since: None,
+                    lifetime,
+                    associated_data: Arc::new(()),
+                });
+            },
+            HashMapEntry::Occupied(mut entry) => {
+                let entry = entry.get_mut();
+                entry.lifetime = entry.lifetime.longest(lifetime);
+            },
         }
 
-        self.load_raw_image(
-            cache_key,
-            lifetime,
-            image_format,
-            width,
-            height,
-            associated_data,
-            image_data,
-        )
+        Ok(cache_key)
     }
 
     /// Download and load the image from the provided URL.
@@ -317,26 +523,7 @@ impl ImageCache {
         url: U,
     ) -> Result<ImageInfo, String> {
         let url = Url::parse(url.as_ref()).map_err(|e| format!("Invalid URL: {}", e))?;
-        let mut handle = curl::easy::Easy::new();
-        handle.follow_location(true).unwrap();
-        handle.url(url.as_str()).unwrap();
-        let mut bytes = Vec::new();
-
-        {
-            let mut transfer = handle.transfer();
-
-            transfer
-                .write_function(|data| {
-                    bytes.extend_from_slice(data);
-                    Ok(data.len())
-                })
-                .unwrap();
-
-            transfer
-                .perform()
-                .map_err(|e| format!("Failed to download: {}", e))?;
-        }
-
+        let bytes = download_url(&url)?;
         self.load_from_bytes(ImageCacheKey::Url(url), lifetime, associated_data, bytes)
     }
 
@@ -348,17 +535,7 @@ impl ImageCache {
         associated_data: D,
         path: P,
     ) -> Result<ImageInfo, String> {
-        use std::fs::File;
-        use std::io::Read;
-
-        let mut handle =
-            File::open(path.as_ref()).map_err(|e| format!("Failed to open file: {}", e))?;
-
-        let mut bytes = Vec::new();
-
-        handle
-            .read_to_end(&mut bytes)
-            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let bytes = read_path(path.as_ref())?;
 
         self.load_from_bytes(
             ImageCacheKey::Path(path.as_ref().to_path_buf()),
@@ -368,6 +545,139 @@ impl ImageCache {
         )
     }
 
+    /// Load the image referenced by the `ImageCacheKey`.
+    ///
+    /// This is supported for `ImageCacheKey::Path`, `ImageCacheKey::Url` and `ImageCacheKey::Svg`
+    /// given the relevant features are enabled.
+    pub fn load_from_key<D: Any + Send + Sync>(
+        &self,
+        lifetime: ImageCacheLifetime,
+        associated_data: D,
+        cache_key: ImageCacheKey,
+    ) -> Result<ImageInfo, String> {
+        let image = decode_from_key(&cache_key)?;
+        Ok(self.insert_image(cache_key, lifetime, associated_data, image))
+    }
+
+    /// Load the image referenced by the `ImageCacheKey` on a background thread.
+    ///
+    /// See `load_from_key` for the supported keys. Loading a key that is already being loaded
+    /// will not decode the image again. Keys that failed to load previously result in the same
+    /// error without loading the image again, until `remove_image` is called with the key.
+    ///
+    /// ***Note:** If `remove_image` is called with the key before the load completes, the loaded
+    /// image is discarded and the result will be an error.*
+    pub fn load_from_key_async<D: Any + Send + Sync>(
+        self: &Arc<Self>,
+        lifetime: ImageCacheLifetime,
+        associated_data: D,
+        cache_key: ImageCacheKey,
+    ) -> ImageLoadHandle {
+        let (result_send, result_recv) = flume::bounded(1);
+
+        self.load_from_key_async_with(
+            lifetime,
+            associated_data,
+            cache_key.clone(),
+            move |result| {
+                let _ = result_send.send(result);
+            },
+        );
+
+        ImageLoadHandle {
+            cache_key,
+            result_recv,
+        }
+    }
+
+    pub(crate) fn load_from_key_async_with<
+        D: Any + Send + Sync,
+        F: FnOnce(Result<ImageInfo, String>) + Send + 'static,
+    >(
+        self: &Arc<Self>,
+        lifetime: ImageCacheLifetime,
+        associated_data: D,
+        cache_key: ImageCacheKey,
+        on_complete: F,
+    ) {
+        if let Some(error) = self.load_error(&cache_key) {
+            on_complete(Err(error));
+            return;
+        }
+
+        match self.pending_loads.lock().entry(cache_key.clone()) {
+            HashMapEntry::Occupied(mut entry) => {
+                entry.get_mut().push(Box::new(on_complete));
+                return;
+            },
+            HashMapEntry::Vacant(entry) => {
+                entry.insert(vec![Box::new(on_complete)]);
+            },
+        }
+
+        let image_cache = self.clone();
+
+        self.queue_load(Box::new(move || {
+            let decode_result = decode_from_key(&cache_key);
+            let mut pending_loads = image_cache.pending_loads.lock();
+
+            // NOTE: If the key isn't pending, `remove_image` was called during the load.
+            let on_complete = match pending_loads.remove(&cache_key) {
+                Some(some) => some,
+                None => return,
+            };
+
+            let result = match decode_result {
+                Ok(image) => {
+                    Ok(image_cache.insert_image(cache_key, lifetime, associated_data, image))
+                },
+                Err(e) => {
+                    image_cache.failed_loads.lock().insert(cache_key, e.clone());
+                    Err(e)
+                },
+            };
+
+            drop(pending_loads);
+
+            for on_complete in on_complete {
+                on_complete(result.clone());
+            }
+        }));
+    }
+
+    fn queue_load(&self, job: ImageLoadJob) {
+        let mut loader_send = self.loader_send.lock();
+
+        // NOTE: The threads exit once the cache is dropped along with the sender.
+        let send = loader_send.get_or_insert_with(|| {
+            let (send, recv) = flume::unbounded::<ImageLoadJob>();
+
+            for _ in 0..LOADER_THREADS {
+                let recv = recv.clone();
+
+                std::thread::spawn(move || {
+                    while let Ok(job) = recv.recv() {
+                        job();
+                    }
+                });
+            }
+
+            send
+        });
+
+        send.send(job).unwrap();
+    }
+
+    /// Check if the image is currently being loaded in the background.
+    pub fn is_loading(&self, cache_key: &ImageCacheKey) -> bool {
+        self.pending_loads.lock().contains_key(cache_key)
+    }
+
+    /// Retrieve the error of the last background load of the image if it failed.
+    pub fn load_error(&self, cache_key: &ImageCacheKey) -> Option<String> {
+        self.failed_loads.lock().get(cache_key).cloned()
+    }
+
     /// Retrieve image information for multiple images.
     pub fn obtain_image_infos<K: IntoIterator<Item = ImageCacheKey>>(
         &self,
@@ -377,20 +687,7 @@ impl ImageCache {
 
         cache_keys
             .into_iter()
-            .map(move |cache_key| {
-                images.get(&cache_key).map(|entry| {
-                    ImageInfo {
-                        width: entry.image.width,
-                        height: entry.image.height,
-                        format: entry.image.format,
-                        depth: match entry.image.data {
-                            ImageData::D8(_) => ImageDepth::D8,
-                            ImageData::D16(_) => ImageDepth::D16,
-                        },
-                        associated_data: entry.associated_data.clone(),
-                    }
-                })
-            })
+            .map(move |cache_key| images.get(&cache_key).map(ImageEntry::image_info))
             .collect()
     }
 
@@ -406,7 +703,19 @@ impl ImageCache {
     ///
     /// ***Note:** If an image is in use this will change the lifetime of the image to
     /// `ImageCacheLifetime::Immeditate`.* Allowing it to be removed after it is no longer used.
+    ///
+    /// ***Note:** If the image is being loaded in the background, the load will be cancelled. If
+    /// the image failed to load in the background, it may be loaded again.*
     pub fn remove_image(&self, cache_key: ImageCacheKey) {
+        self.failed_loads.lock().remove(&cache_key);
+        let pending_load_op = self.pending_loads.lock().remove(&cache_key);
+
+        if let Some(on_complete) = pending_load_op {
+            for on_complete in on_complete {
+                on_complete(Err(String::from("Image was removed while loading.")));
+            }
+        }
+
         let mut images = self.images.lock();
 
         match images.get_mut(&cache_key) {
@@ -459,11 +768,9 @@ impl ImageCache {
                 ObtainedImage {
                     width: entry.image.width,
                     height: entry.image.height,
-                    data: convert::image_data_to_vulkan_format(
-                        entry.image.format,
-                        &entry.image.data,
-                        target_format,
-                    ),
+                    format: entry.image.format,
+                    data: entry.image.data.clone(),
+                    target_format,
                 },
             );
         }
@@ -488,6 +795,410 @@ impl ImageCache {
             }
         });
 
+        if let Some(budget) = *self.budget.lock() {
+            evict_to_budget(&mut images, budget);
+        }
+
         output
     }
 }
+
+fn evict_to_budget(images: &mut HashMap<ImageCacheKey, ImageEntry>, budget: usize) {
+    let mut size: usize = images
+        .values()
+        .map(|entry| entry.image.data.size_in_bytes())
+        .sum();
+
+    if size <= budget {
+        return;
+    }
+
+    let mut evictable: Vec<(Instant, ImageCacheKey)> = images
+        .iter()
+        .filter_map(|(cache_key, entry)| {
+            if entry.refs > 0 || entry.lifetime == ImageCacheLifetime::Indefinite {
+                return None;
+            }
+
+            entry
+                .unused_since
+                .map(|unused_since| (unused_since, cache_key.clone()))
+        })
+        .collect();
+
+    evictable.sort_by_key(|(unused_since, _)| *unused_since);
+
+    for (_, cache_key) in evictable {
+        if size <= budget {
+            break;
+        }
+
+        let entry = images.remove(&cache_key).unwrap();
+        size -= entry.image.data.size_in_bytes();
+    }
+}
+
+// NOTE: Two 64-bit hashes with different prefixes are combined, so that distinct bytes sharing a
+//       key is unlikely even with many encoded images stored.
+#[cfg(feature = "image_decode")]
+fn content_hash(bytes: &[u8]) -> u128 {
+    let [high, low] = [0_u8, 1].map(|prefix| {
+        let mut hasher = DefaultHasher::new();
+        prefix.hash(&mut hasher);
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    });
+
+    ((high as u128) << 64) | low as u128
+}
+
+#[cfg(feature = "image_decode")]
+fn decode_bytes(bytes: &[u8]) -> Result<Image, String> {
+    decode_bytes_with_format(bytes, None)
+}
+
+#[cfg(feature = "image_decode")]
+fn decode_bytes_with_format(
+    bytes: &[u8],
+    format_hint: Option<image::ImageFormat>,
+) -> Result<Image, String> {
+    let format = match format_hint {
+        Some(format) => format,
+        None => {
+            image::guess_format(bytes)
+                .map_err(|e| format!("Failed to guess image format type: {}", e))?
+        },
+    };
+
+    if let Some(image) = decode_animated(bytes, format)? {
+        return Ok(image);
+    }
+
+    let image = image::load_from_memory_with_format(bytes, format)
+        .map_err(|e| format!("Failed to load iamge: {}", e))?;
+
+    let width = image.width();
+    let height = image.height();
+
+    let (mut image_format, image_data) = match image {
+        image::DynamicImage::ImageLuma8(img) => (ImageFormat::LMono, ImageData::D8(img.into_vec())),
+        image::DynamicImage::ImageLumaA8(img) => {
+            (ImageFormat::LMonoA, ImageData::D8(img.into_vec()))
+        },
+        image::DynamicImage::ImageRgb8(img) => (ImageFormat::LRGB, ImageData::D8(img.into_vec())),
+        image::DynamicImage::ImageRgba8(img) => (ImageFormat::LRGBA, ImageData::D8(img.into_vec())),
+        image::DynamicImage::ImageLuma16(img) => {
+            (ImageFormat::LMono, ImageData::D16(img.into_vec()))
+        },
+        image::DynamicImage::ImageLumaA16(img) => {
+            (ImageFormat::LMonoA, ImageData::D16(img.into_vec()))
+        },
+        image::DynamicImage::ImageRgb16(img) => (ImageFormat::LRGB, ImageData::D16(img.into_vec())),
+        image::DynamicImage::ImageRgba16(img) => {
+            (ImageFormat::LRGBA, ImageData::D16(img.into_vec()))
+        },
+        image::DynamicImage::ImageRgb32F(img) => {
+            (
+                ImageFormat::LRGB,
+                ImageData::D16(
+                    img.into_vec()
+                        .into_iter()
+                        .map(|val| (val.clamp(0.0, 1.0) * u16::MAX as f32).trunc() as u16)
+                        .collect(),
+                ),
+            )
+        },
+        image::DynamicImage::ImageRgba32F(img) => {
+            (
+                ImageFormat::LRGBA,
+                ImageData::D16(
+                    img.into_vec()
+                        .into_iter()
+                        .map(|val| (val.clamp(0.0, 1.0) * u16::MAX as f32).trunc() as u16)
+                        .collect(),
+                ),
+            )
+        },
+        _ => return Err(String::from("Image format not supported.")),
+    };
+
+    let is_linear = !matches!(format, image::ImageFormat::Jpeg);
+
+    if !is_linear {
+        image_format = match image_format {
+            ImageFormat::LMono => ImageFormat::SMono,
+            ImageFormat::LMonoA => ImageFormat::SMonoA,
+            ImageFormat::LRGB => ImageFormat::SRGB,
+            ImageFormat::LRGBA => ImageFormat::SRGBA,
+            _ => unreachable!(),
+        };
+    }
+
+    Ok(Image {
+        format: image_format,
+        width,
+        height,
+        data: Arc::new(image_data),
+        animation: None,
+    })
+}
+
+#[cfg(feature = "image_decode")]
+fn decode_animated(bytes: &[u8], format: image::ImageFormat) -> Result<Option<Image>, String> {
+    use std::io::Cursor;
+
+    use image::codecs::gif::GifDecoder;
+    use image::codecs::png::PngDecoder;
+    use image::codecs::webp::WebPDecoder;
+    use image::AnimationDecoder;
+
+    let frames = match format {
+        image::ImageFormat::Gif => {
+            GifDecoder::new(Cursor::new(bytes))
+                .map_err(|e| format!("Failed to load image: {}", e))?
+                .into_frames()
+        },
+        image::ImageFormat::Png => {
+            let decoder = PngDecoder::new(Cursor::new(bytes))
+                .map_err(|e| format!("Failed to load image: {}", e))?;
+
+            if !decoder.is_apng() {
+                return Ok(None);
+            }
+
+            decoder.apng().into_frames()
+        },
+        image::ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(Cursor::new(bytes))
+                .map_err(|e| format!("Failed to load image: {}", e))?;
+
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+
+            decoder.into_frames()
+        },
+        _ => return Ok(None),
+    };
+
+    let frames = frames
+        .collect_frames()
+        .map_err(|e| format!("Failed to load image frames: {}", e))?;
+
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+
+    let frame_width = frames[0].buffer().width();
+    let frame_height = frames[0].buffer().height();
+    let columns = (frames.len() as f32).sqrt().ceil() as u32;
+    let rows = (frames.len() as u32).div_ceil(columns);
+    let width = frame_width * columns;
+    let height = frame_height * rows;
+    let mut image_data = vec![0; width as usize * height as usize * 4];
+    let mut delays = Vec::with_capacity(frames.len());
+
+    for (i, frame) in frames.into_iter().enumerate() {
+        let x = (i as u32 % columns) * frame_width;
+        let y = (i as u32 / columns) * frame_height;
+        delays.push(Duration::from(frame.delay()));
+        let buffer = frame.into_buffer();
+
+        for (row_i, row) in buffer.chunks_exact(frame_width as usize * 4).enumerate() {
+            let start = (((y as usize + row_i) * width as usize) + x as usize) * 4;
+            image_data[start..(start + row.len())].copy_from_slice(row);
+        }
+    }
+
+    Ok(Some(Image {
+        format: ImageFormat::LRGBA,
+        width,
+        height,
+        data: Arc::new(ImageData::D8(image_data)),
+        animation: Some(Arc::new(ImageAnimation {
+            frame_width,
+            frame_height,
+            columns,
+            delays,
+        })),
+    }))
+}
+
+#[cfg(any(feature = "image_decode", feature = "image_svg"))]
+fn read_path(path: &Path) -> Result<Vec<u8>, String> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut handle = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut bytes = Vec::new();
+
+    handle
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok(bytes)
+}
+
+#[cfg(feature = "image_download")]
+fn download_url(url: &Url) -> Result<Vec<u8>, String> {
+    let mut handle = curl::easy::Easy::new();
+    handle.follow_location(true).unwrap();
+    handle.url(url.as_str()).unwrap();
+    let mut bytes = Vec::new();
+
+    {
+        let mut transfer = handle.transfer();
+
+        transfer
+            .write_function(|data| {
+                bytes.extend_from_slice(data);
+                Ok(data.len())
+            })
+            .unwrap();
+
+        transfer
+            .perform()
+            .map_err(|e| format!("Failed to download: {}", e))?;
+    }
+
+    Ok(bytes)
+}
+
+fn decode_from_key(cache_key: &ImageCacheKey) -> Result<Image, String> {
+    match cache_key {
+        ImageCacheKey::Path(_path) => {
+            #[cfg(feature = "image_decode")]
+            {
+                decode_bytes(&read_path(_path)?)
+            }
+            #[cfg(not(feature = "image_decode"))]
+            {
+                Err(String::from(
+                    "Unable to load image via path. 'image_decode' feature is not enabled.",
+                ))
+            }
+        },
+        ImageCacheKey::Url(_url) => {
+            #[cfg(feature = "image_download")]
+            {
+                decode_bytes(&download_url(_url)?)
+            }
+            #[cfg(not(feature = "image_download"))]
+            {
+                Err(String::from(
+                    "Unable to download image from url. 'image_download' feature is not enabled.",
+                ))
+            }
+        },
+        ImageCacheKey::Svg {
+            source: _source,
+            width: _width,
+            height: _height,
+        } => {
+            #[cfg(feature = "image_svg")]
+            {
+                let bytes = match _source.as_ref() {
+                    ImageCacheKey::Path(path) => read_path(path)?,
+                    #[cfg(feature = "image_download")]
+                    ImageCacheKey::Url(url) => download_url(url)?,
+                    _ => return Err(String::from("Unsupported source for a SVG image.")),
+                };
+
+                rasterize_svg(&bytes, *_width, *_height)
+            }
+            #[cfg(not(feature = "image_svg"))]
+            {
+                Err(String::from(
+                    "Unable to load SVG image. 'image_svg' feature is not enabled.",
+                ))
+            }
+        },
+        ImageCacheKey::Glyph(_) => {
+            Err(String::from("Unable to use glyph cache key to load image."))
+        },
+        ImageCacheKey::User(..) => Err(String::from("Unable to use user cache key to load image.")),
+        ImageCacheKey::Encoded(..) => {
+            Err(String::from(
+                "Unable to use encoded cache key to load image. It must be loaded with \
+                 `load_raw_encoded`.",
+            ))
+        },
+    }
+}
+
+#[cfg(feature = "image_svg")]
+fn rasterize_svg(bytes: &[u8], width: u32, height: u32) -> Result<Image, String> {
+    use resvg::{tiny_skia, usvg};
+
+    let tree = usvg::Tree::from_data(bytes, &usvg::Options::default())
+        .map_err(|e| format!("Failed to parse svg: {}", e))?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| String::from("Invalid size to rasterize svg at."))?;
+
+    let tree_size = tree.size();
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(
+            width as f32 / tree_size.width(),
+            height as f32 / tree_size.height(),
+        ),
+        &mut pixmap.as_mut(),
+    );
+
+    let image_data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    Ok(Image {
+        format: ImageFormat::LRGBA,
+        width,
+        height,
+        data: Arc::new(ImageData::D8(image_data)),
+        animation: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "image_decode")]
+    use super::content_hash;
+    use super::svg_raster_size;
+
+    #[cfg(feature = "image_decode")]
+    #[test]
+    fn content_hash_wide() {
+        let hash = content_hash(b"image");
+        assert_eq!(hash, content_hash(b"image"));
+        assert_ne!(hash, content_hash(b"imagf"));
+        assert_ne!(hash, content_hash(b""));
+
+        // NOTE: Both halves are derived from the content, not just one.
+        assert_ne!(hash >> 64, hash & u64::MAX as u128);
+        assert_ne!(hash >> 64, 0);
+    }
+
+    #[test]
+    fn svg_raster_sizes() {
+        assert_eq!(svg_raster_size(0.0), 8);
+        assert_eq!(svg_raster_size(7.2), 8);
+        assert_eq!(svg_raster_size(8.5), 16);
+        assert_eq!(svg_raster_size(100.0), 112);
+        assert_eq!(svg_raster_size(1000.0), 1024);
+        assert_eq!(svg_raster_size(1025.0), 1280);
+
+        // NOTE: A resize across many sizes should only need a few rasters.
+        let mut sizes = (200..=400)
+            .map(|size| svg_raster_size(size as f32))
+            .collect::<Vec<_>>();
+        sizes.dedup();
+        assert!(sizes.len() <= 8);
+        assert!(sizes.iter().all(|size| *size >= 200));
+    }
+}
@@ -2,9 +2,10 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::input::inner::LoopEvent;
+use crate::input::state::SequenceState;
 use crate::input::{
     Char, Hook, HookState, Input, InputError, InputHookCtrl, InputHookID, InputHookTarget, Key,
-    KeyCombo, LocalCursorState, LocalKeyState, WindowState, NO_HOOK_WEIGHT,
+    KeyCombo, LocalCursorState, LocalKeyState, Sequence, WindowState, NO_HOOK_WEIGHT,
 };
 use crate::interface::Bin;
 use crate::interval::IntvlHookCtrl;
@@ -113,6 +114,16 @@ impl<'a> InputHookBuilder<'a> {
         InputMotionBuilder::start(self)
     }
 
+    /// Attach hook to a raw mouse motion event.
+    ///
+    /// Requires a proceeding call to `window`.
+    ///
+    /// ***Note:** Events are only sent while raw mouse is enabled on the window. See
+    /// `Window::enable_raw_mouse`.*
+    pub fn on_raw_motion(self) -> InputRawMotionBuilder<'a> {
+        InputRawMotionBuilder::start(self)
+    }
+
     fn submit(self) -> Result<InputHookID, InputError> {
         let state = self.hook.ok_or(InputError::NoTrigger)?;
 
@@ -150,6 +161,7 @@ pub struct InputPressBuilder<'a> {
     parent: InputHookBuilder<'a>,
     ty: PressOrRelease,
     keys: Vec<Key>,
+    sequence: Option<Sequence>,
     weight: i16,
     method: Option<
         Box<
@@ -166,6 +178,7 @@ impl<'a> InputPressBuilder<'a> {
             parent,
             ty,
             keys: Vec::new(),
+            sequence: None,
             weight: NO_HOOK_WEIGHT,
             method: None,
         }
@@ -175,6 +188,8 @@ impl<'a> InputPressBuilder<'a> {
     ///
     /// # Notes
     /// - This adds to any previous `combo` call.
+    /// - A `Sequence` can't be combined with other keys, the last call takes precedence.
+    /// - A `Sequence` is only supported by `on_press`. With `on_release` its final step is used.
     ///
     /// ```no_run
     /// // Example Inputs
@@ -182,9 +197,27 @@ impl<'a> InputPressBuilder<'a> {
     /// .keys((Qwerty::LCtrl, MouseButton::Left))
     /// .keys(vec![Qwerty::LCtrl, Qwerty::A])
     /// .keys([Qwerty::A, Qwerty::D])
+    /// .keys(Sequence::new([Qwerty::G, Qwerty::I]))
     pub fn keys<C: KeyCombo>(mut self, combo: C) -> Self {
-        let mut combo = combo.into_vec();
-        self.keys.append(&mut combo);
+        match self.ty {
+            PressOrRelease::Press => {
+                match combo.into_sequence() {
+                    Ok(sequence) => {
+                        self.keys.clear();
+                        self.sequence = Some(sequence);
+                    },
+                    Err(mut keys) => {
+                        self.sequence = None;
+                        self.keys.append(&mut keys);
+                    },
+                }
+            },
+            PressOrRelease::Release => {
+                let mut combo = combo.into_vec();
+                self.keys.append(&mut combo);
+            },
+        }
+
         self
     }
 
@@ -218,7 +251,13 @@ impl<'a> InputPressBuilder<'a> {
     /// - `NoMethod`: No method was added. See `call`.
     /// - `NoTarget`: No call to `bin()` or `window()` was made.
     pub fn finish(mut self) -> Result<InputHookID, InputError> {
-        if self.keys.is_empty() {
+        let sequence = self
+            .sequence
+            .take()
+            .filter(|sequence| !sequence.steps.is_empty())
+            .map(SequenceState::new);
+
+        if self.keys.is_empty() && sequence.is_none() {
             Err(InputError::NoKeys)
         } else if self.method.is_none() {
             Err(InputError::NoMethod)
@@ -228,9 +267,13 @@ impl<'a> InputPressBuilder<'a> {
             self.parent.hook = match self.ty {
                 PressOrRelease::Press => {
                     Some(HookState::Press {
-                        state: LocalKeyState::from_keys(self.keys),
+                        state: LocalKeyState::from_keys(match sequence.as_ref() {
+                            Some(sequence) => sequence.first_step(),
+                            None => self.keys,
+                        }),
+                        sequence,
                         weight: self.weight,
-                        method: self.method.unwrap(),
+                        method: self.method.take().unwrap(),
                     })
                 },
                 PressOrRelease::Release => {
@@ -238,7 +281,7 @@ impl<'a> InputPressBuilder<'a> {
                         state: LocalKeyState::from_keys(self.keys),
                         pressed: false,
                         weight: self.weight,
-                        method: self.method.unwrap(),
+                        method: self.method.take().unwrap(),
                     })
                 },
             };
@@ -468,7 +511,7 @@ impl<'a> InputEnterBuilder<'a> {
                         top: self.top,
                         inside: false,
                         pass: true,
-                        method: self.method.unwrap(),
+                        method: self.method.take().unwrap(),
                     })
                 },
                 EnterOrLeave::Leave => {
@@ -476,7 +519,7 @@ impl<'a> InputEnterBuilder<'a> {
                         weight: self.weight,
                         top: self.top,
                         inside: false,
-                        method: self.method.unwrap(),
+                        method: self.method.take().unwrap(),
                     })
                 },
             };
@@ -543,13 +586,13 @@ impl<'a> InputFocusBuilder<'a> {
                 FocusOrFocusLost::Focus => {
                     Some(HookState::Focus {
                         weight: self.weight,
-                        method: self.method.unwrap(),
+                        method: self.method.take().unwrap(),
                     })
                 },
                 FocusOrFocusLost::FocusLost => {
                     Some(HookState::FocusLost {
                         weight: self.weight,
-                        method: self.method.unwrap(),
+                        method: self.method.take().unwrap(),
                     })
                 },
             };
@@ -642,7 +685,7 @@ impl<'a> InputCursorBuilder<'a> {
                 top: self.top,
                 focus: self.focus,
                 inside: false,
-                method: self.method.unwrap(),
+                method: self.method.take().unwrap(),
             });
 
             self.parent.submit()
@@ -700,7 +743,7 @@ impl<'a> InputCharacterBuilder<'a> {
         } else {
             self.parent.hook = Some(HookState::Character {
                 weight: self.weight,
-                method: self.method.unwrap(),
+                method: self.method.take().unwrap(),
             });
 
             self.parent.submit()
@@ -766,6 +809,9 @@ impl<'a> InputScrollBuilder<'a> {
     ///
     /// Convert steps into pixels and provide a smoothed output.
     ///
+    /// Without smoothing the output is in steps, where a step is 100 logical pixels. The kind
+    /// of device the scroll originated from is available via `WindowState::scroll_source`.
+    ///
     /// **Default**: `false`
     pub fn enable_smooth(mut self, smooth: bool) -> Self {
         self.smooth = smooth;
@@ -815,7 +861,7 @@ impl<'a> InputScrollBuilder<'a> {
                 focus: self.focus,
                 smooth: self.smooth,
                 upper_blocks: self.upper_blocks,
-                method: self.method.unwrap(),
+                method: self.method.take().unwrap(),
             });
 
             self.parent.submit()
@@ -867,7 +913,71 @@ impl<'a> InputMotionBuilder<'a> {
         } else {
             self.parent.hook = Some(HookState::Motion {
                 weight: self.weight,
-                method: self.method.unwrap(),
+                method: self.method.take().unwrap(),
+            });
+
+            self.parent.submit()
+        }
+    }
+}
+
+/// Builder returned by `on_raw_motion`.
+pub struct InputRawMotionBuilder<'a> {
+    parent: InputHookBuilder<'a>,
+    weight: i16,
+    method: Option<
+        Box<dyn FnMut(InputHookTarget, &WindowState, f32, f32) -> InputHookCtrl + Send + 'static>,
+    >,
+}
+
+impl<'a> InputRawMotionBuilder<'a> {
+    fn start(parent: InputHookBuilder<'a>) -> Self {
+        Self {
+            parent,
+            weight: NO_HOOK_WEIGHT,
+            method: None,
+        }
+    }
+
+    /// Assigns a weight.
+    ///
+    /// # Notes
+    /// - Higher weights get called first and may not pass events.
+    pub fn weight(mut self, weight: i16) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Assign a function to call.
+    ///
+    /// The provided method is called with the unaccelerated delta `dx` & `dy`.
+    ///
+    /// # Notes
+    /// - Calling this multiple times will not add additional methods.
+    pub fn call<
+        F: FnMut(InputHookTarget, &WindowState, f32, f32) -> InputHookCtrl + Send + 'static,
+    >(
+        mut self,
+        method: F,
+    ) -> Self {
+        self.method = Some(Box::new(method));
+        self
+    }
+
+    /// Finish building, validate, and submit it to `Input`.
+    ///
+    /// # Possible Errors
+    /// - `NoMethod`: No method was added. See `call`.
+    /// - `NoTarget`: No call to `window()` was made.
+    pub fn finish(mut self) -> Result<InputHookID, InputError> {
+        if self.method.is_none() {
+            Err(InputError::NoMethod)
+        } else if !matches!(self.parent.target, InputHookTarget::Window(_)) {
+            Err(InputError::NoTarget)
+        } else {
+            self.parent.hook = Some(HookState::RawMotion {
+                weight: self.weight,
+                method: self.method.take().unwrap(),
             });
 
             self.parent.submit()
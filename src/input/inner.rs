@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use flume::{Receiver, Sender};
+use flume::{Receiver, RecvTimeoutError, Sender};
+use parking_lot::Mutex;
 
 use crate::input::state::WindowState;
-use crate::input::{proc, Hook, InputEvent, InputHookID};
+use crate::input::{
+    proc, Hook, InputEvent, InputHookID, Key, KeyRepeat, Modifiers, SCROLL_STEP_PIXELS,
+};
 use crate::interface::{BinID, Interface};
 use crate::interval::Interval;
 use crate::window::WindowID;
@@ -15,13 +18,27 @@ pub(in crate::input) enum LoopEvent {
     Normal(InputEvent),
     Add { id: InputHookID, hook: Hook },
     FocusBin { win: WindowID, bin: Option<BinID> },
+    CapturePointer { win: WindowID, bin: BinID },
+    ReleasePointer { win: WindowID, bin: Option<BinID> },
     SmoothScroll { win: WindowID, v: f32, h: f32 },
+    SetKeyRepeat(Option<KeyRepeat>),
     Remove(InputHookID),
 }
 
+struct RepeatState {
+    win: WindowID,
+    key: Key,
+    chars: Vec<char>,
+    next: Instant,
+}
+
 pub(in crate::input) fn begin_loop(
     interface: Arc<Interface>,
     interval: Arc<Interval>,
+    focused_bins: Arc<Mutex<HashMap<WindowID, BinID>>>,
+    pointer_captures: Arc<Mutex<HashMap<WindowID, BinID>>>,
+    modifiers: Arc<Mutex<HashMap<WindowID, Modifiers>>>,
+    mut key_repeat: Option<KeyRepeat>,
     event_send: Sender<LoopEvent>,
     event_recv: Receiver<LoopEvent>,
 ) {
@@ -38,6 +55,7 @@ pub(in crate::input) fn begin_loop(
         }
 
         let mut ss_state: HashMap<WindowID, SmoothScroll> = HashMap::new();
+        let mut repeat_state: Option<RepeatState> = None;
         const SS_CYCLES: u16 = 20;
 
         // TODO: Configure frequency of output?
@@ -45,7 +63,7 @@ pub(in crate::input) fn begin_loop(
             while let Ok((win, v, h)) = ss_recv.try_recv() {
                 let state = ss_state.entry(win).or_insert_with(|| {
                     SmoothScroll {
-                        step: 100.0,
+                        step: SCROLL_STEP_PIXELS,
                         rem: [0.0; 2],
                         amt: [0.0; 2],
                         cycles: [0; 2],
@@ -112,7 +130,79 @@ pub(in crate::input) fn begin_loop(
             Default::default()
         }));
 
-        while let Ok(event) = event_recv.recv() {
+        loop {
+            let event = match repeat_state.as_ref() {
+                Some(repeat) => {
+                    match event_recv.recv_deadline(repeat.next) {
+                        Ok(event) => event,
+                        Err(RecvTimeoutError::Timeout) => {
+                            let repeat = repeat_state.as_mut().unwrap();
+                            repeat.next += key_repeat.unwrap().interval;
+
+                            for c in repeat.chars.iter() {
+                                proc::character(&mut hooks, &mut win_state, repeat.win, *c);
+                            }
+
+                            continue;
+                        },
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                },
+                None => {
+                    match event_recv.recv() {
+                        Ok(event) => event,
+                        Err(_) => break,
+                    }
+                },
+            };
+
+            // NOTE: When key repeat isn't provided by the platform, the characters that follow a
+            //       press are repeated until the key is released or another key is pressed.
+            if let Some(key_repeat) = key_repeat.as_ref() {
+                if let LoopEvent::Normal(event) = &event {
+                    match event {
+                        InputEvent::Press {
+                            win,
+                            key: key @ Key::Keyboard(_),
+                        } => {
+                            repeat_state = Some(RepeatState {
+                                win: *win,
+                                key: *key,
+                                chars: Vec::new(),
+                                next: Instant::now() + key_repeat.delay,
+                            });
+                        },
+                        InputEvent::Character {
+                            win,
+                            c,
+                        } if repeat_state
+                            .as_ref()
+                            .is_some_and(|repeat| repeat.win == *win) =>
+                        {
+                            repeat_state.as_mut().unwrap().chars.push(*c);
+                        },
+                        InputEvent::Release {
+                            win,
+                            key,
+                        } if repeat_state
+                            .as_ref()
+                            .is_some_and(|repeat| repeat.win == *win && repeat.key == *key) =>
+                        {
+                            repeat_state = None;
+                        },
+                        InputEvent::FocusLost {
+                            win,
+                        } if repeat_state
+                            .as_ref()
+                            .is_some_and(|repeat| repeat.win == *win) =>
+                        {
+                            repeat_state = None;
+                        },
+                        _ => (),
+                    }
+                }
+            }
+
             match event {
                 LoopEvent::Add {
                     id,
@@ -123,6 +213,10 @@ pub(in crate::input) fn begin_loop(
                 LoopEvent::Remove(id) => {
                     hooks.remove(&id);
                 },
+                LoopEvent::SetKeyRepeat(new_key_repeat) => {
+                    key_repeat = new_key_repeat;
+                    repeat_state = None;
+                },
                 LoopEvent::FocusBin {
                     win,
                     bin,
@@ -134,6 +228,7 @@ pub(in crate::input) fn begin_loop(
                     if let Some((old_bin_id_op, new_bin_id_op)) = window_state.update_focus_bin(bin)
                     {
                         proc::bin_focus(
+                            &interface,
                             &interval,
                             &mut hooks,
                             window_state,
@@ -142,6 +237,34 @@ pub(in crate::input) fn begin_loop(
                         );
                     }
                 },
+                LoopEvent::CapturePointer {
+                    win,
+                    bin,
+                } => {
+                    let window_state = win_state
+                        .entry(win)
+                        .or_insert_with(|| WindowState::new(win));
+
+                    if window_state.update_pointer_capture(Some(bin)) {
+                        let [x, y] = window_state.cursor_pos();
+                        proc::cursor(&interface, &mut hooks, &mut win_state, win, x, y, true);
+                    }
+                },
+                LoopEvent::ReleasePointer {
+                    win,
+                    bin,
+                } => {
+                    let window_state = win_state
+                        .entry(win)
+                        .or_insert_with(|| WindowState::new(win));
+
+                    if (bin.is_none() || window_state.pointer_capture_bin_id() == bin)
+                        && window_state.update_pointer_capture(None)
+                    {
+                        let [x, y] = window_state.cursor_pos();
+                        proc::cursor(&interface, &mut hooks, &mut win_state, win, x, y, true);
+                    }
+                },
                 LoopEvent::SmoothScroll {
                     win,
                     v,
@@ -197,7 +320,13 @@ pub(in crate::input) fn begin_loop(
                             win,
                             v,
                             h,
+                            source,
                         } => {
+                            win_state
+                                .entry(win)
+                                .or_insert_with(|| WindowState::new(win))
+                                .update_scroll_source(source);
+
                             ss_send.send((win, v, h)).unwrap();
                             proc::scroll(&interface, &mut hooks, &mut win_state, win, false, v, h);
                         },
@@ -217,6 +346,13 @@ pub(in crate::input) fn begin_loop(
                         } => {
                             proc::motion(&mut hooks, x, y);
                         },
+                        InputEvent::RawMotion {
+                            win,
+                            dx,
+                            dy,
+                        } => {
+                            proc::raw_motion(&mut hooks, &mut win_state, win, dx, dy);
+                        },
                         InputEvent::CursorCapture {
                             win,
                             captured,
@@ -231,6 +367,7 @@ pub(in crate::input) fn begin_loop(
                                         window_state.update_focus_bin(None)
                                     {
                                         proc::bin_focus(
+                                            &interface,
                                             &interval,
                                             &mut hooks,
                                             window_state,
@@ -256,6 +393,33 @@ pub(in crate::input) fn begin_loop(
                     }
                 },
             }
+
+            // NOTE: Mirrored so that they can be queried outside of the loop.
+            let mut focused_bins = focused_bins.lock();
+            let mut pointer_captures = pointer_captures.lock();
+            let mut modifiers = modifiers.lock();
+
+            for (win, window_state) in win_state.iter() {
+                match window_state.focused_bin_id() {
+                    Some(bin_id) => {
+                        focused_bins.insert(*win, bin_id);
+                    },
+                    None => {
+                        focused_bins.remove(win);
+                    },
+                }
+
+                match window_state.pointer_capture_bin_id() {
+                    Some(bin_id) => {
+                        pointer_captures.insert(*win, bin_id);
+                    },
+                    None => {
+                        pointer_captures.remove(win);
+                    },
+                }
+
+                modifiers.insert(*win, window_state.modifiers());
+            }
         }
     });
 }
@@ -1,7 +1,9 @@
 use std::ops::Deref;
+use std::time::Duration;
 
 /// A keyboard/mouse agnostic type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Key {
     Keyboard(Qwerty),
     Mouse(MouseButton),
@@ -19,6 +21,25 @@ impl From<MouseButton> for Key {
     }
 }
 
+/// The state of the modifier keys.
+///
+/// Each modifier is pressed if either its left or right key is pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The Windows key or the Command key on macOS.
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// Returns `true` if no modifier is pressed.
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt || self.super_key)
+    }
+}
+
 /// A wrapper around `char` that provides some convenience methods.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Char(pub char);
@@ -77,9 +98,94 @@ impl Deref for Char {
 ///
 /// A `Key` can be either `Qwerty` or `MouseButton`.
 ///
-/// Supports being a lone key, a `Vec`, an `array` or a `tuple`.
+/// Supports being a lone key, a `Vec`, an `array`, a `tuple` or a `Sequence`.
 pub trait KeyCombo {
     fn into_vec(self) -> Vec<Key>;
+
+    /// Convert into a `Sequence`. If this isn't a sequence, its keys are returned instead.
+    #[inline]
+    fn into_sequence(self) -> Result<Sequence, Vec<Key>>
+    where
+        Self: Sized,
+    {
+        Err(self.into_vec())
+    }
+}
+
+/// An ordered sequence of key combinations, such as `G` then `I` or `Ctrl+X` then `Ctrl+S`.
+///
+/// Each step must be completed within the timeout of the previous step. Pressing a key that
+/// isn't part of the current step, or exceeding the timeout, restarts the sequence. Keys still
+/// held from the previous step count towards the next step, so a held modifier doesn't need to
+/// be pressed again.
+///
+/// Sequences that share a prefix are matched independently of one another, so both will
+/// progress with the shared steps.
+///
+/// ```no_run
+/// window.on_press(Sequence::new([Qwerty::G, Qwerty::I]), |_, _, _| {
+///     println!("G then I");
+///     Default::default()
+/// });
+///
+/// window.on_press(
+///     Sequence::new([[Qwerty::LCtrl, Qwerty::X], [Qwerty::LCtrl, Qwerty::S]])
+///         .timeout(Duration::from_millis(500)),
+///     |_, _, _| Default::default(),
+/// );
+/// ```
+///
+/// ***Note:** Sequences are only supported by press hooks. When used elsewhere, only the final
+/// step is used.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    pub(in crate::input) steps: Vec<Vec<Key>>,
+    pub(in crate::input) timeout: Duration,
+}
+
+impl Sequence {
+    /// Create a `Sequence` from the provided steps.
+    pub fn new<C: KeyCombo, S: IntoIterator<Item = C>>(steps: S) -> Self {
+        Self {
+            steps: steps
+                .into_iter()
+                .map(|combo| combo.into_vec())
+                .filter(|keys| !keys.is_empty())
+                .collect(),
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Add a step to the end of the sequence.
+    pub fn then<C: KeyCombo>(mut self, combo: C) -> Self {
+        let keys = combo.into_vec();
+
+        if !keys.is_empty() {
+            self.steps.push(keys);
+        }
+
+        self
+    }
+
+    /// Set the maximum time between steps.
+    ///
+    /// **Default:** `1 second`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl KeyCombo for Sequence {
+    #[inline]
+    fn into_vec(self) -> Vec<Key> {
+        self.steps.into_iter().last().unwrap_or_default()
+    }
+
+    #[inline]
+    fn into_sequence(self) -> Result<Sequence, Vec<Key>> {
+        Ok(self)
+    }
 }
 
 impl<T: Into<Key>> KeyCombo for T {
@@ -126,6 +232,7 @@ macro_rules! impl_tuple_combo {
 impl_tuple_combo!(A, B, C, D, E, F, G, H);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Enum of mouse buttons.
 pub enum MouseButton {
     Left,
@@ -136,6 +243,7 @@ pub enum MouseButton {
 
 /// For use when key location matters. May not always correlate to the actual key.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Qwerty {
     Esc,
     F1,
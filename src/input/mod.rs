@@ -35,6 +35,9 @@
 //!
 //! ##### Motion
 //! Similar to Character, but there are no targets.
+//!
+//! ##### Raw Motion
+//! Similar to Character, but only window targets are supported.
 
 mod builder;
 mod inner;
@@ -42,16 +45,21 @@ mod key;
 mod proc;
 mod state;
 
+use std::collections::HashMap;
 use std::sync::atomic::{self, AtomicU64};
 use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub use builder::{
     InputCharacterBuilder, InputCursorBuilder, InputEnterBuilder, InputFocusBuilder,
-    InputHoldBuilder, InputHookBuilder, InputMotionBuilder, InputPressBuilder, InputScrollBuilder,
+    InputHoldBuilder, InputHookBuilder, InputMotionBuilder, InputPressBuilder,
+    InputRawMotionBuilder, InputScrollBuilder,
 };
 use flume::Sender;
 use inner::LoopEvent;
-pub use key::{Char, Key, KeyCombo, MouseButton, Qwerty};
+pub use key::{Char, Key, KeyCombo, Modifiers, MouseButton, Qwerty, Sequence};
+use parking_lot::Mutex;
 use state::HookState;
 pub use state::{LocalCursorState, LocalKeyState, WindowState};
 
@@ -59,8 +67,10 @@ use crate::interface::{Bin, BinID, Interface};
 use crate::interval::Interval;
 use crate::window::{Window, WindowID};
 
-const NO_HOOK_WEIGHT: i16 = i16::min_value();
+const NO_HOOK_WEIGHT: i16 = i16::MIN;
 const BIN_FOCUS_KEY: Key = Key::Mouse(MouseButton::Left);
+/// The amount of pixels a single scroll step represents.
+pub(crate) const SCROLL_STEP_PIXELS: f32 = 100.0;
 
 /// An ID of a `Input` hook.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -151,19 +161,182 @@ pub enum InputHookCtrl {
     RemoveNoPass,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) enum InputEvent {
-    Press { win: WindowID, key: Key },
-    Release { win: WindowID, key: Key },
-    Character { win: WindowID, c: char },
-    Cursor { win: WindowID, x: f32, y: f32 },
-    Scroll { win: WindowID, v: f32, h: f32 },
-    Enter { win: WindowID },
-    Leave { win: WindowID },
-    Focus { win: WindowID },
-    FocusLost { win: WindowID },
-    Motion { x: f32, y: f32 },
-    CursorCapture { win: WindowID, captured: bool },
+/// An event received from the windowing backend.
+///
+/// See `Input::inject_synthetic_event` & `Input::start_recording`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum InputEvent {
+    Press {
+        win: WindowID,
+        key: Key,
+    },
+    Release {
+        win: WindowID,
+        key: Key,
+    },
+    Character {
+        win: WindowID,
+        c: char,
+    },
+    Cursor {
+        win: WindowID,
+        x: f32,
+        y: f32,
+    },
+    /// `v` & `h` are in steps, where a step is 100 logical pixels.
+    Scroll {
+        win: WindowID,
+        v: f32,
+        h: f32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        source: ScrollSource,
+    },
+    Enter {
+        win: WindowID,
+    },
+    Leave {
+        win: WindowID,
+    },
+    Focus {
+        win: WindowID,
+    },
+    FocusLost {
+        win: WindowID,
+    },
+    Motion {
+        x: f32,
+        y: f32,
+    },
+    RawMotion {
+        win: WindowID,
+        dx: f32,
+        dy: f32,
+    },
+    CursorCapture {
+        win: WindowID,
+        captured: bool,
+    },
+}
+
+impl InputEvent {
+    /// The window this event is for. `Motion` isn't for any particular window.
+    pub fn window(&self) -> Option<WindowID> {
+        match self {
+            Self::Press {
+                win, ..
+            }
+            | Self::Release {
+                win, ..
+            }
+            | Self::Character {
+                win, ..
+            }
+            | Self::Cursor {
+                win, ..
+            }
+            | Self::Scroll {
+                win, ..
+            }
+            | Self::Enter {
+                win,
+            }
+            | Self::Leave {
+                win,
+            }
+            | Self::Focus {
+                win,
+            }
+            | Self::FocusLost {
+                win,
+            }
+            | Self::RawMotion {
+                win, ..
+            }
+            | Self::CursorCapture {
+                win, ..
+            } => Some(*win),
+            Self::Motion {
+                ..
+            } => None,
+        }
+    }
+
+    /// Change the window this event is for.
+    ///
+    /// Useful when replaying events recorded from a different window.
+    pub fn set_window(&mut self, window: WindowID) {
+        match self {
+            Self::Press {
+                win, ..
+            }
+            | Self::Release {
+                win, ..
+            }
+            | Self::Character {
+                win, ..
+            }
+            | Self::Cursor {
+                win, ..
+            }
+            | Self::Scroll {
+                win, ..
+            }
+            | Self::Enter {
+                win,
+            }
+            | Self::Leave {
+                win,
+            }
+            | Self::Focus {
+                win,
+            }
+            | Self::FocusLost {
+                win,
+            }
+            | Self::RawMotion {
+                win, ..
+            }
+            | Self::CursorCapture {
+                win, ..
+            } => *win = window,
+            Self::Motion {
+                ..
+            } => (),
+        }
+    }
+}
+
+/// The delay and interval used to repeat characters while a key is held.
+///
+/// See `Input::set_key_repeat` & `BasaltOptions::input_key_repeat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRepeat {
+    /// The time a key has to be held before characters are repeated.
+    pub delay: Duration,
+    /// The time between each repeated character.
+    pub interval: Duration,
+}
+
+/// The kind of device a scroll originated from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollSource {
+    /// The device reports lines, such as a mouse wheel.
+    #[default]
+    Line,
+    /// The device reports pixels, such as a trackpad or touchpad.
+    Pixel,
+}
+
+/// An `InputEvent` recorded with `Input::start_recording`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedInputEvent {
+    /// The time since the recording was started.
+    pub elapsed: Duration,
+    pub event: InputEvent,
 }
 
 /// An error that is returned by various `Input` related methods.
@@ -235,17 +408,44 @@ pub struct Input {
     event_send: Sender<LoopEvent>,
     current_id: AtomicU64,
     interval: Arc<Interval>,
+    focused_bins: Arc<Mutex<HashMap<WindowID, BinID>>>,
+    pointer_captures: Arc<Mutex<HashMap<WindowID, BinID>>>,
+    modifiers: Arc<Mutex<HashMap<WindowID, Modifiers>>>,
+    recording: Mutex<Option<(Instant, Vec<RecordedInputEvent>)>>,
+    key_repeat: Mutex<Option<KeyRepeat>>,
 }
 
 impl Input {
-    pub(crate) fn new(interface: Arc<Interface>, interval: Arc<Interval>) -> Self {
+    pub(crate) fn new(
+        interface: Arc<Interface>,
+        interval: Arc<Interval>,
+        key_repeat: Option<KeyRepeat>,
+    ) -> Self {
         let (event_send, event_recv) = flume::unbounded();
-        inner::begin_loop(interface, interval.clone(), event_send.clone(), event_recv);
+        let focused_bins = Arc::new(Mutex::new(HashMap::new()));
+        let pointer_captures = Arc::new(Mutex::new(HashMap::new()));
+        let modifiers = Arc::new(Mutex::new(HashMap::new()));
+
+        inner::begin_loop(
+            interface,
+            interval.clone(),
+            focused_bins.clone(),
+            pointer_captures.clone(),
+            modifiers.clone(),
+            key_repeat,
+            event_send.clone(),
+            event_recv,
+        );
 
         Self {
             event_send,
             interval,
             current_id: AtomicU64::new(0),
+            focused_bins,
+            pointer_captures,
+            modifiers,
+            recording: Mutex::new(None),
+            key_repeat: Mutex::new(key_repeat),
         }
     }
 
@@ -274,7 +474,7 @@ impl Input {
     ///     .finish()
     ///     .unwrap();
     /// ```
-    pub fn hook(&self) -> InputHookBuilder {
+    pub fn hook(&self) -> InputHookBuilder<'_> {
         InputHookBuilder::start(self)
     }
 
@@ -305,10 +505,130 @@ impl Input {
             .unwrap();
     }
 
+    /// Retrieve the `BinID` of the `Bin` that is focused within a window.
+    ///
+    /// ***Note:** Changes in focus are processed asynchronously, so this may not reflect a
+    /// change from a call to `set_bin_focused` immediately.*
+    pub fn focused_bin_id(&self, win: WindowID) -> Option<BinID> {
+        self.focused_bins.lock().get(&win).copied()
+    }
+
+    /// Capture the pointer to a `Bin`, so that cursor, scroll & mouse button events within the
+    /// window are dispatched to it regardless of what is under the cursor.
+    ///
+    /// ***Note:** If the bin doesn't have an associated window, this does nothing.*
+    pub fn capture_pointer(&self, bin: &Arc<Bin>) {
+        let win = match bin.window() {
+            Some(some) => some.id(),
+            None => return,
+        };
+
+        self.event_send
+            .send(LoopEvent::CapturePointer {
+                win,
+                bin: bin.id(),
+            })
+            .unwrap();
+    }
+
+    /// Release the pointer capture within a window.
+    pub fn release_pointer(&self, win: WindowID) {
+        self.event_send
+            .send(LoopEvent::ReleasePointer {
+                win,
+                bin: None,
+            })
+            .unwrap();
+    }
+
+    pub(crate) fn release_pointer_from(&self, win: WindowID, bin_id: BinID) {
+        self.event_send
+            .send(LoopEvent::ReleasePointer {
+                win,
+                bin: Some(bin_id),
+            })
+            .unwrap();
+    }
+
+    /// Retrieve the `BinID` of the `Bin` that has captured the pointer within a window.
+    ///
+    /// ***Note:** Changes in capture are processed asynchronously, so this may not reflect a
+    /// change from a call to `capture_pointer` immediately.*
+    pub fn pointer_capture_bin_id(&self, win: WindowID) -> Option<BinID> {
+        self.pointer_captures.lock().get(&win).copied()
+    }
+
+    /// Retrieve the state of the modifier keys within a window.
+    ///
+    /// ***Note:** Events are processed asynchronously, so this may not reflect a press or
+    /// release immediately. Within a hook use `WindowState::modifiers` instead.*
+    pub fn modifiers(&self, win: WindowID) -> Modifiers {
+        self.modifiers.lock().get(&win).copied().unwrap_or_default()
+    }
+
+    /// Retrieve the key repeat used instead of the platform's.
+    ///
+    /// *See `set_key_repeat` for more information.*
+    pub fn key_repeat(&self) -> Option<KeyRepeat> {
+        *self.key_repeat.lock()
+    }
+
+    /// Set the key repeat used instead of the platform's.
+    ///
+    /// While a key is held, the characters it produced are repeated to character hooks, such as
+    /// the ones added by `Bin::add_enter_text_events`. With `None` the platform's repeat is used,
+    /// which on wayland is the rate and delay provided by the compositor.
+    ///
+    /// **Default:** The value of `BasaltOptions::input_key_repeat`.
+    ///
+    /// ***Note:** Only characters are repeated. Press hooks are not called again while a key is
+    /// held.*
+    pub fn set_key_repeat(&self, key_repeat: Option<KeyRepeat>) {
+        *self.key_repeat.lock() = key_repeat;
+        self.event_send
+            .send(LoopEvent::SetKeyRepeat(key_repeat))
+            .unwrap();
+    }
+
     pub(crate) fn send_event(&self, event: InputEvent) {
+        record_event(&self.recording, &event);
         self.event_send.send(LoopEvent::Normal(event)).unwrap();
     }
 
+    /// Process an event as if it was received from the windowing backend.
+    ///
+    /// Intended for automated testing of interfaces.
+    ///
+    /// ***Note:** Synthetic events are not recorded.*
+    pub fn inject_synthetic_event(&self, event: InputEvent) {
+        self.event_send.send(LoopEvent::Normal(event)).unwrap();
+    }
+
+    /// Start recording events received from the windowing backend.
+    ///
+    /// ***Note:** If a recording is already in progress, it is discarded.*
+    pub fn start_recording(&self) {
+        *self.recording.lock() = Some((Instant::now(), Vec::new()));
+    }
+
+    /// Stop recording and return the recorded events.
+    ///
+    /// ***Note:** Returns an empty `Vec` if there isn't a recording in progress.*
+    pub fn stop_recording(&self) -> Vec<RecordedInputEvent> {
+        match self.recording.lock().take() {
+            Some((_, events)) => events,
+            None => Vec::new(),
+        }
+    }
+
+    /// Replay recorded events using `inject_synthetic_event`.
+    ///
+    /// When `realtime` is `true`, events are injected from another thread with the timing they
+    /// were recorded with. Otherwise all events are injected immediately.
+    pub fn replay_synthetic_events(&self, events: Vec<RecordedInputEvent>, realtime: bool) {
+        replay_events(self.event_send.clone(), events, realtime);
+    }
+
     fn add_hook(&self, hook: Hook) -> InputHookID {
         let id = InputHookID(self.current_id.fetch_add(1, atomic::Ordering::SeqCst));
         self.event_send
@@ -333,3 +653,131 @@ impl Input {
         InputHookID(self.current_id.fetch_add(1, atomic::Ordering::SeqCst))
     }
 }
+
+fn record_event(recording: &Mutex<Option<(Instant, Vec<RecordedInputEvent>)>>, event: &InputEvent) {
+    if let Some((start, events)) = recording.lock().as_mut() {
+        events.push(RecordedInputEvent {
+            elapsed: start.elapsed(),
+            event: event.clone(),
+        });
+    }
+}
+
+fn replay_events(event_send: Sender<LoopEvent>, events: Vec<RecordedInputEvent>, realtime: bool) {
+    if !realtime {
+        for recorded in events {
+            event_send.send(LoopEvent::Normal(recorded.event)).unwrap();
+        }
+
+        return;
+    }
+
+    thread::spawn(move || {
+        let start = Instant::now();
+
+        for recorded in events {
+            let elapsed = start.elapsed();
+
+            if recorded.elapsed > elapsed {
+                thread::sleep(recorded.elapsed - elapsed);
+            }
+
+            if event_send.send(LoopEvent::Normal(recorded.event)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use parking_lot::Mutex;
+
+    use super::{
+        record_event, replay_events, InputEvent, LoopEvent, Qwerty, RecordedInputEvent,
+        ScrollSource,
+    };
+    use crate::window::WindowID;
+
+    fn record(events: &[InputEvent]) -> Vec<RecordedInputEvent> {
+        let recording = Mutex::new(Some((Instant::now(), Vec::new())));
+
+        for event in events {
+            record_event(&recording, event);
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        recording.into_inner().unwrap().1
+    }
+
+    fn replayed(events: Vec<RecordedInputEvent>, realtime: bool) -> Vec<InputEvent> {
+        let (event_send, event_recv) = flume::unbounded();
+        replay_events(event_send, events, realtime);
+
+        // NOTE: The sender is dropped once all events have been replayed.
+        event_recv
+            .iter()
+            .map(|loop_event| {
+                match loop_event {
+                    LoopEvent::Normal(event) => event,
+                    _ => panic!("unexpected loop event"),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn record_replay_round_trip() {
+        let win = WindowID::from_raw(0);
+
+        let events = vec![
+            InputEvent::Cursor {
+                win,
+                x: 10.0,
+                y: 20.0,
+            },
+            InputEvent::Press {
+                win,
+                key: Qwerty::A.into(),
+            },
+            InputEvent::Character {
+                win,
+                c: 'a',
+            },
+            InputEvent::Release {
+                win,
+                key: Qwerty::A.into(),
+            },
+            InputEvent::Scroll {
+                win,
+                v: 1.0,
+                h: 0.0,
+                source: ScrollSource::Line,
+            },
+        ];
+
+        let recorded = record(&events);
+        assert_eq!(recorded.len(), events.len());
+        assert!(recorded.windows(2).all(|w| w[0].elapsed < w[1].elapsed));
+
+        assert_eq!(replayed(recorded.clone(), false), events);
+        assert_eq!(replayed(recorded, true), events);
+    }
+
+    #[test]
+    fn record_not_started() {
+        let recording = Mutex::new(None);
+
+        record_event(
+            &recording,
+            &InputEvent::Focus {
+                win: WindowID::from_raw(0),
+            },
+        );
+
+        assert!(recording.lock().is_none());
+    }
+}
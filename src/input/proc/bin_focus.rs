@@ -4,16 +4,25 @@ use std::sync::Arc;
 
 use crate::input::state::{HookState, WindowState};
 use crate::input::{Hook, InputHookCtrl, InputHookID, NO_HOOK_WEIGHT};
-use crate::interface::BinID;
+use crate::interface::{BinID, Interface};
 use crate::interval::Interval;
 
 pub(in crate::input) fn bin_focus(
+    interface: &Arc<Interface>,
     interval: &Arc<Interval>,
     hooks: &mut HashMap<InputHookID, Hook>,
     window_state: &mut WindowState,
     old_bin_id_op: Option<BinID>,
     new_bin_id_op: Option<BinID>,
 ) {
+    if let Some(old_bin) = old_bin_id_op.and_then(|bin_id| interface.get_bin(bin_id)) {
+        old_bin.set_focused(false);
+    }
+
+    if let Some(new_bin) = new_bin_id_op.and_then(|bin_id| interface.get_bin(bin_id)) {
+        new_bin.set_focused(true);
+    }
+
     let mut remove_hooks = Vec::new();
 
     if let Some(old_bin_id) = old_bin_id_op {
@@ -21,7 +21,19 @@ pub(in crate::input) fn cursor(
         .or_insert_with(|| WindowState::new(win));
 
     if window_state.update_cursor_pos(x, y) || force {
-        let inside_bin_ids = interface.get_bin_ids_atop(win, x, y);
+        let inside_bin_ids = window_state.pointer_bin_ids(interface);
+
+        window_state.update_hovered_bin(
+            match window_state.is_cursor_inside() && !window_state.is_cursor_captured() {
+                true => {
+                    inside_bin_ids
+                        .first()
+                        .and_then(|bin_id| interface.get_bin(*bin_id))
+                },
+                false => None,
+            },
+        );
+
         let focused_bin_id = window_state.focused_bin_id();
         let mut call_leave_on: Vec<(i16, InputHookID, &mut Hook)> = Vec::new();
         let mut enter: Vec<(i16, InputHookID, &mut Hook)> = Vec::new();
@@ -3,6 +3,7 @@ pub mod character;
 pub mod cursor;
 pub mod motion;
 pub mod press;
+pub mod raw_motion;
 pub mod release;
 pub mod scroll;
 pub mod window;
@@ -12,6 +13,7 @@ pub(in crate::input) use character::character;
 pub(in crate::input) use cursor::cursor;
 pub(in crate::input) use motion::motion;
 pub(in crate::input) use press::press;
+pub(in crate::input) use raw_motion::raw_motion;
 pub(in crate::input) use release::release;
 pub(in crate::input) use scroll::scroll;
 pub(in crate::input) use window::{window_cursor_inside, window_focus};
@@ -22,6 +22,19 @@ pub(in crate::input) fn press(
 
     // Returns true if the state changed
     if window_state.update_key(key, true) {
+        if matches!(key, Key::Mouse(_))
+            && !window_state.has_active_bin()
+            && window_state.is_cursor_inside()
+            && !window_state.is_cursor_captured()
+        {
+            let bin_op = window_state
+                .pointer_bin_ids(interface)
+                .first()
+                .and_then(|bin_id| interface.get_bin(*bin_id));
+
+            window_state.update_active_bin(bin_op);
+        }
+
         let mut proc_in_order: Vec<_> = hooks
             .iter_mut()
             .filter_map(|(hook_id, hook)| {
@@ -29,10 +42,16 @@ pub(in crate::input) fn press(
                     match &mut hook.state {
                         HookState::Press {
                             state,
+                            sequence,
                             weight,
                             ..
                         } => {
-                            if state.update(key, true) {
+                            let complete = match sequence {
+                                Some(sequence) => sequence.press(state, window_state, key),
+                                None => state.update(key, true),
+                            };
+
+                            if complete {
                                 Some((*weight, (hook_id, hook)))
                             } else {
                                 None
@@ -133,15 +152,22 @@ pub(in crate::input) fn press(
 
         if pass_bin_event && !window_state.is_cursor_captured() {
             // Check Bin Focus
-            if key == BIN_FOCUS_KEY {
+            if key == BIN_FOCUS_KEY && window_state.pointer_capture_bin_id().is_none() {
                 if let Some((old_bin_id_op, new_bin_id_op)) =
                     window_state.check_focus_bin(interface)
                 {
-                    proc::bin_focus(interval, hooks, window_state, old_bin_id_op, new_bin_id_op);
+                    proc::bin_focus(
+                        interface,
+                        interval,
+                        hooks,
+                        window_state,
+                        old_bin_id_op,
+                        new_bin_id_op,
+                    );
                 }
             }
 
-            if let Some(focus_bin_id) = window_state.focused_bin_id() {
+            if let Some(focus_bin_id) = window_state.key_bin_id(key) {
                 let mut call_in_order: Vec<_> = hooks
                     .iter_mut()
                     .filter_map(|(hook_id, hook)| {
@@ -149,10 +175,16 @@ pub(in crate::input) fn press(
                             match &mut hook.state {
                                 HookState::Press {
                                     state,
+                                    sequence,
                                     weight,
                                     ..
                                 } => {
-                                    if state.update(key, true) {
+                                    let complete = match sequence {
+                                        Some(sequence) => sequence.press(state, window_state, key),
+                                        None => state.update(key, true),
+                                    };
+
+                                    if complete {
                                         Some((*weight, (hook_id, hook)))
                                     } else {
                                         None
@@ -19,7 +19,11 @@ pub(in crate::input) fn release(
         .or_insert_with(|| WindowState::new(win));
 
     if window_state.update_key(key, false) {
-        let focused_bin_id = window_state.focused_bin_id();
+        if matches!(key, Key::Mouse(_)) && !window_state.is_mouse_pressed() {
+            window_state.update_active_bin(None);
+        }
+
+        let focused_bin_id = window_state.key_bin_id(key);
         let mut remove_hooks: Vec<InputHookID> = Vec::new();
 
         let mut call_release_on: Vec<_> = hooks
@@ -19,8 +19,7 @@ pub(in crate::input) fn scroll(
     let window_state = win_state
         .entry(win)
         .or_insert_with(|| WindowState::new(win));
-    let [x, y] = window_state.cursor_pos();
-    let inside_bin_ids = interface.get_bin_ids_atop(win, x, y);
+    let inside_bin_ids = window_state.pointer_bin_ids(interface);
     let focused_bin_id = window_state.focused_bin_id();
 
     let mut call_in_order: Vec<_> = hooks
@@ -101,6 +101,10 @@ pub(in crate::input) fn window_cursor_inside(
         .or_insert_with(|| WindowState::new(win));
 
     if window_state.update_cursor_inside(inside) {
+        if !inside {
+            window_state.update_hovered_bin(None);
+        }
+
         if inside {
             call_hook_varient!(hooks, window_state, Enter);
         } else {
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
-use crate::input::{Char, InputHookCtrl, InputHookTarget, Key};
-use crate::interface::{BinID, Interface};
+use crate::input::{
+    Char, InputHookCtrl, InputHookTarget, Key, Modifiers, Qwerty, ScrollSource, Sequence,
+};
+use crate::interface::{Bin, BinID, Interface};
 use crate::interval::IntvlHookID;
 use crate::window::WindowID;
 
@@ -16,6 +19,10 @@ pub struct WindowState {
     focused: bool,
     cursor_inside: bool,
     cursor_captured: bool,
+    pointer_capture: Option<BinID>,
+    scroll_source: ScrollSource,
+    hovered_bins: Vec<Weak<Bin>>,
+    active_bins: Vec<Weak<Bin>>,
 }
 
 impl WindowState {
@@ -28,6 +35,10 @@ impl WindowState {
             focused: true,
             cursor_inside: true,
             cursor_captured: false,
+            pointer_capture: None,
+            scroll_source: ScrollSource::default(),
+            hovered_bins: Vec::new(),
+            active_bins: Vec::new(),
         }
     }
 
@@ -114,6 +125,62 @@ impl WindowState {
         }
     }
 
+    // If changed returns true
+    pub(in crate::input) fn update_pointer_capture(&mut self, bin_id_op: Option<BinID>) -> bool {
+        if self.pointer_capture != bin_id_op {
+            self.pointer_capture = bin_id_op;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(in crate::input) fn update_scroll_source(&mut self, source: ScrollSource) {
+        self.scroll_source = source;
+    }
+
+    // Hovers the provided bin & its ancestors, unhovering those previously hovered.
+    pub(in crate::input) fn update_hovered_bin(&mut self, bin_op: Option<Arc<Bin>>) {
+        update_bin_lineage(&mut self.hovered_bins, bin_op, Bin::set_hovered);
+    }
+
+    // Activates the provided bin & its ancestors, deactivating those previously active.
+    pub(in crate::input) fn update_active_bin(&mut self, bin_op: Option<Arc<Bin>>) {
+        update_bin_lineage(&mut self.active_bins, bin_op, Bin::set_active);
+    }
+
+    pub(in crate::input) fn has_active_bin(&self) -> bool {
+        !self.active_bins.is_empty()
+    }
+
+    pub(in crate::input) fn is_mouse_pressed(&self) -> bool {
+        self.key_state
+            .iter()
+            .any(|(key, pressed)| *pressed && matches!(key, Key::Mouse(_)))
+    }
+
+    // Bins under the cursor ordered top-most first, or only the capturing bin.
+    pub(in crate::input) fn pointer_bin_ids(&mut self, interface: &Arc<Interface>) -> Vec<BinID> {
+        if let Some(bin_id) = self.pointer_capture {
+            if interface.get_bin(bin_id).is_some() {
+                return vec![bin_id];
+            }
+
+            // NOTE: The capturing bin has been dropped.
+            self.pointer_capture = None;
+        }
+
+        interface.get_bin_ids_atop(self.window_id, self.cursor_pos[0], self.cursor_pos[1])
+    }
+
+    // Mouse buttons go to the capturing bin, everything else to the focused bin.
+    pub(in crate::input) fn key_bin_id(&self, key: Key) -> Option<BinID> {
+        match (key, self.pointer_capture) {
+            (Key::Mouse(_), Some(bin_id)) => Some(bin_id),
+            _ => self.focus_bin,
+        }
+    }
+
     /// Returns the `WindowID` this state corresponds to.
     pub fn window_id(&self) -> WindowID {
         self.window_id
@@ -138,6 +205,16 @@ impl WindowState {
         self.focus_bin
     }
 
+    /// Returns the `BinID` of the `Bin` that has captured the pointer.
+    pub fn pointer_capture_bin_id(&self) -> Option<BinID> {
+        self.pointer_capture
+    }
+
+    /// Returns the kind of device the most recent scroll originated from.
+    pub fn scroll_source(&self) -> ScrollSource {
+        self.scroll_source
+    }
+
     /// Returns the current cursor position.
     pub fn cursor_pos(&self) -> [f32; 2] {
         self.cursor_pos
@@ -150,6 +227,43 @@ impl WindowState {
         let key = key.into();
         self.key_state.get(&key).copied().unwrap_or(false)
     }
+
+    /// Returns the current state of the modifier keys.
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            shift: self.is_key_pressed(Qwerty::LShift) || self.is_key_pressed(Qwerty::RShift),
+            ctrl: self.is_key_pressed(Qwerty::LCtrl) || self.is_key_pressed(Qwerty::RCtrl),
+            alt: self.is_key_pressed(Qwerty::LAlt) || self.is_key_pressed(Qwerty::RAlt),
+            super_key: self.is_key_pressed(Qwerty::LSuper) || self.is_key_pressed(Qwerty::RSuper),
+        }
+    }
+}
+
+fn update_bin_lineage(
+    lineage: &mut Vec<Weak<Bin>>,
+    bin_op: Option<Arc<Bin>>,
+    set_state: fn(&Arc<Bin>, bool),
+) {
+    let new_lineage = match bin_op {
+        Some(bin) => {
+            let mut new_lineage = bin.ancestors();
+            new_lineage.insert(0, bin);
+            new_lineage
+        },
+        None => Vec::new(),
+    };
+
+    for bin in lineage.drain(..).filter_map(|bin_wk| bin_wk.upgrade()) {
+        if !new_lineage.contains(&bin) {
+            set_state(&bin, false);
+        }
+    }
+
+    for bin in new_lineage.iter() {
+        set_state(bin, true);
+    }
+
+    *lineage = new_lineage.iter().map(Arc::downgrade).collect();
 }
 
 /// State of `Key`'s specific to the hook.
@@ -170,15 +284,11 @@ impl LocalKeyState {
         let all_before = self.state.values().all(|state| *state);
 
         let check_again = match self.state.get_mut(&key) {
-            Some(current) => {
-                if *current != key_state {
-                    *current = key_state;
-                    true
-                } else {
-                    false
-                }
+            Some(current) if *current != key_state => {
+                *current = key_state;
+                true
             },
-            None => false,
+            _ => false,
         };
 
         if check_again {
@@ -216,6 +326,81 @@ impl LocalKeyState {
     }
 }
 
+/// State of a `Sequence` specific to the hook.
+pub(in crate::input) struct SequenceState {
+    steps: Vec<Vec<Key>>,
+    timeout: Duration,
+    step: usize,
+    step_begin: Instant,
+    complete: bool,
+}
+
+impl SequenceState {
+    pub(in crate::input) fn new(sequence: Sequence) -> Self {
+        Self {
+            steps: sequence.steps,
+            timeout: sequence.timeout,
+            step: 0,
+            step_begin: Instant::now(),
+            complete: false,
+        }
+    }
+
+    pub(in crate::input) fn first_step(&self) -> Vec<Key> {
+        self.steps[0].clone()
+    }
+
+    // Returns true if this press completed the final step.
+    pub(in crate::input) fn press(
+        &mut self,
+        state: &mut LocalKeyState,
+        window_state: &WindowState,
+        key: Key,
+    ) -> bool {
+        if self.complete
+            || (self.step != 0
+                && (self.step_begin.elapsed() > self.timeout || !state.is_involved(key)))
+        {
+            self.set_step(0, state, window_state, key);
+        }
+
+        if !state.update(key, true) {
+            return false;
+        }
+
+        if self.step + 1 == self.steps.len() {
+            // NOTE: The state is reset upon the next press, so that the hook's method is called
+            //       with the state of the final step.
+            self.complete = true;
+            true
+        } else {
+            self.set_step(self.step + 1, state, window_state, key);
+            false
+        }
+    }
+
+    fn set_step(
+        &mut self,
+        step: usize,
+        state: &mut LocalKeyState,
+        window_state: &WindowState,
+        key: Key,
+    ) {
+        self.step = step;
+        self.step_begin = Instant::now();
+        self.complete = false;
+        *state = LocalKeyState::from_keys(self.steps[step].iter().copied());
+
+        // NOTE: The key being pressed is excluded as it either completed the previous step or
+        //       is yet to be applied to this step.
+        for step_key in self.steps[step].iter().copied() {
+            if step_key != key && window_state.is_key_pressed(step_key) {
+                state.update(step_key, true);
+            }
+        }
+    }
+}
+
 /// State of cursor specific to the hook.
 #[derive(Default)]
 pub struct LocalCursorState {
@@ -265,6 +450,7 @@ impl LocalCursorState {
 pub(in crate::input) enum HookState {
     Press {
         state: LocalKeyState,
+        sequence: Option<SequenceState>,
         weight: i16,
         method: Box<
             dyn FnMut(InputHookTarget, &WindowState, &LocalKeyState) -> InputHookCtrl
@@ -340,6 +526,12 @@ pub(in crate::input) enum HookState {
         weight: i16,
         method: Box<dyn FnMut(f32, f32) -> InputHookCtrl + Send + 'static>,
     },
+    RawMotion {
+        weight: i16,
+        method: Box<
+            dyn FnMut(InputHookTarget, &WindowState, f32, f32) -> InputHookCtrl + Send + 'static,
+        >,
+    },
 }
 
 impl HookState {
@@ -347,3 +539,112 @@ impl HookState {
         !matches!(self, Self::Motion { .. })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{LocalKeyState, SequenceState, WindowState};
+    use crate::input::{Key, Qwerty, Sequence};
+    use crate::window::WindowID;
+
+    fn sequence_state(sequence: Sequence) -> (SequenceState, LocalKeyState, WindowState) {
+        let sequence = SequenceState::new(sequence);
+        let state = LocalKeyState::from_keys(sequence.first_step());
+        (sequence, state, WindowState::new(WindowID::from_raw(0)))
+    }
+
+    fn press(
+        sequence: &mut SequenceState,
+        state: &mut LocalKeyState,
+        window_state: &mut WindowState,
+        key: Qwerty,
+    ) -> bool {
+        let key = Key::from(key);
+        window_state.update_key(key, true);
+        sequence.press(state, window_state, key)
+    }
+
+    fn release(state: &mut LocalKeyState, window_state: &mut WindowState, key: Qwerty) {
+        let key = Key::from(key);
+        window_state.update_key(key, false);
+        state.update(key, false);
+    }
+
+    // NOTE: Press then release the key returning if the sequence was completed.
+    fn tap(
+        sequence: &mut SequenceState,
+        state: &mut LocalKeyState,
+        window_state: &mut WindowState,
+        key: Qwerty,
+    ) -> bool {
+        let complete = press(sequence, state, window_state, key);
+        release(state, window_state, key);
+        complete
+    }
+
+    #[test]
+    fn sequence_advance() {
+        let (mut seq, mut state, mut ws) =
+            sequence_state(Sequence::new([Qwerty::G, Qwerty::I, Qwerty::T]));
+
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::G));
+        assert_eq!(seq.step, 1);
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::I));
+        assert_eq!(seq.step, 2);
+        assert!(tap(&mut seq, &mut state, &mut ws, Qwerty::T));
+        assert!(seq.complete);
+    }
+
+    #[test]
+    fn sequence_wrong_key_resets() {
+        let (mut seq, mut state, mut ws) = sequence_state(Sequence::new([Qwerty::G, Qwerty::I]));
+
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::G));
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::X));
+        assert_eq!(seq.step, 0);
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::I));
+        assert_eq!(seq.step, 0);
+    }
+
+    #[test]
+    fn sequence_timeout_resets() {
+        let (mut seq, mut state, mut ws) = sequence_state(
+            Sequence::new([Qwerty::G, Qwerty::I]).timeout(Duration::from_millis(500)),
+        );
+
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::G));
+        seq.step_begin = Instant::now() - Duration::from_secs(1);
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::I));
+        assert_eq!(seq.step, 0);
+
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::G));
+        assert!(tap(&mut seq, &mut state, &mut ws, Qwerty::I));
+    }
+
+    #[test]
+    fn sequence_restarts_after_completion() {
+        let (mut seq, mut state, mut ws) = sequence_state(Sequence::new([Qwerty::G, Qwerty::I]));
+
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::G));
+        assert!(tap(&mut seq, &mut state, &mut ws, Qwerty::I));
+        assert!(!tap(&mut seq, &mut state, &mut ws, Qwerty::G));
+        assert_eq!(seq.step, 1);
+        assert!(!seq.complete);
+        assert!(tap(&mut seq, &mut state, &mut ws, Qwerty::I));
+    }
+
+    #[test]
+    fn sequence_held_modifier() {
+        let (mut seq, mut state, mut ws) = sequence_state(Sequence::new([
+            [Qwerty::LCtrl, Qwerty::X],
+            [Qwerty::LCtrl, Qwerty::S],
+        ]));
+
+        assert!(!press(&mut seq, &mut state, &mut ws, Qwerty::LCtrl));
+        assert!(!press(&mut seq, &mut state, &mut ws, Qwerty::X));
+        assert_eq!(seq.step, 1);
+        assert!(state.is_pressed(Qwerty::LCtrl));
+        assert!(press(&mut seq, &mut state, &mut ws, Qwerty::S));
+    }
+}
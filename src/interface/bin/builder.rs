@@ -0,0 +1,167 @@
+use std::sync::{atomic, Arc};
+
+use crate::interface::{Bin, BinPosition, BinStyle, BinStyleError, Color, Interface};
+use crate::window::Window;
+
+/// Builder for a `Bin` returned by `Interface::bin_builder` or `Window::bin_builder`.
+///
+/// The style is validated and applied once the `Bin` is added to its parent & window, so only a
+/// single update is triggered.
+pub struct BinBuilder<'a> {
+    interface: &'a Interface,
+    window: Option<Arc<Window>>,
+    parent: Option<Arc<Bin>>,
+    children: Vec<Arc<Bin>>,
+    tag: Option<String>,
+    style: BinStyle,
+}
+
+impl<'a> BinBuilder<'a> {
+    pub(crate) fn start(interface: &'a Interface) -> Self {
+        Self {
+            interface,
+            window: None,
+            parent: None,
+            children: Vec::new(),
+            tag: None,
+            style: BinStyle::default(),
+        }
+    }
+
+    /// Associate the `Bin` to a window.
+    ///
+    /// When this isn't used, the window of the parent is used if there is one.
+    pub fn window(mut self, window: &Arc<Window>) -> Self {
+        self.window = Some(window.clone());
+        self
+    }
+
+    /// Add the `Bin` as a child of the provided parent.
+    pub fn parent(mut self, parent: &Arc<Bin>) -> Self {
+        self.parent = Some(parent.clone());
+        self
+    }
+
+    /// Add a child to the `Bin`.
+    pub fn child(mut self, child: Arc<Bin>) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Add multiple children to the `Bin`.
+    pub fn children<C: IntoIterator<Item = Arc<Bin>>>(mut self, children: C) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Set the tag of the `Bin`.
+    ///
+    /// See `Bin::set_tag`.
+    pub fn tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Replace the style of the `Bin`.
+    ///
+    /// ***Note:** This replaces the fields set by any previous call.*
+    pub fn style(mut self, style: BinStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Modify the style of the `Bin` given a method.
+    pub fn modify_style<F: FnOnce(&mut BinStyle)>(mut self, method: F) -> Self {
+        method(&mut self.style);
+        self
+    }
+
+    /// Set `BinStyle.position`.
+    pub fn position(mut self, position: BinPosition) -> Self {
+        self.style.position = Some(position);
+        self
+    }
+
+    /// Set `BinStyle.pos_from_t` & `BinStyle.pos_from_l`.
+    pub fn pos(mut self, top: f32, left: f32) -> Self {
+        self.style.pos_from_t = Some(top);
+        self.style.pos_from_l = Some(left);
+        self
+    }
+
+    /// Set `BinStyle.width` & `BinStyle.height`.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.style.width = Some(width);
+        self.style.height = Some(height);
+        self
+    }
+
+    /// Set `BinStyle.back_color`.
+    pub fn color(mut self, color: Color) -> Self {
+        self.style.back_color = Some(color);
+        self
+    }
+
+    /// Set `BinStyle.text`.
+    pub fn text<T: Into<String>>(mut self, text: T) -> Self {
+        self.style.text = text.into();
+        self
+    }
+
+    /// Create the `Bin`.
+    ///
+    /// Returns the errors if the style isn't valid, in which case the `Bin` isn't created.
+    ///
+    /// # Notes
+    /// - Panics if the parent isn't associated to the window provided.
+    #[track_caller]
+    pub fn build(self) -> Result<Arc<Bin>, Vec<BinStyleError>> {
+        let window_op = match (self.window, self.parent.as_ref()) {
+            (Some(window), Some(parent)) => {
+                if parent.window().as_ref() != Some(&window) {
+                    panic!("parent bin is not associated to the window provided");
+                }
+
+                Some(window)
+            },
+            (Some(window), None) => Some(window),
+            (None, Some(parent)) => parent.window(),
+            (None, None) => None,
+        };
+
+        let bin = self.interface.new_bin();
+
+        // NOTE: The hierarchy must be present for validation, but no updates are triggered, as the
+        //       `Bin` isn't associated to a window yet.
+        if let Some(parent) = self.parent.as_ref() {
+            parent.link_children(std::slice::from_ref(&bin));
+        }
+
+        self.style.validate(&bin).into_result()?;
+        bin.link_children(&self.children);
+        let floating = self.style.position == Some(BinPosition::Floating);
+        bin.style.store(Arc::new(self.style));
+        bin.resolve_style();
+        bin.initial.store(false, atomic::Ordering::SeqCst);
+        *bin.tag.lock() = self.tag;
+
+        if let Some(window) = window_op {
+            bin.associate_window_recursive(&window);
+        }
+
+        match self.parent.as_ref() {
+            Some(parent) if floating => parent.trigger_children_update(),
+            _ => bin.trigger_recursive_update(),
+        }
+
+        if let Some(parent) = self.parent {
+            parent.call_children_added_hooks(vec![bin.clone()]);
+        }
+
+        if !self.children.is_empty() {
+            bin.call_children_added_hooks(self.children);
+        }
+
+        Ok(bin)
+    }
+}
@@ -5,6 +5,7 @@ use crate::ulps_eq;
 ///
 /// Component values are normalized from `0.0..=1.0`.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -26,10 +27,13 @@ impl Color {
     ///
     /// Length Of:
     /// - `2` is a luma color.
+    /// - `3` is a RGB color where each digit is repeated.
     /// - `4` is a luma color with alpha.
     /// - `6` is a RGB color.
     /// - `8` is a RGB color with alpha.
     ///
+    /// A leading `#` is ignored.
+    ///
     /// ***Note:** Upon a parsing error, transparent black with be returned. If this isn't desired
     /// use the `checked` varient of this method.*
     pub fn hex<H: AsRef<str>>(hex: H) -> Self {
@@ -48,12 +52,21 @@ impl Color {
     /// *See `hex` for more information.*
     pub fn hex_checked<H: AsRef<str>>(hex: H) -> Option<Self> {
         let hex = hex.as_ref();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+        if !hex.is_ascii() {
+            return None;
+        }
 
         match hex.len() {
             2 => {
                 let l = convert::u8f32(u8::from_str_radix(hex, 16).ok()?);
                 Some(Self::rgb(l, l, l))
             },
+            3 => {
+                let [r, g, b] = [0, 1, 2].map(|i| u8::from_str_radix(&hex[i..(i + 1)], 16));
+                Some(Self::rgb8(r.ok()? * 17, g.ok()? * 17, b.ok()? * 17))
+            },
             4 => {
                 let l = convert::u8f32(u8::from_str_radix(&hex[0..2], 16).ok()?);
                 let a = convert::u8f32(u8::from_str_radix(&hex[2..4], 16).ok()?);
@@ -230,6 +243,36 @@ impl Color {
         Self::srgba(r, g, b, a)
     }
 
+    /// `Color` from HSV values.
+    ///
+    /// - `h` is the hue of the color and ranges from `0.0` to `360.0`.
+    /// - `s` is the saturation of the color and ranges from `0.0` to `100.0`.
+    /// - `v` is the value of the color and ranges from `0.0` to `100.0`.
+    ///
+    /// ***Note:** Values outside of the their range will be clamped.*
+    pub fn hsv(h: f32, s: f32, v: f32) -> Self {
+        Self::hsva(h, s, v, 1.0)
+    }
+
+    /// `Color` from HSV values with alpha.
+    ///
+    /// `a` is the alpha of the color and ranges from `0.0` to `1.0`.
+    ///
+    /// *See `Color::hsv` for more information.*
+    pub fn hsva(h: f32, s: f32, v: f32, a: f32) -> Self {
+        let s = (s / 100.0).clamp(0.0, 1.0);
+        let v = (v / 100.0).clamp(0.0, 1.0);
+        let l = v * (1.0 - (s / 2.0));
+
+        let sl = if ulps_eq(l, 0.0, 4) || ulps_eq(l, 1.0, 4) {
+            0.0
+        } else {
+            (v - l) / l.min(1.0 - l)
+        };
+
+        Self::hsla(h, sl * 100.0, l * 100.0, a)
+    }
+
     fn hsl_to_srgb(mut h: f32, mut s: f32, mut l: f32) -> [f32; 3] {
         h = (h / 360.0).clamp(0.0, 1.0);
         s = (s / 100.0).clamp(0.0, 1.0);
@@ -266,12 +309,85 @@ impl Color {
         } else if t < 0.5 {
             q
         } else if t < 2.0 / 3.0 {
-            (p + (q - p)) * ((2.0 / 3.0) - t) * 6.0
+            p + ((q - p) * ((2.0 / 3.0) - t) * 6.0)
         } else {
             p
         }
     }
 
+    /// Convert into HSL values.
+    ///
+    /// *See `Color::hsl` for the range of the values.*
+    pub fn hsl_array(self) -> [f32; 3] {
+        let [r, g, b] = self.srgbf_array();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+
+        if ulps_eq(max, min, 4) {
+            return [0.0, 0.0, l * 100.0];
+        }
+
+        let d = max - min;
+
+        let s = if l > 0.5 {
+            d / (2.0 - max - min)
+        } else {
+            d / (max + min)
+        };
+
+        let h = if max == r {
+            ((g - b) / d) + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            ((b - r) / d) + 2.0
+        } else {
+            ((r - g) / d) + 4.0
+        };
+
+        [h * 60.0, s * 100.0, l * 100.0]
+    }
+
+    /// Increase the lightness of the color.
+    ///
+    /// `amount` is added to the lightness which ranges from `0.0` to `100.0`.
+    pub fn lighten(self, amount: f32) -> Self {
+        let [h, s, l] = self.hsl_array();
+        Self::hsl(h, s, l + amount).with_alpha(self.a)
+    }
+
+    /// Decrease the lightness of the color.
+    ///
+    /// `amount` is subtracted from the lightness which ranges from `0.0` to `100.0`.
+    pub fn darken(self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Replace the alpha of the color.
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self {
+            a: a.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Blend this color over another color.
+    pub fn blend_over(self, other: Self) -> Self {
+        let a = self.a + (other.a * (1.0 - self.a));
+
+        if ulps_eq(a, 0.0, 4) {
+            return Self::default();
+        }
+
+        let blend = |s: f32, o: f32| ((s * self.a) + (o * other.a * (1.0 - self.a))) / a;
+
+        Self::rgba(
+            blend(self.r, other.r),
+            blend(self.g, other.g),
+            blend(self.b, other.b),
+            a,
+        )
+    }
+
     /// Convert into an RGBF array.
     pub fn rgbf_array(self) -> [f32; 3] {
         [self.r, self.g, self.b]
@@ -529,3 +645,109 @@ impl Color {
     pub fn yellow() -> Self { Self::srgb8(255, 255, 0) }
     pub fn yellow_green() -> Self { Self::srgb8(154, 205, 50) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    fn assert_hsl_eq(a: [f32; 3], b: [f32; 3]) {
+        let h_diff = (a[0] - b[0]).rem_euclid(360.0);
+
+        assert!(
+            h_diff.min(360.0 - h_diff) < 0.1
+                && (a[1] - b[1]).abs() < 0.1
+                && (a[2] - b[2]).abs() < 0.1,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    fn assert_srgb_eq(color: Color, srgb: [f32; 3]) {
+        let a = color.srgbf_array();
+
+        for i in 0..3 {
+            assert!((a[i] - srgb[i]).abs() < 0.001, "{:?} != {:?}", a, srgb);
+        }
+    }
+
+    #[test]
+    fn hex_shorthand() {
+        assert_eq!(Color::hex("f80"), Color::hex("ff8800"));
+        assert_eq!(Color::hex("#f80"), Color::hex("ff8800"));
+        assert_eq!(Color::hex("#ff8800cc"), Color::hex("ff8800cc"));
+        assert_eq!(Color::hex_checked("fg0"), None);
+        assert_eq!(Color::hex_checked("ëa"), None);
+        assert_eq!(Color::hex_checked("#"), None);
+    }
+
+    #[test]
+    fn hsv_primaries() {
+        assert_srgb_eq(Color::hsv(0.0, 100.0, 100.0), [1.0, 0.0, 0.0]);
+        assert_srgb_eq(Color::hsv(120.0, 100.0, 100.0), [0.0, 1.0, 0.0]);
+        assert_srgb_eq(Color::hsv(240.0, 100.0, 100.0), [0.0, 0.0, 1.0]);
+        assert_srgb_eq(Color::hsv(0.0, 0.0, 100.0), [1.0, 1.0, 1.0]);
+        assert_srgb_eq(Color::hsv(0.0, 100.0, 0.0), [0.0, 0.0, 0.0]);
+        assert_srgb_eq(Color::hsv(60.0, 100.0, 50.0), [0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for [h, s, l] in [
+            [0.0, 100.0, 50.0],
+            [30.0, 60.0, 40.0],
+            [200.0, 25.0, 75.0],
+            [300.0, 80.0, 20.0],
+        ] {
+            assert_hsl_eq(Color::hsl(h, s, l).hsl_array(), [h, s, l]);
+        }
+
+        assert_hsl_eq(Color::srgb(0.5, 0.5, 0.5).hsl_array(), [0.0, 0.0, 50.0]);
+    }
+
+    #[test]
+    fn lighten_darken() {
+        let color = Color::hsla(200.0, 50.0, 40.0, 0.5);
+        assert_hsl_eq(color.lighten(20.0).hsl_array(), [200.0, 50.0, 60.0]);
+        assert_hsl_eq(color.darken(20.0).hsl_array(), [200.0, 50.0, 20.0]);
+        assert_eq!(color.lighten(20.0).a, color.a);
+        assert_srgb_eq(color.lighten(100.0), [1.0, 1.0, 1.0]);
+        assert_srgb_eq(color.darken(100.0), [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn alpha() {
+        assert_eq!(Color::red().with_alpha(0.25).a, 0.25);
+        assert_eq!(Color::red().with_alpha(2.0).a, 1.0);
+        assert_eq!(Color::red().with_alpha(-1.0).a, 0.0);
+    }
+
+    #[test]
+    fn blend() {
+        assert_eq!(Color::red().blend_over(Color::blue()), Color::red());
+        assert_eq!(
+            Color::red().with_alpha(0.0).blend_over(Color::blue()),
+            Color::blue()
+        );
+        assert_eq!(
+            Color::red()
+                .with_alpha(0.5)
+                .blend_over(Color::blue())
+                .rgbaf_array(),
+            [0.5, 0.0, 0.5, 1.0]
+        );
+        assert_eq!(
+            Color::red()
+                .with_alpha(0.5)
+                .blend_over(Color::blue().with_alpha(0.5))
+                .a,
+            0.75
+        );
+        assert_eq!(
+            Color::red()
+                .with_alpha(0.0)
+                .blend_over(Color::blue().with_alpha(0.0)),
+            Color::default()
+        );
+    }
+}
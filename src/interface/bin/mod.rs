@@ -1,36 +1,63 @@
+// NOTE: As of vulkano 0.34, Arc<Image> causes this warning though it is ok.
+// This should be solved in a future vulkano release.
+#![allow(clippy::mutable_key_type)]
+
+mod builder;
 pub mod color;
 pub mod style;
 mod text_state;
 
-use std::any::Any;
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap};
 use std::f32::consts::FRAC_PI_2;
-use std::ops::{AddAssign, DivAssign};
-use std::sync::atomic::{self, AtomicBool};
-use std::sync::{Arc, Barrier, Weak};
+use std::ops::{AddAssign, DivAssign, Range};
+use std::sync::atomic::{self, AtomicBool, AtomicU64, AtomicUsize};
+use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 
 use arc_swap::ArcSwapAny;
+pub use builder::BinBuilder;
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
+pub(crate) use text_state::measure_text;
+pub use text_state::TextMetrics;
 use text_state::TextState;
 
-use crate::image_cache::{ImageCacheKey, ImageCacheLifetime};
+use crate::image_cache::{svg_raster_size, ImageAnimation, ImageCacheKey, ImageCacheLifetime};
 use crate::input::{
     Char, InputHookCtrl, InputHookID, InputHookTarget, KeyCombo, LocalCursorState, LocalKeyState,
-    MouseButton, WindowState,
+    MouseButton, WindowState, SCROLL_STEP_PIXELS,
 };
 use crate::interface::{
-    scale_verts, BinPosition, BinStyle, BinStyleValidation, ChildFloatMode, Color, ItfVertInfo,
+    scale_verts, BinPosition, BinStyle, BinStyleError, BinStyleValidation, ChildFloatAlign,
+    ChildFloatJustify, ChildFloatMode, Color, ContextMenu, ContextMenuState, ImageFit, ImageLoop,
+    ItfVertInfo, Theme, Tooltip, TooltipState,
 };
-use crate::interval::IntvlHookCtrl;
+use crate::interval::{IntvlHookCtrl, IntvlHookID};
 use crate::render::{ImageSource, RendererMetricsLevel, UpdateContext};
 use crate::window::Window;
 use crate::Basalt;
 
 /// ID of a `Bin`
+///
+/// IDs are assigned in increasing order as bins are created, starting from *zero* for each
+/// `Basalt` instance. See `Interface::set_next_bin_id`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BinID(pub(crate) u64);
 
+impl BinID {
+    /// Create a `BinID` from its numeric value.
+    ///
+    /// ***Note:** This doesn't ensure that a `Bin` with this ID exists.*
+    pub const fn from_u64(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Retrieve the numeric value of this `BinID`.
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
 /// Information of a `Bin` after an update
 ///
 /// ***Note:** If the `Bin` is hidden, this will reflect its state when it was last visible.*
@@ -58,6 +85,8 @@ pub struct BinPostUpdate {
     pub bri: [f32; 2],
     /// Z-Index as displayed
     pub z_index: i16,
+    /// Z-Layer as displayed
+    pub z_layer: i8,
     /// Optimal inner bounds [MIN_X, MAX_X, MIN_Y, MAX_Y]
     pub optimal_inner_bounds: [f32; 4],
     /// Optimal inner bounds [MIN_X, MAX_X, MIN_Y, MAX_Y] (includes margin & borders)
@@ -73,13 +102,88 @@ pub struct BinPostUpdate {
     text_state: TextState,
 }
 
+/// The styles of a `Bin` and its descendants produced by `Bin::snapshot_styles`.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BinTreeSnapshot {
+    /// The tag of the `Bin` when the snapshot was taken.
+    pub tag: Option<String>,
+    /// The style of the `Bin` when the snapshot was taken.
+    pub style: BinStyle,
+    /// The snapshots of the children in the order they were added.
+    pub children: Vec<BinTreeSnapshot>,
+}
+
+#[derive(Clone, PartialEq)]
 pub(crate) struct BinPlacement {
     z: i16,
+    z_layer: i8,
     tlwh: [f32; 4],
     bounds: [f32; 4],
     opacity: f32,
     hidden: bool,
+    layer: Option<BinID>,
+    layer_opacity: f32,
+}
+
+impl BinPlacement {
+    fn root(extent: [f32; 2]) -> Self {
+        Self {
+            z: 0,
+            z_layer: 0,
+            tlwh: [0.0, 0.0, extent[0], extent[1]],
+            bounds: [0.0, extent[0], 0.0, extent[1]],
+            opacity: 1.0,
+            hidden: false,
+            layer: None,
+            layer_opacity: 1.0,
+        }
+    }
+
+    /// A hidden placement without a size, used when a style can't be placed.
+    fn invalid() -> Self {
+        Self {
+            z: 0,
+            z_layer: 0,
+            tlwh: [0.0; 4],
+            bounds: [0.0; 4],
+            opacity: 0.0,
+            hidden: true,
+            layer: None,
+            layer_opacity: 1.0,
+        }
+    }
+
+    /// The layer the `Bin` is drawn onto, if it is within the subtree of an isolated `Bin`.
+    pub(crate) fn layer(&self) -> Option<BinID> {
+        self.layer
+    }
+
+    /// Calculate the `opacity`, `layer` & `layer_opacity` of a child of this placement.
+    fn child_opacity(
+        &self,
+        id: BinID,
+        style: &BinStyle,
+        hidden: bool,
+    ) -> (f32, Option<BinID>, f32) {
+        let opacity = match style.opacity {
+            Some(opacity) => self.opacity * opacity,
+            None => self.opacity,
+        };
+
+        // NOTE: Only the outermost isolated bin creates a layer. Its content is drawn onto the
+        //       layer opaquely and the layer is blended with the opacity instead.
+        if self.layer.is_none()
+            && style.isolate_opacity.unwrap_or(false)
+            && !hidden
+            && opacity > 0.0
+            && opacity < 1.0
+        {
+            (1.0, Some(id), opacity)
+        } else {
+            (opacity, self.layer, 1.0)
+        }
+    }
 }
 
 #[derive(Default)]
@@ -88,20 +192,32 @@ struct BinHrchy {
     children: Vec<Weak<Bin>>,
 }
 
+struct BackImageAnimation {
+    image_cache_key: ImageCacheKey,
+    frame: Arc<AtomicUsize>,
+    hook_id: IntvlHookID,
+}
+
 #[derive(PartialEq, Eq, Hash)]
 enum InternalHookTy {
     Updated,
     UpdatedOnce,
     ChildrenAdded,
     ChildrenRemoved,
+    OverflowChanged,
 }
 
+type OverflowChangedFn = Arc<Mutex<Box<dyn FnMut(&Arc<Bin>, f32, f32) + Send + 'static>>>;
+
 enum InternalHookFn {
     Updated(Box<dyn FnMut(&Arc<Bin>, &BinPostUpdate) + Send + 'static>),
     ChildrenAdded(Box<dyn FnMut(&Arc<Bin>, &Vec<Arc<Bin>>) + Send + 'static>),
     ChildrenRemoved(Box<dyn FnMut(&Arc<Bin>, &Vec<Weak<Bin>>) + Send + 'static>),
+    OverflowChanged(OverflowChangedFn),
 }
 
+type ThemeStyleFn = Box<dyn FnMut(&Theme) -> BinStyle + Send + 'static>;
+
 struct Coords {
     tlwh: [f32; 4],
 }
@@ -120,6 +236,70 @@ impl Coords {
     fn y_pct(&self, pct: f32) -> f32 {
         (self.tlwh[3] * pct) + self.tlwh[0]
     }
+
+    // Fit these coords of an image to the destination returning the portion of the destination
+    // covered and the coords of the image displayed.
+    fn fit(&self, fit: ImageFit, dst_tlwh: [f32; 4]) -> ([f32; 4], Self) {
+        let [src_t, src_l, src_w, src_h] = self.tlwh;
+        let [dst_t, dst_l, dst_w, dst_h] = dst_tlwh;
+
+        if src_w <= 0.0 || src_h <= 0.0 || dst_w <= 0.0 || dst_h <= 0.0 {
+            return (
+                dst_tlwh,
+                Self {
+                    tlwh: self.tlwh,
+                },
+            );
+        }
+
+        // NOTE: Centers a length of the source within a length of the destination, where `scale`
+        //       is the size of a source unit in the destination. Returns the position & length
+        //       of both the destination and source.
+        let center = |dst_p: f32, dst_len: f32, src_p: f32, src_len: f32, scale: f32| {
+            let scaled_len = src_len * scale;
+
+            if scaled_len >= dst_len {
+                let visible_len = dst_len / scale;
+                [
+                    dst_p,
+                    dst_len,
+                    src_p + ((src_len - visible_len) / 2.0),
+                    visible_len,
+                ]
+            } else {
+                [
+                    dst_p + ((dst_len - scaled_len) / 2.0),
+                    scaled_len,
+                    src_p,
+                    src_len,
+                ]
+            }
+        };
+
+        let scale = match fit {
+            ImageFit::Fill => {
+                return (
+                    dst_tlwh,
+                    Self {
+                        tlwh: self.tlwh,
+                    },
+                )
+            },
+            ImageFit::Contain => (dst_w / src_w).min(dst_h / src_h),
+            ImageFit::Cover => (dst_w / src_w).max(dst_h / src_h),
+            ImageFit::None => 1.0,
+        };
+
+        let [t, h, s_t, s_h] = center(dst_t, dst_h, src_t, src_h, scale);
+        let [l, w, s_l, s_w] = center(dst_l, dst_w, src_l, src_w, scale);
+
+        (
+            [t, l, w, h],
+            Self {
+                tlwh: [s_t, s_l, s_w, s_h],
+            },
+        )
+    }
 }
 
 /// Performance metrics for a `Bin` update.
@@ -132,6 +312,7 @@ pub struct OVDPerfMetrics {
     pub back_image: f32,
     pub back_vertex: f32,
     pub text: f32,
+    /// Time spent clipping the vertexes to the bounds.
     pub overflow: f32,
     pub vertex_scale: f32,
     pub post_update: f32,
@@ -174,11 +355,28 @@ pub struct Bin {
     associated_window: Mutex<Option<Weak<Window>>>,
     hrchy: ArcSwapAny<Arc<BinHrchy>>,
     style: ArcSwapAny<Arc<BinStyle>>,
+    resolved_style: ArcSwapAny<Arc<BinStyle>>,
+    resolve_lock: Mutex<()>,
+    hovered: AtomicBool,
+    focused: AtomicBool,
+    active: AtomicBool,
     initial: AtomicBool,
+    // NOTE: Set once an invalid style has been reported, so that it is reported once per style.
+    invalid_style_warned: AtomicBool,
+    accepts_tab: AtomicBool,
+    update_version: AtomicU64,
     post_update: RwLock<BinPostUpdate>,
     input_hook_ids: Mutex<Vec<InputHookID>>,
     keep_alive_objects: Mutex<Vec<Box<dyn Any + Send + Sync + 'static>>>,
+    tag: Mutex<Option<String>>,
+    user_data: Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync + 'static>>>,
     internal_hooks: Mutex<HashMap<InternalHookTy, Vec<InternalHookFn>>>,
+    last_overflow: Mutex<Option<[f32; 2]>>,
+    tooltip: Mutex<Option<TooltipState>>,
+    context_menu: Mutex<Option<ContextMenuState>>,
+    theme_style: Mutex<Option<ThemeStyleFn>>,
+    back_image_animation: Mutex<Option<BackImageAnimation>>,
+    text_highlights: Mutex<Vec<(Range<usize>, Color)>>,
 }
 
 impl PartialEq for Bin {
@@ -201,6 +399,17 @@ impl Drop for Bin {
             self.basalt.input_ref().remove_hook(hook);
         }
 
+        self.release_pointer();
+        self.stop_back_image_animation();
+
+        if let Some(tooltip) = self.tooltip.lock().take() {
+            tooltip.remove(self);
+        }
+
+        if let Some(context_menu) = self.context_menu.lock().take() {
+            context_menu.remove(self);
+        }
+
         if let Some(parent) = self.parent() {
             let parent_hrchy = parent.hrchy.load();
 
@@ -229,16 +438,33 @@ impl Bin {
             associated_window: Mutex::new(None),
             hrchy: ArcSwapAny::from(Arc::new(BinHrchy::default())),
             style: ArcSwapAny::new(Arc::new(BinStyle::default())),
+            resolved_style: ArcSwapAny::new(Arc::new(BinStyle::default())),
+            resolve_lock: Mutex::new(()),
+            hovered: AtomicBool::new(false),
+            focused: AtomicBool::new(false),
+            active: AtomicBool::new(false),
             initial: AtomicBool::new(true),
+            invalid_style_warned: AtomicBool::new(false),
+            accepts_tab: AtomicBool::new(false),
+            update_version: AtomicU64::new(0),
             post_update: RwLock::new(BinPostUpdate::default()),
             input_hook_ids: Mutex::new(Vec::new()),
             keep_alive_objects: Mutex::new(Vec::new()),
+            tag: Mutex::new(None),
+            user_data: Mutex::new(HashMap::new()),
             internal_hooks: Mutex::new(HashMap::from([
                 (InternalHookTy::Updated, Vec::new()),
                 (InternalHookTy::UpdatedOnce, Vec::new()),
                 (InternalHookTy::ChildrenAdded, Vec::new()),
                 (InternalHookTy::ChildrenRemoved, Vec::new()),
+                (InternalHookTy::OverflowChanged, Vec::new()),
             ])),
+            last_overflow: Mutex::new(None),
+            tooltip: Mutex::new(None),
+            context_menu: Mutex::new(None),
+            theme_style: Mutex::new(None),
+            back_image_animation: Mutex::new(None),
+            text_highlights: Mutex::new(Vec::new()),
         })
     }
 
@@ -350,29 +576,43 @@ impl Bin {
     }
 
     /// Add a child to this `Bin`.
+    ///
+    /// ***Note:** When adding many children, `add_children` should be preferred.*
     pub fn add_child(self: &Arc<Self>, child: Arc<Bin>) {
-        let child_hrchy = child.hrchy.load();
+        self.add_children(vec![child]);
+    }
 
-        child.hrchy.store(Arc::new(BinHrchy {
-            parent: Some(Arc::downgrade(self)),
-            children: child_hrchy.children.clone(),
-        }));
+    /// Add multiple children to this `Bin`.
+    ///
+    /// The children are added as a batch: a single update is dispatched for all affected bins
+    /// and the children added hooks are called once.
+    pub fn add_children(self: &Arc<Self>, children: Vec<Arc<Bin>>) {
+        if children.is_empty() {
+            return;
+        }
 
-        let this_hrchy = self.hrchy.load();
-        let mut children = this_hrchy.children.clone();
-        children.push(Arc::downgrade(&child));
+        self.link_children(&children);
 
-        self.hrchy.store(Arc::new(BinHrchy {
-            children,
-            parent: this_hrchy.parent.clone(),
-        }));
+        // NOTE: Floating children affect the placement of their floating siblings, so all of the
+        //       children are updated instead.
+        if children
+            .iter()
+            .any(|child| child.resolved_style.load().position == Some(BinPosition::Floating))
+        {
+            for child in children.iter() {
+                child.update_version.fetch_add(1, atomic::Ordering::SeqCst);
+            }
 
-        child.trigger_recursive_update();
-        self.call_children_added_hooks(vec![child]);
+            self.trigger_children_update();
+        } else {
+            Self::trigger_recursive_update_batch(&children);
+        }
+
+        self.call_children_added_hooks(children);
     }
 
-    /// Add multiple children to this `Bin`.
-    pub fn add_children(self: &Arc<Self>, children: Vec<Arc<Bin>>) {
+    // Add the children to the hierarchy without triggering updates or calling hooks.
+    fn link_children(self: &Arc<Self>, children: &[Arc<Bin>]) {
         let this_hrchy = self.hrchy.load();
         let mut this_children = this_hrchy.children.clone();
 
@@ -390,14 +630,71 @@ impl Bin {
             children: this_children,
             parent: this_hrchy.parent.clone(),
         }));
+    }
 
-        children
-            .iter()
-            .for_each(|child| child.trigger_recursive_update());
-        self.call_children_added_hooks(children);
+    /// Move this `Bin` from its current parent to `new_parent`.
+    ///
+    /// If the window of `new_parent` differs, this `Bin` & its descendants are associated to it.
+    /// The children removed hooks of the current parent and the children added hooks of
+    /// `new_parent` are called.
+    ///
+    /// Returns an error if `new_parent` is this `Bin` or one of its descendants.
+    pub fn reparent_to(self: &Arc<Self>, new_parent: &Arc<Bin>) -> Result<(), String> {
+        if new_parent.id == self.id
+            || new_parent
+                .ancestors()
+                .into_iter()
+                .any(|ancestor| ancestor.id == self.id)
+        {
+            return Err(String::from(
+                "The new parent can't be this `Bin` or one of its descendants.",
+            ));
+        }
+
+        if let Some(old_parent) = self.parent() {
+            if old_parent.id == new_parent.id {
+                return Ok(());
+            }
+
+            let self_wk = Arc::downgrade(self);
+            let old_parent_hrchy = old_parent.hrchy.load();
+
+            old_parent.hrchy.store(Arc::new(BinHrchy {
+                children: old_parent_hrchy
+                    .children
+                    .iter()
+                    .filter(|child_wk| !child_wk.ptr_eq(&self_wk))
+                    .cloned()
+                    .collect(),
+                parent: old_parent_hrchy.parent.clone(),
+            }));
+
+            old_parent.call_children_removed_hooks(vec![self_wk]);
+
+            // NOTE: The remaining floating siblings fill the space that this `Bin` occupied.
+            if self.resolved_style.load().position == Some(BinPosition::Floating) {
+                old_parent.trigger_children_update();
+            }
+        }
+
+        if let Some(window) = new_parent.window() {
+            if self
+                .window()
+                .map(|current| !Arc::ptr_eq(&current, &window))
+                .unwrap_or(true)
+            {
+                self.associate_window_recursive(&window);
+            }
+        }
+
+        new_parent.add_children(vec![self.clone()]);
+        Ok(())
     }
 
     /// Take the children from this `Bin`.
+    ///
+    /// The children are removed as a batch: a single update is dispatched for all affected bins
+    /// and the children removed hooks are called once.
     pub fn take_children(self: &Arc<Self>) -> Vec<Arc<Bin>> {
         let this_hrchy = self.hrchy.load();
         let mut children = Vec::new();
@@ -421,9 +718,7 @@ impl Bin {
         }));
 
         self.call_children_removed_hooks(this_hrchy.children.clone());
-        children
-            .iter()
-            .for_each(|child| child.trigger_recursive_update());
+        Self::trigger_recursive_update_batch(&children);
         children
     }
 
@@ -457,26 +752,244 @@ impl Bin {
 
         if !validation.errors_present() {
             let old_style = self.style.swap(Arc::new(updated_style));
+            self.resolve_style();
             self.initial.store(false, atomic::Ordering::SeqCst);
+            self.invalid_style_warned
+                .store(false, atomic::Ordering::SeqCst);
+            self.update_version.fetch_add(1, atomic::Ordering::SeqCst);
             effects_siblings |= old_style.position == Some(BinPosition::Floating);
+            self.trigger_style_update(effects_siblings);
+        }
 
-            if effects_siblings {
-                match self.parent() {
-                    Some(parent) => parent.trigger_children_update(),
-                    None => {
-                        // NOTE: Parent should always be Some(_) in this case, but fallback to
-                        //       a standard recursive update for robustness
-                        self.trigger_recursive_update();
-                    },
-                }
-            } else {
-                self.trigger_recursive_update();
+        validation
+    }
+
+    fn trigger_style_update(self: &Arc<Self>, effects_siblings: bool) {
+        if effects_siblings {
+            match self.parent() {
+                Some(parent) => parent.trigger_children_update(),
+                None => {
+                    // NOTE: Parent should always be Some(_) in this case, but fallback to
+                    //       a standard recursive update for robustness
+                    self.trigger_recursive_update();
+                },
+            }
+        } else {
+            self.trigger_recursive_update();
+        }
+    }
+
+    // Resolve the style used during updates from the style & the state overlays that apply.
+    fn resolve_style(&self) {
+        // NOTE: Resolving is serialized, so that a resolve using an older style or state can't
+        //       store its result after one using newer ones.
+        let _resolve_guard = self.resolve_lock.lock();
+
+        self.resolved_style.store(resolve_states(
+            self.style.load_full(),
+            [
+                self.hovered.load(atomic::Ordering::SeqCst),
+                self.focused.load(atomic::Ordering::SeqCst),
+                self.active.load(atomic::Ordering::SeqCst),
+            ],
+        ));
+    }
+
+    fn set_state(
+        self: &Arc<Self>,
+        state: &AtomicBool,
+        value: bool,
+        overlay: fn(&BinStyle) -> Option<&BinStyle>,
+    ) {
+        if state.swap(value, atomic::Ordering::SeqCst) == value {
+            return;
+        }
+
+        let style = self.style.load_full();
+
+        if let Some(overlay) = overlay(&style) {
+            self.resolve_style();
+            self.update_version.fetch_add(1, atomic::Ordering::SeqCst);
+
+            self.trigger_style_update(
+                style.position == Some(BinPosition::Floating)
+                    || overlay.position == Some(BinPosition::Floating),
+            );
+        }
+    }
+
+    /// Check if the pointer is over this `Bin`.
+    ///
+    /// When bins overlap, only the top-most `Bin` under the pointer and its ancestors are
+    /// hovered.
+    pub fn is_hovered(&self) -> bool {
+        self.hovered.load(atomic::Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_hovered(self: &Arc<Self>, hovered: bool) {
+        self.set_state(&self.hovered, hovered, |style| style.hover.as_deref());
+    }
+
+    /// Check if this `Bin` has input focus.
+    pub fn is_focused(&self) -> bool {
+        self.focused.load(atomic::Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_focused(self: &Arc<Self>, focused: bool) {
+        self.set_state(&self.focused, focused, |style| style.focused.as_deref());
+    }
+
+    /// Check if a mouse button is held down on this `Bin`.
+    ///
+    /// Like hover, the top-most `Bin` under the pointer when the button was pressed and its
+    /// ancestors are active until all mouse buttons are released.
+    pub fn is_active(&self) -> bool {
+        self.active.load(atomic::Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_active(self: &Arc<Self>, active: bool) {
+        self.set_state(&self.active, active, |style| style.active.as_deref());
+    }
+
+    /// Update the style of this `Bin` returning the errors if the style isn't valid.
+    ///
+    /// This is the same as `style_update`, but warnings are discarded.
+    ///
+    /// ***Note:** If the style has a validation error, the style will not be updated.*
+    pub fn try_style_update(
+        self: &Arc<Self>,
+        updated_style: BinStyle,
+    ) -> Result<(), Vec<BinStyleError>> {
+        self.style_update(updated_style).into_result()
+    }
+
+    /// Modify the style of this `Bin` given a method.
+    ///
+    /// All modifications are validated together and only a single update is triggered. This is
+    /// preferred over multiple calls to `style_update` when changing several fields at once.
+    ///
+    /// ***Note:** If the style has a validation error, none of the modifications are applied.*
+    #[track_caller]
+    pub fn style_update_batch<F: FnOnce(&mut BinStyle)>(
+        self: &Arc<Self>,
+        method: F,
+    ) -> BinStyleValidation {
+        let mut style = self.style_copy();
+        method(&mut style);
+        self.style_update(style)
+    }
+
+    /// Capture the style of this `Bin` and its descendants.
+    ///
+    /// Use `restore_styles` to apply the snapshot later on.
+    pub fn snapshot_styles(&self) -> BinTreeSnapshot {
+        BinTreeSnapshot {
+            tag: self.tag(),
+            style: self.style_copy(),
+            children: self
+                .children()
+                .into_iter()
+                .map(|child| child.snapshot_styles())
+                .collect(),
+        }
+    }
+
+    /// Apply a snapshot produced by `snapshot_styles` to this `Bin` and its descendants.
+    ///
+    /// Children are matched with the snapshot by their tag. Children without a tag are matched
+    /// by their order amongst the other children without a tag. Bins that don't have a match
+    /// within the snapshot are left unchanged and parts of the snapshot without a match are
+    /// ignored. Only a single update is triggered for all the changes.
+    ///
+    /// ***Note:** Styles with a validation error are not applied, but the others still are.*
+    pub fn restore_styles(
+        self: &Arc<Self>,
+        snapshot: &BinTreeSnapshot,
+    ) -> Result<(), Vec<BinStyleError>> {
+        let mut errors = Vec::new();
+        let effects_siblings = self.restore_styles_inner(snapshot, &mut errors);
+        self.trigger_style_update(effects_siblings);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Returns `true` if the style applied effects the siblings of this `Bin`.
+    fn restore_styles_inner(
+        self: &Arc<Self>,
+        snapshot: &BinTreeSnapshot,
+        errors: &mut Vec<BinStyleError>,
+    ) -> bool {
+        let mut effects_siblings = false;
+
+        match snapshot.style.validate(self).into_result() {
+            Ok(()) => {
+                let old_style = self.style.swap(Arc::new(snapshot.style.clone()));
+                self.resolve_style();
+                self.initial.store(false, atomic::Ordering::SeqCst);
+                self.update_version.fetch_add(1, atomic::Ordering::SeqCst);
+
+                effects_siblings = old_style.position == Some(BinPosition::Floating)
+                    || snapshot.style.position == Some(BinPosition::Floating);
+            },
+            Err(style_errors) => errors.extend(style_errors),
+        }
+
+        let children = self.children();
+        let mut matched = vec![false; children.len()];
+
+        let mut untagged = children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| child.tag.lock().is_none())
+            .map(|(i, _)| i);
+
+        for child_snapshot in snapshot.children.iter() {
+            let index_op = match child_snapshot.tag.as_deref() {
+                Some(tag) => {
+                    (0..children.len()).find(|i| !matched[*i] && children[*i].has_tag(tag))
+                },
+                None => untagged.next(),
+            };
+
+            if let Some(index) = index_op {
+                matched[index] = true;
+                children[index].restore_styles_inner(child_snapshot, errors);
             }
         }
 
+        effects_siblings
+    }
+
+    /// Style this `Bin` from the current `Theme`.
+    ///
+    /// The method is called again to restyle this `Bin` when the theme is changed with
+    /// `Interface::set_theme`.
+    ///
+    /// ***Note:** If the style has a validation error, the style will not be updated.*
+    #[track_caller]
+    pub fn set_theme_style<F: FnMut(&Theme) -> BinStyle + Send + 'static>(
+        self: &Arc<Self>,
+        mut method: F,
+    ) -> BinStyleValidation {
+        let validation = self.style_update(method(&self.basalt.interface_ref().theme()));
+        *self.theme_style.lock() = Some(Box::new(method));
         validation
     }
 
+    pub(crate) fn apply_theme_style(
+        self: &Arc<Self>,
+        theme: &Theme,
+    ) -> Result<(), Vec<BinStyleError>> {
+        match self.theme_style.lock().as_mut() {
+            Some(method) => self.try_style_update(method(theme)),
+            None => Ok(()),
+        }
+    }
+
     /// Check if this `Bin` is hidden.
     ///
     /// ***Note:** This is based on the `BinStyle.hidden` value, not if it is offscreen.*
@@ -515,49 +1028,89 @@ impl Bin {
             None => return,
         };
 
+        self.update_version.fetch_add(1, atomic::Ordering::SeqCst);
         window.update_bin(self.id);
     }
 
     /// Trigger an update to happen on this `Bin` and its children.
+    ///
+    /// ***Note:** Children whose placement hasn't changed will reuse their previous vertex data.*
     pub fn trigger_recursive_update(self: &Arc<Self>) {
         let window = match self.window() {
             Some(some) => some,
             None => return,
         };
 
+        self.update_version.fetch_add(1, atomic::Ordering::SeqCst);
+
         window.update_bin_batch(
             self.children_recursive_with_self()
                 .into_iter()
-                .map(|child| child.id)
-                .collect(),
+                .map(|child| child.id),
         );
     }
 
+    // Same as calling `trigger_recursive_update` on each bin, but dispatches a single update
+    // per window.
+    fn trigger_recursive_update_batch(bins: &[Arc<Bin>]) {
+        let batches = batch_by_window(bins.iter().filter_map(|bin| {
+            let window = bin.window()?;
+            bin.update_version.fetch_add(1, atomic::Ordering::SeqCst);
+
+            Some((
+                window,
+                bin.children_recursive_with_self()
+                    .into_iter()
+                    .map(|bin| bin.id)
+                    .collect::<Vec<_>>(),
+            ))
+        }));
+
+        for (window, bin_ids) in batches {
+            window.update_bin_batch(bin_ids);
+        }
+    }
+
     /// Similar to `trigger_recursive_update` but doesn't trigger an update on this `Bin`.
+    ///
+    /// ***Note:** Children whose placement hasn't changed will reuse their previous vertex data.*
     pub fn trigger_children_update(self: &Arc<Self>) {
         let window = match self.window() {
             Some(some) => some,
             None => return,
         };
 
-        window.update_bin_batch(
-            self.children_recursive()
-                .into_iter()
-                .map(|child| child.id)
-                .collect(),
-        );
+        window.update_bin_batch(self.children_recursive().into_iter().map(|child| child.id));
     }
 
     /// Wait for an update to occur on this `Bin`.
-    pub fn wait_for_update(self: &Arc<Self>) {
-        let barrier = Arc::new(Barrier::new(2));
-        let barrier_copy = barrier.clone();
+    ///
+    /// Returns `false` if this `Bin` isn't associated to a window as no update can occur.
+    ///
+    /// ***Note:** Calling this within an update hook of this `Bin` will block forever. Use
+    /// `wait_for_update_timeout` where this may be the case.*
+    pub fn wait_for_update(self: &Arc<Self>) -> bool {
+        match self.update_receiver() {
+            Some(recv) => recv.recv().is_ok(),
+            None => false,
+        }
+    }
 
-        self.on_update_once(move |_, _| {
-            barrier_copy.wait();
-        });
+    /// Wait for an update to occur on this `Bin` with a timeout.
+    ///
+    /// Returns `false` if the timeout was reached or if this `Bin` isn't associated to a window.
+    pub fn wait_for_update_timeout(self: &Arc<Self>, timeout: Duration) -> bool {
+        match self.update_receiver() {
+            Some(recv) => recv.recv_timeout(timeout).is_ok(),
+            None => false,
+        }
+    }
 
-        barrier.wait();
+    fn update_receiver(self: &Arc<Self>) -> Option<flume::Receiver<()>> {
+        self.window()?;
+        let (mut signal, recv) = update_signal();
+        self.on_update_once(move |_, _| signal());
+        Some(recv)
     }
 
     /// Obtain the `BinPostUpdate` information this `Bin`.
@@ -635,41 +1188,297 @@ impl Bin {
         overflow_l + overflow_r
     }
 
-    /// Check if the mouse is inside of this `Bin`.
+    /// The maximum amount this `Bin` can be scrolled returned as `(max_x, max_y)`.
     ///
-    /// ***Note:** This does not check the window.*
-    pub fn mouse_inside(&self, mouse_x: f32, mouse_y: f32) -> bool {
-        if self.is_hidden() {
+    /// `BinStyle.scroll_y` ranges from `0.0` to `max_y` and `BinStyle.scroll_x` ranges from
+    /// `-max_x` to `0.0`, as positive `scroll_x` moves the content to the right.
+    ///
+    /// ***Note:** This is computed from the last update of this `Bin` and its children.*
+    pub fn scroll_range(self: &Arc<Bin>) -> (f32, f32) {
+        (self.calc_hori_overflow(), self.calc_vert_overflow())
+    }
+
+    /// Adjust the scroll of this `Bin` so that the provided descendant is visible.
+    ///
+    /// The descendant is aligned to the nearest edge of the content area of this `Bin`. When the
+    /// descendant is larger than the content area, it is aligned to the top & left.
+    ///
+    /// Returns `true` if the scroll was changed.
+    ///
+    /// ***Note:** Only the scroll of this `Bin` is changed. Scrollable `Bin`'s between this `Bin`
+    /// and the descendant are not.*
+    pub fn scroll_into_view(self: &Arc<Bin>, descendant: &Arc<Bin>) -> bool {
+        if !descendant
+            .ancestors()
+            .into_iter()
+            .any(|ancestor| ancestor.id == self.id)
+        {
             return false;
         }
 
-        let post = self.post_update.read();
+        let view_bounds = self.post_update.read().optimal_content_bounds;
+        let target_bounds = descendant.post_update.read().optimal_outer_bounds;
 
-        if mouse_x >= post.tlo[0]
-            && mouse_x <= post.tro[0]
-            && mouse_y >= post.tlo[1]
-            && mouse_y <= post.blo[1]
+        let delta_y = if target_bounds[2] < view_bounds[2]
+            || target_bounds[3] - target_bounds[2] > view_bounds[3] - view_bounds[2]
         {
-            return true;
-        }
+            target_bounds[2] - view_bounds[2]
+        } else if target_bounds[3] > view_bounds[3] {
+            target_bounds[3] - view_bounds[3]
+        } else {
+            0.0
+        };
 
-        false
-    }
+        let delta_x = if target_bounds[0] < view_bounds[0]
+            || target_bounds[1] - target_bounds[0] > view_bounds[1] - view_bounds[0]
+        {
+            target_bounds[0] - view_bounds[0]
+        } else if target_bounds[1] > view_bounds[1] {
+            target_bounds[1] - view_bounds[1]
+        } else {
+            0.0
+        };
 
-    /// Keep objects alive for the lifetime of the `Bin`.
-    pub fn keep_alive<O, T>(&self, objects: O)
-    where
-        O: IntoIterator<Item = T>,
-        T: Any + Send + Sync + 'static,
-    {
-        for object in objects {
-            self.keep_alive_objects.lock().push(Box::new(object));
-        }
+        self.scroll_by(delta_x, delta_y)
     }
 
-    pub fn add_enter_text_events(self: &Arc<Self>) {
-        self.on_character(move |target, _, c| {
-            let this = target.into_bin().unwrap();
+    // Scroll the content by the provided amounts, where positive amounts move the view right &
+    // down. The scroll is clamped to the overflow. Returns `true` if the scroll was changed.
+    fn scroll_by(self: &Arc<Bin>, delta_x: f32, delta_y: f32) -> bool {
+        let mut style = self.style_copy();
+        let mut modified = false;
+
+        if delta_y != 0.0 {
+            let scroll_y = style.scroll_y.unwrap_or(0.0);
+            let new_scroll_y = (scroll_y + delta_y)
+                .clamp(0.0, self.calc_vert_overflow())
+                .round();
+
+            if new_scroll_y != scroll_y {
+                style.scroll_y = Some(new_scroll_y);
+                modified = true;
+            }
+        }
+
+        if delta_x != 0.0 {
+            // NOTE: Positive scroll_x moves content to the right.
+            let scroll_x = style.scroll_x.unwrap_or(0.0);
+            let new_scroll_x = (scroll_x - delta_x)
+                .clamp(-self.calc_hori_overflow(), 0.0)
+                .round();
+
+            if new_scroll_x != scroll_x {
+                style.scroll_x = Some(new_scroll_x);
+                modified = true;
+            }
+        }
+
+        if modified {
+            self.style_update(style).expect_valid();
+        }
+
+        modified
+    }
+
+    /// Check if the mouse is inside of this `Bin`.
+    ///
+    /// ***Note:** This does not check the window.*
+    pub fn mouse_inside(&self, mouse_x: f32, mouse_y: f32) -> bool {
+        if self.is_hidden() {
+            return false;
+        }
+
+        outer_contains(&self.post_update.read(), mouse_x, mouse_y)
+    }
+
+    // NOTE: Returns the z-layer & z-index if the position is inside. These are read along with
+    //       the bounds, so that the post update isn't cloned for sorting.
+    pub(crate) fn hit_test(&self, x: f32, y: f32) -> Option<(i8, i16)> {
+        if self.is_hidden() {
+            return None;
+        }
+
+        let post = self.post_update.read();
+
+        if outer_contains(&post, x, y) {
+            Some((post.z_layer, post.z_index))
+        } else {
+            None
+        }
+    }
+
+    /// Retrieve the index of the span within `BinStyle.text_spans` at the provided position.
+    ///
+    /// The position is in window coordinates, the same as `WindowState::cursor_pos`.
+    ///
+    /// ***Note:** This returns `None` if `text_spans` isn't used or the text hasn't been laid
+    /// out yet.*
+    pub fn text_span_at(&self, x: f32, y: f32) -> Option<usize> {
+        if self.is_hidden() {
+            return None;
+        }
+
+        let post = self.post_update.read();
+        post.text_state.span_at(x / post.scale, y / post.scale)
+    }
+
+    /// Highlight byte ranges of the text of this `Bin` with a background of the provided color.
+    ///
+    /// The ranges are within `BinStyle.text` or the text of `BinStyle.text_spans` joined
+    /// together. Ranges spanning multiple lines produce an area for each line. These are kept
+    /// when the text or its layout changes, so they should be updated along with the text.
+    ///
+    /// ***Note:** An empty `Vec` removes all highlights.*
+    pub fn set_text_highlights(&self, highlights: Vec<(Range<usize>, Color)>) {
+        *self.text_highlights.lock() = highlights;
+        self.trigger_update();
+    }
+
+    /// Retrieve the highlights set with `set_text_highlights`.
+    pub fn text_highlights(&self) -> Vec<(Range<usize>, Color)> {
+        self.text_highlights.lock().clone()
+    }
+
+    /// Retrieve the family names of the fonts used to render the text of this `Bin`.
+    ///
+    /// This is useful for debugging which fonts are used for fallback.
+    ///
+    /// ***Note:** This is empty if the text hasn't been laid out yet.*
+    pub fn text_fonts_used(&self) -> Vec<String> {
+        self.post_update.read().text_state.fonts_used()
+    }
+
+    /// Keep objects alive for the lifetime of the `Bin`.
+    pub fn keep_alive<O, T>(&self, objects: O)
+    where
+        O: IntoIterator<Item = T>,
+        T: Any + Send + Sync + 'static,
+    {
+        for object in objects {
+            self.keep_alive_objects.lock().push(Box::new(object));
+        }
+    }
+
+    /// Attach data to this `Bin`, replacing the existing data of the same type.
+    ///
+    /// The data is kept alive for the lifetime of the `Bin` and can be retrieved with
+    /// `user_data`. This is useful for hooks that only have access to the `Bin`.
+    ///
+    /// ***Note:** Data holding an `Arc` of this `Bin` will prevent it from being dropped.*
+    pub fn set_user_data<T: Any + Send + Sync + 'static>(&self, data: T) {
+        self.user_data
+            .lock()
+            .insert(TypeId::of::<T>(), Arc::new(data));
+    }
+
+    /// Retrieve the data of the provided type attached with `set_user_data`.
+    pub fn user_data<T: Any + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.user_data
+            .lock()
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|data| data.downcast::<T>().ok())
+    }
+
+    /// Remove the data of the provided type attached with `set_user_data` returning it.
+    pub fn take_user_data<T: Any + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.user_data
+            .lock()
+            .remove(&TypeId::of::<T>())
+            .and_then(|data| data.downcast::<T>().ok())
+    }
+
+    /// Set the tag of this `Bin`, replacing the existing one.
+    ///
+    /// Tags identify bins so that they can be located later with `Window::find_bins_by_tag`.
+    pub fn set_tag<T: Into<String>>(&self, tag: T) {
+        *self.tag.lock() = Some(tag.into());
+    }
+
+    /// Remove the tag of this `Bin`.
+    pub fn clear_tag(&self) {
+        *self.tag.lock() = None;
+    }
+
+    /// Retrieve the tag of this `Bin`.
+    pub fn tag(&self) -> Option<String> {
+        self.tag.lock().clone()
+    }
+
+    /// Check if this `Bin` has the provided tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tag.lock().as_deref() == Some(tag)
+    }
+
+    /// Set the tooltip displayed when the cursor hovers this `Bin`.
+    ///
+    /// The tooltip is hidden when the cursor leaves or a mouse button is pressed. Providing
+    /// `None` will remove the current tooltip.
+    ///
+    /// Returns the errors of the tooltip's style if it isn't valid, in which case the current
+    /// tooltip is kept.
+    ///
+    /// ***Note:** This has no effect if this `Bin` isn't associated to a window.*
+    pub fn set_tooltip(
+        self: &Arc<Self>,
+        tooltip: Option<Tooltip>,
+    ) -> Result<(), Vec<BinStyleError>> {
+        let new_state = match tooltip {
+            Some(tooltip) => TooltipState::new(self, tooltip)?,
+            None => None,
+        };
+
+        let mut state = self.tooltip.lock();
+
+        if let Some(old_state) = state.take() {
+            old_state.remove(self);
+        }
+
+        *state = new_state;
+        Ok(())
+    }
+
+    /// Set the menu displayed at the cursor when this `Bin` or one of its descendants is
+    /// right-clicked.
+    ///
+    /// The menu is closed when an entry is selected, escape is pressed or a mouse button is
+    /// pressed outside of the menu. Providing `None` will remove the current menu. When a
+    /// descendant also has a menu, only the menu of the descendant is displayed for it.
+    ///
+    /// ***Note:** This has no effect if this `Bin` isn't associated to a window.*
+    pub fn set_context_menu(self: &Arc<Self>, context_menu: Option<ContextMenu>) {
+        let mut state = self.context_menu.lock();
+
+        if let Some(old_state) = state.take() {
+            old_state.remove(self);
+        }
+
+        *state = context_menu.and_then(|context_menu| ContextMenuState::new(self, context_menu));
+    }
+
+    pub(crate) fn has_context_menu(&self) -> bool {
+        self.context_menu.lock().is_some()
+    }
+
+    /// Set if Tab & Shift+Tab are passed to this `Bin` while it is focused instead of moving
+    /// focus to the next or previous focusable `Bin`.
+    ///
+    /// ***Note:** This is set by `add_enter_text_events`.*
+    pub fn set_accepts_tab(&self, accepts_tab: bool) {
+        self.accepts_tab
+            .store(accepts_tab, atomic::Ordering::SeqCst);
+    }
+
+    /// Check if Tab & Shift+Tab are passed to this `Bin` while it is focused.
+    pub fn accepts_tab(&self) -> bool {
+        self.accepts_tab.load(atomic::Ordering::SeqCst)
+    }
+
+    pub fn add_enter_text_events(self: &Arc<Self>) {
+        self.set_accepts_tab(true);
+
+        self.on_character(move |target, _, c| {
+            let this = target.into_bin().unwrap();
             let mut style = this.style_copy();
             c.modify_string(&mut style.text);
             this.style_update(style).expect_valid();
@@ -756,7 +1565,6 @@ impl Bin {
                         })
                         .expect_valid();
 
-                    target.trigger_children_update();
                     Default::default()
                 })
                 .finish()
@@ -769,6 +1577,39 @@ impl Bin {
         });
     }
 
+    /// Scroll the content of this `Bin` with the mouse wheel or trackpad.
+    ///
+    /// `scroll_y` & `scroll_x` are kept within the range of the overflow. When `momentum` is
+    /// `true` scrolling is smoothed and continues briefly after the input stops.
+    pub fn add_scroll_events(self: &Arc<Self>, momentum: bool) {
+        self.attach_input_hook(
+            self.basalt
+                .input_ref()
+                .hook()
+                .bin(self)
+                .on_scroll()
+                .enable_smooth(momentum)
+                .upper_blocks(true)
+                .call(move |target, _, v, h| {
+                    let bin = match target.into_bin() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
+
+                    // NOTE: Smooth scroll hooks are already provided pixels.
+                    let [v, h] = match momentum {
+                        true => [v, h],
+                        false => [v * SCROLL_STEP_PIXELS, h * SCROLL_STEP_PIXELS],
+                    };
+
+                    bin.scroll_by(h, v);
+                    Default::default()
+                })
+                .finish()
+                .unwrap(),
+        );
+    }
+
     pub fn fade_out(self: &Arc<Self>, millis: u64) {
         let bin_wk = Arc::downgrade(self);
         let start_opacity = self.style_copy().opacity.unwrap_or(1.0);
@@ -797,7 +1638,6 @@ impl Bin {
                 }
 
                 bin.style_update(copy).expect_valid();
-                bin.trigger_children_update();
                 step_i += 1;
                 Default::default()
             });
@@ -827,7 +1667,6 @@ impl Bin {
                 copy.opacity = Some(opacity);
                 copy.hidden = Some(false);
                 bin.style_update(copy).expect_valid();
-                bin.trigger_children_update();
                 step_i += 1;
                 Default::default()
             });
@@ -838,6 +1677,20 @@ impl Bin {
         self.input_hook_ids.lock().push(hook_id);
     }
 
+    /// Capture the pointer until `release_pointer` is called or this `Bin` is dropped.
+    pub fn capture_pointer(self: &Arc<Self>) {
+        self.basalt.input_ref().capture_pointer(self);
+    }
+
+    /// Release the pointer if it is captured by this `Bin`.
+    pub fn release_pointer(&self) {
+        if let Some(window) = self.window() {
+            self.basalt
+                .input_ref()
+                .release_pointer_from(window.id(), self.id);
+        }
+    }
+
     pub fn on_press<C: KeyCombo, F>(self: &Arc<Self>, combo: C, method: F) -> InputHookID
     where
         F: FnMut(InputHookTarget, &WindowState, &LocalKeyState) -> InputHookCtrl + Send + 'static,
@@ -1033,6 +1886,24 @@ impl Bin {
             .push(InternalHookFn::Updated(Box::new(func)));
     }
 
+    /// Called after an update when the overflow of this `Bin` has changed.
+    ///
+    /// The method is provided the horizontal and vertical overflow as calculated by
+    /// `calc_hori_overflow` and `calc_vert_overflow`.
+    #[inline]
+    pub fn on_overflow_changed<F: FnMut(&Arc<Bin>, f32, f32) + Send + 'static>(
+        self: &Arc<Self>,
+        func: F,
+    ) {
+        self.internal_hooks
+            .lock()
+            .get_mut(&InternalHookTy::OverflowChanged)
+            .unwrap()
+            .push(InternalHookFn::OverflowChanged(Arc::new(Mutex::new(
+                Box::new(func),
+            ))));
+    }
+
     fn call_children_added_hooks(self: &Arc<Self>, children: Vec<Arc<Bin>>) {
         for func_enum in self
             .internal_hooks
@@ -1062,468 +1933,159 @@ impl Bin {
     }
 
     fn calc_placement(&self, context: &mut UpdateContext) -> BinPlacement {
-        if let Some(placement) = context.placement_cache.get(&self.id) {
+        self.calc_placement_cached(context.extent, context.scale, &mut context.placement_cache)
+    }
+
+    /// Calculate the placement using & populating the provided cache.
+    pub(crate) fn calc_placement_cached(
+        &self,
+        window_extent: [f32; 2],
+        scale: f32,
+        placement_cache: &mut BTreeMap<BinID, BinPlacement>,
+    ) -> BinPlacement {
+        if let Some(placement) = placement_cache.get(&self.id) {
             return placement.clone();
         }
 
-        let extent = [
-            context.extent[0] / context.scale,
-            context.extent[1] / context.scale,
-        ];
+        let extent = [window_extent[0] / scale, window_extent[1] / scale];
 
         if self.initial.load(atomic::Ordering::SeqCst) {
-            return BinPlacement {
-                z: 0,
-                tlwh: [0.0, 0.0, extent[0], extent[1]],
-                bounds: [0.0, extent[0], 0.0, extent[1]],
-                opacity: 1.0,
-                hidden: false,
-            };
+            return BinPlacement::root(extent);
         }
 
-        let style = self.style.load();
-        let extent = context.extent;
+        let style = self.resolved_style.load();
+        let extent = window_extent;
         let position = style.position.unwrap_or(BinPosition::Window);
 
         if position == BinPosition::Floating {
-            let parent = self.parent().unwrap();
-            let parent_plmt = parent.calc_placement(context);
-
-            let (padding_tblr, scroll_xy, float_mode) = {
-                let parent_style = parent.style.load();
-
-                (
-                    [
-                        parent_style.pad_t.unwrap_or(0.0),
-                        parent_style.pad_b.unwrap_or(0.0),
-                        parent_style.pad_l.unwrap_or(0.0),
-                        parent_style.pad_r.unwrap_or(0.0),
-                    ],
-                    [
-                        parent_style.scroll_x.unwrap_or(0.0),
-                        parent_style.scroll_y.unwrap_or(0.0),
-                    ],
-                    parent_style.child_float_mode.unwrap_or(ChildFloatMode::Row),
-                )
+            let (parent, children) = match self.parent() {
+                Some(parent) => {
+                    (
+                        Some((
+                            parent.calc_placement_cached(window_extent, scale, placement_cache),
+                            parent.resolved_style.load_full(),
+                        )),
+                        parent.children(),
+                    )
+                },
+                None => (None, Vec::new()),
             };
 
-            let body_width = parent_plmt.tlwh[2] - padding_tblr[2] - padding_tblr[3];
-            let body_height = parent_plmt.tlwh[3] - padding_tblr[0] - padding_tblr[1];
-
-            struct Sibling {
-                this: bool,
-                weight: i16,
-                size_xy: [f32; 2],
-                margin_tblr: [f32; 4],
-            }
-
-            let mut siblings = parent
-                .children()
-                .into_iter()
-                .enumerate()
-                .filter_map(|(i, sibling)| {
-                    let sibling_style = sibling.style.load();
-
-                    // TODO: Ignore if hidden?
-                    if sibling_style.position != Some(BinPosition::Floating) {
-                        return None;
-                    }
-
-                    let width = match sibling_style.width {
-                        Some(width) => width,
-                        None => {
-                            match sibling_style.width_pct {
-                                Some(width_pct) => width_pct * body_width,
-                                None => unreachable!(),
-                            }
-                        },
-                    } + sibling_style.width_offset.unwrap_or(0.0);
-
-                    let height = match sibling_style.height {
-                        Some(height) => height,
-                        None => {
-                            match sibling_style.height_pct {
-                                Some(height_pct) => height_pct * body_height,
-                                None => unreachable!(),
-                            }
-                        },
-                    } + sibling_style.height_offset.unwrap_or(0.0);
-
-                    Some(Sibling {
-                        this: sibling.id == self.id,
-                        weight: sibling_style.float_weight.unwrap_or(i as i16),
-                        size_xy: [width, height],
-                        margin_tblr: [
-                            sibling_style.margin_t.unwrap_or(0.0),
-                            sibling_style.margin_b.unwrap_or(0.0),
-                            sibling_style.margin_l.unwrap_or(0.0),
-                            sibling_style.margin_r.unwrap_or(0.0),
-                        ],
-                    })
-                })
+            let siblings = children
+                .iter()
+                .map(|child| (child.id, child.resolved_style.load_full()))
                 .collect::<Vec<_>>();
 
-            siblings.sort_by_key(|sibling| sibling.weight);
-
-            let z = match style.z_index {
-                Some(z) => z,
-                None => parent_plmt.z + 1,
-            } + style.add_z_index.unwrap_or(0);
-
-            let opacity = match style.opacity {
-                Some(opacity) => parent_plmt.opacity * opacity,
-                None => parent_plmt.opacity,
-            };
-
-            let hidden = match style.hidden {
-                Some(hidden) => hidden,
-                None => parent_plmt.hidden,
+            let placements = match float_placements(
+                parent
+                    .as_ref()
+                    .map(|(parent_plmt, parent_style)| (parent_plmt, &**parent_style)),
+                &siblings,
+                scale,
+                |i, width| children[i].content_size(&siblings[i].1, scale, width),
+                |i, reason| children[i].warn_invalid_style(reason),
+            ) {
+                Ok(ok) => ok,
+                Err(reason) => return self.invalid_placement(reason, placement_cache),
             };
 
-            match float_mode {
-                ChildFloatMode::Row => {
-                    let mut x = 0.0;
-                    let mut y = 0.0;
-                    let mut row_height = 0.0;
-                    let mut row_bins = 0;
-
-                    for sibling in siblings {
-                        if sibling.this {
-                            let effective_width = sibling.size_xy[0]
-                                + sibling.margin_tblr[2]
-                                + sibling.margin_tblr[3];
-
-                            if x + effective_width > body_width && row_bins != 0 {
-                                x = 0.0;
-                                y += row_height;
-                            }
-
-                            let top =
-                                parent_plmt.tlwh[0] + y + padding_tblr[0] + sibling.margin_tblr[0]
-                                    - scroll_xy[1];
-                            let left = parent_plmt.tlwh[1]
-                                + x
-                                + padding_tblr[2]
-                                + sibling.margin_tblr[2]
-                                + scroll_xy[0];
-                            let [width, height] = sibling.size_xy;
-
-                            let x_bounds = match style.overflow_x.unwrap_or(false) {
-                                true => [parent_plmt.bounds[0], parent_plmt.bounds[1]],
-                                false => {
-                                    [
-                                        left.max(parent_plmt.bounds[0]),
-                                        (left + width).min(parent_plmt.bounds[1]),
-                                    ]
-                                },
-                            };
-
-                            let y_bounds = match style.overflow_y.unwrap_or(false) {
-                                true => [parent_plmt.bounds[2], parent_plmt.bounds[3]],
-                                false => {
-                                    [
-                                        top.max(parent_plmt.bounds[2]),
-                                        (top + height).min(parent_plmt.bounds[3]),
-                                    ]
-                                },
-                            };
-
-                            return BinPlacement {
-                                z,
-                                tlwh: [top, left, width, height],
-                                bounds: [x_bounds[0], x_bounds[1], y_bounds[0], y_bounds[1]],
-                                opacity,
-                                hidden,
-                            };
-                        } else {
-                            let effective_width = sibling.size_xy[0]
-                                + sibling.margin_tblr[2]
-                                + sibling.margin_tblr[3];
-                            let effective_height = sibling.size_xy[1]
-                                + sibling.margin_tblr[0]
-                                + sibling.margin_tblr[1];
-
-                            if x + effective_width > body_width {
-                                if row_bins == 0 {
-                                    y += effective_height;
-                                } else {
-                                    x = effective_width;
-                                    y += row_height;
-                                    row_height = effective_height;
-                                    row_bins = 1;
-                                }
-                            } else {
-                                x += effective_width;
-                                row_height = row_height.max(effective_height);
-                                row_bins += 1;
-                            }
-                        }
-                    }
-                },
-                ChildFloatMode::Column => {
-                    let mut x = 0.0;
-                    let mut y = 0.0;
-                    let mut col_width = 0.0;
-                    let mut col_bins = 0;
-
-                    for sibling in siblings {
-                        if sibling.this {
-                            let effective_height = sibling.size_xy[1]
-                                + sibling.margin_tblr[0]
-                                + sibling.margin_tblr[1];
-
-                            if y + effective_height > body_height && col_bins != 0 {
-                                y = 0.0;
-                                x += col_width;
-                            }
-
-                            let top =
-                                parent_plmt.tlwh[0] + y + padding_tblr[0] + sibling.margin_tblr[0]
-                                    - scroll_xy[1];
-                            let left = parent_plmt.tlwh[1]
-                                + x
-                                + padding_tblr[2]
-                                + sibling.margin_tblr[2]
-                                + scroll_xy[0];
-                            let [width, height] = sibling.size_xy;
-
-                            let x_bounds = match style.overflow_x.unwrap_or(false) {
-                                true => [parent_plmt.bounds[0], parent_plmt.bounds[1]],
-                                false => {
-                                    [
-                                        left.max(parent_plmt.bounds[0]),
-                                        (left + width).min(parent_plmt.bounds[1]),
-                                    ]
-                                },
-                            };
-
-                            let y_bounds = match style.overflow_y.unwrap_or(false) {
-                                true => [parent_plmt.bounds[2], parent_plmt.bounds[3]],
-                                false => {
-                                    [
-                                        top.max(parent_plmt.bounds[2]),
-                                        (top + height).min(parent_plmt.bounds[3]),
-                                    ]
-                                },
-                            };
-
-                            return BinPlacement {
-                                z,
-                                tlwh: [top, left, width, height],
-                                bounds: [x_bounds[0], x_bounds[1], y_bounds[0], y_bounds[1]],
-                                opacity,
-                                hidden,
-                            };
-                        } else {
-                            let effective_width = sibling.size_xy[0]
-                                + sibling.margin_tblr[2]
-                                + sibling.margin_tblr[3];
-                            let effective_height = sibling.size_xy[1]
-                                + sibling.margin_tblr[0]
-                                + sibling.margin_tblr[1];
-
-                            if y + effective_height > body_height {
-                                if col_bins == 0 {
-                                    x += effective_width;
-                                } else {
-                                    y = effective_height;
-                                    x += col_width;
-                                    col_width = effective_width;
-                                    col_bins = 1;
-                                }
-                            } else {
-                                y += effective_height;
-                                col_width = col_width.max(effective_width);
-                                col_bins += 1;
-                            }
-                        }
-                    }
-                },
-            }
-
-            unreachable!()
+            // NOTE: All siblings are placed at once, so their placements are cached as well.
+            placement_cache.extend(placements);
+            return placement_cache.get(&self.id).unwrap().clone();
         }
 
         let (parent_plmt, scroll_xy) = match position {
             BinPosition::Floating => unreachable!(),
-            BinPosition::Window => {
-                (
-                    BinPlacement {
-                        z: 0,
-                        tlwh: [0.0, 0.0, extent[0], extent[1]],
-                        bounds: [0.0, extent[0], 0.0, extent[1]],
-                        opacity: 1.0,
-                        hidden: false,
-                    },
-                    [0.0; 2],
-                )
-            },
+            BinPosition::Window => (BinPlacement::root(extent), [0.0; 2]),
             BinPosition::Parent => {
                 self.parent()
                     .map(|parent| {
                         (
-                            parent.calc_placement(context),
-                            parent.style_inspect(|style| {
-                                [style.scroll_x.unwrap_or(0.0), style.scroll_y.unwrap_or(0.0)]
-                            }),
-                        )
-                    })
-                    .unwrap_or_else(|| {
-                        (
-                            BinPlacement {
-                                z: 0,
-                                tlwh: [0.0, 0.0, extent[0], extent[1]],
-                                bounds: [0.0, extent[0], 0.0, extent[1]],
-                                opacity: 1.0,
-                                hidden: false,
+                            parent.calc_placement_cached(window_extent, scale, placement_cache),
+                            {
+                                let parent_style = parent.resolved_style.load();
+
+                                [
+                                    parent_style.scroll_x.unwrap_or(0.0),
+                                    parent_style.scroll_y.unwrap_or(0.0),
+                                ]
                             },
-                            [0.0; 2],
                         )
                     })
+                    .unwrap_or_else(|| (BinPlacement::root(extent), [0.0; 2]))
             },
         };
 
-        let top_op = match style.pos_from_t {
-            Some(top) => Some(top),
-            None => {
-                style
-                    .pos_from_t_pct
-                    .map(|top_pct| (top_pct / 100.0) * parent_plmt.tlwh[3])
+        match absolute_placement(self.id, &style, &parent_plmt, scroll_xy, scale, |width| {
+            self.content_size(&style, scale, width)
+        }) {
+            Ok(placement) => {
+                placement_cache.insert(self.id, placement.clone());
+                placement
             },
+            Err(reason) => self.invalid_placement(reason, placement_cache),
         }
-        .map(|top| top + style.pos_from_t_offset.unwrap_or(0.0));
+    }
 
-        let bottom_op = match style.pos_from_b {
-            Some(bottom) => Some(bottom),
-            None => {
-                style
-                    .pos_from_b_pct
-                    .map(|bottom_pct| (bottom_pct / 100.0) * parent_plmt.tlwh[3])
-            },
-        }
-        .map(|bottom| bottom + style.pos_from_b_offset.unwrap_or(0.0));
+    // NOTE: The size of the text including padding, used with `size_to_content`. When
+    //       `width` is provided, the text is wrapped to it.
+    fn content_size(&self, style: &BinStyle, scale: f32, width: Option<f32>) -> [f32; 2] {
+        let pad_x = style.pad_l.unwrap_or(0.0) + style.pad_r.unwrap_or(0.0);
+        let pad_y = style.pad_t.unwrap_or(0.0) + style.pad_b.unwrap_or(0.0);
 
-        let left_op = match style.pos_from_l {
-            Some(left) => Some(left),
-            None => {
-                style
-                    .pos_from_l_pct
-                    .map(|left_pct| (left_pct / 100.0) * parent_plmt.tlwh[2])
-            },
-        }
-        .map(|left| left + style.pos_from_l_offset.unwrap_or(0.0));
+        let text = match style.text_spans.as_ref() {
+            Some(text_spans) => text_spans.iter().map(|span| span.text.as_str()).collect(),
+            None => style.text.clone(),
+        };
 
-        let right_op = match style.pos_from_r {
-            Some(right) => Some(right),
-            None => {
-                style
-                    .pos_from_r_pct
-                    .map(|right_pct| (right_pct / 100.0) * parent_plmt.tlwh[2])
-            },
-        }
-        .map(|right| right + style.pos_from_r_offset.unwrap_or(0.0));
+        let metrics = self.basalt.interface_ref().measure_text_scaled(
+            &text,
+            style,
+            width.map(|width| (width - pad_x).max(0.0)),
+            scale,
+        );
 
-        let width_op = match style.width {
-            Some(width) => Some(width),
-            None => {
-                style
-                    .width_pct
-                    .map(|width_pct| (width_pct / 100.0) * parent_plmt.tlwh[2])
-            },
-        }
-        .map(|width| width + style.width_offset.unwrap_or(0.0));
+        // NOTE: Rounded up, so that the text doesn't wrap due to precision when laid out.
+        [metrics.width.ceil() + pad_x, metrics.height.ceil() + pad_y]
+    }
 
-        let height_op = match style.height {
-            Some(height) => Some(height),
-            None => {
-                style
-                    .height_pct
-                    .map(|height_pct| (height_pct / 100.0) * parent_plmt.tlwh[3])
-            },
-        }
-        .map(|height| height + style.height_offset.unwrap_or(0.0));
+    // NOTE: Validation should prevent styles that can't be placed, but if one occurs it is
+    //       hidden instead of panicking, so that other bins continue to be rendered.
+    fn invalid_placement(
+        &self,
+        reason: &str,
+        placement_cache: &mut BTreeMap<BinID, BinPlacement>,
+    ) -> BinPlacement {
+        self.warn_invalid_style(reason);
+        let placement = BinPlacement::invalid();
+
+        placement_cache.insert(self.id, placement.clone());
+        placement
+    }
 
-        let [top, height] = match (top_op, bottom_op, height_op) {
-            (Some(top), _, Some(height)) => [parent_plmt.tlwh[0] + top - scroll_xy[1], height],
-            (_, Some(bottom), Some(height)) => {
-                [
-                    parent_plmt.tlwh[0] + parent_plmt.tlwh[3] - bottom - height - scroll_xy[1],
-                    height,
-                ]
-            },
-            (Some(top), Some(bottom), _) => {
-                let top = parent_plmt.tlwh[0] + top + scroll_xy[1];
-                let bottom = parent_plmt.tlwh[0] + parent_plmt.tlwh[3] - bottom - scroll_xy[1];
-                [top, bottom - top + style.height_offset.unwrap_or(0.0)]
-            },
-            _ => panic!("invalid style"),
-        };
+    fn warn_invalid_style(&self, reason: &str) {
+        if !self
+            .invalid_style_warned
+            .swap(true, atomic::Ordering::SeqCst)
+        {
+            println!(
+                "[Basalt]: Bin {:?} has an invalid style: {}. It will be hidden.",
+                self.id, reason
+            );
+        }
+    }
 
-        let [left, width] = match (left_op, right_op, width_op) {
-            (Some(left), _, Some(width)) => [parent_plmt.tlwh[1] + left + scroll_xy[0], width],
-            (_, Some(right), Some(width)) => {
-                [
-                    parent_plmt.tlwh[1] + parent_plmt.tlwh[2] - right - width + scroll_xy[0],
-                    width,
-                ]
-            },
-            (Some(left), Some(right), _) => {
-                let left = parent_plmt.tlwh[1] + left + scroll_xy[0];
-                let right = parent_plmt.tlwh[1] + parent_plmt.tlwh[2] - right + scroll_xy[0];
-                [left, right - left + style.width_offset.unwrap_or(0.0)]
-            },
-            _ => panic!("invalid style"),
-        };
+    /// Complete an update where the vertex data wasn't obtained, as the inputs of this `Bin`
+    /// haven't changed since its last update.
+    pub(crate) fn complete_unchanged_update(self: &Arc<Self>) {
+        let bpu = self.post_update.read();
+        self.call_on_update_hooks(&bpu);
+        drop(bpu);
+        self.call_overflow_changed_hooks();
+    }
 
-        let z = match style.z_index {
-            Some(z) => z,
-            None => parent_plmt.z + 1,
-        } + style.add_z_index.unwrap_or(0);
-
-        let x_bounds = match style.overflow_x.unwrap_or(false) {
-            true => [parent_plmt.bounds[0], parent_plmt.bounds[1]],
-            false => {
-                [
-                    left.max(parent_plmt.bounds[0]),
-                    (left + width).min(parent_plmt.bounds[1]),
-                ]
-            },
-        };
-
-        let y_bounds = match style.overflow_y.unwrap_or(false) {
-            true => [parent_plmt.bounds[2], parent_plmt.bounds[3]],
-            false => {
-                [
-                    top.max(parent_plmt.bounds[2]),
-                    (top + height).min(parent_plmt.bounds[3]),
-                ]
-            },
-        };
-
-        let opacity = match style.opacity {
-            Some(opacity) => parent_plmt.opacity * opacity,
-            None => parent_plmt.opacity,
-        };
-
-        let hidden = match style.hidden {
-            Some(hidden) => hidden,
-            None => parent_plmt.hidden,
-        };
-
-        let placement = BinPlacement {
-            z,
-            tlwh: [top, left, width, height],
-            bounds: [x_bounds[0], x_bounds[1], y_bounds[0], y_bounds[1]],
-            opacity,
-            hidden,
-        };
-
-        context.placement_cache.insert(self.id, placement.clone());
-        placement
-    }
-
-    fn call_on_update_hooks(self: &Arc<Self>, bpu: &BinPostUpdate) {
-        let mut internal_hooks = self.internal_hooks.lock();
+    fn call_on_update_hooks(self: &Arc<Self>, bpu: &BinPostUpdate) {
+        let mut internal_hooks = self.internal_hooks.lock();
 
         for hook_enum in internal_hooks
             .get_mut(&InternalHookTy::Updated)
@@ -1546,6 +2108,173 @@ impl Bin {
         }
     }
 
+    /// Call the overflow changed hooks if the overflow has changed since they were last called.
+    ///
+    /// ***Note:** The overflow depends on the post update of the children, so this is also called
+    /// on parents once their children have been updated.*
+    pub(crate) fn call_overflow_changed_hooks(self: &Arc<Self>) {
+        // NOTE: The hooks are cloned, so that the lock isn't held while they are called. This
+        //       allows hooks to modify this bin, e.g. add more hooks.
+        let hooks = self
+            .internal_hooks
+            .lock()
+            .get(&InternalHookTy::OverflowChanged)
+            .unwrap()
+            .iter()
+            .filter_map(|hook_enum| {
+                match hook_enum {
+                    InternalHookFn::OverflowChanged(func) => Some(func.clone()),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if hooks.is_empty() {
+            return;
+        }
+
+        let hori = self.calc_hori_overflow();
+        let vert = self.calc_vert_overflow();
+
+        {
+            let mut last_overflow = self.last_overflow.lock();
+
+            if *last_overflow == Some([hori, vert]) {
+                return;
+            }
+
+            *last_overflow = Some([hori, vert]);
+        }
+
+        for func in hooks {
+            (func.lock())(self, hori, vert);
+        }
+    }
+
+    fn back_image_animation_frame(
+        self: &Arc<Self>,
+        image_cache_key: &ImageCacheKey,
+        animation: &ImageAnimation,
+    ) -> usize {
+        let mut animation_state = self.back_image_animation.lock();
+
+        if let Some(state) = animation_state.as_ref() {
+            if state.image_cache_key == *image_cache_key {
+                return state.frame.load(atomic::Ordering::SeqCst);
+            }
+
+            self.basalt.interval_ref().remove(state.hook_id);
+        }
+
+        let frame = Arc::new(AtomicUsize::new(0));
+        let hook_frame = frame.clone();
+        let bin_wk = Arc::downgrade(self);
+        let last_frame = animation.frame_count() - 1;
+        let mut elapsed = Duration::ZERO;
+        let mut reverse = false;
+
+        // NOTE: Some images specify a delay of zero which would cause a frame to never display.
+        let delays = animation
+            .delays
+            .iter()
+            .map(|delay| (*delay).max(Duration::from_millis(10)))
+            .collect::<Vec<_>>();
+
+        // NOTE: The hook is called at the delay of the current frame. The hook id is only known
+        //       after it is added, so it is retrieved from the animation state.
+        let hook_id = self
+            .basalt
+            .interval_ref()
+            .do_every(delays[0], None, move |last_call| {
+                let bin = match bin_wk.upgrade() {
+                    Some(some) => some,
+                    None => return IntvlHookCtrl::Remove,
+                };
+
+                let frame = hook_frame.load(atomic::Ordering::SeqCst);
+                elapsed += last_call.unwrap_or_default();
+
+                if elapsed < delays[frame] {
+                    return IntvlHookCtrl::Continue;
+                }
+
+                elapsed -= delays[frame];
+
+                let next_frame = match bin
+                    .resolved_style
+                    .load()
+                    .back_image_loop
+                    .unwrap_or_default()
+                {
+                    ImageLoop::Loop => {
+                        if frame >= last_frame {
+                            0
+                        } else {
+                            frame + 1
+                        }
+                    },
+                    ImageLoop::Once => {
+                        if frame >= last_frame {
+                            return IntvlHookCtrl::Remove;
+                        }
+
+                        frame + 1
+                    },
+                    ImageLoop::PingPong => {
+                        if frame >= last_frame {
+                            reverse = true;
+                        } else if frame == 0 {
+                            reverse = false;
+                        }
+
+                        if reverse {
+                            frame - 1
+                        } else {
+                            frame + 1
+                        }
+                    },
+                };
+
+                hook_frame.store(next_frame, atomic::Ordering::SeqCst);
+
+                if let Some(state) = bin.back_image_animation.lock().as_ref() {
+                    if Arc::ptr_eq(&state.frame, &hook_frame) {
+                        bin.basalt
+                            .interval_ref()
+                            .set_every(state.hook_id, delays[next_frame].saturating_sub(elapsed));
+                    }
+                }
+
+                if bin.post_update.read().visible {
+                    bin.trigger_update();
+                }
+
+                IntvlHookCtrl::Continue
+            });
+
+        self.basalt.interval_ref().start(hook_id);
+
+        *animation_state = Some(BackImageAnimation {
+            image_cache_key: image_cache_key.clone(),
+            frame,
+            hook_id,
+        });
+
+        0
+    }
+
+    fn stop_back_image_animation(&self) {
+        if let Some(state) = self.back_image_animation.lock().take() {
+            self.basalt.interval_ref().remove(state.hook_id);
+        }
+    }
+
+    /// A value that changes whenever this `Bin` requires its vertex data to be regenerated
+    /// regardless of its placement.
+    pub(crate) fn update_version(&self) -> u64 {
+        self.update_version.load(atomic::Ordering::SeqCst)
+    }
+
     pub(crate) fn obtain_vertex_data(
         self: &Arc<Self>,
         context: &mut UpdateContext,
@@ -1569,7 +2298,7 @@ impl Bin {
         // -- Obtain BinPostUpdate & Style --------------------------------------------------- //
 
         let mut bpu = self.post_update.write();
-        let style = self.style.load();
+        let style = self.resolved_style.load();
 
         if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
             metrics.style = inst.elapsed().as_micros() as f32 / 1000.0;
@@ -1580,20 +2309,36 @@ impl Bin {
 
         let BinPlacement {
             z: z_index,
+            z_layer,
             tlwh,
             bounds: inner_bounds,
             opacity,
             hidden,
+            layer,
+            layer_opacity,
         } = self.calc_placement(context);
 
+        let composite_layer = layer == Some(self.id);
+
         // -- Update BinPostUpdate ----------------------------------------------------------- //
 
         let last_text_state = bpu.text_state.extract();
         let [top, left, width, height] = tlwh;
-        let border_size_t = style.border_size_t.unwrap_or(0.0);
-        let border_size_b = style.border_size_b.unwrap_or(0.0);
-        let border_size_l = style.border_size_l.unwrap_or(0.0);
-        let border_size_r = style.border_size_r.unwrap_or(0.0);
+        let pixel_snap = style.pixel_snap.unwrap_or(false);
+
+        let [border_size_t, border_size_b, border_size_l, border_size_r] = [
+            style.border_size_t,
+            style.border_size_b,
+            style.border_size_l,
+            style.border_size_r,
+        ]
+        .map(|border_size| {
+            match pixel_snap {
+                true => snap_border(border_size.unwrap_or(0.0), context.scale),
+                false => border_size.unwrap_or(0.0),
+            }
+        });
+
         let margin_t = style.margin_t.unwrap_or(0.0);
         let margin_b = style.margin_b.unwrap_or(0.0);
         let margin_l = style.margin_l.unwrap_or(0.0);
@@ -1602,8 +2347,8 @@ impl Bin {
         let pad_b = style.pad_b.unwrap_or(0.0);
         let pad_l = style.pad_l.unwrap_or(0.0);
         let pad_r = style.pad_r.unwrap_or(0.0);
-        let base_z = z_unorm(z_index);
-        let content_z = z_unorm(z_index + 1);
+        let base_z = z_unorm(z_layer, z_index);
+        let content_z = z_unorm(z_layer, z_index + 1);
 
         let outer_bounds = [
             inner_bounds[0] - border_size_l,
@@ -1624,6 +2369,7 @@ impl Bin {
             bro: [left + width + border_size_r, top + height + border_size_b],
             bri: [left + width, top + height],
             z_index,
+            z_layer,
             optimal_inner_bounds: [left, left + width, top, top + height],
             optimal_outer_bounds: [
                 left - border_size_l.max(margin_l),
@@ -1646,6 +2392,20 @@ impl Bin {
             scale: context.scale,
         };
 
+        // SVG images are rasterized at about the size they are displayed at.
+        let back_image_key = style.back_image.clone().map(|image_cache_key| {
+            match image_cache_key {
+                ImageCacheKey::Path(_) | ImageCacheKey::Url(_) if image_cache_key.is_svg() => {
+                    ImageCacheKey::svg(
+                        image_cache_key,
+                        svg_raster_size(width * context.scale),
+                        svg_raster_size(height * context.scale),
+                    )
+                },
+                image_cache_key => image_cache_key,
+            }
+        });
+
         if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
             metrics.placement = inst.elapsed().as_micros() as f32 / 1000.0;
             *inst = Instant::now();
@@ -1665,7 +2425,7 @@ impl Bin {
 
             let mut vertex_data = HashMap::new();
 
-            match style.back_image.clone() {
+            match back_image_key {
                 Some(image_cache_key) => {
                     if self
                         .basalt
@@ -1738,10 +2498,19 @@ impl Bin {
                 }
             }
 
+            if composite_layer {
+                vertex_data.insert(
+                    ImageSource::Layer(self.id),
+                    layer_composite_vertexes(base_z, layer_opacity),
+                );
+            }
+
             // Post update things
 
             let bpu = RwLockWriteGuard::downgrade(bpu);
             self.call_on_update_hooks(&bpu);
+            drop(bpu);
+            self.call_overflow_changed_hooks();
 
             let metrics_op = metrics_op.take().map(|(inst, inst_total, mut metrics)| {
                 metrics.visibility = inst.elapsed().as_micros() as f32 / 1000.0;
@@ -1759,7 +2528,9 @@ impl Bin {
 
         // -- Background Image --------------------------------------------------------- //
 
-        let (back_image_src, mut back_image_coords) = match style.back_image.clone() {
+        let mut back_image_animated = false;
+
+        let (back_image_src, mut back_image_coords) = match back_image_key {
             Some(image_cache_key) => {
                 match self
                     .basalt
@@ -1767,106 +2538,60 @@ impl Bin {
                     .obtain_image_info(image_cache_key.clone())
                 {
                     Some(image_info) => {
-                        (
-                            ImageSource::Cache(image_cache_key),
-                            Coords::new(image_info.width as f32, image_info.height as f32),
-                        )
-                    },
-                    None => {
-                        match &image_cache_key {
-                            ImageCacheKey::Path(_path) => {
-                                #[cfg(feature = "image_decode")]
-                                {
-                                    match self.basalt.image_cache_ref().load_from_path(
-                                        ImageCacheLifetime::Immeditate,
-                                        (),
-                                        _path,
-                                    ) {
-                                        Ok(image_info) => {
-                                            (
-                                                ImageSource::Cache(image_cache_key),
-                                                Coords::new(
-                                                    image_info.width as f32,
-                                                    image_info.height as f32,
-                                                ),
-                                            )
-                                        },
-                                        Err(e) => {
-                                            println!(
-                                                "[Basalt]: Bin ID: {:?} | Failed to load image \
-                                                 from path, '{}': {}",
-                                                self.id,
-                                                _path.display(),
-                                                e
-                                            );
-                                            (ImageSource::None, Coords::new(0.0, 0.0))
-                                        },
-                                    }
-                                }
-                                #[cfg(not(feature = "image_decode"))]
-                                {
-                                    println!(
-                                        "[Basalt]: Bin ID: {:?} | Unable to load image via path. \
-                                         'image_decode' feature is not enabled.",
-                                        self.id,
-                                    );
-                                    (ImageSource::None, Coords::new(0.0, 0.0))
+                        let coords = match image_info.animation.as_ref() {
+                            Some(animation) => {
+                                back_image_animated = true;
+                                let frame =
+                                    self.back_image_animation_frame(&image_cache_key, animation);
+
+                                Coords {
+                                    tlwh: animation.frame_coords(frame),
                                 }
                             },
-                            ImageCacheKey::Url(_url) => {
-                                #[cfg(feature = "image_download")]
-                                {
-                                    match self.basalt.image_cache_ref().load_from_url(
-                                        ImageCacheLifetime::Immeditate,
-                                        (),
-                                        _url.as_str(),
-                                    ) {
-                                        Ok(image_info) => {
-                                            (
-                                                ImageSource::Cache(image_cache_key),
-                                                Coords::new(
-                                                    image_info.width as f32,
-                                                    image_info.height as f32,
-                                                ),
-                                            )
-                                        },
-                                        Err(e) => {
-                                            println!(
-                                                "[Basalt]: Bin ID: {:?} | Failed to load image \
-                                                 from url, '{}': {}",
-                                                self.id, _url, e
-                                            );
-                                            (ImageSource::None, Coords::new(0.0, 0.0))
-                                        },
-                                    }
-                                }
-                                #[cfg(not(feature = "image_download"))]
-                                {
-                                    println!(
-                                        "[Basalt]: Bin ID: {:?} | Unable to download image from \
-                                         url. 'image_download' feature is not enabled.",
-                                        self.id,
-                                    );
-                                    (ImageSource::None, Coords::new(0.0, 0.0))
+                            None => Coords::new(image_info.width as f32, image_info.height as f32),
+                        };
+
+                        (ImageSource::Cache(image_cache_key), coords)
+                    },
+                    None if self
+                        .basalt
+                        .image_cache_ref()
+                        .load_error(&image_cache_key)
+                        .is_some() =>
+                    {
+                        // NOTE: The error was reported when the load failed.
+                        (ImageSource::None, Coords::new(0.0, 0.0))
+                    },
+                    None => {
+                        // NOTE: The image is loaded in the background and the back image is
+                        //       omitted until it has loaded. If this Bin is dropped before then,
+                        //       the image is still added to the cache.
+
+                        let bin_wk = Arc::downgrade(self);
+                        let bin_id = self.id;
+
+                        self.basalt.image_cache_ref().load_from_key_async_with(
+                            ImageCacheLifetime::Immeditate,
+                            (),
+                            image_cache_key,
+                            move |result| {
+                                match result {
+                                    Ok(_) => {
+                                        if let Some(bin) = bin_wk.upgrade() {
+                                            bin.trigger_update();
+                                        }
+                                    },
+                                    Err(e) => {
+                                        println!(
+                                            "[Basalt]: Bin ID: {:?} | Failed to load image: {}",
+                                            bin_id, e
+                                        );
+                                    },
                                 }
                             },
-                            ImageCacheKey::Glyph(_) => {
-                                println!(
-                                    "[Basalt]: Bin ID: {:?} | Unable to use glyph cache key to \
-                                     load image.",
-                                    self.id,
-                                );
-                                (ImageSource::None, Coords::new(0.0, 0.0))
-                            },
-                            ImageCacheKey::User(..) => {
-                                println!(
-                                    "[Basalt]: Bin ID: {:?} | Unable to use user cache key to \
-                                     load image.",
-                                    self.id,
-                                );
-                                (ImageSource::None, Coords::new(0.0, 0.0))
-                            },
-                        }
+                        );
+
+                        (ImageSource::None, Coords::new(0.0, 0.0))
                     },
                 }
             },
@@ -1884,13 +2609,21 @@ impl Bin {
             },
         };
 
+        if !back_image_animated {
+            self.stop_back_image_animation();
+        }
+
         if let Some(user_coords) = style.back_image_coords.as_ref() {
+            // NOTE: With animated images, the coords are relative to the current frame.
+            let [frame_top, frame_left, ..] = back_image_coords.tlwh;
             back_image_coords.tlwh[0] = user_coords[0];
             back_image_coords.tlwh[1] = user_coords[1];
             back_image_coords.tlwh[2] =
                 user_coords[2].clamp(0.0, back_image_coords.tlwh[2] - back_image_coords.tlwh[1]);
             back_image_coords.tlwh[3] =
                 user_coords[3].clamp(0.0, back_image_coords.tlwh[3] - back_image_coords.tlwh[0]);
+            back_image_coords.tlwh[0] += frame_top;
+            back_image_coords.tlwh[1] += frame_left;
         }
 
         if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
@@ -1935,6 +2668,10 @@ impl Bin {
             a: 0.0,
         });
 
+        // NOTE: Background image vertexes have the opacity applied to their output instead, as
+        //       the back color is an input to the image effects.
+        let back_image_color = back_color.rgbaf_array();
+
         if opacity != 1.0 {
             border_color_t.a *= opacity;
             border_color_b.a *= opacity;
@@ -2408,31 +3145,30 @@ impl Bin {
 
         let mut outer_vert_data: HashMap<ImageSource, Vec<ItfVertInfo>> = HashMap::new();
 
-        if back_image_src != ImageSource::None {
-            let ty = style
-                .back_image_effect
-                .as_ref()
-                .map(|effect| effect.vert_type())
-                .unwrap_or(100);
-            let color = back_color.rgbaf_array();
+        let (back_image_dst, back_image_coords) = back_image_coords.fit(
+            style.back_image_fit.unwrap_or_default(),
+            [top, left, width, height],
+        );
 
-            outer_vert_data.entry(back_image_src).or_default().append(
-                &mut back_vertexes
-                    .into_iter()
-                    .map(|[x, y]| {
-                        ItfVertInfo {
-                            position: [x, y, base_z],
-                            coords: [
-                                back_image_coords.x_pct((x - left) / width),
-                                back_image_coords.y_pct((y - top) / height),
-                            ],
-                            color,
-                            ty,
-                            tex_i: 0,
-                        }
-                    })
-                    .collect(),
-            );
+        let back_image_ty = style
+            .back_image_effect
+            .as_ref()
+            .map(|effect| effect.vert_type())
+            .unwrap_or(100);
+
+        if back_image_src != ImageSource::None && back_image_dst == [top, left, width, height] {
+            outer_vert_data
+                .entry(back_image_src)
+                .or_default()
+                .append(&mut back_image_vertexes(
+                    back_vertexes,
+                    back_image_dst,
+                    &back_image_coords,
+                    base_z,
+                    back_image_color,
+                    back_image_ty,
+                    opacity,
+                ));
         } else {
             let color = back_color.rgbaf_array();
 
@@ -2449,10 +3185,35 @@ impl Bin {
                                 color,
                                 ty: 0,
                                 tex_i: 0,
+                                opacity: 1.0,
                             }
                         })
                         .collect(),
                 );
+
+            // NOTE: The image only covers part of this bin, so it is placed atop the background.
+            if back_image_src != ImageSource::None {
+                let [t, l, w, h] = back_image_dst;
+
+                outer_vert_data.entry(back_image_src).or_default().append(
+                    &mut back_image_vertexes(
+                        [
+                            [l + w, t],
+                            [l, t],
+                            [l, t + h],
+                            [l + w, t],
+                            [l, t + h],
+                            [l + w, t + h],
+                        ],
+                        back_image_dst,
+                        &back_image_coords,
+                        base_z,
+                        back_image_color,
+                        back_image_ty,
+                        opacity,
+                    ),
+                );
+            }
         }
 
         if !border_vertexes.is_empty() {
@@ -2469,6 +3230,7 @@ impl Bin {
                                 color: color.rgbaf_array(),
                                 ty: 0,
                                 tex_i: 0,
+                                opacity: 1.0,
                             }
                         })
                         .collect(),
@@ -2489,7 +3251,7 @@ impl Bin {
                         let z = if vertex.position.2 == 0 {
                             content_z
                         } else {
-                            z_unorm(vertex.position.2)
+                            z_unorm(z_layer, vertex.position.2)
                         };
 
                         let x = left + vertex.position.0;
@@ -2507,6 +3269,7 @@ impl Bin {
                             color: color.rgbaf_array(),
                             ty: 0,
                             tex_i: 0,
+                            opacity: 1.0,
                         }
                     })
                     .collect(),
@@ -2534,6 +3297,30 @@ impl Bin {
         bpu.text_state
             .update_layout(context, self.basalt.image_cache_ref());
         bpu.text_state.update_vertexes(Some(&mut inner_vert_data));
+        let text_highlights = self.text_highlights.lock().clone();
+
+        if !text_highlights.is_empty() {
+            // NOTE: Untextured vertexes are drawn before textured ones of the same z, so these
+            //       are drawn behind the glyphs.
+            let vertexes = inner_vert_data.entry(ImageSource::None).or_default();
+
+            for ([t, l, w, h], mut color) in bpu.text_state.highlight_tlwhs(&text_highlights) {
+                color.a *= opacity;
+                let color = color.rgbaf_array();
+                let [r, b] = [l + w, t + h];
+
+                for [x, y] in [[r, t], [l, t], [l, b], [r, t], [l, b], [r, b]] {
+                    vertexes.push(ItfVertInfo {
+                        position: [x, y, content_z],
+                        coords: [0.0; 2],
+                        color,
+                        ty: 0,
+                        tex_i: 0,
+                        opacity: 1.0,
+                    });
+                }
+            }
+        }
 
         if let Some(text_bounds) = bpu.text_state.bounds() {
             match bpu.content_bounds.as_mut() {
@@ -2556,11 +3343,19 @@ impl Bin {
 
         // -- Bounds Checks --------------------------------------------------------------------- //
 
+        let clip_content = style.clip_content.unwrap_or(true);
         let mut vert_data = inner_vert_data.values_mut();
         let mut bounds = inner_bounds;
 
         for vdi in 0..2 {
             for vertexes in vert_data {
+                // NOTE: Typically all vertexes are within the bounds, so check that first to
+                //       avoid the per-triangle checks.
+
+                if !clip_content || vertexes_within_bounds(vertexes, bounds) {
+                    continue;
+                }
+
                 let mut remove_indexes = Vec::new();
                 let mut x_lt = Vec::with_capacity(2);
                 let mut x_gt = Vec::with_capacity(2);
@@ -2729,12 +3524,23 @@ impl Bin {
             verts.shrink_to_fit();
         }
 
+        // NOTE: The composite of the layer covers the whole window, as descendants aren't
+        //       necessarily within the bounds of this bin.
+        if composite_layer {
+            vert_data.insert(
+                ImageSource::Layer(self.id),
+                layer_composite_vertexes(base_z, layer_opacity),
+            );
+        }
+
         if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
             metrics.vertex_scale = inst.elapsed().as_micros() as f32 / 1000.0;
         }
 
         let bpu = RwLockWriteGuard::downgrade(bpu);
         self.call_on_update_hooks(&bpu);
+        drop(bpu);
+        self.call_overflow_changed_hooks();
 
         (
             vert_data,
@@ -2747,9 +3553,623 @@ impl Bin {
     }
 }
 
+// NOTE: The layer & z-index are combined into a 24-bit value so that it is exactly
+//       representable as a f32 within the range of 0..1.
 #[inline(always)]
-fn z_unorm(z: i16) -> f32 {
-    (z as f32 + i16::max_value() as f32) / u16::max_value() as f32
+// NOTE: The signal never blocks the caller. If it is dropped without being called, the receiver
+//       is disconnected, so waiting on it doesn't block forever.
+fn update_signal() -> (impl FnMut() + Send + 'static, flume::Receiver<()>) {
+    let (send, recv) = flume::bounded(1);
+
+    (
+        move || {
+            let _ = send.try_send(());
+        },
+        recv,
+    )
+}
+
+// NOTE: Returns the placements of the floating bins within `siblings`, which are all of the
+//       children of `parent`. `content_size` & `warn_invalid` are given the index of the sibling.
+fn float_placements(
+    parent: Option<(&BinPlacement, &BinStyle)>,
+    siblings: &[(BinID, Arc<BinStyle>)],
+    scale: f32,
+    mut content_size: impl FnMut(usize, Option<f32>) -> [f32; 2],
+    mut warn_invalid: impl FnMut(usize, &str),
+) -> Result<Vec<(BinID, BinPlacement)>, &'static str> {
+    let (parent_plmt, parent_style) = match parent {
+        Some(some) => some,
+        None => return Err("floating without a parent"),
+    };
+
+    let (padding_tblr, scroll_xy, float_mode, float_wrap, float_justify, float_align) = (
+        [
+            parent_style.pad_t.unwrap_or(0.0),
+            parent_style.pad_b.unwrap_or(0.0),
+            parent_style.pad_l.unwrap_or(0.0),
+            parent_style.pad_r.unwrap_or(0.0),
+        ],
+        [
+            parent_style.scroll_x.unwrap_or(0.0),
+            parent_style.scroll_y.unwrap_or(0.0),
+        ],
+        parent_style.child_float_mode.unwrap_or(ChildFloatMode::Row),
+        parent_style.child_float_wrap.unwrap_or(true),
+        parent_style
+            .child_float_justify
+            .unwrap_or(ChildFloatJustify::Start),
+        parent_style
+            .child_float_align
+            .unwrap_or(ChildFloatAlign::Start),
+    );
+
+    let body_width = parent_plmt.tlwh[2] - padding_tblr[2] - padding_tblr[3];
+    let body_height = parent_plmt.tlwh[3] - padding_tblr[0] - padding_tblr[1];
+
+    struct Sibling {
+        id: BinID,
+        weight: i16,
+        size_xy: [f32; 2],
+        margin_tblr: [f32; 4],
+        z: i16,
+        z_layer: i8,
+        opacity: f32,
+        hidden: bool,
+        layer: Option<BinID>,
+        layer_opacity: f32,
+        overflow_xy: [bool; 2],
+        pixel_snap: bool,
+    }
+
+    impl Sibling {
+        fn effective_size(&self) -> [f32; 2] {
+            [
+                self.size_xy[0] + self.margin_tblr[2] + self.margin_tblr[3],
+                self.size_xy[1] + self.margin_tblr[0] + self.margin_tblr[1],
+            ]
+        }
+    }
+
+    let mut siblings = siblings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (id, sibling_style))| {
+            // TODO: Ignore if hidden?
+            if sibling_style.position != Some(BinPosition::Floating) {
+                return None;
+            }
+
+            // NOTE: Validation should prevent the size from being undefined, but if it
+            //       is the sibling is hidden instead of panicking.
+            let mut invalid = false;
+
+            let auto_size = sibling_style.size_to_content.unwrap_or(false);
+
+            let width = match sibling_style.width {
+                Some(width) => width,
+                None => {
+                    match sibling_style.width_pct {
+                        Some(width_pct) => (width_pct / 100.0) * body_width,
+                        None if auto_size => content_size(i, None)[0],
+                        None => {
+                            invalid = true;
+                            0.0
+                        },
+                    }
+                },
+            } + sibling_style.width_offset.unwrap_or(0.0);
+
+            let height = match sibling_style.height {
+                Some(height) => height,
+                None => {
+                    match sibling_style.height_pct {
+                        Some(height_pct) => (height_pct / 100.0) * body_height,
+                        None if auto_size => content_size(i, Some(width))[1],
+                        None => {
+                            invalid = true;
+                            0.0
+                        },
+                    }
+                },
+            } + sibling_style.height_offset.unwrap_or(0.0);
+
+            if invalid {
+                warn_invalid(i, "floating without a size");
+            }
+
+            let z = match sibling_style.z_index {
+                Some(z) => z,
+                None => parent_plmt.z + 1,
+            } + sibling_style.add_z_index.unwrap_or(0);
+
+            let z_layer = sibling_style.z_layer.unwrap_or(parent_plmt.z_layer);
+
+            let hidden = invalid
+                || match sibling_style.hidden {
+                    Some(hidden) => hidden,
+                    None => parent_plmt.hidden,
+                };
+
+            let (opacity, layer, layer_opacity) =
+                parent_plmt.child_opacity(*id, sibling_style, hidden);
+
+            Some(Sibling {
+                id: *id,
+                weight: sibling_style.float_weight.unwrap_or(i as i16),
+                size_xy: [width, height],
+                margin_tblr: [
+                    sibling_style.margin_t.unwrap_or(0.0),
+                    sibling_style.margin_b.unwrap_or(0.0),
+                    sibling_style.margin_l.unwrap_or(0.0),
+                    sibling_style.margin_r.unwrap_or(0.0),
+                ],
+                z,
+                z_layer,
+                opacity,
+                hidden,
+                layer,
+                layer_opacity,
+                overflow_xy: [
+                    sibling_style.overflow_x.unwrap_or(false),
+                    sibling_style.overflow_y.unwrap_or(false),
+                ],
+                pixel_snap: sibling_style.pixel_snap.unwrap_or(false),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    siblings.sort_by_key(|sibling| sibling.weight);
+
+    let offsets = float_offsets(
+        &siblings
+            .iter()
+            .map(|sibling| sibling.effective_size())
+            .collect::<Vec<_>>(),
+        [body_width, body_height],
+        float_mode,
+        float_wrap,
+        float_justify,
+        float_align,
+    );
+
+    Ok(siblings
+        .iter()
+        .zip(offsets)
+        .map(|(sibling, xy)| {
+            let top = parent_plmt.tlwh[0] + xy[1] + padding_tblr[0] + sibling.margin_tblr[0]
+                - scroll_xy[1];
+            let left = parent_plmt.tlwh[1]
+                + xy[0]
+                + padding_tblr[2]
+                + sibling.margin_tblr[2]
+                + scroll_xy[0];
+            let [width, height] = sibling.size_xy;
+
+            let [top, left, width, height] = match sibling.pixel_snap {
+                true => snap_tlwh([top, left, width, height], scale),
+                false => [top, left, width, height],
+            };
+
+            let x_bounds = match sibling.overflow_xy[0] {
+                true => [parent_plmt.bounds[0], parent_plmt.bounds[1]],
+                false => {
+                    [
+                        left.max(parent_plmt.bounds[0]),
+                        (left + width).min(parent_plmt.bounds[1]),
+                    ]
+                },
+            };
+
+            let y_bounds = match sibling.overflow_xy[1] {
+                true => [parent_plmt.bounds[2], parent_plmt.bounds[3]],
+                false => {
+                    [
+                        top.max(parent_plmt.bounds[2]),
+                        (top + height).min(parent_plmt.bounds[3]),
+                    ]
+                },
+            };
+
+            (
+                sibling.id,
+                BinPlacement {
+                    z: sibling.z,
+                    z_layer: sibling.z_layer,
+                    tlwh: [top, left, width, height],
+                    bounds: [x_bounds[0], x_bounds[1], y_bounds[0], y_bounds[1]],
+                    opacity: sibling.opacity,
+                    hidden: sibling.hidden,
+                    layer: sibling.layer,
+                    layer_opacity: sibling.layer_opacity,
+                },
+            )
+        })
+        .collect())
+}
+
+// NOTE: `content_size` is given the width the content is wrapped to, if any.
+fn absolute_placement(
+    id: BinID,
+    style: &BinStyle,
+    parent_plmt: &BinPlacement,
+    scroll_xy: [f32; 2],
+    scale: f32,
+    mut content_size: impl FnMut(Option<f32>) -> [f32; 2],
+) -> Result<BinPlacement, &'static str> {
+    let top_op = match style.pos_from_t {
+        Some(top) => Some(top),
+        None => {
+            style
+                .pos_from_t_pct
+                .map(|top_pct| (top_pct / 100.0) * parent_plmt.tlwh[3])
+        },
+    }
+    .map(|top| top + style.pos_from_t_offset.unwrap_or(0.0));
+
+    let bottom_op = match style.pos_from_b {
+        Some(bottom) => Some(bottom),
+        None => {
+            style
+                .pos_from_b_pct
+                .map(|bottom_pct| (bottom_pct / 100.0) * parent_plmt.tlwh[3])
+        },
+    }
+    .map(|bottom| bottom + style.pos_from_b_offset.unwrap_or(0.0));
+
+    let left_op = match style.pos_from_l {
+        Some(left) => Some(left),
+        None => {
+            style
+                .pos_from_l_pct
+                .map(|left_pct| (left_pct / 100.0) * parent_plmt.tlwh[2])
+        },
+    }
+    .map(|left| left + style.pos_from_l_offset.unwrap_or(0.0));
+
+    let right_op = match style.pos_from_r {
+        Some(right) => Some(right),
+        None => {
+            style
+                .pos_from_r_pct
+                .map(|right_pct| (right_pct / 100.0) * parent_plmt.tlwh[2])
+        },
+    }
+    .map(|right| right + style.pos_from_r_offset.unwrap_or(0.0));
+
+    let width_op = match style.width {
+        Some(width) => Some(width),
+        None => {
+            style
+                .width_pct
+                .map(|width_pct| (width_pct / 100.0) * parent_plmt.tlwh[2])
+        },
+    }
+    .map(|width| width + style.width_offset.unwrap_or(0.0));
+
+    let height_op = match style.height {
+        Some(height) => Some(height),
+        None => {
+            style
+                .height_pct
+                .map(|height_pct| (height_pct / 100.0) * parent_plmt.tlwh[3])
+        },
+    }
+    .map(|height| height + style.height_offset.unwrap_or(0.0));
+
+    let size_to_content = style.size_to_content.unwrap_or(false);
+
+    let width_op = match width_op {
+        None if size_to_content && (left_op.is_none() || right_op.is_none()) => {
+            Some(content_size(None)[0])
+        },
+        width_op => width_op,
+    };
+
+    let height_op = match height_op {
+        None if size_to_content && (top_op.is_none() || bottom_op.is_none()) => {
+            let max_width = match (width_op, left_op, right_op) {
+                (Some(width), ..) => Some(width),
+                (None, Some(left), Some(right)) => Some(parent_plmt.tlwh[2] - left - right),
+                _ => None,
+            };
+
+            Some(content_size(max_width)[1])
+        },
+        height_op => height_op,
+    };
+
+    let [top, height] = match (top_op, bottom_op, height_op) {
+        (Some(top), _, Some(height)) => [parent_plmt.tlwh[0] + top - scroll_xy[1], height],
+        (_, Some(bottom), Some(height)) => {
+            [
+                parent_plmt.tlwh[0] + parent_plmt.tlwh[3] - bottom - height - scroll_xy[1],
+                height,
+            ]
+        },
+        (Some(top), Some(bottom), _) => {
+            let top = parent_plmt.tlwh[0] + top + scroll_xy[1];
+            let bottom = parent_plmt.tlwh[0] + parent_plmt.tlwh[3] - bottom - scroll_xy[1];
+            [top, bottom - top + style.height_offset.unwrap_or(0.0)]
+        },
+        _ => return Err("vertical position undefined"),
+    };
+
+    let [left, width] = match (left_op, right_op, width_op) {
+        (Some(left), _, Some(width)) => [parent_plmt.tlwh[1] + left + scroll_xy[0], width],
+        (_, Some(right), Some(width)) => {
+            [
+                parent_plmt.tlwh[1] + parent_plmt.tlwh[2] - right - width + scroll_xy[0],
+                width,
+            ]
+        },
+        (Some(left), Some(right), _) => {
+            let left = parent_plmt.tlwh[1] + left + scroll_xy[0];
+            let right = parent_plmt.tlwh[1] + parent_plmt.tlwh[2] - right + scroll_xy[0];
+            [left, right - left + style.width_offset.unwrap_or(0.0)]
+        },
+        _ => return Err("horizontal position undefined"),
+    };
+
+    let [top, left, width, height] = match style.pixel_snap.unwrap_or(false) {
+        true => snap_tlwh([top, left, width, height], scale),
+        false => [top, left, width, height],
+    };
+
+    let z = match style.z_index {
+        Some(z) => z,
+        None => parent_plmt.z + 1,
+    } + style.add_z_index.unwrap_or(0);
+
+    let z_layer = style.z_layer.unwrap_or(parent_plmt.z_layer);
+
+    let x_bounds = match style.overflow_x.unwrap_or(false) {
+        true => [parent_plmt.bounds[0], parent_plmt.bounds[1]],
+        false => {
+            [
+                left.max(parent_plmt.bounds[0]),
+                (left + width).min(parent_plmt.bounds[1]),
+            ]
+        },
+    };
+
+    let y_bounds = match style.overflow_y.unwrap_or(false) {
+        true => [parent_plmt.bounds[2], parent_plmt.bounds[3]],
+        false => {
+            [
+                top.max(parent_plmt.bounds[2]),
+                (top + height).min(parent_plmt.bounds[3]),
+            ]
+        },
+    };
+
+    let hidden = match style.hidden {
+        Some(hidden) => hidden,
+        None => parent_plmt.hidden,
+    };
+
+    let (opacity, layer, layer_opacity) = parent_plmt.child_opacity(id, style, hidden);
+
+    Ok(BinPlacement {
+        z,
+        z_layer,
+        tlwh: [top, left, width, height],
+        bounds: [x_bounds[0], x_bounds[1], y_bounds[0], y_bounds[1]],
+        opacity,
+        hidden,
+        layer,
+        layer_opacity,
+    })
+}
+
+// NOTE: Returns the offset of each floating sibling within the body of the parent. Sizes include
+//       margins. Siblings are placed in terms of a main & cross axis. With ChildFloatMode::Row
+//       the main axis is horizontal and with ChildFloatMode::Column it is vertical.
+fn float_offsets(
+    sizes: &[[f32; 2]],
+    body: [f32; 2],
+    mode: ChildFloatMode,
+    wrap: bool,
+    justify: ChildFloatJustify,
+    align: ChildFloatAlign,
+) -> Vec<[f32; 2]> {
+    let (main_i, cross_i, reverse) = match mode {
+        ChildFloatMode::Row => (0, 1, false),
+        ChildFloatMode::Column => (1, 0, false),
+        ChildFloatMode::RowReverse => (0, 1, true),
+        ChildFloatMode::ColumnReverse => (1, 0, true),
+    };
+
+    let body_main = body[main_i];
+    let mut lines: Vec<Range<usize>> = Vec::new();
+    let mut line_main = 0.0;
+
+    for (i, size) in sizes.iter().enumerate() {
+        match lines.last_mut() {
+            Some(line) if !wrap || line_main + size[main_i] <= body_main => {
+                line.end = i + 1;
+                line_main += size[main_i];
+            },
+            _ => {
+                lines.push(i..(i + 1));
+                line_main = size[main_i];
+            },
+        }
+    }
+
+    let mut offsets = vec![[0.0; 2]; sizes.len()];
+    let mut cross_offset = 0.0;
+
+    for line in lines {
+        let line_sizes = &sizes[line.clone()];
+        let line_main: f32 = line_sizes.iter().map(|size| size[main_i]).sum();
+        let line_cross = line_sizes
+            .iter()
+            .map(|size| size[cross_i])
+            .fold(0.0, f32::max);
+        let free_space = (body_main - line_main).max(0.0);
+        let count = line_sizes.len() as f32;
+
+        let (mut main_offset, spacing) = match justify {
+            ChildFloatJustify::Start => (0.0, 0.0),
+            ChildFloatJustify::Center => (free_space / 2.0, 0.0),
+            ChildFloatJustify::End => (free_space, 0.0),
+            ChildFloatJustify::SpaceBetween => {
+                if line_sizes.len() > 1 {
+                    (0.0, free_space / (count - 1.0))
+                } else {
+                    (0.0, 0.0)
+                }
+            },
+            ChildFloatJustify::SpaceAround => (free_space / count / 2.0, free_space / count),
+        };
+
+        for (i, size) in line.zip(line_sizes) {
+            let cross_align = match align {
+                ChildFloatAlign::Start => 0.0,
+                ChildFloatAlign::Center => (line_cross - size[cross_i]) / 2.0,
+                ChildFloatAlign::End => line_cross - size[cross_i],
+            };
+
+            offsets[i][main_i] = match reverse {
+                true => body_main - main_offset - size[main_i],
+                false => main_offset,
+            };
+
+            offsets[i][cross_i] = cross_offset + cross_align;
+            main_offset += size[main_i] + spacing;
+        }
+
+        cross_offset += line_cross;
+    }
+
+    offsets
+}
+
+fn outer_contains(post: &BinPostUpdate, x: f32, y: f32) -> bool {
+    x >= post.tlo[0] && x <= post.tro[0] && y >= post.tlo[1] && y <= post.blo[1]
+}
+
+fn z_unorm(z_layer: i8, z: i16) -> f32 {
+    let layer = (z_layer as i32 - i8::MIN as i32) << 16;
+    let z = z as i32 - i16::MIN as i32;
+    (layer | z) as f32 / (1 << 24) as f32
+}
+
+// Group the ids by window, so that a single update is dispatched per window.
+fn batch_by_window<W, I>(items: I) -> Vec<(Arc<W>, Vec<BinID>)>
+where
+    I: IntoIterator<Item = (Arc<W>, Vec<BinID>)>,
+{
+    let mut batches: Vec<(Arc<W>, Vec<BinID>)> = Vec::new();
+
+    for (window, bin_ids) in items {
+        match batches
+            .iter_mut()
+            .find(|(batch_window, _)| Arc::ptr_eq(batch_window, &window))
+        {
+            Some((_, batch)) => batch.extend(bin_ids),
+            None => batches.push((window, bin_ids)),
+        }
+    }
+
+    batches
+}
+
+fn back_image_vertexes<P: IntoIterator<Item = [f32; 2]>>(
+    points: P,
+    dst_tlwh: [f32; 4],
+    coords: &Coords,
+    z: f32,
+    color: [f32; 4],
+    ty: i32,
+    opacity: f32,
+) -> Vec<ItfVertInfo> {
+    let [t, l, w, h] = dst_tlwh;
+
+    points
+        .into_iter()
+        .map(|[x, y]| {
+            ItfVertInfo {
+                position: [x, y, z],
+                coords: [coords.x_pct((x - l) / w), coords.y_pct((y - t) / h)],
+                color,
+                ty,
+                tex_i: 0,
+                opacity,
+            }
+        })
+        .collect()
+}
+
+// NOTE: These vertexes are already in normalized device coordinates. `tex_i` is assigned by the
+//       renderer's worker once the index of the layer is known.
+fn layer_composite_vertexes(z: f32, opacity: f32) -> Vec<ItfVertInfo> {
+    [
+        [1.0, -1.0],
+        [-1.0, -1.0],
+        [-1.0, 1.0],
+        [1.0, -1.0],
+        [-1.0, 1.0],
+        [1.0, 1.0],
+    ]
+    .into_iter()
+    .map(|[x, y]| {
+        ItfVertInfo {
+            position: [x, y, z],
+            coords: [0.0; 2],
+            color: [1.0, 1.0, 1.0, opacity],
+            ty: 109,
+            tex_i: 0,
+            opacity: 1.0,
+        }
+    })
+    .collect()
+}
+
+// NOTE: Edges are rounded instead of sizes, so that adjacent bins remain adjacent.
+fn snap_tlwh(tlwh: [f32; 4], scale: f32) -> [f32; 4] {
+    let [top, left, width, height] = tlwh;
+    let snap = |v: f32| (v * scale).round() / scale;
+    let [top_s, left_s] = [snap(top), snap(left)];
+    [
+        top_s,
+        left_s,
+        snap(left + width) - left_s,
+        snap(top + height) - top_s,
+    ]
+}
+
+// NOTE: Borders that aren't zero are at least one physical pixel.
+fn snap_border(size: f32, scale: f32) -> f32 {
+    if size > 0.0 {
+        (size * scale).round().max(1.0) / scale
+    } else {
+        size
+    }
+}
+
+/// Overlay the styles of the states that are set onto the style.
+fn resolve_states(style: Arc<BinStyle>, [hovered, focused, active]: [bool; 3]) -> Arc<BinStyle> {
+    let mut resolved: Option<BinStyle> = None;
+
+    // NOTE: Ordered by precedence, where the last has the greatest.
+    for (state, overlay_op) in [
+        (hovered, style.hover.as_deref()),
+        (focused, style.focused.as_deref()),
+        (active, style.active.as_deref()),
+    ] {
+        if let Some(overlay) = overlay_op {
+            if state {
+                resolved = Some(resolved.as_ref().unwrap_or(&style).overlay(overlay));
+            }
+        }
+    }
+
+    match resolved {
+        Some(resolved) => Arc::new(resolved),
+        None => style,
+    }
 }
 
 #[inline(always)]
@@ -2764,3 +4184,618 @@ fn curve(t: f32, a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> [f32; 2] {
         lerp(t, lerp(t, a[1], b[1]), lerp(t, b[1], c[1])),
     ]
 }
+
+fn vertexes_within_bounds(vertexes: &[ItfVertInfo], bounds: [f32; 4]) -> bool {
+    vertexes.iter().all(|vertex| {
+        vertex.position[0] >= bounds[0]
+            && vertex.position[0] <= bounds[1]
+            && vertex.position[1] >= bounds[2]
+            && vertex.position[1] <= bounds[3]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{
+        absolute_placement, back_image_vertexes, batch_by_window, float_offsets, float_placements,
+        outer_contains, resolve_states, snap_border, snap_tlwh, update_signal, BinID, BinPlacement,
+        BinPostUpdate, Coords,
+    };
+    use crate::interface::{
+        BinPosition, BinStyle, ChildFloatAlign, ChildFloatJustify, ChildFloatMode, Color, ImageFit,
+    };
+
+    fn assert_tlwh_eq(a: [f32; 4], b: [f32; 4]) {
+        assert!(
+            a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 0.001),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    fn back_color_style(back_color: &str) -> BinStyle {
+        BinStyle {
+            back_color: Some(Color::shex(back_color)),
+            ..BinStyle::default()
+        }
+    }
+
+    fn assert_invalid(placement: &BinPlacement) {
+        assert!(placement.hidden);
+        assert_eq!(placement.tlwh, [0.0; 4]);
+        assert_eq!(placement.bounds, [0.0; 4]);
+    }
+
+    fn floating_style(width: Option<f32>, height: Option<f32>) -> Arc<BinStyle> {
+        Arc::new(BinStyle {
+            position: Some(BinPosition::Floating),
+            width,
+            height,
+            ..BinStyle::default()
+        })
+    }
+
+    #[test]
+    fn resolve_hover_enter_leave() {
+        let style = Arc::new(BinStyle {
+            hover: Some(Box::new(back_color_style("ff0000"))),
+            border_size_t: Some(1.0),
+            ..back_color_style("000000")
+        });
+
+        let hovered = resolve_states(style.clone(), [true, false, false]);
+        assert_eq!(hovered.back_color, Some(Color::shex("ff0000")));
+        assert_eq!(hovered.border_size_t, Some(1.0));
+
+        let left = resolve_states(style.clone(), [false, false, false]);
+        assert!(Arc::ptr_eq(&left, &style));
+        assert_eq!(left.back_color, Some(Color::shex("000000")));
+    }
+
+    #[test]
+    fn resolve_state_precedence() {
+        let style = Arc::new(BinStyle {
+            hover: Some(Box::new(back_color_style("ff0000"))),
+            focused: Some(Box::new(back_color_style("00ff00"))),
+            active: Some(Box::new(back_color_style("0000ff"))),
+            ..back_color_style("000000")
+        });
+
+        for (states, expected) in [
+            ([true, false, false], "ff0000"),
+            ([false, true, false], "00ff00"),
+            ([true, true, false], "00ff00"),
+            ([false, false, true], "0000ff"),
+            ([true, false, true], "0000ff"),
+            ([false, true, true], "0000ff"),
+            ([true, true, true], "0000ff"),
+        ] {
+            assert_eq!(
+                resolve_states(style.clone(), states).back_color,
+                Some(Color::shex(expected)),
+                "states: {:?}",
+                states,
+            );
+        }
+    }
+
+    #[test]
+    fn fit_modes() {
+        let coords = Coords::new(200.0, 100.0);
+        let dst = [0.0, 0.0, 100.0, 100.0];
+
+        let (fit_dst, fit_src) = coords.fit(ImageFit::Fill, dst);
+        assert_tlwh_eq(fit_dst, [0.0, 0.0, 100.0, 100.0]);
+        assert_tlwh_eq(fit_src.tlwh, [0.0, 0.0, 200.0, 100.0]);
+
+        let (fit_dst, fit_src) = coords.fit(ImageFit::Contain, dst);
+        assert_tlwh_eq(fit_dst, [25.0, 0.0, 100.0, 50.0]);
+        assert_tlwh_eq(fit_src.tlwh, [0.0, 0.0, 200.0, 100.0]);
+
+        let (fit_dst, fit_src) = coords.fit(ImageFit::Cover, dst);
+        assert_tlwh_eq(fit_dst, [0.0, 0.0, 100.0, 100.0]);
+        assert_tlwh_eq(fit_src.tlwh, [0.0, 50.0, 100.0, 100.0]);
+
+        let (fit_dst, fit_src) = coords.fit(ImageFit::None, dst);
+        assert_tlwh_eq(fit_dst, [0.0, 0.0, 100.0, 100.0]);
+        assert_tlwh_eq(fit_src.tlwh, [0.0, 50.0, 100.0, 100.0]);
+
+        let (fit_dst, fit_src) = coords.fit(ImageFit::None, [0.0, 0.0, 300.0, 300.0]);
+        assert_tlwh_eq(fit_dst, [100.0, 50.0, 200.0, 100.0]);
+        assert_tlwh_eq(fit_src.tlwh, [0.0, 0.0, 200.0, 100.0]);
+    }
+
+    #[test]
+    fn fit_preserves_aspect() {
+        let coords = Coords {
+            tlwh: [10.0, 20.0, 200.0, 100.0],
+        };
+
+        for fit in [ImageFit::Contain, ImageFit::Cover, ImageFit::None] {
+            let (dst, src) = coords.fit(fit, [5.0, 5.0, 150.0, 120.0]);
+            let [scale_x, scale_y] = [dst[2] / src.tlwh[2], dst[3] / src.tlwh[3]];
+            assert!((scale_x - scale_y).abs() < 0.001, "{:?}", fit);
+        }
+    }
+
+    #[test]
+    fn fit_zero_size() {
+        for (src, dst) in [
+            ([0.0, 0.0, 0.0, 100.0], [0.0, 0.0, 100.0, 100.0]),
+            ([0.0, 0.0, 100.0, 0.0], [0.0, 0.0, 100.0, 100.0]),
+            ([0.0, 0.0, 100.0, 100.0], [0.0, 0.0, 0.0, 100.0]),
+            ([0.0, 0.0, 100.0, 100.0], [0.0, 0.0, 100.0, 0.0]),
+        ] {
+            for fit in [
+                ImageFit::Fill,
+                ImageFit::Contain,
+                ImageFit::Cover,
+                ImageFit::None,
+            ] {
+                let (fit_dst, fit_src) = Coords {
+                    tlwh: src,
+                }
+                .fit(fit, dst);
+
+                assert_tlwh_eq(fit_dst, dst);
+                assert_tlwh_eq(fit_src.tlwh, src);
+            }
+        }
+    }
+
+    #[test]
+    fn fit_non_square() {
+        let coords = Coords {
+            tlwh: [0.0, 0.0, 100.0, 300.0],
+        };
+
+        let (dst, src) = coords.fit(ImageFit::Contain, [0.0, 0.0, 300.0, 100.0]);
+        assert_tlwh_eq(dst, [0.0, 133.333, 33.333, 100.0]);
+        assert_tlwh_eq(src.tlwh, [0.0, 0.0, 100.0, 300.0]);
+
+        let (dst, src) = coords.fit(ImageFit::Cover, [0.0, 0.0, 300.0, 100.0]);
+        assert_tlwh_eq(dst, [0.0, 0.0, 300.0, 100.0]);
+        assert_tlwh_eq(src.tlwh, [133.333, 0.0, 100.0, 33.333]);
+    }
+
+    #[test]
+    fn back_image_fade() {
+        let dst = [0.0, 0.0, 100.0, 100.0];
+        let coords = Coords::new(100.0, 100.0);
+        let mut last_alpha = f32::INFINITY;
+
+        for step in (0..=10).rev() {
+            let opacity = step as f32 / 10.0;
+            let vertexes = back_image_vertexes(
+                [[0.0, 0.0], [100.0, 0.0], [0.0, 100.0]],
+                dst,
+                &coords,
+                0.0,
+                [0.0; 4],
+                100,
+                opacity,
+            );
+
+            // NOTE: The back color is transparent, so the alpha of the output is the opacity.
+            let alpha = vertexes[0].opacity;
+            assert!(vertexes.iter().all(|vertex| vertex.opacity == alpha));
+            assert!(alpha < last_alpha);
+            last_alpha = alpha;
+        }
+
+        assert_eq!(last_alpha, 0.0);
+    }
+
+    #[test]
+    fn invalid_float_no_parent() {
+        let siblings = [(BinID(0), floating_style(Some(10.0), Some(10.0)))];
+
+        let result = float_placements(None, &siblings, 1.0, |_, _| [0.0; 2], |_, _| ());
+        assert_eq!(result.err(), Some("floating without a parent"));
+        assert_invalid(&BinPlacement::invalid());
+    }
+
+    #[test]
+    fn invalid_float_no_size() {
+        let parent_plmt = BinPlacement::root([100.0, 100.0]);
+        let parent_style = BinStyle::default();
+
+        let siblings = [
+            (BinID(0), floating_style(Some(10.0), Some(10.0))),
+            (BinID(1), floating_style(None, None)),
+        ];
+
+        let mut warned = Vec::new();
+
+        let placements = float_placements(
+            Some((&parent_plmt, &parent_style)),
+            &siblings,
+            1.0,
+            |_, _| unreachable!(),
+            |i, _| warned.push(i),
+        )
+        .unwrap();
+
+        assert_eq!(warned, vec![1]);
+        assert_eq!(placements.len(), 2);
+
+        for (id, placement) in placements {
+            if id == BinID(0) {
+                assert!(!placement.hidden);
+                assert_tlwh_eq(placement.tlwh, [0.0, 0.0, 10.0, 10.0]);
+            } else {
+                assert!(placement.hidden);
+                assert_eq!([placement.tlwh[2], placement.tlwh[3]], [0.0; 2]);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_unresolved_axis() {
+        let parent_plmt = BinPlacement::root([100.0, 100.0]);
+
+        for (style, reason) in [
+            (BinStyle::default(), "vertical position undefined"),
+            (
+                BinStyle {
+                    pos_from_t: Some(0.0),
+                    height: Some(10.0),
+                    ..BinStyle::default()
+                },
+                "horizontal position undefined",
+            ),
+            (
+                BinStyle {
+                    pos_from_l: Some(0.0),
+                    pos_from_r: Some(0.0),
+                    pos_from_t: Some(0.0),
+                    ..BinStyle::default()
+                },
+                "vertical position undefined",
+            ),
+        ] {
+            let result = absolute_placement(
+                BinID(0),
+                &style,
+                &parent_plmt,
+                [0.0; 2],
+                1.0,
+                |_| unreachable!(),
+            );
+            assert_eq!(result.err(), Some(reason));
+        }
+
+        assert_invalid(&BinPlacement::invalid());
+    }
+
+    #[test]
+    fn pct_float_and_absolute() {
+        let parent_plmt = BinPlacement::root([200.0, 100.0]);
+
+        let parent_style = BinStyle {
+            pad_t: Some(10.0),
+            pad_b: Some(10.0),
+            pad_l: Some(20.0),
+            pad_r: Some(20.0),
+            ..BinStyle::default()
+        };
+
+        let pct_style = BinStyle {
+            width_pct: Some(50.0),
+            height_pct: Some(25.0),
+            ..BinStyle::default()
+        };
+
+        // NOTE: Floating bins are relative to the body of the parent, excluding its padding.
+        let siblings = [(
+            BinID(0),
+            Arc::new(BinStyle {
+                position: Some(BinPosition::Floating),
+                ..pct_style.clone()
+            }),
+        )];
+
+        let placements = float_placements(
+            Some((&parent_plmt, &parent_style)),
+            &siblings,
+            1.0,
+            |_, _| unreachable!(),
+            |_, _| unreachable!(),
+        )
+        .unwrap();
+
+        assert_tlwh_eq(placements[0].1.tlwh, [10.0, 20.0, 80.0, 20.0]);
+
+        // NOTE: Absolute bins are relative to the size of the parent, including its padding.
+        let absolute = absolute_placement(
+            BinID(1),
+            &BinStyle {
+                position: Some(BinPosition::Parent),
+                pos_from_t: Some(0.0),
+                pos_from_l: Some(0.0),
+                ..pct_style.clone()
+            },
+            &parent_plmt,
+            [0.0; 2],
+            1.0,
+            |_| unreachable!(),
+        )
+        .unwrap();
+
+        assert_tlwh_eq(absolute.tlwh, [0.0, 0.0, 100.0, 25.0]);
+    }
+
+    #[test]
+    fn batch_children_dispatch() {
+        let window_a = Arc::new(());
+        let window_b = Arc::new(());
+
+        let batches = batch_by_window(
+            (0..500).map(|i| (window_a.clone(), vec![BinID(i * 2), BinID(i * 2 + 1)])),
+        );
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].1.len(), 1000);
+
+        let batches = batch_by_window((0..500).map(|i| {
+            let window = if i % 2 == 0 {
+                window_a.clone()
+            } else {
+                window_b.clone()
+            };
+
+            (window, vec![BinID(i)])
+        }));
+
+        assert_eq!(batches.len(), 2);
+        assert!(batches.iter().all(|(_, bin_ids)| bin_ids.len() == 250));
+    }
+
+    #[test]
+    fn hit_outer_bounds() {
+        let post = BinPostUpdate {
+            tlo: [10.0, 20.0],
+            tro: [110.0, 20.0],
+            blo: [10.0, 70.0],
+            bro: [110.0, 70.0],
+            ..BinPostUpdate::default()
+        };
+
+        assert!(outer_contains(&post, 50.0, 40.0));
+        assert!(outer_contains(&post, 10.0, 20.0));
+        assert!(outer_contains(&post, 110.0, 70.0));
+        assert!(!outer_contains(&post, 9.9, 40.0));
+        assert!(!outer_contains(&post, 110.1, 40.0));
+        assert!(!outer_contains(&post, 50.0, 19.9));
+        assert!(!outer_contains(&post, 50.0, 70.1));
+    }
+
+    #[test]
+    fn update_signal_received() {
+        let (mut signal, recv) = update_signal();
+        signal();
+        signal();
+        assert!(recv.recv().is_ok());
+    }
+
+    #[test]
+    fn update_signal_dropped() {
+        let (signal, recv) = update_signal();
+        drop(signal);
+        assert!(recv.recv().is_err());
+    }
+
+    #[test]
+    fn update_signal_timeout() {
+        let (_signal, recv) = update_signal();
+        assert!(recv.recv_timeout(Duration::from_millis(10)).is_err());
+    }
+
+    #[test]
+    fn snap_to_pixels() {
+        assert_tlwh_eq(
+            snap_tlwh([10.4, 20.6, 30.3, 40.4], 1.0),
+            [10.0, 21.0, 30.0, 41.0],
+        );
+
+        for v in snap_tlwh([10.4, 20.6, 30.3, 40.4], 1.5) {
+            assert!(((v * 1.5) - (v * 1.5).round()).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn snap_shared_edges() {
+        let a = snap_tlwh([0.0, 0.3, 10.4, 10.0], 1.0);
+        let b = snap_tlwh([0.0, 10.7, 10.4, 10.0], 1.0);
+        assert_eq!(a[1] + a[2], b[1]);
+    }
+
+    #[test]
+    fn snap_border_fractional_scale() {
+        let scale = 1.25;
+
+        let placement = absolute_placement(
+            BinID(0),
+            &BinStyle {
+                pos_from_t: Some(10.3),
+                pos_from_l: Some(20.7),
+                width: Some(50.1),
+                height: Some(30.9),
+                pixel_snap: Some(true),
+                ..BinStyle::default()
+            },
+            &BinPlacement::root([200.0, 200.0]),
+            [0.0; 2],
+            scale,
+            |_| unreachable!(),
+        )
+        .unwrap();
+
+        let [top, left, width, height] = placement.tlwh;
+        let border = snap_border(1.0, scale);
+        assert_eq!(border * scale, 1.0);
+
+        for v in [
+            top - border,
+            top,
+            top + height,
+            top + height + border,
+            left - border,
+            left,
+            left + width,
+            left + width + border,
+        ] {
+            assert!(((v * scale) - (v * scale).round()).abs() < 0.001, "{}", v);
+        }
+    }
+
+    #[test]
+    fn snap_borders() {
+        assert_eq!(snap_border(0.0, 1.0), 0.0);
+        assert_eq!(snap_border(0.2, 1.0), 1.0);
+        assert_eq!(snap_border(1.4, 1.0), 1.0);
+        assert_eq!(snap_border(1.6, 2.0), 1.5);
+        assert_eq!(snap_border(0.2, 2.0), 0.5);
+    }
+
+    #[test]
+    fn float_row_wrap() {
+        let sizes = [[40.0, 10.0], [40.0, 20.0], [40.0, 10.0]];
+
+        assert_eq!(
+            float_offsets(
+                &sizes,
+                [100.0, 100.0],
+                ChildFloatMode::Row,
+                true,
+                ChildFloatJustify::Start,
+                ChildFloatAlign::Start,
+            ),
+            vec![[0.0, 0.0], [40.0, 0.0], [0.0, 20.0]],
+        );
+
+        assert_eq!(
+            float_offsets(
+                &sizes,
+                [100.0, 100.0],
+                ChildFloatMode::Row,
+                false,
+                ChildFloatJustify::Start,
+                ChildFloatAlign::Start,
+            ),
+            vec![[0.0, 0.0], [40.0, 0.0], [80.0, 0.0]],
+        );
+    }
+
+    #[test]
+    fn float_modes() {
+        let sizes = [[10.0, 20.0], [30.0, 40.0]];
+        let offsets = |mode| {
+            float_offsets(
+                &sizes,
+                [100.0, 100.0],
+                mode,
+                true,
+                ChildFloatJustify::Start,
+                ChildFloatAlign::Start,
+            )
+        };
+
+        assert_eq!(offsets(ChildFloatMode::Row), vec![[0.0, 0.0], [10.0, 0.0]]);
+        assert_eq!(
+            offsets(ChildFloatMode::Column),
+            vec![[0.0, 0.0], [0.0, 20.0]]
+        );
+        assert_eq!(
+            offsets(ChildFloatMode::RowReverse),
+            vec![[90.0, 0.0], [60.0, 0.0]]
+        );
+        assert_eq!(
+            offsets(ChildFloatMode::ColumnReverse),
+            vec![[0.0, 80.0], [0.0, 40.0]]
+        );
+    }
+
+    #[test]
+    fn float_justify() {
+        let sizes = [[20.0, 10.0], [20.0, 10.0]];
+        let offsets = |justify| {
+            float_offsets(
+                &sizes,
+                [100.0, 100.0],
+                ChildFloatMode::Row,
+                true,
+                justify,
+                ChildFloatAlign::Start,
+            )
+            .into_iter()
+            .map(|xy| xy[0])
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(offsets(ChildFloatJustify::Start), vec![0.0, 20.0]);
+        assert_eq!(offsets(ChildFloatJustify::Center), vec![30.0, 50.0]);
+        assert_eq!(offsets(ChildFloatJustify::End), vec![60.0, 80.0]);
+        assert_eq!(offsets(ChildFloatJustify::SpaceBetween), vec![0.0, 80.0]);
+        assert_eq!(offsets(ChildFloatJustify::SpaceAround), vec![15.0, 65.0]);
+    }
+
+    #[test]
+    fn float_align() {
+        let sizes = [[20.0, 10.0], [20.0, 30.0]];
+        let offsets = |align| {
+            float_offsets(
+                &sizes,
+                [100.0, 100.0],
+                ChildFloatMode::Row,
+                true,
+                ChildFloatJustify::Start,
+                align,
+            )
+            .into_iter()
+            .map(|xy| xy[1])
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(offsets(ChildFloatAlign::Start), vec![0.0, 0.0]);
+        assert_eq!(offsets(ChildFloatAlign::Center), vec![10.0, 0.0]);
+        assert_eq!(offsets(ChildFloatAlign::End), vec![20.0, 0.0]);
+    }
+
+    fn position_errors(style: &BinStyle) -> bool {
+        style.validate_position(false).errors_present()
+    }
+
+    #[test]
+    fn batch_validated_together() {
+        let style = BinStyle {
+            position: Some(BinPosition::Window),
+            pos_from_t: Some(0.0),
+            pos_from_l: Some(0.0),
+            width: Some(100.0),
+            height: Some(100.0),
+            ..BinStyle::default()
+        };
+
+        assert!(!position_errors(&style));
+
+        // NOTE: Changing from a width to a right position is invalid when done one field at a
+        //       time, but valid when both are changed together.
+        let mut step = style.clone();
+        step.pos_from_r = Some(0.0);
+        assert!(position_errors(&step));
+
+        let mut step = style.clone();
+        step.width = None;
+        assert!(position_errors(&step));
+
+        let mut batch = style.clone();
+        batch.width = None;
+        batch.pos_from_r = Some(0.0);
+        assert!(!position_errors(&batch));
+    }
+}
@@ -9,6 +9,7 @@ use crate::NonExhaustive;
 
 /// Position of a `Bin`
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinPosition {
     /// Position will be done from the window's dimensions
     #[default]
@@ -22,14 +23,52 @@ pub enum BinPosition {
 
 /// How floating children `Bin` are placed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ChildFloatMode {
+    /// Children are placed left to right, then top to bottom.
     #[default]
     Row,
+    /// Children are placed top to bottom, then left to right.
     Column,
+    /// Children are placed right to left, then top to bottom.
+    RowReverse,
+    /// Children are placed bottom to top, then left to right.
+    ColumnReverse,
 }
 
-/// Text wrap method used
+/// How floating children `Bin` are distributed along the main axis of a line.
+///
+/// The main axis is horizontal for `ChildFloatMode::Row` & `ChildFloatMode::RowReverse` and
+/// vertical for `ChildFloatMode::Column` & `ChildFloatMode::ColumnReverse`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChildFloatJustify {
+    /// Children are packed at the start of the line.
+    #[default]
+    Start,
+    /// Children are packed at the center of the line.
+    Center,
+    /// Children are packed at the end of the line.
+    End,
+    /// Remaining space is distributed evenly between children.
+    SpaceBetween,
+    /// Remaining space is distributed evenly around children.
+    SpaceAround,
+}
+
+/// How floating children `Bin` are aligned along the cross axis of a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChildFloatAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+/// Text wrap method used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextWrap {
     Shift,
     #[default]
@@ -39,6 +78,7 @@ pub enum TextWrap {
 
 /// Text horizonal alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextHoriAlign {
     #[default]
     Left,
@@ -46,8 +86,22 @@ pub enum TextHoriAlign {
     Right,
 }
 
+/// Direction of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextDirection {
+    /// Determined by the first strong directional character of each line.
+    #[default]
+    Auto,
+    /// Left to right
+    Ltr,
+    /// Right to left
+    Rtl,
+}
+
 /// Text vertical alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextVertAlign {
     #[default]
     Top,
@@ -56,7 +110,8 @@ pub enum TextVertAlign {
 }
 
 /// Weight of a font
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontWeight {
     Thin,
     ExtraLight,
@@ -87,7 +142,8 @@ impl From<FontWeight> for cosmic_text::Weight {
 }
 
 /// Stretch of a font
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontStretch {
     UltraCondensed,
     ExtraCondensed,
@@ -118,7 +174,8 @@ impl From<FontStretch> for cosmic_text::Stretch {
 }
 
 /// Style of a font
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontStyle {
     #[default]
     Normal,
@@ -136,17 +193,84 @@ impl From<FontStyle> for cosmic_text::Style {
     }
 }
 
+/// A section of text with its own styling.
+///
+/// See `BinStyle.text_spans`. Fields that are `None` use the value from the `BinStyle`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextSpan {
+    pub text: String,
+    pub color: Option<Color>,
+    pub font_family: Option<String>,
+    pub font_weight: Option<FontWeight>,
+    pub font_style: Option<FontStyle>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _ne: NonExhaustive,
+}
+
+impl TextSpan {
+    /// Create a span from text with the styling of the `BinStyle`.
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for TextSpan {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            color: None,
+            font_family: None,
+            font_weight: None,
+            font_style: None,
+            _ne: NonExhaustive(()),
+        }
+    }
+}
+
 /// Style of a `Bin`
+///
+/// ***Note:** With the `serde` feature, `back_image` can only be (de)serialized for
+/// `ImageCacheKey::Url` & `ImageCacheKey::Path`. `back_image_vk` is skipped.*
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct BinStyle {
     /// Determines the positioning type
     pub position: Option<BinPosition>,
     /// Overrides the z-index automatically calculated.
+    ///
+    /// Bins with the same z-index within a layer are drawn in the order they were created, where
+    /// bins created later are drawn above. See `BinID`.
     pub z_index: Option<i16>,
     /// Offsets the z-index automatically calculated.
     pub add_z_index: Option<i16>,
+    /// Places this `Bin` and its children into a layer.
+    ///
+    /// Bins in a greater layer are always displayed above bins in a lesser layer regardless of
+    /// their z-index. The z-index, including `add_z_index`, only orders bins within a layer.
+    /// When `None` the layer is inherited from the parent.
+    ///
+    /// **Default**: `0`
+    pub z_layer: Option<i8>,
     /// How children of this `Bin` float.
     pub child_float_mode: Option<ChildFloatMode>,
+    /// If children of this `Bin` wrap onto a new line when they exceed the body.
+    ///
+    /// When `false` all children are placed on a single line which may overflow.
+    ///
+    /// **Default**: `true`
+    pub child_float_wrap: Option<bool>,
+    /// How children of this `Bin` are distributed along the main axis of a line.
+    pub child_float_justify: Option<ChildFloatJustify>,
+    /// How children of this `Bin` are aligned along the cross axis of a line.
+    ///
+    /// The size of the cross axis of a line is determined by its largest child.
+    pub child_float_align: Option<ChildFloatAlign>,
     /// The floating weight of this `Bin`.
     ///
     /// Lesser values will be left-most and greator values right-most in `ChildFloatMode::Row`.
@@ -162,14 +286,40 @@ pub struct BinStyle {
     pub hidden: Option<bool>,
     /// Set the opacity of the bin's content.
     pub opacity: Option<f32>,
+    /// Composite this `Bin` & its descendants as a group with its opacity.
+    ///
+    /// Normally the opacity of each descendant is multiplied by its ancestors' and every bin is
+    /// blended on its own, so overlapping descendants of a translucent `Bin` show through each
+    /// other. When set, the subtree is instead drawn onto an offscreen layer which is then
+    /// blended once with the effective opacity of this `Bin`.
+    ///
+    /// ***Note:** A layer is only used while the effective opacity is between zero and one. The
+    /// layer is composited at the z-index of this `Bin`, so descendants can't be interleaved
+    /// with bins outside of the subtree. Layers aren't multisampled and each one uses memory the
+    /// size of the window. Within the subtree of a `Bin` that already uses a layer, this has no
+    /// effect.*
+    ///
+    /// **Default**: `false`
+    pub isolate_opacity: Option<bool>,
+    /// Round the edges & border sizes of this `Bin` to whole physical pixels.
+    ///
+    /// Avoids blurry edges when the effective scale isn't a whole number. Edges are rounded
+    /// instead of sizes, so bins that share an edge remain adjacent.
+    ///
+    /// **Default**: `false`
+    pub pixel_snap: Option<bool>,
     // Position from Edges
     pub pos_from_t: Option<f32>,
     pub pos_from_b: Option<f32>,
     pub pos_from_l: Option<f32>,
     pub pos_from_r: Option<f32>,
+    /// Percent of the parent's height, where `100.0` is the full height.
     pub pos_from_t_pct: Option<f32>,
+    /// Percent of the parent's height, where `100.0` is the full height.
     pub pos_from_b_pct: Option<f32>,
+    /// Percent of the parent's width, where `100.0` is the full width.
     pub pos_from_l_pct: Option<f32>,
+    /// Percent of the parent's width, where `100.0` is the full width.
     pub pos_from_r_pct: Option<f32>,
     pub pos_from_l_offset: Option<f32>,
     pub pos_from_t_offset: Option<f32>,
@@ -177,13 +327,24 @@ pub struct BinStyle {
     pub pos_from_b_offset: Option<f32>,
     // Size
     pub width: Option<f32>,
+    /// Percent of the parent's width, where `100.0` is the full width.
+    ///
+    /// ***Note:** For floating bins, this is of the width within the parent's padding.*
     pub width_pct: Option<f32>,
     /// Used in conjunction with `width_pct` to provide additional flexibility
     pub width_offset: Option<f32>,
     pub height: Option<f32>,
+    /// Percent of the parent's height, where `100.0` is the full height.
+    ///
+    /// ***Note:** For floating bins, this is of the height within the parent's padding.*
     pub height_pct: Option<f32>,
     /// Used in conjunction with `height_pct` to provide additional flexibility
     pub height_offset: Option<f32>,
+    /// Size the width and/or height that aren't otherwise defined to the text, including
+    /// padding. When the width is defined, the text is wrapped to it when measuring the height.
+    ///
+    /// See `Interface::measure_text`.
+    pub size_to_content: Option<bool>,
     pub margin_t: Option<f32>,
     pub margin_b: Option<f32>,
     pub margin_l: Option<f32>,
@@ -198,6 +359,23 @@ pub struct BinStyle {
     pub scroll_x: Option<f32>,
     pub overflow_y: Option<bool>,
     pub overflow_x: Option<bool>,
+    /// Determines if the content of the `Bin` is clipped to its bounds.
+    /// - `None`/`Some(true)`: Content is clipped.
+    /// - `Some(false)`: Content is not clipped.
+    ///
+    /// ***Note:** This is intended for `Bin`'s known to be fully inside their parent. When
+    /// clipping is disabled, content outside of the bounds is still drawn.*
+    pub clip_content: Option<bool>,
+    // Focus
+    /// Determines if the `Bin` can be focused by `Window::focus_next` & `Window::focus_prev`.
+    pub focusable: Option<bool>,
+    /// Order in which focusable `Bin`'s are traversed.
+    ///
+    /// `Bin`'s with a `tab_index` are traversed first, lesser values first. Then `Bin`'s without
+    /// are traversed in the order of the hierarchy.
+    pub tab_index: Option<i16>,
+    /// When focus is within this `Bin`, traversal is limited to the focusable descendants.
+    pub focus_scope: Option<bool>,
     // Border
     pub border_size_t: Option<f32>,
     pub border_size_b: Option<f32>,
@@ -213,12 +391,26 @@ pub struct BinStyle {
     pub border_radius_br: Option<f32>,
     // Background
     pub back_color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_back_image"))]
     pub back_image: Option<ImageCacheKey>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub back_image_vk: Option<Arc<Image>>,
     pub back_image_coords: Option<[f32; 4]>,
     pub back_image_effect: Option<ImageEffect>,
+    pub back_image_loop: Option<ImageLoop>,
+    /// How the background image is fit within this `Bin`.
+    ///
+    /// ***Note:** When the image doesn't cover this `Bin`, the rest is filled with `back_color`
+    /// and the image isn't clipped to the border radii.*
+    ///
+    /// **Default**: `ImageFit::Fill`
+    pub back_image_fit: Option<ImageFit>,
     // Text
     pub text: String,
+    /// Text composed of spans with individual styling. When set, `text` is not used.
+    ///
+    /// See `Bin::text_span_at` for determining which span is at a position.
+    pub text_spans: Option<Vec<TextSpan>>,
     pub text_color: Option<Color>,
     pub text_height: Option<f32>,
     pub text_secret: Option<bool>,
@@ -226,13 +418,36 @@ pub struct BinStyle {
     pub line_limit: Option<usize>,
     pub text_wrap: Option<TextWrap>,
     pub text_vert_align: Option<TextVertAlign>,
+    /// ***Note:** When `None`, lines are aligned to the start of their direction.*
     pub text_hori_align: Option<TextHoriAlign>,
+    pub text_direction: Option<TextDirection>,
     pub font_family: Option<String>,
     pub font_weight: Option<FontWeight>,
     pub font_stretch: Option<FontStretch>,
     pub font_style: Option<FontStyle>,
+    /// Font families searched in order for characters missing from the font.
+    ///
+    /// When `None`, `Interface::fallback_fonts` is used.
+    pub font_fallback: Option<Vec<String>>,
+    // States
+    /// Style overlaid while the pointer is over this `Bin`.
+    ///
+    /// Fields set on the overlay take precedence over the fields of this style. When bins
+    /// overlap, only the top-most `Bin` under the pointer and its ancestors are hovered.
+    ///
+    /// See `Bin::is_hovered`.
+    pub hover: Option<Box<BinStyle>>,
+    /// Style overlaid while this `Bin` has input focus.
+    ///
+    /// Takes precedence over `hover`. See `Bin::is_focused`.
+    pub focused: Option<Box<BinStyle>>,
+    /// Style overlaid while a mouse button is held down on this `Bin`.
+    ///
+    /// Takes precedence over `hover` & `focused`. See `Bin::is_active`.
+    pub active: Option<Box<BinStyle>>,
     // Misc
     pub custom_verts: Vec<BinVert>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub _ne: NonExhaustive,
 }
 
@@ -242,10 +457,16 @@ impl Default for BinStyle {
             position: None,
             z_index: None,
             add_z_index: None,
+            z_layer: None,
             child_float_mode: None,
+            child_float_wrap: None,
+            child_float_justify: None,
+            child_float_align: None,
             float_weight: None,
             hidden: None,
             opacity: None,
+            isolate_opacity: None,
+            pixel_snap: None,
             pos_from_t: None,
             pos_from_b: None,
             pos_from_l: None,
@@ -264,6 +485,7 @@ impl Default for BinStyle {
             height: None,
             height_pct: None,
             height_offset: None,
+            size_to_content: None,
             margin_t: None,
             margin_b: None,
             margin_l: None,
@@ -276,6 +498,10 @@ impl Default for BinStyle {
             scroll_x: None,
             overflow_y: None,
             overflow_x: None,
+            clip_content: None,
+            focusable: None,
+            tab_index: None,
+            focus_scope: None,
             border_size_t: None,
             border_size_b: None,
             border_size_l: None,
@@ -293,7 +519,10 @@ impl Default for BinStyle {
             back_image_vk: None,
             back_image_coords: None,
             back_image_effect: None,
+            back_image_loop: None,
+            back_image_fit: None,
             text: String::new(),
+            text_spans: None,
             text_color: None,
             text_height: None,
             text_secret: None,
@@ -302,10 +531,15 @@ impl Default for BinStyle {
             text_wrap: None,
             text_vert_align: None,
             text_hori_align: None,
+            text_direction: None,
             font_family: None,
             font_weight: None,
             font_stretch: None,
             font_style: None,
+            font_fallback: None,
+            hover: None,
+            focused: None,
+            active: None,
             custom_verts: Vec::new(),
             _ne: NonExhaustive(()),
         }
@@ -316,6 +550,8 @@ impl Default for BinStyle {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BinStyleError {
     pub ty: BinStyleErrorType,
+    /// The names of the `BinStyle` fields involved.
+    pub fields: Vec<&'static str>,
     pub desc: String,
 }
 
@@ -345,7 +581,7 @@ impl std::fmt::Display for BinStyleErrorType {
             Self::ConflictingFields => write!(f, "Conflicting Fields"),
             Self::TooManyConstraints => write!(f, "Too Many Constraints"),
             Self::NotEnoughConstraints => write!(f, "Not Enough Constraints"),
-            _ => write!(f, "Unknown"),
+            Self::InvalidImage => write!(f, "Invalid Image"),
         }
     }
 }
@@ -402,9 +638,10 @@ impl BinStyleValidation {
     }
 
     #[track_caller]
-    fn error<D: Into<String>>(&mut self, ty: BinStyleErrorType, desc: D) {
+    fn error<D: Into<String>>(&mut self, ty: BinStyleErrorType, fields: &[&'static str], desc: D) {
         self.errors.push(BinStyleError {
             ty,
+            fields: fields.to_vec(),
             desc: desc.into(),
         });
 
@@ -425,6 +662,19 @@ impl BinStyleValidation {
         }
     }
 
+    fn overlay_errors(&mut self, overlay_name: &str, mut overlay_validation: Self) {
+        overlay_validation.used = true;
+
+        for mut error in overlay_validation.errors.drain(..) {
+            error.desc = format!("With '{}' overlaid: {}", overlay_name, error.desc);
+            self.errors.push(error);
+        }
+
+        if self.location.is_none() {
+            self.location = overlay_validation.location.take();
+        }
+    }
+
     /// Expect `BinStyle` provided to `style_update()` is valid panicking if that is not the case.
     pub fn expect_valid(mut self) {
         self.used = true;
@@ -502,6 +752,17 @@ impl BinStyleValidation {
         self.errors.split_off(0).into_iter()
     }
 
+    /// Convert into a `Result`, discarding any warnings.
+    pub fn into_result(mut self) -> Result<(), Vec<BinStyleError>> {
+        self.used = true;
+
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors.split_off(0))
+        }
+    }
+
     /// Returns `true` if warnings are present.
     pub fn warnings_present(&self) -> bool {
         !self.warnings.is_empty()
@@ -563,10 +824,497 @@ macro_rules! useless_field {
 }
 
 impl BinStyle {
+    /// Apply the fields set on `overlay` on top of this style.
+    ///
+    /// `text` & `custom_verts` are only applied when they aren't empty. The state overlays of the
+    /// returned style, such as `hover`, are `None`.
+    pub(crate) fn overlay(&self, overlay: &Self) -> Self {
+        Self {
+            position: overlay
+                .position
+                .as_ref()
+                .or(self.position.as_ref())
+                .cloned(),
+            z_index: overlay.z_index.as_ref().or(self.z_index.as_ref()).cloned(),
+            add_z_index: overlay
+                .add_z_index
+                .as_ref()
+                .or(self.add_z_index.as_ref())
+                .cloned(),
+            z_layer: overlay.z_layer.as_ref().or(self.z_layer.as_ref()).cloned(),
+            child_float_mode: overlay
+                .child_float_mode
+                .as_ref()
+                .or(self.child_float_mode.as_ref())
+                .cloned(),
+            child_float_wrap: overlay
+                .child_float_wrap
+                .as_ref()
+                .or(self.child_float_wrap.as_ref())
+                .cloned(),
+            child_float_justify: overlay
+                .child_float_justify
+                .as_ref()
+                .or(self.child_float_justify.as_ref())
+                .cloned(),
+            child_float_align: overlay
+                .child_float_align
+                .as_ref()
+                .or(self.child_float_align.as_ref())
+                .cloned(),
+            float_weight: overlay
+                .float_weight
+                .as_ref()
+                .or(self.float_weight.as_ref())
+                .cloned(),
+            hidden: overlay.hidden.as_ref().or(self.hidden.as_ref()).cloned(),
+            opacity: overlay.opacity.as_ref().or(self.opacity.as_ref()).cloned(),
+            isolate_opacity: overlay
+                .isolate_opacity
+                .as_ref()
+                .or(self.isolate_opacity.as_ref())
+                .cloned(),
+            pixel_snap: overlay
+                .pixel_snap
+                .as_ref()
+                .or(self.pixel_snap.as_ref())
+                .cloned(),
+            pos_from_t: overlay
+                .pos_from_t
+                .as_ref()
+                .or(self.pos_from_t.as_ref())
+                .cloned(),
+            pos_from_b: overlay
+                .pos_from_b
+                .as_ref()
+                .or(self.pos_from_b.as_ref())
+                .cloned(),
+            pos_from_l: overlay
+                .pos_from_l
+                .as_ref()
+                .or(self.pos_from_l.as_ref())
+                .cloned(),
+            pos_from_r: overlay
+                .pos_from_r
+                .as_ref()
+                .or(self.pos_from_r.as_ref())
+                .cloned(),
+            pos_from_t_pct: overlay
+                .pos_from_t_pct
+                .as_ref()
+                .or(self.pos_from_t_pct.as_ref())
+                .cloned(),
+            pos_from_b_pct: overlay
+                .pos_from_b_pct
+                .as_ref()
+                .or(self.pos_from_b_pct.as_ref())
+                .cloned(),
+            pos_from_l_pct: overlay
+                .pos_from_l_pct
+                .as_ref()
+                .or(self.pos_from_l_pct.as_ref())
+                .cloned(),
+            pos_from_r_pct: overlay
+                .pos_from_r_pct
+                .as_ref()
+                .or(self.pos_from_r_pct.as_ref())
+                .cloned(),
+            pos_from_l_offset: overlay
+                .pos_from_l_offset
+                .as_ref()
+                .or(self.pos_from_l_offset.as_ref())
+                .cloned(),
+            pos_from_t_offset: overlay
+                .pos_from_t_offset
+                .as_ref()
+                .or(self.pos_from_t_offset.as_ref())
+                .cloned(),
+            pos_from_r_offset: overlay
+                .pos_from_r_offset
+                .as_ref()
+                .or(self.pos_from_r_offset.as_ref())
+                .cloned(),
+            pos_from_b_offset: overlay
+                .pos_from_b_offset
+                .as_ref()
+                .or(self.pos_from_b_offset.as_ref())
+                .cloned(),
+            width: overlay.width.as_ref().or(self.width.as_ref()).cloned(),
+            width_pct: overlay
+                .width_pct
+                .as_ref()
+                .or(self.width_pct.as_ref())
+                .cloned(),
+            width_offset: overlay
+                .width_offset
+                .as_ref()
+                .or(self.width_offset.as_ref())
+                .cloned(),
+            height: overlay.height.as_ref().or(self.height.as_ref()).cloned(),
+            height_pct: overlay
+                .height_pct
+                .as_ref()
+                .or(self.height_pct.as_ref())
+                .cloned(),
+            height_offset: overlay
+                .height_offset
+                .as_ref()
+                .or(self.height_offset.as_ref())
+                .cloned(),
+            size_to_content: overlay
+                .size_to_content
+                .as_ref()
+                .or(self.size_to_content.as_ref())
+                .cloned(),
+            margin_t: overlay
+                .margin_t
+                .as_ref()
+                .or(self.margin_t.as_ref())
+                .cloned(),
+            margin_b: overlay
+                .margin_b
+                .as_ref()
+                .or(self.margin_b.as_ref())
+                .cloned(),
+            margin_l: overlay
+                .margin_l
+                .as_ref()
+                .or(self.margin_l.as_ref())
+                .cloned(),
+            margin_r: overlay
+                .margin_r
+                .as_ref()
+                .or(self.margin_r.as_ref())
+                .cloned(),
+            pad_t: overlay.pad_t.as_ref().or(self.pad_t.as_ref()).cloned(),
+            pad_b: overlay.pad_b.as_ref().or(self.pad_b.as_ref()).cloned(),
+            pad_l: overlay.pad_l.as_ref().or(self.pad_l.as_ref()).cloned(),
+            pad_r: overlay.pad_r.as_ref().or(self.pad_r.as_ref()).cloned(),
+            scroll_y: overlay
+                .scroll_y
+                .as_ref()
+                .or(self.scroll_y.as_ref())
+                .cloned(),
+            scroll_x: overlay
+                .scroll_x
+                .as_ref()
+                .or(self.scroll_x.as_ref())
+                .cloned(),
+            overflow_y: overlay
+                .overflow_y
+                .as_ref()
+                .or(self.overflow_y.as_ref())
+                .cloned(),
+            overflow_x: overlay
+                .overflow_x
+                .as_ref()
+                .or(self.overflow_x.as_ref())
+                .cloned(),
+            clip_content: overlay
+                .clip_content
+                .as_ref()
+                .or(self.clip_content.as_ref())
+                .cloned(),
+            focusable: overlay
+                .focusable
+                .as_ref()
+                .or(self.focusable.as_ref())
+                .cloned(),
+            tab_index: overlay
+                .tab_index
+                .as_ref()
+                .or(self.tab_index.as_ref())
+                .cloned(),
+            focus_scope: overlay
+                .focus_scope
+                .as_ref()
+                .or(self.focus_scope.as_ref())
+                .cloned(),
+            border_size_t: overlay
+                .border_size_t
+                .as_ref()
+                .or(self.border_size_t.as_ref())
+                .cloned(),
+            border_size_b: overlay
+                .border_size_b
+                .as_ref()
+                .or(self.border_size_b.as_ref())
+                .cloned(),
+            border_size_l: overlay
+                .border_size_l
+                .as_ref()
+                .or(self.border_size_l.as_ref())
+                .cloned(),
+            border_size_r: overlay
+                .border_size_r
+                .as_ref()
+                .or(self.border_size_r.as_ref())
+                .cloned(),
+            border_color_t: overlay
+                .border_color_t
+                .as_ref()
+                .or(self.border_color_t.as_ref())
+                .cloned(),
+            border_color_b: overlay
+                .border_color_b
+                .as_ref()
+                .or(self.border_color_b.as_ref())
+                .cloned(),
+            border_color_l: overlay
+                .border_color_l
+                .as_ref()
+                .or(self.border_color_l.as_ref())
+                .cloned(),
+            border_color_r: overlay
+                .border_color_r
+                .as_ref()
+                .or(self.border_color_r.as_ref())
+                .cloned(),
+            border_radius_tl: overlay
+                .border_radius_tl
+                .as_ref()
+                .or(self.border_radius_tl.as_ref())
+                .cloned(),
+            border_radius_tr: overlay
+                .border_radius_tr
+                .as_ref()
+                .or(self.border_radius_tr.as_ref())
+                .cloned(),
+            border_radius_bl: overlay
+                .border_radius_bl
+                .as_ref()
+                .or(self.border_radius_bl.as_ref())
+                .cloned(),
+            border_radius_br: overlay
+                .border_radius_br
+                .as_ref()
+                .or(self.border_radius_br.as_ref())
+                .cloned(),
+            back_color: overlay
+                .back_color
+                .as_ref()
+                .or(self.back_color.as_ref())
+                .cloned(),
+            back_image: overlay
+                .back_image
+                .as_ref()
+                .or(self.back_image.as_ref())
+                .cloned(),
+            back_image_vk: overlay
+                .back_image_vk
+                .as_ref()
+                .or(self.back_image_vk.as_ref())
+                .cloned(),
+            back_image_coords: overlay
+                .back_image_coords
+                .as_ref()
+                .or(self.back_image_coords.as_ref())
+                .cloned(),
+            back_image_effect: overlay
+                .back_image_effect
+                .as_ref()
+                .or(self.back_image_effect.as_ref())
+                .cloned(),
+            back_image_loop: overlay
+                .back_image_loop
+                .as_ref()
+                .or(self.back_image_loop.as_ref())
+                .cloned(),
+            back_image_fit: overlay
+                .back_image_fit
+                .as_ref()
+                .or(self.back_image_fit.as_ref())
+                .cloned(),
+            text: match overlay.text.is_empty() {
+                true => self.text.clone(),
+                false => overlay.text.clone(),
+            },
+            text_spans: overlay
+                .text_spans
+                .as_ref()
+                .or(self.text_spans.as_ref())
+                .cloned(),
+            text_color: overlay
+                .text_color
+                .as_ref()
+                .or(self.text_color.as_ref())
+                .cloned(),
+            text_height: overlay
+                .text_height
+                .as_ref()
+                .or(self.text_height.as_ref())
+                .cloned(),
+            text_secret: overlay
+                .text_secret
+                .as_ref()
+                .or(self.text_secret.as_ref())
+                .cloned(),
+            line_spacing: overlay
+                .line_spacing
+                .as_ref()
+                .or(self.line_spacing.as_ref())
+                .cloned(),
+            line_limit: overlay
+                .line_limit
+                .as_ref()
+                .or(self.line_limit.as_ref())
+                .cloned(),
+            text_wrap: overlay
+                .text_wrap
+                .as_ref()
+                .or(self.text_wrap.as_ref())
+                .cloned(),
+            text_vert_align: overlay
+                .text_vert_align
+                .as_ref()
+                .or(self.text_vert_align.as_ref())
+                .cloned(),
+            text_hori_align: overlay
+                .text_hori_align
+                .as_ref()
+                .or(self.text_hori_align.as_ref())
+                .cloned(),
+            text_direction: overlay
+                .text_direction
+                .as_ref()
+                .or(self.text_direction.as_ref())
+                .cloned(),
+            font_family: overlay
+                .font_family
+                .as_ref()
+                .or(self.font_family.as_ref())
+                .cloned(),
+            font_weight: overlay
+                .font_weight
+                .as_ref()
+                .or(self.font_weight.as_ref())
+                .cloned(),
+            font_stretch: overlay
+                .font_stretch
+                .as_ref()
+                .or(self.font_stretch.as_ref())
+                .cloned(),
+            font_style: overlay
+                .font_style
+                .as_ref()
+                .or(self.font_style.as_ref())
+                .cloned(),
+            font_fallback: overlay
+                .font_fallback
+                .as_ref()
+                .or(self.font_fallback.as_ref())
+                .cloned(),
+            custom_verts: match overlay.custom_verts.is_empty() {
+                true => self.custom_verts.clone(),
+                false => overlay.custom_verts.clone(),
+            },
+            hover: None,
+            focused: None,
+            active: None,
+            _ne: NonExhaustive(()),
+        }
+    }
+
     #[track_caller]
     pub(crate) fn validate(&self, bin: &Arc<Bin>) -> BinStyleValidation {
+        let mut validation = self.validate_position(bin.hrchy.load().parent.is_some());
+
+        if self.back_image.is_some() && self.back_image_vk.is_some() {
+            validation.error(
+                BinStyleErrorType::ConflictingFields,
+                &["back_image", "back_image_vk"],
+                "Both 'back_image' and 'back_image_vk' are set.",
+            );
+        }
+
+        if let Some(back_image_vk) = self.back_image_vk.as_ref() {
+            if back_image_vk.image_type() != ImageType::Dim2d {
+                validation.error(
+                    BinStyleErrorType::InvalidImage,
+                    &["back_image_vk"],
+                    "Image provided with 'back_image_vk' isn't a 2d.",
+                );
+            }
+
+            if back_image_vk.array_layers() != 1 {
+                validation.error(
+                    BinStyleErrorType::InvalidImage,
+                    &["back_image_vk"],
+                    "Image provided with 'back_image_vk' must not have array layers.",
+                );
+            }
+
+            if back_image_vk.mip_levels() != 1 {
+                validation.error(
+                    BinStyleErrorType::InvalidImage,
+                    &["back_image_vk"],
+                    "Image provided with 'back_image_vk' must not have multiple mip levels.",
+                );
+            }
+
+            if !back_image_vk.format_features().contains(
+                FormatFeatures::TRANSFER_DST
+                    | FormatFeatures::TRANSFER_SRC
+                    | FormatFeatures::SAMPLED_IMAGE
+                    | FormatFeatures::SAMPLED_IMAGE_FILTER_LINEAR,
+            ) {
+                validation.error(
+                    BinStyleErrorType::InvalidImage,
+                    &["back_image_vk"],
+                    "Image provided with 'back_image_vk' must have a format that supports, \
+                     'TRANSFER_DST`, `TRANSFER_SRC`, `SAMPLED_IMAGE`, & \
+                     `SAMPLED_IMAGE_FILTER_LINEAR`.",
+                );
+            }
+        }
+
+        if let Some(image_cache_key) = self.back_image.as_ref() {
+            if matches!(image_cache_key, ImageCacheKey::Glyph(..)) {
+                validation.error(
+                    BinStyleErrorType::InvalidImage,
+                    &["back_image"],
+                    "'ImageCacheKey' provided with 'back_image' must not be \
+                     'ImageCacheKey::Glyph'. 'ImageCacheKey::User' should be used instead.",
+                );
+            }
+
+            if matches!(
+                image_cache_key,
+                ImageCacheKey::User(..) | ImageCacheKey::Encoded(..)
+            ) && bin
+                .basalt
+                .image_cache_ref()
+                .obtain_image_info(image_cache_key.clone())
+                .is_none()
+            {
+                validation.error(
+                    BinStyleErrorType::InvalidImage,
+                    &["back_image"],
+                    "'ImageCacheKey::User' or 'ImageCacheKey::Encoded' provided with 'back_image' \
+                     must be preloaded into the `ImageCache`.",
+                );
+            }
+        }
+
+        if let Some(hover) = self.hover.as_ref() {
+            validation.overlay_errors("hover", self.overlay(hover).validate(bin));
+        }
+
+        if let Some(focused) = self.focused.as_ref() {
+            validation.overlay_errors("focused", self.overlay(focused).validate(bin));
+        }
+
+        if let Some(active) = self.active.as_ref() {
+            validation.overlay_errors("active", self.overlay(active).validate(bin));
+        }
+
+        validation
+    }
+
+    // NOTE: Validation of the fields used for positioning, which only depends on if the `Bin`
+    //       has a parent.
+    pub(crate) fn validate_position(&self, has_parent: bool) -> BinStyleValidation {
         let mut validation = BinStyleValidation::new();
-        let has_parent = bin.hrchy.load().parent.is_some();
 
         match self.position.unwrap_or(BinPosition::Window) {
             BinPosition::Window | BinPosition::Parent => {
@@ -575,6 +1323,7 @@ impl BinStyle {
                 if self.pos_from_t.is_some() && self.pos_from_t_pct.is_some() {
                     validation.error(
                         BinStyleErrorType::ConflictingFields,
+                        &["pos_from_t", "pos_from_t_pct"],
                         "Both 'pos_from_t' and 'pos_from_t_pct' are set.",
                     );
                 }
@@ -582,6 +1331,7 @@ impl BinStyle {
                 if self.pos_from_b.is_some() && self.pos_from_b_pct.is_some() {
                     validation.error(
                         BinStyleErrorType::ConflictingFields,
+                        &["pos_from_b", "pos_from_b_pct"],
                         "Both 'pos_from_b' and 'pos_from_b_pct' are set.",
                     );
                 }
@@ -589,6 +1339,7 @@ impl BinStyle {
                 if self.pos_from_l.is_some() && self.pos_from_l_pct.is_some() {
                     validation.error(
                         BinStyleErrorType::ConflictingFields,
+                        &["pos_from_l", "pos_from_l_pct"],
                         "Both 'pos_from_l' and 'pos_from_l_pct' are set.",
                     );
                 }
@@ -596,6 +1347,7 @@ impl BinStyle {
                 if self.pos_from_r.is_some() && self.pos_from_r_pct.is_some() {
                     validation.error(
                         BinStyleErrorType::ConflictingFields,
+                        &["pos_from_r", "pos_from_r_pct"],
                         "Both 'pos_from_r' and 'pos_from_r_pct' are set.",
                     );
                 }
@@ -603,6 +1355,7 @@ impl BinStyle {
                 if self.width.is_some() && self.width_pct.is_some() {
                     validation.error(
                         BinStyleErrorType::ConflictingFields,
+                        &["width", "width_pct"],
                         "Both 'width' and 'width_pct' are set.",
                     );
                 }
@@ -610,6 +1363,7 @@ impl BinStyle {
                 if self.height.is_some() && self.height_pct.is_some() {
                     validation.error(
                         BinStyleErrorType::ConflictingFields,
+                        &["height", "height_pct"],
                         "Both 'height' and 'height_pct' are set.",
                     );
                 }
@@ -619,8 +1373,16 @@ impl BinStyle {
                     let pfb = self.pos_from_b.is_some() || self.pos_from_b_pct.is_some();
                     let pfl = self.pos_from_l.is_some() || self.pos_from_l_pct.is_some();
                     let pfr = self.pos_from_r.is_some() || self.pos_from_r_pct.is_some();
-                    let width = self.width.is_some() || self.width_pct.is_some();
-                    let height = self.height.is_some() || self.height_pct.is_some();
+                    let auto_size = self.size_to_content == Some(true);
+
+                    // NOTE: With size_to_content, a single position is enough, as the size is
+                    //       taken from the content.
+                    let width = self.width.is_some()
+                        || self.width_pct.is_some()
+                        || (auto_size && pfl != pfr);
+                    let height = self.height.is_some()
+                        || self.height_pct.is_some()
+                        || (auto_size && pft != pfb);
 
                     match (pft, pfb, height) {
                         (true, true, true) => {
@@ -644,6 +1406,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::TooManyConstraints,
+                                &[pft_field, pfb_field, height_field],
                                 format!(
                                     "'{}', '{}' & '{}' are all defined. Only two can be defined.",
                                     pft_field, pfb_field, height_field,
@@ -659,6 +1422,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::NotEnoughConstraints,
+                                &[pft_field],
                                 format!(
                                     "'{}' is defined, but one of `pos_from_b`, `pos_from_b_pct`, \
                                      `height` or `height_pct` must also be defined.",
@@ -675,6 +1439,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::NotEnoughConstraints,
+                                &[pfb_field],
                                 format!(
                                     "'{}' is defined, but one of `pos_from_t`, `pos_from_t_pct`, \
                                      `height` or `height_pct` must also be defined.",
@@ -691,6 +1456,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::NotEnoughConstraints,
+                                &[height_field],
                                 format!(
                                     "'{}' is defined, but one of `pos_from_t`, `pos_from_t_pct`, \
                                      `pos_from_b` or `pos_from_b_pct` must also be defined.",
@@ -698,6 +1464,14 @@ impl BinStyle {
                                 ),
                             );
                         },
+                        (false, false, false) => {
+                            validation.error(
+                                BinStyleErrorType::NotEnoughConstraints,
+                                &["pos_from_t", "pos_from_b", "height"],
+                                "Two of `pos_from_t`, `pos_from_b` & `height` or their `_pct` \
+                                 variants must be defined.",
+                            );
+                        },
                         _ => (),
                     }
 
@@ -723,6 +1497,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::TooManyConstraints,
+                                &[pfl_field, pfr_field, width_field],
                                 format!(
                                     "'{}', '{}' & '{}' are all defined. Only two can be defined.",
                                     pfl_field, pfr_field, width_field,
@@ -730,7 +1505,7 @@ impl BinStyle {
                             );
                         },
                         (true, false, false) => {
-                            let pfl_field = if self.pos_from_t.is_some() {
+                            let pfl_field = if self.pos_from_l.is_some() {
                                 "pos_from_l"
                             } else {
                                 "pos_from_l_pct"
@@ -738,6 +1513,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::NotEnoughConstraints,
+                                &[pfl_field],
                                 format!(
                                     "'{}' is defined, but one of `pos_from_r`, `pos_from_r_pct`, \
                                      `width` or `width_pct` must also be defined.",
@@ -746,7 +1522,7 @@ impl BinStyle {
                             );
                         },
                         (false, true, false) => {
-                            let pfr_field = if self.pos_from_t.is_some() {
+                            let pfr_field = if self.pos_from_r.is_some() {
                                 "pos_from_r"
                             } else {
                                 "pos_from_r_pct"
@@ -754,6 +1530,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::NotEnoughConstraints,
+                                &[pfr_field],
                                 format!(
                                     "'{}' is defined, but one of `pos_from_l`, `pos_from_l_pct`, \
                                      `width` or `width_pct` must also be defined.",
@@ -762,7 +1539,7 @@ impl BinStyle {
                             );
                         },
                         (false, false, true) => {
-                            let width_field = if self.pos_from_t.is_some() {
+                            let width_field = if self.width.is_some() {
                                 "width"
                             } else {
                                 "width_pct"
@@ -770,6 +1547,7 @@ impl BinStyle {
 
                             validation.error(
                                 BinStyleErrorType::NotEnoughConstraints,
+                                &[width_field],
                                 format!(
                                     "'{}' is defined, but one of `pos_from_l`, `pos_from_l_pct`, \
                                      `pos_from_r` or `pos_from_r_pct` must also be defined.",
@@ -777,6 +1555,14 @@ impl BinStyle {
                                 ),
                             );
                         },
+                        (false, false, false) => {
+                            validation.error(
+                                BinStyleErrorType::NotEnoughConstraints,
+                                &["pos_from_l", "pos_from_r", "width"],
+                                "Two of `pos_from_l`, `pos_from_r` & `width` or their `_pct` \
+                                 variants must be defined.",
+                            );
+                        },
                         _ => (),
                     }
                 }
@@ -798,100 +1584,38 @@ impl BinStyle {
                 if !has_parent {
                     validation.error(
                         BinStyleErrorType::NotEnoughConstraints,
+                        &["position"],
                         "Floating Bin's must have a parent.",
                     );
                 }
 
-                if self.width.is_none() && self.width_pct.is_none() {
+                let auto_size = self.size_to_content == Some(true);
+
+                if self.width.is_none() && self.width_pct.is_none() && !auto_size {
                     validation.error(
                         BinStyleErrorType::NotEnoughConstraints,
+                        &["width", "width_pct"],
                         "'width' or 'width_pct' must be defined.",
                     );
                 }
 
-                if self.height.is_none() && self.height_pct.is_none() {
+                if self.height.is_none() && self.height_pct.is_none() && !auto_size {
                     validation.error(
                         BinStyleErrorType::NotEnoughConstraints,
+                        &["height", "height_pct"],
                         "'height' or 'height_pct' must be defined.",
                     );
                 }
             },
         }
 
-        if self.back_image.is_some() && self.back_image_vk.is_some() {
-            validation.error(
-                BinStyleErrorType::ConflictingFields,
-                "Both 'back_image' and 'back_image_vk' are set.",
-            );
-        }
-
-        if let Some(back_image_vk) = self.back_image_vk.as_ref() {
-            if back_image_vk.image_type() != ImageType::Dim2d {
-                validation.error(
-                    BinStyleErrorType::InvalidImage,
-                    "Image provided with 'back_image_vk' isn't a 2d.",
-                );
-            }
-
-            if back_image_vk.array_layers() != 1 {
-                validation.error(
-                    BinStyleErrorType::InvalidImage,
-                    "Image provided with 'back_image_vk' must not have array layers.",
-                );
-            }
-
-            if back_image_vk.mip_levels() != 1 {
-                validation.error(
-                    BinStyleErrorType::InvalidImage,
-                    "Image provided with 'back_image_vk' must not have multiple mip levels.",
-                );
-            }
-
-            if !back_image_vk.format_features().contains(
-                FormatFeatures::TRANSFER_DST
-                    | FormatFeatures::TRANSFER_SRC
-                    | FormatFeatures::SAMPLED_IMAGE
-                    | FormatFeatures::SAMPLED_IMAGE_FILTER_LINEAR,
-            ) {
-                validation.error(
-                    BinStyleErrorType::InvalidImage,
-                    "Image provided with 'back_image_vk' must have a format that supports, \
-                     'TRANSFER_DST`, `TRANSFER_SRC`, `SAMPLED_IMAGE`, & \
-                     `SAMPLED_IMAGE_FILTER_LINEAR`.",
-                );
-            }
-        }
-
-        if let Some(image_cache_key) = self.back_image.as_ref() {
-            if matches!(image_cache_key, ImageCacheKey::Glyph(..)) {
-                validation.error(
-                    BinStyleErrorType::InvalidImage,
-                    "'ImageCacheKey' provided with 'back_image' must not be \
-                     'ImageCacheKey::Glyph'. 'ImageCacheKey::User' should be used instead.",
-                );
-            }
-
-            if matches!(image_cache_key, ImageCacheKey::User(..))
-                && bin
-                    .basalt
-                    .image_cache_ref()
-                    .obtain_image_info(image_cache_key.clone())
-                    .is_none()
-            {
-                validation.error(
-                    BinStyleErrorType::InvalidImage,
-                    "'ImageCacheKey::User' provided with 'back_image' must be preloaded into the \
-                     `ImageCache`.",
-                );
-            }
-        }
-
         validation
     }
 }
 
 /// Effect used on the background image of a `Bin`
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImageEffect {
     BackColorAdd,
     BackColorBehind,
@@ -916,11 +1640,155 @@ impl ImageEffect {
     }
 }
 
+/// How an animated background image of a `Bin` is played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageLoop {
+    /// Restart from the first frame after the last frame.
+    #[default]
+    Loop,
+    /// Stop on the last frame.
+    Once,
+    /// Play in reverse after the last frame and forward again after the first frame.
+    PingPong,
+}
+
+/// How the background image of a `Bin` is fit within it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageFit {
+    /// Stretch the image to fill the `Bin`.
+    #[default]
+    Fill,
+    /// Scale the image to fit within the `Bin` preserving its aspect ratio. The image is centered.
+    Contain,
+    /// Scale the image to cover the `Bin` preserving its aspect ratio. The image is centered and
+    /// the parts beyond the `Bin` are cropped.
+    Cover,
+    /// Don't scale the image, where each pixel of the image is a unit. The image is centered and
+    /// the parts beyond the `Bin` are cropped.
+    None,
+}
+
 /// Custom vertex for `Bin`
 ///
 /// Used for `BinStyle.custom_verts`
 #[derive(Default, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BinVert {
     pub position: (f32, f32, i16),
     pub color: Color,
 }
+
+#[cfg(feature = "serde")]
+mod serde_back_image {
+    use std::path::PathBuf;
+
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::image_cache::ImageCacheKey;
+
+    #[derive(Serialize, Deserialize)]
+    enum BackImage {
+        Url(String),
+        Path(PathBuf),
+    }
+
+    pub fn serialize<S: Serializer>(
+        back_image: &Option<ImageCacheKey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match back_image {
+            None => None,
+            Some(ImageCacheKey::Url(url)) => Some(BackImage::Url(url.to_string())),
+            Some(ImageCacheKey::Path(path)) => Some(BackImage::Path(path.clone())),
+            Some(_) => {
+                return Err(S::Error::custom(
+                    "only `ImageCacheKey::Url` & `ImageCacheKey::Path` can be serialized",
+                ));
+            },
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<ImageCacheKey>, D::Error> {
+        Ok(match Option::<BackImage>::deserialize(deserializer)? {
+            None => None,
+            Some(BackImage::Url(url)) => Some(ImageCacheKey::url(url).map_err(D::Error::custom)?),
+            Some(BackImage::Path(path)) => Some(ImageCacheKey::Path(path)),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{BinPosition, BinStyle, BinVert, ImageEffect, TextWrap};
+    use crate::image_cache::ImageCacheKey;
+    use crate::interface::Color;
+
+    #[test]
+    fn serde_round_trip() {
+        let style = BinStyle {
+            position: Some(BinPosition::Parent),
+            pos_from_t: Some(10.0),
+            width_pct: Some(50.0),
+            back_color: Some(Color::shex("ff8800")),
+            back_image: Some(ImageCacheKey::Path(PathBuf::from("image.png"))),
+            back_image_effect: Some(ImageEffect::Invert),
+            text: String::from("text"),
+            text_wrap: Some(TextWrap::None),
+            custom_verts: vec![BinVert {
+                position: (1.0, 2.0, 3),
+                color: Color::red(),
+            }],
+            ..BinStyle::default()
+        };
+
+        let json = serde_json::to_string(&style).unwrap();
+        let de: BinStyle = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(de.position, style.position);
+        assert_eq!(de.pos_from_t, style.pos_from_t);
+        assert_eq!(de.width_pct, style.width_pct);
+        assert_eq!(de.back_color, style.back_color);
+        assert_eq!(de.back_image, style.back_image);
+        assert_eq!(de.back_image_effect, style.back_image_effect);
+        assert_eq!(de.text, style.text);
+        assert_eq!(de.text_wrap, style.text_wrap);
+        assert_eq!(de.custom_verts, style.custom_verts);
+        assert_eq!(serde_json::to_string(&de).unwrap(), json);
+    }
+
+    #[test]
+    fn serde_missing_fields() {
+        let style: BinStyle = serde_json::from_str(r#"{"pos_from_l":5.0}"#).unwrap();
+        assert_eq!(style.pos_from_l, Some(5.0));
+        assert_eq!(style.position, None);
+        assert!(style.text.is_empty());
+    }
+
+    #[test]
+    fn serde_back_image() {
+        let style = BinStyle {
+            back_image: Some(ImageCacheKey::url("https://example.com/image.png").unwrap()),
+            ..BinStyle::default()
+        };
+
+        let json = serde_json::to_string(&style).unwrap();
+        let de: BinStyle = serde_json::from_str(&json).unwrap();
+        assert_eq!(de.back_image, style.back_image);
+
+        let style = BinStyle {
+            back_image: Some(ImageCacheKey::Encoded(0)),
+            ..BinStyle::default()
+        };
+
+        assert!(serde_json::to_string(&style).is_err());
+    }
+}
@@ -1,13 +1,16 @@
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 use std::sync::Arc;
 
 use cosmic_text as ct;
 
 use crate::image_cache::{ImageCache, ImageCacheKey, ImageData, ImageFormat};
 use crate::interface::bin::ImageCacheLifetime;
-use crate::interface::{BinStyle, Color, ItfVertInfo, TextHoriAlign, TextVertAlign, TextWrap};
+use crate::interface::{
+    BinStyle, Color, ItfVertInfo, TextDirection, TextHoriAlign, TextVertAlign, TextWrap,
+};
 use crate::render::{ImageSource, UpdateContext};
 use crate::ulps_eq;
 
@@ -23,10 +26,20 @@ struct Inner {
     buffer_width: f32,
     metrics: ct::Metrics,
     attrs: ct::AttrsOwned,
+    // NOTE: The metadata of each span's attrs is the index of the span.
+    spans: Option<Vec<(String, ct::AttrsOwned)>>,
+    // NOTE: False when spans are only used for font fallback.
+    user_spans: bool,
+    // NOTE: The fallback fonts & spans that `spans` was produced from by font fallback.
+    fallback_input: Option<(Vec<String>, Vec<(String, ct::AttrsOwned)>)>,
+    fonts_used: Vec<String>,
     wrap: TextWrap,
     vert_align: TextVertAlign,
-    hori_align: TextHoriAlign,
+    hori_align: Option<TextHoriAlign>,
     buffer: ct::Buffer,
+    // NOTE: The offset of each buffer line within the text and the length of the direction mark
+    //       prepended to the line, if any.
+    line_offsets: Vec<[usize; 2]>,
     update_layout: bool,
     update_vertexes: bool,
     layout_tlwh: [f32; 4],
@@ -36,6 +49,101 @@ struct Inner {
     vertex_data: HashMap<ImageCacheKey, Vec<ItfVertInfo>>,
 }
 
+/// Metrics of text laid out with `Interface::measure_text`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextMetrics {
+    /// The width of the widest line.
+    pub width: f32,
+    /// The height of all lines.
+    pub height: f32,
+    pub line_count: usize,
+    /// The byte offset into the text of where each line starts.
+    pub line_starts: Vec<usize>,
+}
+
+/// Lay out text the same as `TextState` without producing vertexes.
+///
+/// ***Note:** The returned metrics are in logical units. The text is shaped at the provided
+/// scale, so that the result matches what is rendered.*
+pub(crate) fn measure_text(
+    font_system: &mut ct::FontSystem,
+    fallback_fonts: &[String],
+    text: &str,
+    style: &BinStyle,
+    max_width: Option<f32>,
+    scale: f32,
+) -> TextMetrics {
+    if text.is_empty() {
+        return TextMetrics::default();
+    }
+
+    let source_text = text;
+    let text = prepare_text(style, text, true);
+    let metrics = text_metrics(style, scale);
+    let attrs = text_attrs(style, ct::Color::rgba(0, 0, 0, 255));
+
+    let fallback_fonts = match style.font_fallback.as_ref() {
+        Some(font_fallback) => font_fallback.as_slice(),
+        None => fallback_fonts,
+    };
+
+    let spans = if fallback_fonts.is_empty() {
+        None
+    } else {
+        Some(apply_font_fallback(
+            font_system,
+            fallback_fonts,
+            vec![(text.clone(), attrs.clone())],
+        ))
+    };
+
+    let buffer_width = match (max_width, style.text_wrap.unwrap_or_default()) {
+        (_, TextWrap::Shift | TextWrap::None) | (None, _) => f32::MAX,
+        (Some(max_width), _) => max_width * scale,
+    };
+
+    let mut buffer = ct::Buffer::new(font_system, metrics);
+    buffer.set_size(font_system, buffer_width, f32::MAX);
+
+    match spans.as_ref() {
+        Some(spans) => {
+            buffer.set_rich_text(
+                font_system,
+                spans
+                    .iter()
+                    .map(|(text, attrs)| (text.as_str(), attrs.as_attrs())),
+                attrs.as_attrs(),
+                ct::Shaping::Advanced,
+            );
+        },
+        None => {
+            buffer.set_text(font_system, &text, attrs.as_attrs(), ct::Shaping::Advanced);
+        },
+    }
+
+    buffer.shape_until_scroll(font_system, false);
+    let line_offsets = buffer_line_offsets(&buffer, source_text, direction_mark(style));
+    let mut output = TextMetrics::default();
+
+    for run in buffer.layout_runs() {
+        let [line_offset, mark_len] = line_offsets[run.line_i];
+        output.width = output.width.max(run.line_w / scale);
+        output.line_count += 1;
+        output.line_starts.push(
+            line_offset
+                + run
+                    .glyphs
+                    .iter()
+                    .map(|glyph| glyph.start.saturating_sub(mark_len))
+                    .min()
+                    .unwrap_or(0),
+        );
+    }
+
+    output.height = (output.line_count as f32 * metrics.line_height) / scale;
+    output
+}
+
 #[derive(Debug, Clone)]
 struct GlyphInfo {
     cache_key: Option<ImageCacheKey>,
@@ -43,6 +151,11 @@ struct GlyphInfo {
     image_dim: [u32; 2],
     vertex_type: Option<i32>,
     color: Color,
+    span: usize,
+    // NOTE: The byte range of the glyph within the text.
+    range: [usize; 2],
+    // NOTE: The area of the line occupied by the glyph, used for hit testing.
+    hit_tlwh: [f32; 4],
 }
 
 struct GlyphImageAssociatedData {
@@ -66,10 +179,7 @@ impl TextState {
     }
 
     pub fn bounds(&self) -> Option<[f32; 4]> {
-        let inner = match self.inner_op.as_ref() {
-            Some(inner) => inner,
-            None => return None,
-        };
+        let inner = self.inner_op.as_ref()?;
 
         if inner.glyph_infos.is_empty() {
             return None;
@@ -93,6 +203,76 @@ impl TextState {
         Some(bounds)
     }
 
+    /// Retrieve the index of the span at the provided position.
+    pub fn span_at(&self, x: f32, y: f32) -> Option<usize> {
+        let inner = self.inner_op.as_ref()?;
+
+        if !inner.user_spans {
+            return None;
+        }
+
+        inner
+            .glyph_infos
+            .iter()
+            .find(|glyph_info| {
+                let t = glyph_info.hit_tlwh[0] + inner.layout_tlwh[0];
+                let l = glyph_info.hit_tlwh[1] + inner.layout_tlwh[1];
+                x >= l
+                    && x <= l + glyph_info.hit_tlwh[2]
+                    && y >= t
+                    && y <= t + glyph_info.hit_tlwh[3]
+            })
+            .map(|glyph_info| glyph_info.span)
+    }
+
+    /// Retrieve the areas occupied by the glyphs within the provided byte ranges.
+    ///
+    /// Adjacent glyphs on the same line are merged into a single area.
+    pub fn highlight_tlwhs(&self, highlights: &[(Range<usize>, Color)]) -> Vec<([f32; 4], Color)> {
+        let inner = match self.inner_op.as_ref() {
+            Some(inner) => inner,
+            None => return Vec::new(),
+        };
+
+        let mut output: Vec<([f32; 4], Color)> = Vec::new();
+
+        for (range, color) in highlights.iter() {
+            let mut last_included = false;
+
+            for glyph_info in inner.glyph_infos.iter() {
+                if glyph_info.range[0] >= range.end || range.start >= glyph_info.range[1] {
+                    last_included = false;
+                    continue;
+                }
+
+                let t = glyph_info.hit_tlwh[0] + inner.layout_tlwh[0];
+                let l = glyph_info.hit_tlwh[1] + inner.layout_tlwh[1];
+                let [w, h] = [glyph_info.hit_tlwh[2], glyph_info.hit_tlwh[3]];
+
+                match output.last_mut() {
+                    Some((tlwh, _)) if last_included && ulps_eq(tlwh[0], t, 4) => {
+                        let r = (tlwh[1] + tlwh[2]).max(l + w);
+                        tlwh[1] = tlwh[1].min(l);
+                        tlwh[2] = r - tlwh[1];
+                    },
+                    _ => output.push(([t, l, w, h], *color)),
+                }
+
+                last_included = true;
+            }
+        }
+
+        output
+    }
+
+    /// Retrieve the family names of the fonts used to render the text.
+    pub fn fonts_used(&self) -> Vec<String> {
+        self.inner_op
+            .as_ref()
+            .map(|inner| inner.fonts_used.clone())
+            .unwrap_or_default()
+    }
+
     pub fn update_buffer(
         &mut self,
         tlwh: [f32; 4],
@@ -101,65 +281,136 @@ impl TextState {
         style: &BinStyle,
         context: &mut UpdateContext,
     ) {
-        if style.text.is_empty() {
+        let text_spans = style
+            .text_spans
+            .as_ref()
+            .filter(|text_spans| text_spans.iter().any(|span| !span.text.is_empty()));
+
+        if style.text.is_empty() && text_spans.is_none() {
             self.inner_op = None;
             return;
         }
 
-        let text = if style.text_secret == Some(true) {
-            (0..style.text.len()).map(|_| '*').collect::<String>()
-        } else {
-            style.text.clone()
+        let direction_mark = direction_mark(style);
+        let prepare = |text: &String, line_start: bool| prepare_text(style, text, line_start);
+
+        let text = match text_spans {
+            Some(_) => String::new(),
+            None => prepare(&style.text, true),
         };
 
-        let hash = {
-            let mut hasher = DefaultHasher::new();
-            text.hash(&mut hasher);
-            hasher.finish()
+        let metrics = text_metrics(style, context.scale);
+
+        let ct_color = |color: Option<Color>| -> ct::Color {
+            let mut color = color
+                .or(style.text_color)
+                .unwrap_or_else(|| Color::shex("000000"));
+
+            color.a *= opacity;
+            let [r, g, b, a] = color.srgba8_array();
+            ct::Color::rgba(r, g, b, a)
         };
 
-        let font_size = style.text_height.unwrap_or(12.0) * context.scale;
-        let line_height = match style.line_spacing {
-            Some(spacing) => font_size + (spacing * context.scale),
-            None => font_size * 1.2,
+        let attrs = text_attrs(style, ct_color(None));
+
+        let spans = text_spans.map(|text_spans| {
+            text_spans
+                .iter()
+                .enumerate()
+                .map(|(i, span)| {
+                    (
+                        prepare(&span.text, i == 0),
+                        ct::AttrsOwned {
+                            color_opt: Some(ct_color(span.color)),
+                            family_owned: span
+                                .font_family
+                                .clone()
+                                .map(ct::FamilyOwned::Name)
+                                .unwrap_or_else(|| attrs.family_owned.clone()),
+                            style: span.font_style.map(Into::into).unwrap_or(attrs.style),
+                            weight: span.font_weight.map(Into::into).unwrap_or(attrs.weight),
+                            metadata: i,
+                            ..attrs.clone()
+                        },
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let user_spans = spans.is_some();
+
+        let source_text = || -> String {
+            match text_spans {
+                Some(text_spans) => text_spans.iter().map(|span| span.text.as_str()).collect(),
+                None => style.text.clone(),
+            }
         };
 
-        let metrics = ct::Metrics {
-            font_size,
-            line_height,
+        let fallback_fonts = match style.font_fallback.as_ref() {
+            Some(font_fallback) => font_fallback.clone(),
+            None => context.fallback_fonts.clone(),
         };
 
-        let mut color = style.text_color.unwrap_or_else(|| Color::shex("000000"));
+        let fallback_input = if fallback_fonts.is_empty() {
+            None
+        } else {
+            Some((
+                fallback_fonts,
+                spans
+                    .clone()
+                    .unwrap_or_else(|| vec![(text.clone(), attrs.clone())]),
+            ))
+        };
 
-        color.a *= opacity;
-        let [r, g, b, a] = color.srgba8_array();
+        let spans = match fallback_input.as_ref() {
+            Some((fallback_fonts, fallback_spans)) => {
+                match self
+                    .inner_op
+                    .as_ref()
+                    .filter(|inner| inner.fallback_input.as_ref() == fallback_input.as_ref())
+                {
+                    Some(inner) => inner.spans.clone(),
+                    None => {
+                        Some(apply_font_fallback(
+                            &mut context.font_system,
+                            fallback_fonts,
+                            fallback_spans.clone(),
+                        ))
+                    },
+                }
+            },
+            None => spans,
+        };
 
-        let attrs = ct::AttrsOwned {
-            color_opt: Some(ct::Color::rgba(r, g, b, a)),
-            family_owned: style
-                .font_family
-                .clone()
-                .map(ct::FamilyOwned::Name)
-                .unwrap_or(ct::FamilyOwned::SansSerif),
-            stretch: style.font_stretch.unwrap_or_default().into(),
-            style: style.font_style.unwrap_or_default().into(),
-            weight: style.font_weight.unwrap_or_default().into(),
-            metadata: 0,
-            cache_key_flags: ct::CacheKeyFlags::empty(),
+        let hash = {
+            let mut hasher = DefaultHasher::new();
+
+            match spans.as_ref() {
+                Some(spans) => {
+                    for (text, _) in spans.iter() {
+                        text.hash(&mut hasher);
+                    }
+                },
+                None => text.hash(&mut hasher),
+            }
+
+            hasher.finish()
         };
 
         let wrap = style.text_wrap.unwrap_or_default();
         let vert_align = style.text_vert_align.unwrap_or_default();
-        let hori_align = style.text_hori_align.unwrap_or_default();
+        let hori_align = style.text_hori_align;
 
         let buffer_width = matches!(wrap, TextWrap::Shift | TextWrap::None)
             .then_some(f32::MAX)
             .unwrap_or_else(|| tlwh[2] * context.scale);
 
         if let Some(inner) = self.inner_op.as_mut() {
+            inner.fallback_input = fallback_input;
             let metrics_eq = inner.metrics == metrics;
             let buffer_width_eq = inner.buffer_width == buffer_width;
-            let text_and_attrs_eq = inner.hash == hash && inner.attrs == attrs;
+            let text_and_attrs_eq =
+                inner.hash == hash && inner.attrs == attrs && inner.spans == spans;
 
             if metrics_eq
                 && buffer_width_eq
@@ -221,13 +472,18 @@ impl TextState {
             if !text_and_attrs_eq {
                 inner.hash = hash;
                 inner.attrs = attrs;
-
-                inner.buffer.set_text(
-                    &mut context.font_system,
-                    text.as_str(),
-                    inner.attrs.as_attrs(),
-                    ct::Shaping::Advanced,
+                inner.spans = spans;
+                inner.user_spans = user_spans;
+                set_buffer_text(
+                    &mut inner.buffer,
+                    context,
+                    &text,
+                    &inner.attrs,
+                    inner.spans.as_ref(),
                 );
+
+                inner.line_offsets =
+                    buffer_line_offsets(&inner.buffer, &source_text(), direction_mark);
             }
 
             inner.layout_tlwh = tlwh;
@@ -241,12 +497,8 @@ impl TextState {
         let mut buffer = ct::Buffer::new(&mut context.font_system, metrics);
         buffer.set_size(&mut context.font_system, buffer_width, f32::MAX);
 
-        buffer.set_text(
-            &mut context.font_system,
-            text.as_str(),
-            attrs.as_attrs(),
-            ct::Shaping::Advanced,
-        );
+        set_buffer_text(&mut buffer, context, &text, &attrs, spans.as_ref());
+        let line_offsets = buffer_line_offsets(&buffer, &source_text(), direction_mark);
 
         self.inner_op = Some(Inner {
             hash,
@@ -254,10 +506,15 @@ impl TextState {
             buffer_width,
             metrics,
             attrs,
+            spans,
+            user_spans,
+            fallback_input,
+            fonts_used: Vec::new(),
             wrap,
             vert_align,
             hori_align,
             buffer,
+            line_offsets,
             update_layout: true,
             update_vertexes: false,
             layout_tlwh: tlwh,
@@ -278,6 +535,7 @@ impl TextState {
             let mut max_line_y = None;
             let mut image_cache_keys = HashSet::new();
             let mut glyph_infos = Vec::new();
+            let mut font_ids = HashSet::new();
 
             for run in inner.buffer.layout_runs() {
                 if run.line_i == 0 {
@@ -288,18 +546,13 @@ impl TextState {
                     max_line_y = Some(run.line_y);
                 }
 
-                let hori_align =
-                    if inner.wrap == TextWrap::Shift && run.line_w > inner.layout_tlwh[2] {
-                        TextHoriAlign::Right
-                    } else {
-                        inner.hori_align
-                    };
-
-                let hori_align_offset = match hori_align {
-                    TextHoriAlign::Left => 0.0,
-                    TextHoriAlign::Center => ((inner.layout_tlwh[2] - run.line_w) / 2.0).round(),
-                    TextHoriAlign::Right => (inner.layout_tlwh[2] - run.line_w).round(),
-                };
+                let hori_align_offset = hori_align_offset(
+                    inner.wrap,
+                    inner.hori_align,
+                    run.rtl,
+                    run.line_w,
+                    inner.layout_tlwh[2],
+                );
 
                 for glyph in run.glyphs.iter() {
                     let color = glyph
@@ -311,6 +564,16 @@ impl TextState {
                         })
                         .unwrap();
 
+                    font_ids.insert(glyph.font_id);
+                    let span = glyph.metadata;
+                    let [line_offset, mark_len] = inner.line_offsets[run.line_i];
+
+                    let range = [
+                        line_offset + glyph.start.saturating_sub(mark_len),
+                        line_offset + glyph.end.saturating_sub(mark_len),
+                    ];
+
+                    let glyph_w = glyph.w;
                     let glyph = glyph.physical((0.0, 0.0), 1.0);
                     let image_cache_key = ImageCacheKey::Glyph(glyph.cache_key);
                     image_cache_keys.insert(image_cache_key.clone());
@@ -318,6 +581,9 @@ impl TextState {
                     glyph_infos.push((
                         image_cache_key,
                         color,
+                        span,
+                        range,
+                        glyph_w,
                         glyph.x as f32 + hori_align_offset,
                         run.line_y
                             - ((inner.metrics.line_height - inner.metrics.font_size) / 2.0).floor(),
@@ -325,6 +591,21 @@ impl TextState {
                 }
             }
 
+            let mut fonts_used = font_ids
+                .into_iter()
+                .filter_map(|font_id| {
+                    context
+                        .font_system
+                        .db()
+                        .face(font_id)
+                        .and_then(|face| face.families.first().map(|(name, _)| name.clone()))
+                })
+                .collect::<Vec<_>>();
+
+            fonts_used.sort();
+            fonts_used.dedup();
+            inner.fonts_used = fonts_used;
+
             if glyph_infos.is_empty() {
                 inner.glyph_infos = Vec::new();
                 inner.update_vertexes = true;
@@ -338,7 +619,7 @@ impl TextState {
             for (image_info_op, image_cache_key) in image_cache
                 .obtain_image_infos(image_cache_keys.clone())
                 .into_iter()
-                .zip(image_cache_keys.into_iter())
+                .zip(image_cache_keys)
             {
                 if let Some(image_info) = image_info_op {
                     image_infos.insert(image_cache_key.clone(), image_info);
@@ -398,43 +679,63 @@ impl TextState {
 
             inner.glyph_infos = glyph_infos
                 .into_iter()
-                .map(|(image_cache_key, color, mut glyph_x, mut glyph_y)| {
-                    match image_infos.get(&image_cache_key) {
-                        Some(image_info) => {
-                            let associated_data = image_info
-                                .associated_data::<GlyphImageAssociatedData>()
-                                .unwrap();
-
-                            let image_dim = [image_info.width, image_info.height];
-                            glyph_y += vert_align_offset - associated_data.placement_top as f32;
-                            glyph_x += associated_data.placement_left as f32;
-
-                            let glyph_tlwh = [
-                                glyph_y / context.scale,
-                                glyph_x / context.scale,
-                                image_dim[0] as f32 / context.scale,
-                                image_dim[1] as f32 / context.scale,
-                            ];
-
-                            GlyphInfo {
-                                cache_key: Some(image_cache_key),
-                                tlwh: glyph_tlwh,
-                                image_dim,
-                                vertex_type: Some(associated_data.vertex_type),
-                                color,
-                            }
-                        },
-                        None => {
-                            GlyphInfo {
-                                cache_key: None,
-                                tlwh: [glyph_y / context.scale, glyph_x / context.scale, 0.0, 0.0],
-                                image_dim: [0; 2],
-                                vertex_type: None,
-                                color,
-                            }
-                        },
-                    }
-                })
+                .map(
+                    |(image_cache_key, color, span, range, glyph_w, mut glyph_x, mut glyph_y)| {
+                        let hit_tlwh = [
+                            (glyph_y + vert_align_offset - inner.metrics.font_size) / context.scale,
+                            glyph_x / context.scale,
+                            glyph_w / context.scale,
+                            inner.metrics.line_height / context.scale,
+                        ];
+
+                        match image_infos.get(&image_cache_key) {
+                            Some(image_info) => {
+                                let associated_data = image_info
+                                    .associated_data::<GlyphImageAssociatedData>()
+                                    .unwrap();
+
+                                let image_dim = [image_info.width, image_info.height];
+                                glyph_y += vert_align_offset - associated_data.placement_top as f32;
+                                glyph_x += associated_data.placement_left as f32;
+
+                                let glyph_tlwh = [
+                                    glyph_y / context.scale,
+                                    glyph_x / context.scale,
+                                    image_dim[0] as f32 / context.scale,
+                                    image_dim[1] as f32 / context.scale,
+                                ];
+
+                                GlyphInfo {
+                                    cache_key: Some(image_cache_key),
+                                    tlwh: glyph_tlwh,
+                                    image_dim,
+                                    vertex_type: Some(associated_data.vertex_type),
+                                    color,
+                                    span,
+                                    range,
+                                    hit_tlwh,
+                                }
+                            },
+                            None => {
+                                GlyphInfo {
+                                    cache_key: None,
+                                    tlwh: [
+                                        glyph_y / context.scale,
+                                        glyph_x / context.scale,
+                                        0.0,
+                                        0.0,
+                                    ],
+                                    image_dim: [0; 2],
+                                    vertex_type: None,
+                                    color,
+                                    span,
+                                    range,
+                                    hit_tlwh,
+                                }
+                            },
+                        }
+                    },
+                )
                 .collect();
 
             inner.image_cache_keys = valid_image_cache_keys;
@@ -519,6 +820,7 @@ impl TextState {
                                     color,
                                     ty,
                                     tex_i: 0,
+                                    opacity: 1.0,
                                 },
                                 ItfVertInfo {
                                     position: [l[0], t[0], z],
@@ -526,6 +828,7 @@ impl TextState {
                                     color,
                                     ty,
                                     tex_i: 0,
+                                    opacity: 1.0,
                                 },
                                 ItfVertInfo {
                                     position: [l[0], b[0], z],
@@ -533,6 +836,7 @@ impl TextState {
                                     color,
                                     ty,
                                     tex_i: 0,
+                                    opacity: 1.0,
                                 },
                                 ItfVertInfo {
                                     position: [r[0], t[0], z],
@@ -540,6 +844,7 @@ impl TextState {
                                     color,
                                     ty,
                                     tex_i: 0,
+                                    opacity: 1.0,
                                 },
                                 ItfVertInfo {
                                     position: [l[0], b[0], z],
@@ -547,6 +852,7 @@ impl TextState {
                                     color,
                                     ty,
                                     tex_i: 0,
+                                    opacity: 1.0,
                                 },
                                 ItfVertInfo {
                                     position: [r[0], b[0], z],
@@ -554,6 +860,7 @@ impl TextState {
                                     color,
                                     ty,
                                     tex_i: 0,
+                                    opacity: 1.0,
                                 },
                             ]);
                     }
@@ -575,3 +882,352 @@ impl TextState {
         }
     }
 }
+
+// NOTE: Lines without an alignment are aligned to the side their direction starts from.
+fn hori_align_offset(
+    wrap: TextWrap,
+    hori_align: Option<TextHoriAlign>,
+    rtl: bool,
+    line_w: f32,
+    width: f32,
+) -> f32 {
+    let hori_align = if wrap == TextWrap::Shift && line_w > width {
+        TextHoriAlign::Right
+    } else {
+        match hori_align {
+            Some(hori_align) => hori_align,
+            None if rtl => TextHoriAlign::Right,
+            None => TextHoriAlign::Left,
+        }
+    };
+
+    match hori_align {
+        TextHoriAlign::Left => 0.0,
+        TextHoriAlign::Center => ((width - line_w) / 2.0).round(),
+        TextHoriAlign::Right => (width - line_w).round(),
+    }
+}
+
+fn text_metrics(style: &BinStyle, scale: f32) -> ct::Metrics {
+    let font_size = style.text_height.unwrap_or(12.0) * scale;
+
+    let line_height = match style.line_spacing {
+        Some(spacing) => font_size + (spacing * scale),
+        None => font_size * 1.2,
+    };
+
+    ct::Metrics {
+        font_size,
+        line_height,
+    }
+}
+
+fn text_attrs(style: &BinStyle, color: ct::Color) -> ct::AttrsOwned {
+    ct::AttrsOwned {
+        color_opt: Some(color),
+        family_owned: style
+            .font_family
+            .clone()
+            .map(ct::FamilyOwned::Name)
+            .unwrap_or(ct::FamilyOwned::SansSerif),
+        stretch: style.font_stretch.unwrap_or_default().into(),
+        style: style.font_style.unwrap_or_default().into(),
+        weight: style.font_weight.unwrap_or_default().into(),
+        metadata: 0,
+        cache_key_flags: ct::CacheKeyFlags::empty(),
+    }
+}
+
+fn set_buffer_text(
+    buffer: &mut ct::Buffer,
+    context: &mut UpdateContext,
+    text: &str,
+    attrs: &ct::AttrsOwned,
+    spans: Option<&Vec<(String, ct::AttrsOwned)>>,
+) {
+    match spans {
+        Some(spans) => {
+            buffer.set_rich_text(
+                &mut context.font_system,
+                spans
+                    .iter()
+                    .map(|(text, attrs)| (text.as_str(), attrs.as_attrs())),
+                attrs.as_attrs(),
+                ct::Shaping::Advanced,
+            );
+        },
+        None => {
+            buffer.set_text(
+                &mut context.font_system,
+                text,
+                attrs.as_attrs(),
+                ct::Shaping::Advanced,
+            );
+        },
+    }
+}
+
+// NOTE: Offsets of each buffer line within the text, as line endings aren't retained. The
+//       direction mark, if present at the start of a line, isn't part of the text.
+fn buffer_line_offsets(buffer: &ct::Buffer, text: &str, mark: Option<char>) -> Vec<[usize; 2]> {
+    let mut line_offsets = Vec::with_capacity(buffer.lines.len());
+    let mut offset = 0;
+
+    for line in buffer.lines.iter() {
+        let mark_len = match mark {
+            Some(mark) if line.text().starts_with(mark) => mark.len_utf8(),
+            _ => 0,
+        };
+
+        line_offsets.push([offset, mark_len]);
+        offset += line.text().len() - mark_len;
+
+        let rest = text.get(offset..).unwrap_or("");
+
+        if rest.starts_with("\r\n") {
+            offset += 2;
+        } else if rest.starts_with(['\n', '\r']) {
+            offset += 1;
+        }
+    }
+
+    line_offsets
+}
+
+// NOTE: cosmic-text determines the direction of each line from its first strong directional
+//       character, so a mark is inserted at the start of each line.
+fn direction_mark(style: &BinStyle) -> Option<char> {
+    match style.text_direction.unwrap_or_default() {
+        TextDirection::Auto => None,
+        TextDirection::Ltr => Some('\u{200E}'),
+        TextDirection::Rtl => Some('\u{200F}'),
+    }
+}
+
+fn prepare_text(style: &BinStyle, text: &str, line_start: bool) -> String {
+    let text = if style.text_secret == Some(true) {
+        (0..text.len()).map(|_| '*').collect::<String>()
+    } else {
+        text.to_string()
+    };
+
+    match direction_mark(style) {
+        Some(mark) => apply_direction_mark(&text, mark, line_start),
+        None => text,
+    }
+}
+
+fn apply_direction_mark(text: &str, mark: char, line_start: bool) -> String {
+    let mut output = String::with_capacity(text.len() + 3);
+
+    if line_start {
+        output.push(mark);
+    }
+
+    for c in text.chars() {
+        output.push(c);
+
+        if c == '\n' {
+            output.push(mark);
+        }
+    }
+
+    output
+}
+
+// NOTE: Splits spans so that characters missing from the font of their span use the first font
+//       in the fallback list that contains them.
+fn apply_font_fallback(
+    font_system: &mut ct::FontSystem,
+    fallback_fonts: &[String],
+    spans: Vec<(String, ct::AttrsOwned)>,
+) -> Vec<(String, ct::AttrsOwned)> {
+    let mut fonts: HashMap<(Option<usize>, usize), Option<Arc<ct::Font>>> = HashMap::new();
+    let mut output: Vec<(String, ct::AttrsOwned)> = Vec::new();
+
+    for (span_i, (text, attrs)) in spans.iter().enumerate() {
+        let mut font = |font_system: &mut ct::FontSystem, fallback_i: Option<usize>| {
+            fonts
+                .entry((fallback_i, span_i))
+                .or_insert_with(|| {
+                    let attrs = attrs.as_attrs();
+
+                    let family = match fallback_i {
+                        Some(i) => ct::Family::Name(fallback_fonts[i].as_str()),
+                        None => attrs.family,
+                    };
+
+                    let id = font_system.db().query(&ct::fontdb::Query {
+                        families: &[family],
+                        weight: attrs.weight,
+                        stretch: attrs.stretch,
+                        style: attrs.style,
+                    })?;
+
+                    font_system.get_font(id)
+                })
+                .clone()
+        };
+
+        for c in text.chars() {
+            let has_char = |font: Option<Arc<ct::Font>>| {
+                font.map(|font| font.rustybuzz().glyph_index(c).is_some())
+                    .unwrap_or(false)
+            };
+
+            let family = if c.is_control() || has_char(font(font_system, None)) {
+                None
+            } else {
+                (0..fallback_fonts.len()).find(|i| has_char(font(font_system, Some(*i))))
+            };
+
+            let family_owned = match family {
+                Some(i) => ct::FamilyOwned::Name(fallback_fonts[i].clone()),
+                None => attrs.family_owned.clone(),
+            };
+
+            match output.last_mut() {
+                Some((last_text, last_attrs))
+                    if last_attrs.metadata == attrs.metadata
+                        && last_attrs.family_owned == family_owned =>
+                {
+                    last_text.push(c);
+                },
+                _ => {
+                    output.push((
+                        c.to_string(),
+                        ct::AttrsOwned {
+                            family_owned,
+                            ..attrs.clone()
+                        },
+                    ));
+                },
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmic_text as ct;
+
+    use super::{hori_align_offset, prepare_text};
+    use crate::interface::{BinStyle, TextDirection, TextHoriAlign, TextWrap};
+
+    fn layout(text: &str, style: &BinStyle) -> Vec<(bool, Vec<(f32, usize)>)> {
+        let mut db = ct::fontdb::Database::new();
+
+        db.load_font_data(
+            include_bytes!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fonts/NotoSansHebrew.ttf"
+            ))
+            .to_vec(),
+        );
+
+        let mut font_system = ct::FontSystem::new_with_locale_and_db(String::from("en-US"), db);
+        let mut buffer = ct::Buffer::new(&mut font_system, ct::Metrics::new(12.0, 12.0));
+        buffer.set_size(&mut font_system, 1000.0, f32::MAX);
+
+        buffer.set_text(
+            &mut font_system,
+            &prepare_text(style, text, true),
+            ct::Attrs::new(),
+            ct::Shaping::Advanced,
+        );
+
+        buffer.shape_until_scroll(&mut font_system, false);
+
+        buffer
+            .layout_runs()
+            .map(|run| {
+                (
+                    run.rtl,
+                    run.glyphs
+                        .iter()
+                        .map(|glyph| (glyph.x, glyph.start))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn line_directions(text: &str, style: &BinStyle) -> Vec<bool> {
+        layout(text, style)
+            .into_iter()
+            .map(|(rtl, _)| rtl)
+            .collect()
+    }
+
+    #[test]
+    fn prepare_secret_rtl() {
+        let style = BinStyle {
+            text_secret: Some(true),
+            text_direction: Some(TextDirection::Rtl),
+            ..BinStyle::default()
+        };
+
+        assert_eq!(prepare_text(&style, "abc", true), "\u{200F}***");
+        assert_eq!(prepare_text(&style, "abc", false), "***");
+
+        let style = BinStyle {
+            text_direction: Some(TextDirection::Ltr),
+            ..BinStyle::default()
+        };
+
+        assert_eq!(prepare_text(&style, "a\nb", true), "\u{200E}a\n\u{200E}b");
+    }
+
+    #[test]
+    fn rtl_layout() {
+        let text = "\u{5E9}\u{5DC}\u{5D5}\u{5DD}\nabc";
+        assert_eq!(line_directions(text, &BinStyle::default()), [true, false]);
+
+        let style = BinStyle {
+            text_direction: Some(TextDirection::Rtl),
+            ..BinStyle::default()
+        };
+
+        assert_eq!(line_directions(text, &style), [true, true]);
+
+        let style = BinStyle {
+            text_direction: Some(TextDirection::Ltr),
+            ..BinStyle::default()
+        };
+
+        assert_eq!(line_directions(text, &style), [false, false]);
+    }
+
+    #[test]
+    fn rtl_visual_order() {
+        let text = "\u{5E9}\u{5DC}\u{5D5}\u{5DD}";
+        let lines = layout(text, &BinStyle::default());
+        assert_eq!(lines.len(), 1);
+
+        let (rtl, mut glyphs) = lines.into_iter().next().unwrap();
+        assert!(rtl);
+        assert_eq!(glyphs.len(), 4);
+
+        // NOTE: In visual order from left to right, the first character is right-most.
+        glyphs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let starts = glyphs.iter().map(|(_, start)| *start).collect::<Vec<_>>();
+        assert_eq!(starts, [6, 4, 2, 0]);
+    }
+
+    #[test]
+    fn rtl_alignment() {
+        let wrap = TextWrap::Normal;
+        assert_eq!(hori_align_offset(wrap, None, true, 40.0, 100.0), 60.0);
+        assert_eq!(hori_align_offset(wrap, None, false, 40.0, 100.0), 0.0);
+
+        let left = Some(TextHoriAlign::Left);
+        let center = Some(TextHoriAlign::Center);
+        assert_eq!(hori_align_offset(wrap, left, true, 40.0, 100.0), 0.0);
+        assert_eq!(hori_align_offset(wrap, center, true, 40.0, 100.0), 30.0);
+
+        let shift = TextWrap::Shift;
+        assert_eq!(hori_align_offset(shift, left, false, 140.0, 100.0), -40.0);
+    }
+}
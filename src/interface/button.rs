@@ -0,0 +1,267 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::input::{InputHookCtrl, MouseButton};
+use crate::interface::widget::{new_widget_bin, set_widget_theme_style};
+use crate::interface::{Bin, BinStyle, TextHoriAlign, Theme};
+use crate::window::Window;
+
+/// The visual state of a `Button`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState {
+    Normal,
+    Hover,
+    Pressed,
+    Disabled,
+}
+
+/// A button with a text label.
+///
+/// Colors are derived from the current `Theme` and the state of the button. The positioning of
+/// the button can be changed through the `Bin` it derefs to.
+pub struct Button {
+    bin: Arc<Bin>,
+    state: Mutex<State>,
+    on_click: Mutex<Vec<Box<dyn FnMut(&Arc<Button>) + Send + 'static>>>,
+}
+
+struct State {
+    enabled: bool,
+    hovered: bool,
+    pressed: bool,
+}
+
+impl State {
+    fn button_state(&self) -> ButtonState {
+        if !self.enabled {
+            ButtonState::Disabled
+        } else if self.pressed {
+            ButtonState::Pressed
+        } else if self.hovered {
+            ButtonState::Hover
+        } else {
+            ButtonState::Normal
+        }
+    }
+}
+
+impl Deref for Button {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bin
+    }
+}
+
+impl Button {
+    /// Create a new button.
+    ///
+    /// The button is placed at the top left of the parent or window if no parent is provided.
+    ///
+    /// # Notes
+    /// - Panics if parent bin is not associated to the window provided.
+    pub fn new<T: Into<String>>(
+        window: Arc<Window>,
+        parent: Option<Arc<Bin>>,
+        text: T,
+    ) -> Arc<Self> {
+        let bin = new_widget_bin(
+            &window,
+            parent,
+            BinStyle {
+                pos_from_t: Some(0.0),
+                pos_from_l: Some(0.0),
+                width: Some(120.0),
+                height: Some(30.0),
+                text: text.into(),
+                ..BinStyle::default()
+            },
+        );
+
+        let button = Arc::new(Self {
+            bin,
+            state: Mutex::new(State {
+                enabled: true,
+                hovered: false,
+                pressed: false,
+            }),
+            on_click: Mutex::new(Vec::new()),
+        });
+
+        let button_wk = Arc::downgrade(&button);
+
+        set_widget_theme_style(&button.bin, move |theme, base| {
+            match button_wk.upgrade() {
+                Some(button) => button.themed_style(theme, base),
+                None => base,
+            }
+        });
+
+        let button_wk = Arc::downgrade(&button);
+
+        button.bin.on_enter(move |_, _| {
+            match button_wk.upgrade() {
+                Some(button) => {
+                    button.modify_state(|state| state.hovered = true);
+                    Default::default()
+                },
+                None => InputHookCtrl::Remove,
+            }
+        });
+
+        let button_wk = Arc::downgrade(&button);
+
+        button.bin.on_leave(move |_, _| {
+            match button_wk.upgrade() {
+                Some(button) => {
+                    button.modify_state(|state| {
+                        state.hovered = false;
+                        state.pressed = false;
+                    });
+
+                    Default::default()
+                },
+                None => InputHookCtrl::Remove,
+            }
+        });
+
+        let button_wk = Arc::downgrade(&button);
+
+        button.bin.on_press(MouseButton::Left, move |_, _, _| {
+            match button_wk.upgrade() {
+                Some(button) => {
+                    button.modify_state(|state| {
+                        if state.enabled {
+                            state.pressed = true;
+                        }
+                    });
+
+                    Default::default()
+                },
+                None => InputHookCtrl::Remove,
+            }
+        });
+
+        let button_wk = Arc::downgrade(&button);
+
+        button.bin.on_release(MouseButton::Left, move |_, _, _| {
+            let button = match button_wk.upgrade() {
+                Some(some) => some,
+                None => return InputHookCtrl::Remove,
+            };
+
+            let mut clicked = false;
+
+            button.modify_state(|state| {
+                clicked = state.enabled && state.pressed && state.hovered;
+                state.pressed = false;
+            });
+
+            if clicked {
+                for method in button.on_click.lock().iter_mut() {
+                    method(&button);
+                }
+            }
+
+            Default::default()
+        });
+
+        button
+    }
+
+    /// Add a method to be called when the button is clicked.
+    ///
+    /// ***Note:** This is not called while the button is disabled.*
+    pub fn on_click<F: FnMut(&Arc<Button>) + Send + 'static>(&self, method: F) {
+        self.on_click.lock().push(Box::new(method));
+    }
+
+    /// Enable or disable the button.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.modify_state(|state| {
+            state.enabled = enabled;
+
+            if !enabled {
+                state.pressed = false;
+            }
+        });
+    }
+
+    /// Check if the button is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().enabled
+    }
+
+    /// Retrieve the current visual state of the button.
+    pub fn state(&self) -> ButtonState {
+        self.state.lock().button_state()
+    }
+
+    /// Change the text of the button's label.
+    pub fn set_text<T: Into<String>>(&self, text: T) {
+        self.bin
+            .style_update(BinStyle {
+                text: text.into(),
+                ..self.bin.style_copy()
+            })
+            .expect_valid();
+    }
+
+    fn modify_state<F: FnOnce(&mut State)>(&self, method: F) {
+        let mut state = self.state.lock();
+        let before = state.button_state();
+        method(&mut state);
+
+        if state.button_state() != before {
+            drop(state);
+            let theme = self.bin.basalt_ref().interface_ref().theme();
+            let _ = self.bin.apply_theme_style(&theme);
+        }
+    }
+
+    fn themed_style(&self, theme: &Theme, base: BinStyle) -> BinStyle {
+        let themed = theme.button_style(base.text.clone());
+
+        let (back_color, border_color, text_color) = match self.state() {
+            ButtonState::Normal => (theme.colors.back3, theme.colors.border1, theme.colors.text1),
+            ButtonState::Hover => (theme.colors.back3, theme.colors.border2, theme.colors.text1),
+            ButtonState::Pressed => {
+                (
+                    theme.colors.accent2,
+                    theme.colors.accent2,
+                    theme.colors.text_on_accent,
+                )
+            },
+            ButtonState::Disabled => (theme.colors.back2, theme.colors.border1, theme.colors.text2),
+        };
+
+        BinStyle {
+            back_color: Some(back_color),
+            border_size_t: themed.border_size_t,
+            border_size_b: themed.border_size_b,
+            border_size_l: themed.border_size_l,
+            border_size_r: themed.border_size_r,
+            border_color_t: Some(border_color),
+            border_color_b: Some(border_color),
+            border_color_l: Some(border_color),
+            border_color_r: Some(border_color),
+            border_radius_tl: themed.border_radius_tl,
+            border_radius_tr: themed.border_radius_tr,
+            border_radius_bl: themed.border_radius_bl,
+            border_radius_br: themed.border_radius_br,
+            pad_l: themed.pad_l,
+            pad_r: themed.pad_r,
+            text_color: Some(text_color),
+            text_height: themed.text_height,
+            text_vert_align: themed.text_vert_align,
+            text_hori_align: Some(TextHoriAlign::Center),
+            font_family: themed.font_family,
+            font_weight: themed.font_weight,
+            font_stretch: themed.font_stretch,
+            font_style: themed.font_style,
+            ..base
+        }
+    }
+}
@@ -0,0 +1,610 @@
+use std::ops::Deref;
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+
+use crate::input::{InputHookCtrl, MouseButton, Qwerty};
+use crate::interface::widget::{new_widget_bin, set_widget_theme_style};
+use crate::interface::{Bin, BinPosition, BinStyle, TextVertAlign, TextWrap, Theme};
+use crate::window::Window;
+
+/// The maximum height of the list of options before it becomes scrollable.
+const MAX_POPUP_HEIGHT: f32 = 240.0;
+
+/// A box displaying the selected option that opens a list of options when clicked.
+///
+/// While focused, the arrow keys move through the options, enter selects and escape closes the
+/// list. Typing filters the options. Colors are derived from the current `Theme`.
+pub struct ComboBox {
+    bin: Arc<Bin>,
+    popup: Arc<Bin>,
+    state: Mutex<State>,
+    on_select: Mutex<Vec<Box<dyn FnMut(&Arc<ComboBox>, usize) + Send + 'static>>>,
+}
+
+struct State {
+    options: Vec<String>,
+    selected: Option<usize>,
+    open: bool,
+    filter: String,
+    // NOTE: Indexes of options that match the filter.
+    filtered: Vec<usize>,
+    // NOTE: Index into filtered.
+    highlighted: Option<usize>,
+    // NOTE: A bin for each option, created when the list is first opened.
+    option_bins: Vec<Arc<Bin>>,
+    // NOTE: The index into filtered & if highlighted that each option bin is styled with. `None`
+    //       when the option bin is hidden.
+    option_states: Vec<Option<(usize, bool)>>,
+}
+
+impl Deref for ComboBox {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bin
+    }
+}
+
+impl ComboBox {
+    /// Create a new combo box with the provided options.
+    ///
+    /// The combo box is placed at the top left of the parent or window if no parent is provided.
+    ///
+    /// # Notes
+    /// - Panics if parent bin is not associated to the window provided.
+    pub fn new<T: Into<String>>(
+        window: Arc<Window>,
+        parent: Option<Arc<Bin>>,
+        options: Vec<T>,
+    ) -> Arc<Self> {
+        let bin = new_widget_bin(
+            &window,
+            parent,
+            BinStyle {
+                pos_from_t: Some(0.0),
+                pos_from_l: Some(0.0),
+                width: Some(160.0),
+                height: Some(30.0),
+                ..BinStyle::default()
+            },
+        );
+
+        let popup = window.new_bin();
+
+        // NOTE: The list is placed in the layer below tooltips, so that it is displayed above
+        //       everything else.
+        popup
+            .style_update(BinStyle {
+                position: Some(BinPosition::Window),
+                z_layer: Some(i8::MAX - 1),
+                hidden: Some(true),
+                pos_from_t: Some(0.0),
+                pos_from_l: Some(0.0),
+                width: Some(0.0),
+                height: Some(0.0),
+                ..BinStyle::default()
+            })
+            .expect_valid();
+
+        popup.add_scroll_events(false);
+
+        let combo_box = Arc::new(Self {
+            bin,
+            popup,
+            state: Mutex::new(State {
+                options: options.into_iter().map(Into::into).collect(),
+                selected: None,
+                open: false,
+                filter: String::new(),
+                filtered: Vec::new(),
+                highlighted: None,
+                option_bins: Vec::new(),
+                option_states: Vec::new(),
+            }),
+            on_select: Mutex::new(Vec::new()),
+        });
+
+        let cb_wk = Arc::downgrade(&combo_box);
+
+        set_widget_theme_style(&combo_box.bin, move |theme, base| {
+            match cb_wk.upgrade() {
+                Some(combo_box) => combo_box.themed_style(theme, base),
+                None => base,
+            }
+        });
+
+        let cb_wk = Arc::downgrade(&combo_box);
+
+        combo_box.bin.on_press(MouseButton::Left, move |_, _, _| {
+            match cb_wk.upgrade() {
+                Some(combo_box) => {
+                    match combo_box.is_open() {
+                        true => combo_box.close(),
+                        false => combo_box.open(),
+                    }
+
+                    Default::default()
+                },
+                None => InputHookCtrl::Remove,
+            }
+        });
+
+        for (key, step) in [(Qwerty::ArrowDown, 1), (Qwerty::ArrowUp, -1)] {
+            let cb_wk = Arc::downgrade(&combo_box);
+
+            combo_box.bin.on_press(key, move |_, _, _| {
+                match cb_wk.upgrade() {
+                    Some(combo_box) => {
+                        match combo_box.is_open() {
+                            true => combo_box.move_highlight(step),
+                            false => combo_box.open(),
+                        }
+
+                        Default::default()
+                    },
+                    None => InputHookCtrl::Remove,
+                }
+            });
+        }
+
+        let cb_wk = Arc::downgrade(&combo_box);
+
+        combo_box.bin.on_press(Qwerty::Enter, move |_, _, _| {
+            let combo_box = match cb_wk.upgrade() {
+                Some(some) => some,
+                None => return InputHookCtrl::Remove,
+            };
+
+            if !combo_box.is_open() {
+                combo_box.open();
+                return Default::default();
+            }
+
+            let index_op = {
+                let state = combo_box.state.lock();
+                state.highlighted.map(|i| state.filtered[i])
+            };
+
+            match index_op {
+                Some(index) => combo_box.select_and_emit(index),
+                None => combo_box.close(),
+            }
+
+            Default::default()
+        });
+
+        let cb_wk = Arc::downgrade(&combo_box);
+
+        combo_box.bin.on_press(Qwerty::Esc, move |_, _, _| {
+            match cb_wk.upgrade() {
+                Some(combo_box) => {
+                    combo_box.close();
+                    Default::default()
+                },
+                None => InputHookCtrl::Remove,
+            }
+        });
+
+        let cb_wk = Arc::downgrade(&combo_box);
+
+        combo_box.bin.on_character(move |_, _, c| {
+            let combo_box = match cb_wk.upgrade() {
+                Some(some) => some,
+                None => return InputHookCtrl::Remove,
+            };
+
+            if !c.is_backspace() && c.is_control() {
+                return Default::default();
+            }
+
+            let open = {
+                let mut state = combo_box.state.lock();
+                c.modify_string(&mut state.filter);
+                state.open
+            };
+
+            match open {
+                true => combo_box.refresh(),
+                false => combo_box.open(),
+            }
+
+            Default::default()
+        });
+
+        // NOTE: Pressing anywhere outside of the combo box or list closes the list.
+        let cb_wk = Arc::downgrade(&combo_box);
+
+        combo_box.bin.attach_input_hook(
+            window
+                .basalt_ref()
+                .input_ref()
+                .hook()
+                .window(&window)
+                .on_press()
+                .keys(MouseButton::Left)
+                .call(move |target, window_state, _| {
+                    let combo_box = match cb_wk.upgrade() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
+
+                    if !combo_box.is_open() {
+                        return Default::default();
+                    }
+
+                    let window = match target.into_window() {
+                        Some(some) => some,
+                        None => return Default::default(),
+                    };
+
+                    let [x, y] = window_state.cursor_pos();
+
+                    let inside = match window.bin_at(x, y) {
+                        Some(bin) => {
+                            bin == combo_box.bin
+                                || bin == combo_box.popup
+                                || bin.ancestors().contains(&combo_box.popup)
+                        },
+                        None => false,
+                    };
+
+                    if !inside {
+                        combo_box.close();
+                    }
+
+                    Default::default()
+                })
+                .finish()
+                .unwrap(),
+        );
+
+        combo_box
+    }
+
+    /// Add a method to be called when an option is selected by the user.
+    ///
+    /// ***Note:** This is not called when an option is selected with `select`.*
+    pub fn on_select<F: FnMut(&Arc<ComboBox>, usize) + Send + 'static>(&self, method: F) {
+        self.on_select.lock().push(Box::new(method));
+    }
+
+    /// Replace the options.
+    ///
+    /// ***Note:** This clears the selection.*
+    pub fn set_options<T: Into<String>>(self: &Arc<Self>, options: Vec<T>) {
+        {
+            let mut state = self.state.lock();
+            state.options = options.into_iter().map(Into::into).collect();
+            state.selected = None;
+            state.option_bins.clear();
+            state.option_states.clear();
+        }
+
+        self.update_text();
+        self.refresh();
+    }
+
+    /// Retrieve the options.
+    pub fn options(&self) -> Vec<String> {
+        self.state.lock().options.clone()
+    }
+
+    /// Retrieve the index of the selected option.
+    pub fn selected(&self) -> Option<usize> {
+        self.state.lock().selected
+    }
+
+    /// Select an option by its index or clear the selection with `None`.
+    ///
+    /// ***Note:** Indexes beyond the amount of options clear the selection.*
+    pub fn select(&self, index: Option<usize>) {
+        {
+            let mut state = self.state.lock();
+            state.selected = index.filter(|index| *index < state.options.len());
+        }
+
+        self.update_text();
+    }
+
+    /// Check if the list of options is open.
+    pub fn is_open(&self) -> bool {
+        self.state.lock().open
+    }
+
+    /// Open the list of options.
+    pub fn open(self: &Arc<Self>) {
+        {
+            let mut state = self.state.lock();
+
+            if state.open {
+                return;
+            }
+
+            state.open = true;
+        }
+
+        self.refresh();
+    }
+
+    /// Close the list of options.
+    pub fn close(&self) {
+        {
+            let mut state = self.state.lock();
+
+            if !state.open {
+                return;
+            }
+
+            state.open = false;
+            state.filter.clear();
+            state.highlighted = None;
+        }
+
+        self.popup.set_hidden(Some(true));
+        self.update_text();
+    }
+
+    fn select_and_emit(self: &Arc<Self>, index: usize) {
+        self.select(Some(index));
+        self.close();
+
+        for method in self.on_select.lock().iter_mut() {
+            method(self, index);
+        }
+    }
+
+    fn update_text(&self) {
+        let theme = self.bin.basalt_ref().interface_ref().theme();
+        let _ = self.bin.apply_theme_style(&theme);
+    }
+
+    fn themed_style(&self, theme: &Theme, base: BinStyle) -> BinStyle {
+        let text = {
+            let state = self.state.lock();
+
+            if state.open && !state.filter.is_empty() {
+                state.filter.clone()
+            } else {
+                state
+                    .selected
+                    .map(|index| state.options[index].clone())
+                    .unwrap_or_default()
+            }
+        };
+
+        let themed = theme.field_style(text);
+
+        BinStyle {
+            text: themed.text,
+            back_color: themed.back_color,
+            border_size_t: themed.border_size_t,
+            border_size_b: themed.border_size_b,
+            border_size_l: themed.border_size_l,
+            border_size_r: themed.border_size_r,
+            border_color_t: themed.border_color_t,
+            border_color_b: themed.border_color_b,
+            border_color_l: themed.border_color_l,
+            border_color_r: themed.border_color_r,
+            border_radius_tl: themed.border_radius_tl,
+            border_radius_tr: themed.border_radius_tr,
+            border_radius_bl: themed.border_radius_bl,
+            border_radius_br: themed.border_radius_br,
+            pad_l: themed.pad_l,
+            pad_r: themed.pad_r,
+            text_color: themed.text_color,
+            text_height: themed.text_height,
+            text_vert_align: themed.text_vert_align,
+            text_wrap: Some(TextWrap::None),
+            font_family: themed.font_family,
+            font_weight: themed.font_weight,
+            font_stretch: themed.font_stretch,
+            font_style: themed.font_style,
+            ..base
+        }
+    }
+
+    fn move_highlight(self: &Arc<Self>, step: isize) {
+        {
+            let mut state = self.state.lock();
+
+            if state.filtered.is_empty() {
+                return;
+            }
+
+            let last = state.filtered.len() as isize - 1;
+
+            state.highlighted = Some(match state.highlighted {
+                Some(i) => (i as isize + step).clamp(0, last) as usize,
+                None if step < 0 => last as usize,
+                None => 0,
+            });
+        }
+
+        self.refresh();
+    }
+
+    // NOTE: Updates the list of options for the current filter & highlight. Only option bins
+    //       whose position or highlight changed are restyled.
+    fn refresh(self: &Arc<Self>) {
+        let theme = self.bin.basalt_ref().interface_ref().theme();
+        let window = match self.bin.window() {
+            Some(some) => some,
+            None => return,
+        };
+
+        let mut state = self.state.lock();
+
+        if !state.open {
+            return;
+        }
+
+        let filter = state.filter.to_lowercase();
+
+        state.filtered = state
+            .options
+            .iter()
+            .enumerate()
+            .filter(|(_, option)| option.to_lowercase().contains(&filter))
+            .map(|(i, _)| i)
+            .collect();
+
+        if state.highlighted.is_none() || !filter.is_empty() {
+            state.highlighted = match state.selected {
+                Some(selected) => state.filtered.iter().position(|i| *i == selected),
+                None => None,
+            }
+            .or(if state.filtered.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        }
+
+        if let Some(i) = state.highlighted {
+            if i >= state.filtered.len() {
+                state.highlighted = state.filtered.len().checked_sub(1);
+            }
+        }
+
+        let post = self.bin.post_update();
+        let width = post.tro[0] - post.tlo[0];
+        let option_height = theme.text_height + (theme.spacing * 2.0);
+        let content_height = state.filtered.len() as f32 * option_height;
+        let height = content_height.min(MAX_POPUP_HEIGHT);
+
+        // NOTE: Keep the highlighted option visible.
+        let mut scroll_y = self
+            .popup
+            .style_inspect(|style| style.scroll_y.unwrap_or(0.0));
+
+        if let Some(i) = state.highlighted {
+            let option_top = i as f32 * option_height;
+
+            if option_top < scroll_y {
+                scroll_y = option_top;
+            } else if option_top + option_height > scroll_y + height {
+                scroll_y = option_top + option_height - height;
+            }
+        }
+
+        scroll_y = scroll_y.clamp(0.0, (content_height - height).max(0.0));
+
+        self.popup
+            .style_update(BinStyle {
+                position: Some(BinPosition::Window),
+                z_layer: Some(i8::MAX - 1),
+                hidden: Some(false),
+                pos_from_t: Some(post.blo[1]),
+                pos_from_l: Some(post.tlo[0]),
+                width: Some(width),
+                height: Some(height),
+                scroll_y: Some(scroll_y),
+                back_color: Some(theme.colors.back2),
+                border_size_t: Some(theme.border_size),
+                border_size_b: Some(theme.border_size),
+                border_size_l: Some(theme.border_size),
+                border_size_r: Some(theme.border_size),
+                border_color_t: Some(theme.colors.border1),
+                border_color_b: Some(theme.colors.border1),
+                border_color_l: Some(theme.colors.border1),
+                border_color_r: Some(theme.colors.border1),
+                ..BinStyle::default()
+            })
+            .expect_valid();
+
+        if state.option_bins.len() != state.options.len() {
+            state.option_bins = window.new_bins(state.options.len());
+            state.option_states = vec![None; state.options.len()];
+            self.popup.add_children(state.option_bins.clone());
+
+            for (index, option_bin) in state.option_bins.iter().enumerate() {
+                let cb_wk: Weak<Self> = Arc::downgrade(self);
+
+                option_bin.on_press(MouseButton::Left, move |_, _, _| {
+                    match cb_wk.upgrade() {
+                        Some(combo_box) => {
+                            combo_box.select_and_emit(index);
+                            Default::default()
+                        },
+                        None => InputHookCtrl::Remove,
+                    }
+                });
+
+                let cb_wk: Weak<Self> = Arc::downgrade(self);
+
+                option_bin.on_enter(move |_, _| {
+                    match cb_wk.upgrade() {
+                        Some(combo_box) => {
+                            let changed = {
+                                let mut state = combo_box.state.lock();
+                                let i = state.filtered.iter().position(|i| *i == index);
+                                let changed = i.is_some() && state.highlighted != i;
+
+                                if changed {
+                                    state.highlighted = i;
+                                }
+
+                                changed
+                            };
+
+                            if changed {
+                                combo_box.refresh();
+                            }
+
+                            Default::default()
+                        },
+                        None => InputHookCtrl::Remove,
+                    }
+                });
+            }
+        }
+
+        let mut option_states = vec![None; state.options.len()];
+
+        for (i, index) in state.filtered.iter().enumerate() {
+            option_states[*index] = Some((i, state.highlighted == Some(i)));
+        }
+
+        for (index, option_state) in option_states.iter().enumerate() {
+            if state.option_states[index] == *option_state {
+                continue;
+            }
+
+            let (i, highlighted) = option_state.unwrap_or((0, false));
+
+            let (back_color, text_color) = match highlighted {
+                true => (Some(theme.colors.accent1), theme.colors.text_on_accent),
+                false => (None, theme.colors.text1),
+            };
+
+            state.option_bins[index]
+                .style_update(BinStyle {
+                    position: Some(BinPosition::Parent),
+                    hidden: Some(option_state.is_none()),
+                    pos_from_t: Some(i as f32 * option_height),
+                    pos_from_l: Some(0.0),
+                    width_pct: Some(100.0),
+                    height: Some(option_height),
+                    pad_l: Some(theme.spacing),
+                    pad_r: Some(theme.spacing),
+                    back_color,
+                    text: state.options[index].clone(),
+                    text_color: Some(text_color),
+                    text_height: Some(theme.text_height),
+                    text_vert_align: Some(TextVertAlign::Center),
+                    text_wrap: Some(TextWrap::None),
+                    font_family: theme.font.family.clone(),
+                    font_weight: theme.font.weight,
+                    font_stretch: theme.font.strench,
+                    font_style: theme.font.style,
+                    ..BinStyle::default()
+                })
+                .expect_valid();
+        }
+
+        state.option_states = option_states;
+        drop(state);
+        self.update_text();
+    }
+}
@@ -0,0 +1,547 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::input::{InputHookCtrl, InputHookID, MouseButton, Qwerty};
+use crate::interface::{Bin, BinPosition, BinStyle, TextHoriAlign, TextVertAlign, TextWrap, Theme};
+use crate::interval::{IntvlHookCtrl, IntvlHookID};
+use crate::window::Window;
+use crate::NonExhaustive;
+
+/// An item within a `ContextMenu`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextMenuItem {
+    /// An item that can be selected. When selected its `id` is provided to the method set with
+    /// `ContextMenu::on_select`.
+    Entry {
+        id: u64,
+        label: String,
+        enabled: bool,
+    },
+    /// An item that opens another menu when hovered.
+    Submenu {
+        label: String,
+        items: Vec<ContextMenuItem>,
+    },
+    /// A line separating groups of items.
+    Separator,
+}
+
+impl ContextMenuItem {
+    /// Create an enabled entry.
+    pub fn entry<T: Into<String>>(id: u64, label: T) -> Self {
+        Self::Entry {
+            id,
+            label: label.into(),
+            enabled: true,
+        }
+    }
+
+    /// Create a disabled entry.
+    pub fn disabled<T: Into<String>>(id: u64, label: T) -> Self {
+        Self::Entry {
+            id,
+            label: label.into(),
+            enabled: false,
+        }
+    }
+
+    /// Create a submenu.
+    pub fn submenu<T: Into<String>>(label: T, items: Vec<ContextMenuItem>) -> Self {
+        Self::Submenu {
+            label: label.into(),
+            items,
+        }
+    }
+}
+
+type OnSelectFn = Arc<Mutex<Box<dyn FnMut(u64) + Send + 'static>>>;
+
+/// A menu displayed at the cursor when right-clicking a `Bin`.
+///
+/// See `Bin::set_context_menu`.
+#[derive(Clone)]
+pub struct ContextMenu {
+    pub items: Vec<ContextMenuItem>,
+    /// The width of the menu & its submenus.
+    ///
+    /// **Default**: `180.0`
+    pub width: f32,
+    /// How long an item must be hovered before its submenu is opened.
+    ///
+    /// **Default**: `Duration::from_millis(300)`
+    pub submenu_delay: Duration,
+    on_select: Option<OnSelectFn>,
+    pub _ne: NonExhaustive,
+}
+
+impl ContextMenu {
+    /// Create a context menu from items.
+    pub fn new(items: Vec<ContextMenuItem>) -> Self {
+        Self {
+            items,
+            ..Self::default()
+        }
+    }
+
+    /// Set the method called with the id of the selected entry.
+    pub fn on_select<F: FnMut(u64) + Send + 'static>(mut self, method: F) -> Self {
+        self.on_select = Some(Arc::new(Mutex::new(Box::new(method))));
+        self
+    }
+}
+
+impl Default for ContextMenu {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            width: 180.0,
+            submenu_delay: Duration::from_millis(300),
+            on_select: None,
+            _ne: NonExhaustive(()),
+        }
+    }
+}
+
+pub(crate) struct ContextMenuState {
+    inner: Arc<MenuInner>,
+    input_hook_ids: Vec<InputHookID>,
+}
+
+struct MenuInner {
+    window: Weak<Window>,
+    menu: ContextMenu,
+    intvl_hook_id: IntvlHookID,
+    panels: Mutex<Vec<Panel>>,
+    // NOTE: The depth of the hovered item & the submenu to open after the delay.
+    pending: Mutex<Option<(usize, Option<PendingSubmenu>)>>,
+}
+
+struct PendingSubmenu {
+    item: Weak<Bin>,
+    items: Vec<ContextMenuItem>,
+}
+
+struct Panel {
+    bin: Arc<Bin>,
+    _items: Vec<Arc<Bin>>,
+}
+
+impl ContextMenuState {
+    pub(crate) fn new(owner: &Arc<Bin>, menu: ContextMenu) -> Option<Self> {
+        let window = owner.window()?;
+        let basalt = owner.basalt_ref();
+        let submenu_delay = menu.submenu_delay;
+
+        let inner = Arc::new_cyclic(|inner_wk: &Weak<MenuInner>| {
+            let inner_wk = inner_wk.clone();
+
+            let intvl_hook_id =
+                basalt
+                    .interval_ref()
+                    .do_every(submenu_delay, Some(submenu_delay), move |_| {
+                        let inner = match inner_wk.upgrade() {
+                            Some(some) => some,
+                            None => return IntvlHookCtrl::Remove,
+                        };
+
+                        inner.open_pending();
+                        IntvlHookCtrl::Pause
+                    });
+
+            MenuInner {
+                window: Arc::downgrade(&window),
+                menu,
+                intvl_hook_id,
+                panels: Mutex::new(Vec::new()),
+                pending: Mutex::new(None),
+            }
+        });
+
+        let mut input_hook_ids = Vec::new();
+
+        // NOTE: Window hooks are used as bin hooks are only called for the focused bin, which
+        //       isn't changed by a right-click.
+        for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+            let inner_wk = Arc::downgrade(&inner);
+            let owner_wk = Arc::downgrade(owner);
+
+            input_hook_ids.push(
+                basalt
+                    .input_ref()
+                    .hook()
+                    .window(&window)
+                    .on_press()
+                    .keys(button)
+                    .call(move |target, window_state, _| {
+                        let (inner, owner) = match (inner_wk.upgrade(), owner_wk.upgrade()) {
+                            (Some(inner), Some(owner)) => (inner, owner),
+                            _ => return InputHookCtrl::Remove,
+                        };
+
+                        let window = match target.into_window() {
+                            Some(some) => some,
+                            None => return Default::default(),
+                        };
+
+                        let [x, y] = window_state.cursor_pos();
+                        let hit = window.bin_at(x, y);
+
+                        if let Some(hit) = hit.as_ref() {
+                            if inner.contains(hit) {
+                                return Default::default();
+                            }
+                        }
+
+                        inner.close();
+
+                        // NOTE: Only the innermost bin with a menu opens its menu.
+                        if button == MouseButton::Right {
+                            if let Some(hit) = hit {
+                                if std::iter::once(hit.clone())
+                                    .chain(hit.ancestors())
+                                    .find(|bin| bin.has_context_menu())
+                                    .is_some_and(|menu_owner| menu_owner == owner)
+                                {
+                                    let scale = window.effective_interface_scale();
+                                    inner.open_panel(0, &inner.menu.items, x / scale, y / scale);
+                                }
+                            }
+                        }
+
+                        Default::default()
+                    })
+                    .finish()
+                    .unwrap(),
+            );
+        }
+
+        let inner_wk = Arc::downgrade(&inner);
+
+        input_hook_ids.push(
+            basalt
+                .input_ref()
+                .hook()
+                .window(&window)
+                .on_press()
+                .keys(Qwerty::Esc)
+                .call(move |_, _, _| {
+                    match inner_wk.upgrade() {
+                        Some(inner) => {
+                            inner.close();
+                            Default::default()
+                        },
+                        None => InputHookCtrl::Remove,
+                    }
+                })
+                .finish()
+                .unwrap(),
+        );
+
+        Some(Self {
+            inner,
+            input_hook_ids,
+        })
+    }
+
+    pub(crate) fn remove(self, owner: &Bin) {
+        for hook_id in self.input_hook_ids {
+            owner.basalt_ref().input_ref().remove_hook(hook_id);
+        }
+
+        owner
+            .basalt_ref()
+            .interval_ref()
+            .remove(self.inner.intvl_hook_id);
+
+        self.inner.close();
+    }
+}
+
+impl MenuInner {
+    fn contains(&self, bin: &Arc<Bin>) -> bool {
+        let ancestors = bin.ancestors();
+
+        self.panels
+            .lock()
+            .iter()
+            .any(|panel| panel.bin == *bin || ancestors.contains(&panel.bin))
+    }
+
+    fn close(&self) {
+        *self.pending.lock() = None;
+        self.panels.lock().clear();
+    }
+
+    fn open_pending(self: &Arc<Self>) {
+        let pending = self.pending.lock().take();
+
+        let (depth, submenu_op) = match pending {
+            Some(some) => some,
+            None => return,
+        };
+
+        let submenu = match submenu_op {
+            Some(some) => some,
+            None => {
+                self.panels.lock().truncate(depth + 1);
+                return;
+            },
+        };
+
+        let item = match submenu.item.upgrade() {
+            Some(some) => some,
+            None => return,
+        };
+
+        let post = item.post_update();
+        let theme = item.basalt_ref().interface_ref().theme();
+        self.open_panel(
+            depth + 1,
+            &submenu.items,
+            post.tro[0],
+            post.tlo[1] - (theme.spacing / 2.0),
+        );
+    }
+
+    // NOTE: Opens a panel at the provided depth, closing any panels at or beyond it. The
+    //       position is in logical units.
+    fn open_panel(self: &Arc<Self>, depth: usize, items: &[ContextMenuItem], x: f32, y: f32) {
+        let window = match self.window.upgrade() {
+            Some(some) => some,
+            None => return,
+        };
+
+        let theme = window.basalt_ref().interface_ref().theme();
+        let mut panels = self.panels.lock();
+        panels.truncate(depth);
+
+        let width = self.menu.width;
+        let entry_height = theme.text_height + (theme.spacing * 2.0);
+        let separator_height = theme.border_size + theme.spacing;
+
+        let height = items
+            .iter()
+            .map(|item| {
+                match item {
+                    ContextMenuItem::Separator => separator_height,
+                    _ => entry_height,
+                }
+            })
+            .sum::<f32>()
+            + theme.spacing;
+
+        // NOTE: When the panel would extend beyond the window, it is flipped to the other side.
+        let scale = window.effective_interface_scale();
+        let [window_w, window_h] = window.inner_dimensions();
+        let [window_w, window_h] = [window_w as f32 / scale, window_h as f32 / scale];
+
+        let left = if x + width > window_w {
+            match panels.last() {
+                Some(parent) => parent.bin.post_update().tlo[0] - width,
+                None => x - width,
+            }
+            .max(0.0)
+        } else {
+            x
+        };
+
+        let top = if y + height > window_h {
+            (window_h - height).max(0.0)
+        } else {
+            y
+        };
+
+        let mut bins = window.new_bins(items.len() + 1);
+        let panel_bin = bins.pop().unwrap();
+
+        // NOTE: Menus are placed in the layer below tooltips, so that they are displayed above
+        //       everything else.
+        panel_bin
+            .style_update(BinStyle {
+                position: Some(BinPosition::Window),
+                z_layer: Some(i8::MAX - 1),
+                pos_from_t: Some(top),
+                pos_from_l: Some(left),
+                width: Some(width),
+                height: Some(height),
+                pad_t: Some(theme.spacing / 2.0),
+                pad_b: Some(theme.spacing / 2.0),
+                back_color: Some(theme.colors.back2),
+                border_size_t: Some(theme.border_size),
+                border_size_b: Some(theme.border_size),
+                border_size_l: Some(theme.border_size),
+                border_size_r: Some(theme.border_size),
+                border_color_t: Some(theme.colors.border1),
+                border_color_b: Some(theme.colors.border1),
+                border_color_l: Some(theme.colors.border1),
+                border_color_r: Some(theme.colors.border1),
+                border_radius_tl: Some(theme.border_radius),
+                border_radius_tr: Some(theme.border_radius),
+                border_radius_bl: Some(theme.border_radius),
+                border_radius_br: Some(theme.border_radius),
+                ..BinStyle::default()
+            })
+            .expect_valid();
+
+        panel_bin.add_children(bins.clone());
+        let mut submenu_arrows = Vec::new();
+
+        for (item, item_bin) in items.iter().zip(bins.iter()) {
+            let (label, enabled) = match item {
+                ContextMenuItem::Separator => {
+                    item_bin
+                        .style_update(BinStyle {
+                            position: Some(BinPosition::Floating),
+                            width: Some(width),
+                            height: Some(theme.border_size),
+                            margin_t: Some(theme.spacing / 2.0),
+                            margin_b: Some(theme.spacing / 2.0),
+                            back_color: Some(theme.colors.border1),
+                            ..BinStyle::default()
+                        })
+                        .expect_valid();
+
+                    continue;
+                },
+                ContextMenuItem::Entry {
+                    label,
+                    enabled,
+                    ..
+                } => (label, *enabled),
+                ContextMenuItem::Submenu {
+                    label, ..
+                } => (label, true),
+            };
+
+            let item_style = item_style(&theme, label.clone(), width, entry_height, enabled);
+            item_bin.style_update(item_style.clone()).expect_valid();
+
+            if let ContextMenuItem::Submenu {
+                ..
+            } = item
+            {
+                let arrow = window.new_bin();
+                item_bin.add_child(arrow.clone());
+
+                arrow
+                    .style_update(BinStyle {
+                        position: Some(BinPosition::Parent),
+                        pos_from_t: Some(0.0),
+                        pos_from_b: Some(0.0),
+                        pos_from_l: Some(0.0),
+                        pos_from_r: Some(0.0),
+                        pad_r: Some(theme.spacing),
+                        text: String::from(">"),
+                        text_hori_align: Some(TextHoriAlign::Right),
+                        ..theme.text_style(String::new())
+                    })
+                    .expect_valid();
+
+                submenu_arrows.push(arrow);
+            }
+
+            if !enabled {
+                continue;
+            }
+
+            let inner_wk = Arc::downgrade(self);
+            let item_wk = Arc::downgrade(item_bin);
+            let basalt = window.basalt();
+            let submenu_items = match item {
+                ContextMenuItem::Submenu {
+                    items, ..
+                } => Some(items.clone()),
+                _ => None,
+            };
+
+            let hover_style = BinStyle {
+                back_color: Some(theme.colors.accent1),
+                text_color: Some(theme.colors.text_on_accent),
+                ..item_style.clone()
+            };
+
+            item_bin.on_enter(move |target, _| {
+                let inner = match inner_wk.upgrade() {
+                    Some(some) => some,
+                    None => return InputHookCtrl::Remove,
+                };
+
+                if let Some(item_bin) = target.into_bin() {
+                    item_bin.style_update(hover_style.clone()).expect_valid();
+                }
+
+                *inner.pending.lock() = Some((
+                    depth,
+                    submenu_items.clone().map(|items| {
+                        PendingSubmenu {
+                            item: item_wk.clone(),
+                            items,
+                        }
+                    }),
+                ));
+
+                // NOTE: Pausing resets the delay.
+                basalt.interval_ref().pause(inner.intvl_hook_id);
+                basalt.interval_ref().start(inner.intvl_hook_id);
+                Default::default()
+            });
+
+            item_bin.on_leave(move |target, _| {
+                if let Some(item_bin) = target.into_bin() {
+                    item_bin.style_update(item_style.clone()).expect_valid();
+                }
+
+                Default::default()
+            });
+
+            if let ContextMenuItem::Entry {
+                id, ..
+            } = item
+            {
+                let id = *id;
+                let inner_wk = Arc::downgrade(self);
+
+                item_bin.on_press(MouseButton::Left, move |_, _, _| {
+                    let inner = match inner_wk.upgrade() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
+
+                    inner.close();
+
+                    if let Some(on_select) = inner.menu.on_select.as_ref() {
+                        (on_select.lock())(id);
+                    }
+
+                    Default::default()
+                });
+            }
+        }
+
+        bins.append(&mut submenu_arrows);
+
+        panels.push(Panel {
+            bin: panel_bin,
+            _items: bins,
+        });
+    }
+}
+
+fn item_style(theme: &Theme, label: String, width: f32, height: f32, enabled: bool) -> BinStyle {
+    BinStyle {
+        position: Some(BinPosition::Floating),
+        width: Some(width),
+        height: Some(height),
+        pad_l: Some(theme.spacing),
+        pad_r: Some(theme.spacing),
+        text_color: Some(match enabled {
+            true => theme.colors.text1,
+            false => theme.colors.text2,
+        }),
+        text_vert_align: Some(TextVertAlign::Center),
+        text_wrap: Some(TextWrap::None),
+        ..theme.text_style(label)
+    }
+}
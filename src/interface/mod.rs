@@ -1,15 +1,25 @@
 //! System for storing interface related objects.
 
 mod bin;
+pub mod button;
 pub mod checkbox;
+pub mod combo_box;
+mod context_menu;
 pub mod on_off_button;
+pub mod progress_bar;
+pub mod scaler;
 pub mod scroll_bar;
 pub mod slider;
+mod theme;
+mod tooltip;
+pub mod virtual_list;
+mod widget;
 
-use std::cmp::Reverse;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Weak};
 
+use cosmic_text::fontdb::Source as FontSource;
+use cosmic_text::FontSystem;
 use parking_lot::{Mutex, RwLock};
 use vulkano::buffer::BufferContents;
 use vulkano::pipeline::graphics::vertex_input::Vertex;
@@ -17,11 +27,19 @@ use vulkano::pipeline::graphics::vertex_input::Vertex;
 pub use self::bin::color::Color;
 pub use self::bin::style::{
     BinPosition, BinStyle, BinStyleError, BinStyleErrorType, BinStyleValidation, BinStyleWarn,
-    BinStyleWarnType, BinVert, ChildFloatMode, FontStretch, FontStyle, FontWeight, ImageEffect,
-    TextHoriAlign, TextVertAlign, TextWrap,
+    BinStyleWarnType, BinVert, ChildFloatAlign, ChildFloatJustify, ChildFloatMode, FontStretch,
+    FontStyle, FontWeight, ImageEffect, ImageFit, ImageLoop, TextDirection, TextHoriAlign,
+    TextSpan, TextVertAlign, TextWrap,
 };
 pub(crate) use self::bin::BinPlacement;
-pub use self::bin::{Bin, BinID, BinPostUpdate, OVDPerfMetrics};
+pub use self::bin::{
+    Bin, BinBuilder, BinID, BinPostUpdate, BinTreeSnapshot, OVDPerfMetrics, TextMetrics,
+};
+pub(crate) use self::context_menu::ContextMenuState;
+pub use self::context_menu::{ContextMenu, ContextMenuItem};
+pub use self::theme::{ColorScheme, Theme, ThemeColors};
+pub use self::tooltip::Tooltip;
+pub(crate) use self::tooltip::TooltipState;
 use crate::window::WindowID;
 use crate::Basalt;
 
@@ -47,6 +65,10 @@ pub(crate) struct ItfVertInfo {
     pub ty: i32,
     #[format(R32_UINT)]
     pub tex_i: u32,
+    /// Multiplies the alpha of textured vertexes. Untextured vertexes have the opacity applied to
+    /// their color instead.
+    #[format(R32_SFLOAT)]
+    pub opacity: f32,
 }
 
 impl Default for ItfVertInfo {
@@ -57,6 +79,7 @@ impl Default for ItfVertInfo {
             color: [0.0; 4],
             ty: 0,
             tex_i: 0,
+            opacity: 1.0,
         }
     }
 }
@@ -76,7 +99,72 @@ pub(crate) fn scale_verts(win_size: &[f32; 2], scale: f32, verts: &mut Vec<ItfVe
 pub struct Interface {
     bins_state: RwLock<BinsState>,
     default_font: Mutex<DefaultFont>,
+    fallback_fonts: Mutex<Vec<String>>,
+    theme: Mutex<Theme>,
+    system_color_scheme: Mutex<ColorScheme>,
+    on_system_color_scheme: Mutex<Vec<Box<dyn FnMut(ColorScheme) + Send + 'static>>>,
     binary_fonts: Mutex<Vec<Arc<dyn AsRef<[u8]> + Sync + Send>>>,
+    // NOTE: The cache is locked separately, so that a hit doesn't wait on text being measured.
+    measure_cache: Mutex<MeasureCache>,
+    // NOTE: Created on first use, as loading system fonts is slow.
+    measure_font_system: Mutex<Option<FontSystem>>,
+}
+
+/// The maximum amount of results retained by `Interface::measure_text`.
+const MEASURE_CACHE_SIZE: usize = 1024;
+
+// NOTE: Keyed by the full input, as a hash collision would return the metrics of other text.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct MeasureKey {
+    text: String,
+    text_height: Option<u32>,
+    line_spacing: Option<u32>,
+    text_wrap: Option<TextWrap>,
+    font_family: Option<String>,
+    font_weight: Option<FontWeight>,
+    font_stretch: Option<FontStretch>,
+    font_style: Option<FontStyle>,
+    font_fallback: Option<Vec<String>>,
+    text_secret: Option<bool>,
+    text_direction: Option<TextDirection>,
+    max_width: Option<u32>,
+    scale: u32,
+}
+
+// NOTE: When full, the least recently used entry is evicted.
+#[derive(Default)]
+struct MeasureCache {
+    entries: HashMap<MeasureKey, (TextMetrics, u64)>,
+    last_used: u64,
+}
+
+impl MeasureCache {
+    fn get(&mut self, key: &MeasureKey) -> Option<TextMetrics> {
+        self.last_used += 1;
+        let (metrics, last_used) = self.entries.get_mut(key)?;
+        *last_used = self.last_used;
+        Some(metrics.clone())
+    }
+
+    fn insert(&mut self, key: MeasureKey, metrics: TextMetrics) {
+        if self.entries.len() >= MEASURE_CACHE_SIZE && !self.entries.contains_key(&key) {
+            if let Some(lru_key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+            {
+                self.entries.remove(&lru_key);
+            }
+        }
+
+        self.last_used += 1;
+        self.entries.insert(key, (metrics, self.last_used));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
 }
 
 #[derive(Default)]
@@ -91,7 +179,13 @@ impl Interface {
         Arc::new(Interface {
             bins_state: RwLock::new(BinsState::default()),
             default_font: Mutex::new(DefaultFont::default()),
+            fallback_fonts: Mutex::new(Vec::new()),
+            theme: Mutex::new(Theme::default()),
+            system_color_scheme: Mutex::new(ColorScheme::default()),
+            on_system_color_scheme: Mutex::new(Vec::new()),
             binary_fonts: Mutex::new(binary_fonts),
+            measure_cache: Mutex::new(MeasureCache::default()),
+            measure_font_system: Mutex::new(None),
         })
     }
 
@@ -124,6 +218,87 @@ impl Interface {
             .set_default_font(default_font);
     }
 
+    /// Retrieve the current fallback fonts.
+    pub fn fallback_fonts(&self) -> Vec<String> {
+        self.fallback_fonts.lock().clone()
+    }
+
+    /// Set the font families searched in order for characters missing from a font.
+    ///
+    /// Characters missing from every font in the list use the fallback of cosmic-text, which
+    /// depends on the fonts available on the system. This can be overridden per `Bin` with
+    /// `BinStyle.font_fallback`.
+    pub fn set_fallback_fonts(&self, fallback_fonts: Vec<String>) {
+        *self.fallback_fonts.lock() = fallback_fonts.clone();
+        self.measure_cache.lock().clear();
+
+        self.bins_state
+            .read()
+            .bst
+            .as_ref()
+            .unwrap()
+            .window_manager_ref()
+            .set_fallback_fonts(fallback_fonts);
+    }
+
+    /// Retrieve the current theme.
+    pub fn theme(&self) -> Theme {
+        self.theme.lock().clone()
+    }
+
+    /// Set the current theme.
+    ///
+    /// Bins styled with `Bin::set_theme_style` will be restyled.
+    ///
+    /// ***Note:** Styles with a validation error are not applied, but the others still are.*
+    pub fn set_theme(&self, theme: Theme) -> Result<(), Vec<BinStyleError>> {
+        *self.theme.lock() = theme.clone();
+        let mut errors = Vec::new();
+
+        for bin in self.bins() {
+            if let Err(bin_errors) = bin.apply_theme_style(&theme) {
+                errors.extend(bin_errors);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Retrieve the color scheme preferred by the system.
+    ///
+    /// ***Note:** This is `ColorScheme::Light` when the system doesn't report a preference.*
+    pub fn system_color_scheme(&self) -> ColorScheme {
+        *self.system_color_scheme.lock()
+    }
+
+    /// Add a method that is called when the color scheme preferred by the system changes.
+    ///
+    /// This can be used with `Theme::from_color_scheme` & `Interface::set_theme` to follow the
+    /// system preference.
+    pub fn on_system_color_scheme_change<F: FnMut(ColorScheme) + Send + 'static>(&self, method: F) {
+        self.on_system_color_scheme.lock().push(Box::new(method));
+    }
+
+    pub(crate) fn set_system_color_scheme(&self, color_scheme: ColorScheme) {
+        {
+            let mut system_color_scheme = self.system_color_scheme.lock();
+
+            if *system_color_scheme == color_scheme {
+                return;
+            }
+
+            *system_color_scheme = color_scheme;
+        }
+
+        for method in self.on_system_color_scheme.lock().iter_mut() {
+            method(color_scheme);
+        }
+    }
+
     /// Load a font from a binary source.
     ///
     /// **Note**: Invalid fonts will not cause an error, but text may not render.*
@@ -131,6 +306,17 @@ impl Interface {
         let binary_font = Arc::new(data);
         self.binary_fonts.lock().push(binary_font.clone());
 
+        {
+            let mut font_system_op = self.measure_font_system.lock();
+            self.measure_cache.lock().clear();
+
+            if let Some(font_system) = font_system_op.as_mut() {
+                font_system
+                    .db_mut()
+                    .load_font_source(FontSource::Binary(binary_font.clone()));
+            }
+        }
+
         self.bins_state
             .read()
             .bst
@@ -140,6 +326,73 @@ impl Interface {
             .add_binary_font(binary_font);
     }
 
+    /// Measure text as it would be laid out within a `Bin` without rendering it.
+    ///
+    /// The text fields of `style` are used, but `text` & `text_spans` are ignored. When
+    /// `max_width` is provided, text is wrapped according to `text_wrap`. Results are cached.
+    ///
+    /// ***Note:** The metrics are in logical units at a scale of `1.0`. See
+    /// `Window::measure_text` for measuring at the scale of a window.*
+    pub fn measure_text(
+        &self,
+        text: &str,
+        style: &BinStyle,
+        max_width: Option<f32>,
+    ) -> TextMetrics {
+        self.measure_text_scaled(text, style, max_width, 1.0)
+    }
+
+    pub(crate) fn measure_text_scaled(
+        &self,
+        text: &str,
+        style: &BinStyle,
+        max_width: Option<f32>,
+        scale: f32,
+    ) -> TextMetrics {
+        let fallback_fonts = self.fallback_fonts();
+
+        let key = MeasureKey {
+            text: text.to_string(),
+            text_height: style.text_height.map(f32::to_bits),
+            line_spacing: style.line_spacing.map(f32::to_bits),
+            text_wrap: style.text_wrap,
+            font_family: style.font_family.clone(),
+            font_weight: style.font_weight,
+            font_stretch: style.font_stretch,
+            font_style: style.font_style,
+            font_fallback: style.font_fallback.clone(),
+            text_secret: style.text_secret,
+            text_direction: style.text_direction,
+            max_width: max_width.map(f32::to_bits),
+            scale: scale.to_bits(),
+        };
+
+        if let Some(metrics) = self.measure_cache.lock().get(&key) {
+            return metrics;
+        }
+
+        let metrics = {
+            let mut font_system_op = self.measure_font_system.lock();
+
+            let font_system = font_system_op.get_or_insert_with(|| {
+                let mut font_system = FontSystem::new();
+
+                for binary_font in self.binary_fonts() {
+                    font_system
+                        .db_mut()
+                        .load_font_source(FontSource::Binary(binary_font));
+                }
+
+                font_system
+            });
+
+            bin::measure_text(font_system, &fallback_fonts, text, style, max_width, scale)
+        };
+
+        self.measure_cache.lock().insert(key, metrics.clone());
+        metrics
+    }
+
     /// Get the top-most `Bin` given a window & position.
     #[inline]
     pub fn get_bin_atop(&self, window: WindowID, x: f32, y: f32) -> Option<Arc<Bin>> {
@@ -149,10 +402,10 @@ impl Interface {
     /// Get `Bin`'s atop the provided window & position.
     ///
     /// ***Note:** This is sorted where the top-most is first and the bottom-most is last.*
-    pub fn get_bins_atop(&self, window_id: WindowID, mut x: f32, mut y: f32) -> Vec<Arc<Bin>> {
-        let state = self.bins_state.read();
-
-        let window = match state
+    pub fn get_bins_atop(&self, window_id: WindowID, x: f32, y: f32) -> Vec<Arc<Bin>> {
+        let window = match self
+            .bins_state
+            .read()
             .bst
             .as_ref()
             .unwrap()
@@ -163,18 +416,7 @@ impl Interface {
             None => return Vec::new(),
         };
 
-        let effective_scale = window.effective_interface_scale();
-        x /= effective_scale;
-        y /= effective_scale;
-
-        let mut bins = window
-            .associated_bins()
-            .into_iter()
-            .filter(|bin| bin.mouse_inside(x, y))
-            .collect::<Vec<_>>();
-
-        bins.sort_by_cached_key(|bin| Reverse(bin.post_update().z_index));
-        bins
+        window.bins_at(x, y)
     }
 
     /// Get the top-most `BinID` given a window & position.
@@ -205,8 +447,8 @@ impl Interface {
         self.bins_state
             .read()
             .map
-            .iter()
-            .filter_map(|(_, b)| b.upgrade())
+            .values()
+            .filter_map(|b| b.upgrade())
             .collect()
     }
 
@@ -237,6 +479,35 @@ impl Interface {
         out
     }
 
+    /// Create a `Bin` with a builder.
+    ///
+    /// ***Note:** Unless a window or a parent with a window is provided to the builder, the `Bin`
+    /// will not have a window association. Using `Window::bin_builder` should be preferred.*
+    pub fn bin_builder(&self) -> BinBuilder<'_> {
+        BinBuilder::start(self)
+    }
+
+    /// Set the ID assigned to the next `Bin` created.
+    ///
+    /// This is intended for tests, where IDs should be the same between runs regardless of how
+    /// many bins were created beforehand.
+    ///
+    /// Returns an error if `id` is less than the ID that would be assigned next, as IDs are never
+    /// reused. State of dropped bins may still be retained by the renderer and input.
+    pub fn set_next_bin_id(&self, id: u64) -> Result<(), String> {
+        let mut bins_state = self.bins_state.write();
+
+        if id < bins_state.id {
+            return Err(format!(
+                "The next ID is already {}, so IDs can't start at {}.",
+                bins_state.id, id
+            ));
+        }
+
+        bins_state.id = id;
+        Ok(())
+    }
+
     /// Retreive a `Bin` given its `BinID`.
     pub fn get_bin(&self, id: BinID) -> Option<Arc<Bin>> {
         match self.bins_state.read().map.get(&id) {
@@ -264,7 +535,7 @@ impl Interface {
         x /= effective_scale;
         y /= effective_scale;
 
-        for bin in window.associated_bins() {
+        for bin in window.interface_bins() {
             if bin.mouse_inside(x, y) {
                 return true;
             }
@@ -273,3 +544,63 @@ impl Interface {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{MeasureCache, MeasureKey, TextMetrics, MEASURE_CACHE_SIZE};
+
+    fn measure_key(text: &str) -> MeasureKey {
+        MeasureKey {
+            text: text.to_string(),
+            text_height: None,
+            line_spacing: None,
+            text_wrap: None,
+            font_family: None,
+            font_weight: None,
+            font_stretch: None,
+            font_style: None,
+            font_fallback: None,
+            text_secret: None,
+            text_direction: None,
+            max_width: None,
+            scale: 1.0_f32.to_bits(),
+        }
+    }
+
+    fn metrics(width: f32) -> TextMetrics {
+        TextMetrics {
+            width,
+            ..TextMetrics::default()
+        }
+    }
+
+    #[test]
+    fn measure_cache_lru() {
+        let mut cache = MeasureCache::default();
+
+        for i in 0..MEASURE_CACHE_SIZE {
+            cache.insert(measure_key(&i.to_string()), metrics(i as f32));
+        }
+
+        assert_eq!(cache.get(&measure_key("0")), Some(metrics(0.0)));
+        cache.insert(measure_key("new"), metrics(-1.0));
+
+        assert_eq!(cache.entries.len(), MEASURE_CACHE_SIZE);
+        assert_eq!(cache.get(&measure_key("0")), Some(metrics(0.0)));
+        assert_eq!(cache.get(&measure_key("1")), None);
+        assert_eq!(cache.get(&measure_key("2")), Some(metrics(2.0)));
+        assert_eq!(cache.get(&measure_key("new")), Some(metrics(-1.0)));
+    }
+
+    #[test]
+    fn measure_cache_full_key() {
+        let mut cache = MeasureCache::default();
+        cache.insert(measure_key("a"), metrics(1.0));
+
+        let mut key = measure_key("a");
+        key.text_secret = Some(true);
+        assert_eq!(cache.get(&key), None);
+        assert_eq!(cache.get(&measure_key("b")), None);
+        assert_eq!(cache.get(&measure_key("a")), Some(metrics(1.0)));
+    }
+}
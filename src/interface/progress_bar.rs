@@ -0,0 +1,349 @@
+use std::f32::consts::PI;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::interface::widget::{new_widget_bin, set_widget_theme_style, with_border_radius};
+use crate::interface::{Bin, BinPosition, BinStyle, BinVert, Color};
+use crate::interval::{IntvlHookCtrl, IntvlHookID};
+use crate::window::Window;
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// The time it takes the fill of an indeterminate `ProgressBar` to sweep across the track.
+const SWEEP_DURATION: f32 = 1.5;
+/// The width of the fill of an indeterminate `ProgressBar` as a percent of the track.
+const SWEEP_WIDTH_PCT: f32 = 30.0;
+const SPINNER_DOTS: usize = 8;
+/// The time it takes a `Spinner` to complete a rotation.
+const SPINNER_ROTATION: f32 = 1.0;
+
+/// A bar that displays the progress of an operation.
+///
+/// Changes in progress are animated. When the progress isn't known, an indeterminate mode can be
+/// used where the fill sweeps across the track. Colors are derived from the current `Theme`.
+pub struct ProgressBar {
+    track: Arc<Bin>,
+    /// The `Bin` displaying the progress within the track.
+    pub fill: Arc<Bin>,
+    state: Arc<Mutex<ProgressState>>,
+    intvl_hook_id: IntvlHookID,
+}
+
+struct ProgressState {
+    target: f32,
+    displayed: f32,
+    indeterminate: bool,
+    sweep: f32,
+}
+
+impl Deref for ProgressBar {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.track
+    }
+}
+
+impl ProgressBar {
+    /// Create a new progress bar.
+    ///
+    /// The progress bar is placed at the top left of the parent or window if no parent is
+    /// provided.
+    ///
+    /// # Notes
+    /// - Panics if parent bin is not associated to the window provided.
+    pub fn new(window: Arc<Window>, parent: Option<Arc<Bin>>) -> Arc<Self> {
+        let track = new_widget_bin(&window, parent, widget_style(200.0, 8.0));
+        let fill = window.new_bin();
+        track.add_child(fill.clone());
+
+        set_widget_theme_style(&track, |theme, base| {
+            with_border_radius(
+                BinStyle {
+                    back_color: Some(theme.colors.back2),
+                    ..base
+                },
+                theme.border_radius,
+            )
+        });
+
+        fill.style_update(BinStyle {
+            position: Some(BinPosition::Parent),
+            pos_from_t: Some(0.0),
+            pos_from_b: Some(0.0),
+            pos_from_l: Some(0.0),
+            width_pct: Some(0.0),
+            ..BinStyle::default()
+        })
+        .expect_valid();
+
+        set_widget_theme_style(&fill, |theme, base| {
+            with_border_radius(
+                BinStyle {
+                    back_color: Some(theme.colors.accent1),
+                    ..base
+                },
+                theme.border_radius,
+            )
+        });
+
+        let state = Arc::new(Mutex::new(ProgressState {
+            target: 0.0,
+            displayed: 0.0,
+            indeterminate: false,
+            sweep: 0.0,
+        }));
+
+        let state_cp = state.clone();
+        let fill_wk = Arc::downgrade(&fill);
+
+        let intvl_hook_id =
+            window
+                .basalt_ref()
+                .interval_ref()
+                .do_every(FRAME_INTERVAL, None, move |last_call| {
+                    let fill = match fill_wk.upgrade() {
+                        Some(some) => some,
+                        None => return IntvlHookCtrl::Remove,
+                    };
+
+                    let elapsed = last_call.unwrap_or(FRAME_INTERVAL).as_secs_f32();
+                    let mut state = state_cp.lock();
+
+                    if state.indeterminate {
+                        state.sweep = (state.sweep + (elapsed / SWEEP_DURATION)) % 1.0;
+
+                        fill.style_update(BinStyle {
+                            pos_from_l: None,
+                            pos_from_l_pct: Some(
+                                (state.sweep * (100.0 + SWEEP_WIDTH_PCT)) - SWEEP_WIDTH_PCT,
+                            ),
+                            width_pct: Some(SWEEP_WIDTH_PCT),
+                            ..fill.style_copy()
+                        })
+                        .expect_valid();
+
+                        return IntvlHookCtrl::Continue;
+                    }
+
+                    let difference = state.target - state.displayed;
+
+                    let ctrl = if difference.abs() < 0.001 {
+                        state.displayed = state.target;
+                        IntvlHookCtrl::Pause
+                    } else {
+                        state.displayed += difference * (elapsed * 12.0).min(1.0);
+                        IntvlHookCtrl::Continue
+                    };
+
+                    fill.style_update(BinStyle {
+                        pos_from_l: Some(0.0),
+                        pos_from_l_pct: None,
+                        width_pct: Some(state.displayed * 100.0),
+                        ..fill.style_copy()
+                    })
+                    .expect_valid();
+
+                    ctrl
+                });
+
+        Arc::new(Self {
+            track,
+            fill,
+            state,
+            intvl_hook_id,
+        })
+    }
+
+    /// Set the progress where `0.0` is none and `1.0` is complete.
+    ///
+    /// ***Note:** This disables the indeterminate mode.*
+    pub fn set_progress(&self, progress: f32) {
+        let mut state = self.state.lock();
+        state.target = progress.clamp(0.0, 1.0);
+        state.indeterminate = false;
+        self.track
+            .basalt_ref()
+            .interval_ref()
+            .start(self.intvl_hook_id);
+    }
+
+    /// Retrieve the progress set with `set_progress`.
+    pub fn progress(&self) -> f32 {
+        self.state.lock().target
+    }
+
+    /// Enable or disable the indeterminate mode.
+    ///
+    /// When enabled, the fill continuously sweeps across the track.
+    pub fn set_indeterminate(&self, indeterminate: bool) {
+        let mut state = self.state.lock();
+        state.indeterminate = indeterminate;
+        state.sweep = 0.0;
+        state.displayed = 0.0;
+        self.track
+            .basalt_ref()
+            .interval_ref()
+            .start(self.intvl_hook_id);
+    }
+
+    /// Check if the indeterminate mode is enabled.
+    pub fn is_indeterminate(&self) -> bool {
+        self.state.lock().indeterminate
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        self.track
+            .basalt_ref()
+            .interval_ref()
+            .remove(self.intvl_hook_id);
+    }
+}
+
+/// A rotating indicator for operations without a known progress.
+///
+/// The spinner is drawn as a ring of dots within the bounds of the `Bin`. Colors are derived from
+/// the current `Theme`.
+pub struct Spinner {
+    bin: Arc<Bin>,
+    color: Arc<Mutex<Color>>,
+    intvl_hook_id: IntvlHookID,
+}
+
+impl Deref for Spinner {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bin
+    }
+}
+
+impl Spinner {
+    /// Create a new spinner.
+    ///
+    /// The spinner is placed at the top left of the parent or window if no parent is provided.
+    /// It begins spinning immediately.
+    ///
+    /// # Notes
+    /// - Panics if parent bin is not associated to the window provided.
+    pub fn new(window: Arc<Window>, parent: Option<Arc<Bin>>) -> Arc<Self> {
+        let bin = new_widget_bin(&window, parent, widget_style(24.0, 24.0));
+        let color = Arc::new(Mutex::new(
+            window.basalt_ref().interface_ref().theme().colors.accent1,
+        ));
+        let color_cp = color.clone();
+
+        set_widget_theme_style(&bin, move |theme, base| {
+            *color_cp.lock() = theme.colors.accent1;
+            base
+        });
+
+        let bin_wk = Arc::downgrade(&bin);
+        let color_cp = color.clone();
+        let mut rotation: f32 = 0.0;
+
+        let intvl_hook_id =
+            window
+                .basalt_ref()
+                .interval_ref()
+                .do_every(FRAME_INTERVAL, None, move |last_call| {
+                    let bin = match bin_wk.upgrade() {
+                        Some(some) => some,
+                        None => return IntvlHookCtrl::Remove,
+                    };
+
+                    let elapsed = last_call.unwrap_or(FRAME_INTERVAL).as_secs_f32();
+                    rotation = (rotation + (elapsed / SPINNER_ROTATION)) % 1.0;
+                    spinner_update(&bin, rotation, *color_cp.lock());
+                    IntvlHookCtrl::Continue
+                });
+
+        window.basalt_ref().interval_ref().start(intvl_hook_id);
+
+        Arc::new(Self {
+            bin,
+            color,
+            intvl_hook_id,
+        })
+    }
+
+    /// Start or stop the spinner.
+    ///
+    /// ***Note:** A stopped spinner is still displayed. Use `set_hidden` to hide it.*
+    pub fn set_spinning(&self, spinning: bool) {
+        let interval = self.bin.basalt_ref().interval_ref();
+
+        match spinning {
+            true => interval.start(self.intvl_hook_id),
+            false => interval.pause(self.intvl_hook_id),
+        }
+    }
+
+    /// Override the color of the spinner until the theme changes.
+    pub fn set_color(&self, color: Color) {
+        *self.color.lock() = color;
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.bin
+            .basalt_ref()
+            .interval_ref()
+            .remove(self.intvl_hook_id);
+    }
+}
+
+fn spinner_update(bin: &Arc<Bin>, rotation: f32, color: Color) {
+    let post = bin.post_update();
+    let [width, height] = [post.tri[0] - post.tli[0], post.bli[1] - post.tli[1]];
+    let size = width.min(height);
+
+    if size <= 0.0 {
+        return;
+    }
+
+    let dot_size = size / 8.0;
+    let radius = (size / 2.0) - dot_size;
+    let center = [width / 2.0, height / 2.0];
+    let head = (rotation * SPINNER_DOTS as f32).floor() as usize;
+    let mut custom_verts = Vec::with_capacity(SPINNER_DOTS * 6);
+
+    for i in 0..SPINNER_DOTS {
+        let angle = (i as f32 / SPINNER_DOTS as f32) * 2.0 * PI;
+        let x = center[0] + (angle.cos() * radius);
+        let y = center[1] + (angle.sin() * radius);
+
+        // NOTE: Dots trailing the head fade out.
+        let trail = (head + SPINNER_DOTS - i) % SPINNER_DOTS;
+        let color = color.with_alpha(color.a * (1.0 - (trail as f32 / SPINNER_DOTS as f32)));
+        let [l, r, t, b] = [x - dot_size, x + dot_size, y - dot_size, y + dot_size];
+
+        for position in [(r, t), (l, t), (l, b), (r, t), (l, b), (r, b)] {
+            custom_verts.push(BinVert {
+                position: (position.0, position.1, 0),
+                color,
+            });
+        }
+    }
+
+    bin.style_update(BinStyle {
+        custom_verts,
+        ..bin.style_copy()
+    })
+    .expect_valid();
+}
+
+fn widget_style(width: f32, height: f32) -> BinStyle {
+    BinStyle {
+        pos_from_t: Some(0.0),
+        pos_from_l: Some(0.0),
+        width: Some(width),
+        height: Some(height),
+        ..BinStyle::default()
+    }
+}
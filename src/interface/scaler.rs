@@ -0,0 +1,479 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::input::{InputHookCtrl, MouseButton, Qwerty};
+use crate::interface::widget::{new_widget_bin, set_widget_theme_style, with_border_radius};
+use crate::interface::{Bin, BinPosition, BinStyle};
+use crate::window::Window;
+
+const TRACK_THICKNESS: f32 = 6.0;
+const HANDLE_LENGTH: f32 = 14.0;
+const HANDLE_OVERHANG: f32 = 5.0;
+
+/// The direction a `Scaler` is laid out in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScalerOrientation {
+    /// The minimum is at the left.
+    #[default]
+    Horizontal,
+    /// The minimum is at the bottom.
+    Vertical,
+}
+
+/// A handle that is dragged along a track to select a value within a range.
+///
+/// While focused, the arrow keys adjust the value by the step, page up & page down by the page
+/// step and home & end move to the minimum & maximum. Colors are derived from the current
+/// `Theme`.
+pub struct Scaler {
+    track: Arc<Bin>,
+    /// The `Bin` dragged along the track.
+    pub handle: Arc<Bin>,
+    orientation: ScalerOrientation,
+    state: Mutex<State>,
+    on_change: Mutex<Vec<Box<dyn FnMut(&Arc<Scaler>, f32) + Send + 'static>>>,
+    on_commit: Mutex<Vec<Box<dyn FnMut(&Arc<Scaler>, f32) + Send + 'static>>>,
+}
+
+struct State {
+    min: f32,
+    max: f32,
+    step: Option<f32>,
+    page_step: Option<f32>,
+    value: f32,
+    dragging: bool,
+}
+
+impl State {
+    fn constrain(&self, value: f32) -> f32 {
+        let value = match self.step {
+            Some(step) if step > 0.0 => (((value - self.min) / step).round() * step) + self.min,
+            _ => value,
+        };
+
+        value.clamp(self.min, self.max)
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn page_step(&self) -> f32 {
+        match self.page_step {
+            Some(page_step) => page_step,
+            None => (self.max - self.min) / 10.0,
+        }
+    }
+}
+
+impl Deref for Scaler {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.track
+    }
+}
+
+impl Scaler {
+    /// Create a new scaler with a range of `0.0..=100.0` and no step.
+    ///
+    /// The scaler is placed at the top left of the parent or window if no parent is provided.
+    ///
+    /// # Notes
+    /// - Panics if parent bin is not associated to the window provided.
+    pub fn new(
+        window: Arc<Window>,
+        parent: Option<Arc<Bin>>,
+        orientation: ScalerOrientation,
+    ) -> Arc<Self> {
+        let [width, height] = match orientation {
+            ScalerOrientation::Horizontal => [200.0, TRACK_THICKNESS],
+            ScalerOrientation::Vertical => [TRACK_THICKNESS, 200.0],
+        };
+
+        let track = new_widget_bin(
+            &window,
+            parent,
+            BinStyle {
+                pos_from_t: Some(HANDLE_OVERHANG),
+                pos_from_l: Some(HANDLE_OVERHANG),
+                width: Some(width),
+                height: Some(height),
+                overflow_x: Some(true),
+                overflow_y: Some(true),
+                ..BinStyle::default()
+            },
+        );
+
+        let handle = window.new_bin();
+        track.add_child(handle.clone());
+
+        handle
+            .style_update(match orientation {
+                ScalerOrientation::Horizontal => {
+                    BinStyle {
+                        position: Some(BinPosition::Parent),
+                        pos_from_t: Some(-HANDLE_OVERHANG),
+                        pos_from_b: Some(-HANDLE_OVERHANG),
+                        pos_from_l: Some(0.0),
+                        width: Some(HANDLE_LENGTH),
+                        ..BinStyle::default()
+                    }
+                },
+                ScalerOrientation::Vertical => {
+                    BinStyle {
+                        position: Some(BinPosition::Parent),
+                        pos_from_l: Some(-HANDLE_OVERHANG),
+                        pos_from_r: Some(-HANDLE_OVERHANG),
+                        pos_from_b: Some(0.0),
+                        height: Some(HANDLE_LENGTH),
+                        ..BinStyle::default()
+                    }
+                },
+            })
+            .expect_valid();
+
+        set_widget_theme_style(&track, |theme, base| {
+            with_border_radius(
+                BinStyle {
+                    back_color: Some(theme.colors.back3),
+                    ..base
+                },
+                TRACK_THICKNESS / 2.0,
+            )
+        });
+
+        set_widget_theme_style(&handle, |theme, base| {
+            with_border_radius(
+                BinStyle {
+                    back_color: Some(theme.colors.accent1),
+                    ..base
+                },
+                theme.border_radius,
+            )
+        });
+
+        let scaler = Arc::new(Self {
+            track,
+            handle,
+            orientation,
+            state: Mutex::new(State {
+                min: 0.0,
+                max: 100.0,
+                step: None,
+                page_step: None,
+                value: 0.0,
+                dragging: false,
+            }),
+            on_change: Mutex::new(Vec::new()),
+            on_commit: Mutex::new(Vec::new()),
+        });
+
+        let scaler_wk = Arc::downgrade(&scaler);
+
+        scaler.track.on_update(move |_, _| {
+            if let Some(scaler) = scaler_wk.upgrade() {
+                scaler.update_handle();
+            }
+        });
+
+        // NOTE: Pressing the track moves the handle to the cursor, while pressing the handle
+        //       begins dragging from its current value.
+        for (bin, jump) in [(&scaler.track, true), (&scaler.handle, false)] {
+            let scaler_wk = Arc::downgrade(&scaler);
+
+            bin.on_press(MouseButton::Left, move |target, window_state, _| {
+                let scaler = match scaler_wk.upgrade() {
+                    Some(some) => some,
+                    None => return InputHookCtrl::Remove,
+                };
+
+                scaler.state.lock().dragging = true;
+
+                if jump {
+                    if let Some(window) = target.into_bin().and_then(|bin| bin.window()) {
+                        let scale = window.effective_interface_scale();
+                        let [x, y] = window_state.cursor_pos();
+                        scaler.drag_to(x / scale, y / scale);
+                    }
+                }
+
+                Default::default()
+            });
+
+            let (decrease, increase) = match orientation {
+                ScalerOrientation::Horizontal => (Qwerty::ArrowLeft, Qwerty::ArrowRight),
+                ScalerOrientation::Vertical => (Qwerty::ArrowDown, Qwerty::ArrowUp),
+            };
+
+            for key in [
+                decrease,
+                increase,
+                Qwerty::PageDown,
+                Qwerty::PageUp,
+                Qwerty::Home,
+                Qwerty::End,
+            ] {
+                let scaler_wk = Arc::downgrade(&scaler);
+
+                bin.on_press(key, move |_, _, _| {
+                    let scaler = match scaler_wk.upgrade() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
+
+                    let value = {
+                        let state = scaler.state.lock();
+                        let step = state.step.unwrap_or((state.max - state.min) / 100.0);
+
+                        match key {
+                            Qwerty::PageDown => state.value - state.page_step(),
+                            Qwerty::PageUp => state.value + state.page_step(),
+                            Qwerty::Home => state.min,
+                            Qwerty::End => state.max,
+                            key if key == decrease => state.value - step,
+                            _ => state.value + step,
+                        }
+                    };
+
+                    if scaler.change_value(value) {
+                        scaler.emit_commit();
+                    }
+
+                    Default::default()
+                });
+            }
+        }
+
+        let scaler_wk = Arc::downgrade(&scaler);
+
+        scaler.track.attach_input_hook(
+            window
+                .basalt_ref()
+                .input_ref()
+                .hook()
+                .window(&window)
+                .on_cursor()
+                .call(move |target, window_state, _| {
+                    let scaler = match scaler_wk.upgrade() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
+
+                    if !scaler.state.lock().dragging {
+                        return Default::default();
+                    }
+
+                    if let Some(window) = target.into_window() {
+                        let scale = window.effective_interface_scale();
+                        let [x, y] = window_state.cursor_pos();
+                        scaler.drag_to(x / scale, y / scale);
+                    }
+
+                    Default::default()
+                })
+                .finish()
+                .unwrap(),
+        );
+
+        let scaler_wk = Arc::downgrade(&scaler);
+
+        scaler.track.attach_input_hook(
+            window
+                .basalt_ref()
+                .input_ref()
+                .hook()
+                .window(&window)
+                .on_release()
+                .keys(MouseButton::Left)
+                .call(move |_, _, _| {
+                    let scaler = match scaler_wk.upgrade() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
+
+                    let dragging = std::mem::replace(&mut scaler.state.lock().dragging, false);
+
+                    if dragging {
+                        scaler.emit_commit();
+                    }
+
+                    Default::default()
+                })
+                .finish()
+                .unwrap(),
+        );
+
+        scaler
+    }
+
+    /// Retrieve the orientation of the scaler.
+    pub fn orientation(&self) -> ScalerOrientation {
+        self.orientation
+    }
+
+    /// Add a method to be called as the value changes, including while dragging.
+    ///
+    /// ***Note:** This is not called when the value is changed with `set_value`.*
+    pub fn on_change<F: FnMut(&Arc<Scaler>, f32) + Send + 'static>(&self, method: F) {
+        self.on_change.lock().push(Box::new(method));
+    }
+
+    /// Add a method to be called when the user finishes changing the value. This is when the
+    /// handle is released or after a key press.
+    pub fn on_commit<F: FnMut(&Arc<Scaler>, f32) + Send + 'static>(&self, method: F) {
+        self.on_commit.lock().push(Box::new(method));
+    }
+
+    /// Set the range of values.
+    ///
+    /// ***Note:** The current value is constrained to the new range.*
+    pub fn set_range(&self, min: f32, max: f32) {
+        {
+            let mut state = self.state.lock();
+            state.min = min.min(max);
+            state.max = max.max(min);
+            state.value = state.constrain(state.value);
+        }
+
+        self.update_handle();
+    }
+
+    /// Retrieve the range of values.
+    pub fn range(&self) -> (f32, f32) {
+        let state = self.state.lock();
+        (state.min, state.max)
+    }
+
+    /// Set the step that values are rounded to. When `None` values are continuous and the
+    /// arrow keys adjust the value by one percent of the range.
+    pub fn set_step(&self, step: Option<f32>) {
+        {
+            let mut state = self.state.lock();
+            state.step = step;
+            state.value = state.constrain(state.value);
+        }
+
+        self.update_handle();
+    }
+
+    /// Set the amount page up & page down adjust the value by.
+    ///
+    /// **Default**: ten percent of the range.
+    pub fn set_page_step(&self, page_step: Option<f32>) {
+        self.state.lock().page_step = page_step;
+    }
+
+    /// Set the value.
+    ///
+    /// ***Note:** The value is constrained to the range & step.*
+    pub fn set_value(&self, value: f32) {
+        {
+            let mut state = self.state.lock();
+            state.value = state.constrain(value);
+        }
+
+        self.update_handle();
+    }
+
+    /// Retrieve the current value.
+    pub fn value(&self) -> f32 {
+        self.state.lock().value
+    }
+
+    // NOTE: Position is in logical units.
+    fn drag_to(self: &Arc<Self>, x: f32, y: f32) {
+        let post = self.track.post_update();
+
+        let fraction = match self.orientation {
+            ScalerOrientation::Horizontal => {
+                let length = post.tro[0] - post.tlo[0] - HANDLE_LENGTH;
+
+                if length <= 0.0 {
+                    return;
+                }
+
+                (x - post.tlo[0] - (HANDLE_LENGTH / 2.0)) / length
+            },
+            ScalerOrientation::Vertical => {
+                let length = post.blo[1] - post.tlo[1] - HANDLE_LENGTH;
+
+                if length <= 0.0 {
+                    return;
+                }
+
+                (post.blo[1] - y - (HANDLE_LENGTH / 2.0)) / length
+            },
+        };
+
+        let value = {
+            let state = self.state.lock();
+            state.min + ((state.max - state.min) * fraction.clamp(0.0, 1.0))
+        };
+
+        self.change_value(value);
+    }
+
+    // NOTE: Returns true if the value changed.
+    fn change_value(self: &Arc<Self>, value: f32) -> bool {
+        let value = {
+            let mut state = self.state.lock();
+            let value = state.constrain(value);
+
+            if value == state.value {
+                return false;
+            }
+
+            state.value = value;
+            value
+        };
+
+        self.update_handle();
+
+        for method in self.on_change.lock().iter_mut() {
+            method(self, value);
+        }
+
+        true
+    }
+
+    fn emit_commit(self: &Arc<Self>) {
+        let value = self.value();
+
+        for method in self.on_commit.lock().iter_mut() {
+            method(self, value);
+        }
+    }
+
+    fn update_handle(&self) {
+        let fraction = self.state.lock().fraction();
+        let post = self.track.post_update();
+
+        let style = match self.orientation {
+            ScalerOrientation::Horizontal => {
+                let length = (post.tro[0] - post.tlo[0] - HANDLE_LENGTH).max(0.0);
+
+                BinStyle {
+                    pos_from_l: Some(length * fraction),
+                    ..self.handle.style_copy()
+                }
+            },
+            ScalerOrientation::Vertical => {
+                let length = (post.blo[1] - post.tlo[1] - HANDLE_LENGTH).max(0.0);
+
+                BinStyle {
+                    pos_from_b: Some(length * fraction),
+                    ..self.handle.style_copy()
+                }
+            },
+        };
+
+        self.handle.style_update(style).expect_valid();
+    }
+}
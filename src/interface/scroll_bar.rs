@@ -1,3 +1,4 @@
+use std::ops::Deref;
 use std::sync::Arc;
 
 use parking_lot::Mutex;
@@ -6,11 +7,30 @@ use crate::input::{InputHookCtrl, MouseButton};
 use crate::interface::{Bin, BinPosition, BinStyle, BinVert, Color};
 use crate::window::Window;
 
+/// The minimum length of the bar of a `ScrollBar`.
+const MIN_BAR_LENGTH: f32 = 8.0;
+
+/// The axis a `ScrollBar` scrolls along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollAxis {
+    /// Uses `BinStyle.scroll_y` and is placed on the right of the parent or window.
+    #[default]
+    Vertical,
+    /// Uses `BinStyle.scroll_x` and is placed on the bottom of the parent or window.
+    Horizontal,
+}
+
 pub struct ScrollBarStyle {
     pub border_color: Color,
     pub arrow_color: Color,
     pub bar_color: Color,
     pub back_color: Color,
+    /// **Default**: `ScrollAxis::Vertical`
+    pub axis: ScrollAxis,
+    /// Hide the scroll bar when there is no overflow.
+    ///
+    /// **Default**: `true`
+    pub auto_hide: bool,
 }
 
 impl Default for ScrollBarStyle {
@@ -20,16 +40,25 @@ impl Default for ScrollBarStyle {
             bar_color: Color::shex("f0f0f0"),
             arrow_color: Color::shex("f0f0f0"),
             border_color: Color::shex("222227"),
+            axis: ScrollAxis::Vertical,
+            auto_hide: true,
         }
     }
 }
 
+/// A scroll bar for a `Bin` with overflowing content.
+///
+/// The bar is sized by the visible portion of the content and can be dragged.
+///
+/// ***Note:** With `ScrollAxis::Horizontal`, `up` is the left arrow and `down` is the right arrow.*
 pub struct ScrollBar {
     pub back: Arc<Bin>,
     pub up: Arc<Bin>,
     pub down: Arc<Bin>,
     pub bar: Arc<Bin>,
     scroll: Arc<Bin>,
+    axis: ScrollAxis,
+    auto_hide: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -42,6 +71,14 @@ pub enum ScrollTo {
     Set(f32),
 }
 
+impl Deref for ScrollBar {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.back
+    }
+}
+
 impl ScrollBar {
     /// # Notes
     /// - Panics if parent bin is not associated to the window provided.
@@ -82,7 +119,24 @@ impl ScrollBar {
         back.add_child(down.clone());
         back.add_child(bar.clone());
 
-        back.style_update(BinStyle {
+        // NOTE: Styles are defined for the vertical axis. For the horizontal axis, the
+        //       vertical & horizontal fields are swapped.
+        let arrow_verts = |verts: [(f32, f32); 3]| {
+            verts
+                .into_iter()
+                .map(|(x, y)| {
+                    BinVert {
+                        position: match style.axis {
+                            ScrollAxis::Vertical => (x, y, 0),
+                            ScrollAxis::Horizontal => (y, x, 0),
+                        },
+                        color: style.arrow_color,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let back_style = BinStyle {
             position: Some(position),
             pos_from_t: Some(0.0),
             pos_from_b: Some(0.0),
@@ -92,58 +146,29 @@ impl ScrollBar {
             border_size_l: Some(1.0),
             border_color_l: Some(style.border_color),
             ..BinStyle::default()
-        })
-        .expect_valid();
+        };
 
-        up.style_update(BinStyle {
+        let up_style = BinStyle {
             position: Some(BinPosition::Parent),
             pos_from_t: Some(0.0),
             pos_from_l: Some(0.0),
             pos_from_r: Some(0.0),
             height: Some(13.0),
-            custom_verts: vec![
-                BinVert {
-                    position: (7.5, 4.0, 0),
-                    color: style.arrow_color,
-                },
-                BinVert {
-                    position: (4.0, 9.0, 0),
-                    color: style.arrow_color,
-                },
-                BinVert {
-                    position: (11.0, 9.0, 0),
-                    color: style.arrow_color,
-                },
-            ],
+            custom_verts: arrow_verts([(7.5, 4.0), (4.0, 9.0), (11.0, 9.0)]),
             ..BinStyle::default()
-        })
-        .expect_valid();
+        };
 
-        down.style_update(BinStyle {
+        let down_style = BinStyle {
             position: Some(BinPosition::Parent),
             pos_from_b: Some(0.0),
             pos_from_l: Some(0.0),
             pos_from_r: Some(0.0),
             height: Some(13.0),
-            custom_verts: vec![
-                BinVert {
-                    position: (11.0, 4.0, 0),
-                    color: style.arrow_color,
-                },
-                BinVert {
-                    position: (4.0, 4.0, 0),
-                    color: style.arrow_color,
-                },
-                BinVert {
-                    position: (7.5, 9.0, 0),
-                    color: style.arrow_color,
-                },
-            ],
+            custom_verts: arrow_verts([(11.0, 4.0), (4.0, 4.0), (7.5, 9.0)]),
             ..BinStyle::default()
-        })
-        .expect_valid();
+        };
 
-        bar.style_update(BinStyle {
+        let bar_style = BinStyle {
             position: Some(BinPosition::Parent),
             pos_from_t: Some(15.0),
             pos_from_b: Some(15.0),
@@ -151,8 +176,24 @@ impl ScrollBar {
             pos_from_r: Some(2.0),
             back_color: Some(style.bar_color),
             ..BinStyle::default()
-        })
-        .expect_valid();
+        };
+
+        let (back_style, up_style, down_style, bar_style) = match style.axis {
+            ScrollAxis::Vertical => (back_style, up_style, down_style, bar_style),
+            ScrollAxis::Horizontal => {
+                (
+                    swap_axis(back_style),
+                    swap_axis(up_style),
+                    swap_axis(down_style),
+                    swap_axis(bar_style),
+                )
+            },
+        };
+
+        back.style_update(back_style).expect_valid();
+        up.style_update(up_style).expect_valid();
+        down.style_update(down_style).expect_valid();
+        bar.style_update(bar_style).expect_valid();
 
         let sb = Arc::new(ScrollBar {
             back,
@@ -160,6 +201,8 @@ impl ScrollBar {
             down,
             bar,
             scroll,
+            axis: style.axis,
+            auto_hide: style.auto_hide,
         });
 
         let sb_wk = Arc::downgrade(&sb);
@@ -172,9 +215,8 @@ impl ScrollBar {
                 None => return InputHookCtrl::Remove,
             };
 
-            let [_, mouse_y] = window.cursor_pos();
-            let scroll_y = sb.scroll.style_copy().scroll_y.unwrap_or(0.0);
-            *drag_data_cp.lock() = Some((mouse_y, scroll_y));
+            let mouse = sb.main_axis(window.cursor_pos());
+            *drag_data_cp.lock() = Some((mouse, sb.scroll_position()));
             Default::default()
         });
 
@@ -200,30 +242,24 @@ impl ScrollBar {
                         None => return InputHookCtrl::Remove,
                     };
 
-                    let [_, mouse_y] = window.cursor_pos();
+                    let mouse = sb.main_axis(window.cursor_pos());
                     let drag_data_op = drag_data.lock();
 
-                    let drag_data = match drag_data_op.as_ref() {
-                        Some(some) => some,
+                    let (start_mouse, start_position) = match drag_data_op.as_ref() {
+                        Some(some) => *some,
                         None => return Default::default(),
                     };
 
-                    let overflow = sb.scroll.calc_vert_overflow();
-                    let up_post = sb.up.post_update();
-                    let down_post = sb.down.post_update();
-                    let max_bar_h = down_post.tlo[1] - up_post.blo[1];
-                    let mut bar_sp = overflow / 10.0;
-                    let mut bar_h = max_bar_h - bar_sp;
+                    let overflow = sb.overflow();
+                    let [track_length, bar_length] = sb.track_and_bar_length(overflow);
 
-                    if bar_h < 3.0 {
-                        bar_h = 3.0;
-                        bar_sp = max_bar_h - bar_h;
+                    if track_length > bar_length {
+                        let bar_inc = overflow / (track_length - bar_length);
+                        sb.update(ScrollTo::Set(
+                            start_position + ((mouse - start_mouse) * bar_inc),
+                        ));
                     }
 
-                    let bar_inc = overflow / bar_sp;
-                    sb.update(ScrollTo::Set(
-                        drag_data.1 + ((mouse_y - drag_data.0) * bar_inc),
-                    ));
                     Default::default()
                 })
                 .finish()
@@ -247,16 +283,9 @@ impl ScrollBar {
 
         let sb_wk = Arc::downgrade(&sb);
 
-        sb.scroll.on_children_added(move |_, _| {
+        sb.scroll.on_overflow_changed(move |_, _, _| {
             if let Some(sb) = sb_wk.upgrade() {
-                sb.back.trigger_update();
-                let sb_wk = Arc::downgrade(&sb);
-
-                sb.back.on_update_once(move |_, _| {
-                    if let Some(sb) = sb_wk.upgrade() {
-                        sb.update(ScrollTo::Same);
-                    }
-                });
+                sb.update(ScrollTo::Same);
             }
         });
 
@@ -309,6 +338,7 @@ impl ScrollBar {
 
         let sb_wk = Arc::downgrade(&sb);
 
+        // NOTE: When scrolling over the scroll bar itself, either direction scrolls it.
         sb.back.attach_input_hook(
             window
                 .basalt_ref()
@@ -317,8 +347,8 @@ impl ScrollBar {
                 .bin(&sb.back)
                 .on_scroll()
                 .enable_smooth(true)
-                .call(move |_, _, mut amt, _| {
-                    amt = amt.round();
+                .call(move |_, _, v, h| {
+                    let amt = if v != 0.0 { v.round() } else { h.round() };
 
                     if amt == 0.0 {
                         return Default::default();
@@ -347,166 +377,190 @@ impl ScrollBar {
                 .on_scroll()
                 .enable_smooth(true)
                 .upper_blocks(true)
-                .call(move |_, _, mut amt, _| {
-                    amt = amt.round();
+                .call(move |_, _, v, h| {
+                    let sb = match sb_wk.upgrade() {
+                        Some(some) => some,
+                        None => return InputHookCtrl::Remove,
+                    };
 
-                    if amt == 0.0 {
-                        return Default::default();
-                    }
+                    let amt = match sb.axis {
+                        ScrollAxis::Vertical => v.round(),
+                        ScrollAxis::Horizontal => h.round(),
+                    };
 
-                    match sb_wk.upgrade() {
-                        Some(sb) => {
-                            sb.update(ScrollTo::Amount(amt));
-                            Default::default()
-                        },
-                        None => crate::input::InputHookCtrl::Remove,
+                    if amt != 0.0 {
+                        sb.update(ScrollTo::Amount(amt));
                     }
+
+                    Default::default()
                 })
                 .finish()
                 .unwrap(),
         );
 
+        sb.update(ScrollTo::Same);
         sb
     }
 
-    pub fn update(&self, amount: ScrollTo) {
-        let mut scroll_y = self.scroll.style_copy().scroll_y.unwrap_or(0.0);
-        let overflow = self.scroll.calc_vert_overflow();
-
-        if match amount {
-            ScrollTo::Same => {
-                if scroll_y > overflow {
-                    scroll_y = overflow;
-                    true
-                } else {
-                    false
-                }
-            },
-            ScrollTo::Top => {
-                if scroll_y == 0.0 {
-                    false
-                } else {
-                    scroll_y = 0.0;
-                    true
-                }
-            },
-            ScrollTo::Bottom => {
-                if scroll_y == overflow {
-                    false
-                } else {
-                    scroll_y = overflow;
-                    true
-                }
-            },
-            ScrollTo::Percent(p) => {
-                if p.is_sign_positive() {
-                    if scroll_y == overflow {
-                        false
-                    } else {
-                        let amt = overflow * p;
-
-                        if scroll_y + amt > overflow {
-                            scroll_y = overflow;
-                        } else {
-                            scroll_y += amt;
-                        }
-
-                        true
-                    }
-                } else if scroll_y == 0.0 {
-                    false
-                } else {
-                    let amt = overflow * p;
-
-                    if scroll_y + amt < 0.0 {
-                        scroll_y = 0.0;
-                    } else {
-                        scroll_y += amt;
-                    }
-
-                    true
-                }
-            },
-            ScrollTo::Amount(amt) => {
-                if amt.is_sign_positive() {
-                    if scroll_y == overflow {
-                        false
-                    } else {
-                        if scroll_y + amt > overflow {
-                            scroll_y = overflow;
-                        } else {
-                            scroll_y += amt;
-                        }
+    /// The axis this scroll bar scrolls along.
+    pub fn axis(&self) -> ScrollAxis {
+        self.axis
+    }
 
-                        true
-                    }
-                } else if scroll_y == 0.0 {
-                    false
-                } else {
-                    if scroll_y + amt < 0.0 {
-                        scroll_y = 0.0;
-                    } else {
-                        scroll_y += amt;
-                    }
+    pub fn update(&self, amount: ScrollTo) {
+        let position = self.scroll_position();
+        let overflow = self.overflow();
+
+        let new_position = match amount {
+            ScrollTo::Same => position,
+            ScrollTo::Top => 0.0,
+            ScrollTo::Bottom => overflow,
+            ScrollTo::Percent(p) => position + (overflow * p),
+            ScrollTo::Amount(amt) => position + amt,
+            ScrollTo::Set(to) => to,
+        }
+        .clamp(0.0, overflow);
 
-                    true
-                }
-            },
-            ScrollTo::Set(to) => {
-                if to < 0.0 {
-                    if scroll_y == 0.0 {
-                        false
-                    } else {
-                        scroll_y = 0.0;
-                        true
-                    }
-                } else if to > overflow {
-                    if scroll_y == overflow {
-                        false
-                    } else {
-                        scroll_y = overflow;
-                        true
-                    }
-                } else {
-                    scroll_y = to;
-                    true
-                }
-            },
-        } {
+        if new_position != position {
             self.scroll
-                .style_update(BinStyle {
-                    scroll_y: Some(scroll_y),
-                    ..self.scroll.style_copy()
+                .style_update(match self.axis {
+                    ScrollAxis::Vertical => {
+                        BinStyle {
+                            scroll_y: Some(new_position),
+                            ..self.scroll.style_copy()
+                        }
+                    },
+                    // NOTE: Positive scroll_x moves content to the right.
+                    ScrollAxis::Horizontal => {
+                        BinStyle {
+                            scroll_x: Some(-new_position),
+                            ..self.scroll.style_copy()
+                        }
+                    },
                 })
                 .expect_valid();
         }
 
-        let up_post = self.up.post_update();
-        let down_post = self.down.post_update();
-        let max_bar_h = down_post.tlo[1] - up_post.blo[1];
+        if self.auto_hide {
+            let hide = overflow <= 0.0;
 
-        if max_bar_h < 3.0 {
-            // println!("Scroll bar less than minimum height.");
+            if self.back.style_inspect(|style| style.hidden) != Some(hide) {
+                self.back.set_hidden(Some(hide));
+            }
+
+            if hide {
+                return;
+            }
         }
 
-        let mut bar_sp = overflow / 10.0;
-        let mut bar_h = max_bar_h - bar_sp;
+        let [track_length, bar_length] = self.track_and_bar_length(overflow);
 
-        if bar_h < 3.0 {
-            bar_h = 3.0;
-            bar_sp = max_bar_h - bar_h;
-        }
+        let bar_position = if overflow > 0.0 {
+            (new_position / overflow) * (track_length - bar_length)
+        } else {
+            0.0
+        };
 
-        let bar_inc = overflow / bar_sp;
-        let bar_pos = scroll_y / bar_inc;
+        let up_post = self.up.post_update();
+        let offset = match self.axis {
+            ScrollAxis::Vertical => up_post.blo[1] - up_post.tlo[1],
+            ScrollAxis::Horizontal => up_post.tro[0] - up_post.tlo[0],
+        };
 
         self.bar
-            .style_update(BinStyle {
-                pos_from_t: Some(bar_pos + up_post.blo[1] - up_post.tlo[1]),
-                pos_from_b: None,
-                height: Some(bar_h),
-                ..self.bar.style_copy()
+            .style_update(match self.axis {
+                ScrollAxis::Vertical => {
+                    BinStyle {
+                        pos_from_t: Some(offset + bar_position),
+                        pos_from_b: None,
+                        height: Some(bar_length),
+                        ..self.bar.style_copy()
+                    }
+                },
+                ScrollAxis::Horizontal => {
+                    BinStyle {
+                        pos_from_l: Some(offset + bar_position),
+                        pos_from_r: None,
+                        width: Some(bar_length),
+                        ..self.bar.style_copy()
+                    }
+                },
             })
             .expect_valid();
     }
+
+    fn main_axis(&self, xy: [f32; 2]) -> f32 {
+        match self.axis {
+            ScrollAxis::Vertical => xy[1],
+            ScrollAxis::Horizontal => xy[0],
+        }
+    }
+
+    fn scroll_position(&self) -> f32 {
+        self.scroll.style_inspect(|style| {
+            match self.axis {
+                ScrollAxis::Vertical => style.scroll_y.unwrap_or(0.0),
+                ScrollAxis::Horizontal => -style.scroll_x.unwrap_or(0.0),
+            }
+        })
+    }
+
+    fn overflow(&self) -> f32 {
+        match self.axis {
+            ScrollAxis::Vertical => self.scroll.calc_vert_overflow(),
+            ScrollAxis::Horizontal => self.scroll.calc_hori_overflow(),
+        }
+    }
+
+    // NOTE: The bar length is proportional to the visible portion of the content.
+    fn track_and_bar_length(&self, overflow: f32) -> [f32; 2] {
+        let up_post = self.up.post_update();
+        let down_post = self.down.post_update();
+        let scroll_post = self.scroll.post_update();
+
+        let (track_length, visible_length) = match self.axis {
+            ScrollAxis::Vertical => {
+                (
+                    down_post.tlo[1] - up_post.blo[1],
+                    scroll_post.bli[1] - scroll_post.tli[1],
+                )
+            },
+            ScrollAxis::Horizontal => {
+                (
+                    down_post.tlo[0] - up_post.tro[0],
+                    scroll_post.tri[0] - scroll_post.tli[0],
+                )
+            },
+        };
+
+        let track_length = track_length.max(0.0);
+        let content_length = visible_length + overflow;
+
+        let bar_length = if content_length > 0.0 {
+            track_length * (visible_length / content_length)
+        } else {
+            track_length
+        };
+
+        [
+            track_length,
+            bar_length.max(MIN_BAR_LENGTH).min(track_length),
+        ]
+    }
+}
+
+fn swap_axis(style: BinStyle) -> BinStyle {
+    BinStyle {
+        pos_from_t: style.pos_from_l,
+        pos_from_b: style.pos_from_r,
+        pos_from_l: style.pos_from_t,
+        pos_from_r: style.pos_from_b,
+        width: style.height,
+        height: style.width,
+        border_size_t: style.border_size_l,
+        border_size_l: style.border_size_t,
+        border_color_t: style.border_color_l,
+        border_color_l: style.border_color_t,
+        ..style
+    }
 }
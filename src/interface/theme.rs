@@ -0,0 +1,202 @@
+use crate::interface::{BinStyle, Color, DefaultFont, TextVertAlign};
+use crate::NonExhaustive;
+
+/// The color scheme preferred by the system.
+///
+/// See `Interface::system_color_scheme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorScheme {
+    /// Also used when the system doesn't report a preference.
+    #[default]
+    Light,
+    Dark,
+}
+
+impl From<winit::window::Theme> for ColorScheme {
+    fn from(theme: winit::window::Theme) -> Self {
+        match theme {
+            winit::window::Theme::Light => Self::Light,
+            winit::window::Theme::Dark => Self::Dark,
+        }
+    }
+}
+
+/// Colors used by a `Theme`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeColors {
+    /// Primary background color.
+    pub back1: Color,
+    /// Background color of elements within `back1`.
+    pub back2: Color,
+    /// Background color of elements within `back2`.
+    pub back3: Color,
+    /// Primary border color.
+    pub border1: Color,
+    /// Border color of elements that are focused or hovered.
+    pub border2: Color,
+    /// Primary accent color.
+    pub accent1: Color,
+    /// Accent color of elements that are pressed or active.
+    pub accent2: Color,
+    /// Primary text color.
+    pub text1: Color,
+    /// Text color of secondary or disabled text.
+    pub text2: Color,
+    /// Text color used on top of `accent1` & `accent2`.
+    pub text_on_accent: Color,
+}
+
+/// Shared appearance of an interface.
+///
+/// The current theme is retrieved with `Interface::theme` and changed with
+/// `Interface::set_theme`. See `Bin::set_theme_style` for restyling bins when the theme changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub colors: ThemeColors,
+    /// The font used for text. Fields that are `None` use the default font.
+    pub font: DefaultFont,
+    /// **Default**: `12.0`
+    pub text_height: f32,
+    /// The spacing used for padding and margins.
+    ///
+    /// **Default**: `6.0`
+    pub spacing: f32,
+    /// **Default**: `1.0`
+    pub border_size: f32,
+    /// **Default**: `3.0`
+    pub border_radius: f32,
+    pub _ne: NonExhaustive,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The theme for the provided `ColorScheme`.
+    pub fn from_color_scheme(color_scheme: ColorScheme) -> Self {
+        match color_scheme {
+            ColorScheme::Light => Self::light(),
+            ColorScheme::Dark => Self::dark(),
+        }
+    }
+
+    /// A theme with dark backgrounds & light text.
+    pub fn dark() -> Self {
+        Self {
+            colors: ThemeColors {
+                back1: Color::shex("1e1e1e"),
+                back2: Color::shex("2a2a2a"),
+                back3: Color::shex("363636"),
+                border1: Color::shex("4a4a4a"),
+                border2: Color::shex("707070"),
+                accent1: Color::shex("3d7bd9"),
+                accent2: Color::shex("2f62b0"),
+                text1: Color::shex("e8e8e8"),
+                text2: Color::shex("a0a0a0"),
+                text_on_accent: Color::shex("ffffff"),
+            },
+            ..Self::light()
+        }
+    }
+
+    /// A theme with light backgrounds & dark text.
+    pub fn light() -> Self {
+        Self {
+            colors: ThemeColors {
+                back1: Color::shex("f4f4f4"),
+                back2: Color::shex("e6e6e6"),
+                back3: Color::shex("d8d8d8"),
+                border1: Color::shex("b8b8b8"),
+                border2: Color::shex("808080"),
+                accent1: Color::shex("3d7bd9"),
+                accent2: Color::shex("2f62b0"),
+                text1: Color::shex("1a1a1a"),
+                text2: Color::shex("606060"),
+                text_on_accent: Color::shex("ffffff"),
+            },
+            font: DefaultFont::default(),
+            text_height: 12.0,
+            spacing: 6.0,
+            border_size: 1.0,
+            border_radius: 3.0,
+            _ne: NonExhaustive(()),
+        }
+    }
+
+    /// Style for a container of other elements.
+    ///
+    /// ***Note:** Position & size fields are not set.*
+    pub fn container_style(&self) -> BinStyle {
+        BinStyle {
+            back_color: Some(self.colors.back1),
+            pad_t: Some(self.spacing),
+            pad_b: Some(self.spacing),
+            pad_l: Some(self.spacing),
+            pad_r: Some(self.spacing),
+            ..BinStyle::default()
+        }
+    }
+
+    /// Style for text without a background.
+    ///
+    /// ***Note:** Position & size fields are not set.*
+    pub fn text_style<T: Into<String>>(&self, text: T) -> BinStyle {
+        BinStyle {
+            text: text.into(),
+            text_color: Some(self.colors.text1),
+            text_height: Some(self.text_height),
+            font_family: self.font.family.clone(),
+            font_weight: self.font.weight,
+            font_stretch: self.font.strench,
+            font_style: self.font.style,
+            ..BinStyle::default()
+        }
+    }
+
+    /// Style for an element that can be interacted with, such as a button.
+    ///
+    /// ***Note:** Position & size fields are not set.*
+    pub fn button_style<T: Into<String>>(&self, text: T) -> BinStyle {
+        BinStyle {
+            back_color: Some(self.colors.back3),
+            pad_l: Some(self.spacing),
+            pad_r: Some(self.spacing),
+            text_vert_align: Some(TextVertAlign::Center),
+            ..self.bordered(self.text_style(text))
+        }
+    }
+
+    /// Style for an element that accepts input, such as a text field.
+    ///
+    /// ***Note:** Position & size fields are not set.*
+    pub fn field_style<T: Into<String>>(&self, text: T) -> BinStyle {
+        BinStyle {
+            back_color: Some(self.colors.back2),
+            pad_l: Some(self.spacing),
+            pad_r: Some(self.spacing),
+            text_vert_align: Some(TextVertAlign::Center),
+            ..self.bordered(self.text_style(text))
+        }
+    }
+
+    fn bordered(&self, style: BinStyle) -> BinStyle {
+        BinStyle {
+            border_size_t: Some(self.border_size),
+            border_size_b: Some(self.border_size),
+            border_size_l: Some(self.border_size),
+            border_size_r: Some(self.border_size),
+            border_color_t: Some(self.colors.border1),
+            border_color_b: Some(self.colors.border1),
+            border_color_l: Some(self.colors.border1),
+            border_color_r: Some(self.colors.border1),
+            border_radius_tl: Some(self.border_radius),
+            border_radius_tr: Some(self.border_radius),
+            border_radius_bl: Some(self.border_radius),
+            border_radius_br: Some(self.border_radius),
+            ..style
+        }
+    }
+}
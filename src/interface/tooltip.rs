@@ -0,0 +1,245 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::input::{InputHookCtrl, InputHookID, MouseButton};
+use crate::interface::{Bin, BinPosition, BinStyle, BinStyleError, Color, TextWrap};
+use crate::interval::{IntvlHookCtrl, IntvlHookID};
+use crate::window::Window;
+use crate::NonExhaustive;
+
+/// A tooltip displayed when hovering a `Bin`.
+///
+/// See `Bin::set_tooltip`.
+#[derive(Clone)]
+pub struct Tooltip {
+    /// The style of the tooltip `Bin`.
+    ///
+    /// ***Note:** Position fields are overridden when the tooltip is displayed. When `width` or
+    /// `height` isn't set, it is measured from the text when the tooltip is displayed.*
+    pub style: BinStyle,
+    /// The width at which the text is wrapped when `width` of `style` isn't set.
+    ///
+    /// **Default**: `320.0`
+    pub max_width: f32,
+    /// How long the cursor must hover before the tooltip is displayed.
+    ///
+    /// **Default**: `Duration::from_millis(500)`
+    pub delay: Duration,
+    /// The distance between the cursor and the tooltip.
+    ///
+    /// **Default**: `12.0`
+    pub cursor_offset: f32,
+    pub _ne: NonExhaustive,
+}
+
+impl Tooltip {
+    /// Create a tooltip from text using the default style.
+    pub fn text<T: Into<String>>(text: T) -> Self {
+        let mut tooltip = Self::default();
+        tooltip.style.text = text.into();
+        tooltip
+    }
+}
+
+impl Default for Tooltip {
+    fn default() -> Self {
+        Self {
+            style: BinStyle {
+                pad_t: Some(6.0),
+                pad_b: Some(6.0),
+                pad_l: Some(8.0),
+                pad_r: Some(8.0),
+                border_radius_tl: Some(3.0),
+                border_radius_tr: Some(3.0),
+                border_radius_bl: Some(3.0),
+                border_radius_br: Some(3.0),
+                back_color: Some(Color::shex("202020e8")),
+                text_color: Some(Color::shex("f0f0f0")),
+                text_height: Some(12.0),
+                text_wrap: Some(TextWrap::Normal),
+                ..BinStyle::default()
+            },
+            max_width: 320.0,
+            delay: Duration::from_millis(500),
+            cursor_offset: 12.0,
+            _ne: NonExhaustive(()),
+        }
+    }
+}
+
+// NOTE: Sizes not set by the style are measured from the text, which wraps at `max_width`.
+fn tooltip_size(window: &Window, tooltip: &Tooltip) -> [f32; 2] {
+    let style = &tooltip.style;
+    let pad_x = style.pad_l.unwrap_or(0.0) + style.pad_r.unwrap_or(0.0);
+    let pad_y = style.pad_t.unwrap_or(0.0) + style.pad_b.unwrap_or(0.0);
+
+    let text = match style.text_spans.as_ref() {
+        Some(text_spans) => text_spans.iter().map(|span| span.text.as_str()).collect(),
+        None => style.text.clone(),
+    };
+
+    let max_text_width = (style.width.unwrap_or(tooltip.max_width) - pad_x).max(0.0);
+    let metrics = window.measure_text(&text, style, Some(max_text_width));
+
+    [
+        style.width.unwrap_or(metrics.width.ceil() + pad_x),
+        style.height.unwrap_or(metrics.height.ceil() + pad_y),
+    ]
+}
+
+pub(crate) struct TooltipState {
+    _bin: Arc<Bin>,
+    input_hook_ids: Vec<InputHookID>,
+    intvl_hook_id: IntvlHookID,
+}
+
+impl TooltipState {
+    pub(crate) fn new(
+        owner: &Arc<Bin>,
+        tooltip: Tooltip,
+    ) -> Result<Option<Self>, Vec<BinStyleError>> {
+        let window = match owner.window() {
+            Some(some) => some,
+            None => return Ok(None),
+        };
+
+        let basalt = owner.basalt_ref();
+        let bin = window.new_bin();
+
+        // NOTE: The tooltip is placed in the top-most layer, so that it is displayed above
+        //       everything else.
+        bin.try_style_update(BinStyle {
+            position: Some(BinPosition::Window),
+            z_layer: Some(i8::MAX),
+            hidden: Some(true),
+            pos_from_t: Some(0.0),
+            pos_from_l: Some(0.0),
+            width: Some(tooltip.style.width.unwrap_or(0.0)),
+            height: Some(tooltip.style.height.unwrap_or(0.0)),
+            ..tooltip.style.clone()
+        })?;
+
+        // NOTE: Some when the cursor is hovering the owner.
+        let hovering: Arc<Mutex<Option<[f32; 2]>>> = Arc::new(Mutex::new(None));
+        let bin_wk = Arc::downgrade(&bin);
+        let window_wk = Arc::downgrade(&window);
+        let hovering_cp = hovering.clone();
+
+        let intvl_hook_id =
+            basalt
+                .interval_ref()
+                .do_every(tooltip.delay, Some(tooltip.delay), move |_| {
+                    let (bin, window) = match (bin_wk.upgrade(), window_wk.upgrade()) {
+                        (Some(bin), Some(window)) => (bin, window),
+                        _ => return IntvlHookCtrl::Remove,
+                    };
+
+                    let hovering = hovering_cp.lock();
+
+                    let [cursor_x, cursor_y] = match *hovering {
+                        Some(some) => some,
+                        None => return IntvlHookCtrl::Pause,
+                    };
+
+                    let scale = window.effective_interface_scale();
+                    let [window_w, window_h] = window.inner_dimensions();
+                    let [window_w, window_h] = [window_w as f32 / scale, window_h as f32 / scale];
+                    let [x, y] = [cursor_x / scale, cursor_y / scale];
+                    let offset = tooltip.cursor_offset;
+                    let [width, height] = tooltip_size(&window, &tooltip);
+
+                    // NOTE: When the tooltip would extend beyond the window, it is flipped to
+                    //       the other side of the cursor.
+                    let left = match x + offset + width > window_w {
+                        true => (x - offset - width).max(0.0),
+                        false => x + offset,
+                    };
+
+                    let top = match y + offset + height > window_h {
+                        true => (y - offset - height).max(0.0),
+                        false => y + offset,
+                    };
+
+                    // NOTE: The style was validated when the tooltip was set, but should the
+                    //       update still be invalid it is skipped.
+                    let _ = bin.try_style_update(BinStyle {
+                        hidden: Some(false),
+                        pos_from_t: Some(top),
+                        pos_from_l: Some(left),
+                        width: Some(width),
+                        height: Some(height),
+                        ..bin.style_copy()
+                    });
+
+                    IntvlHookCtrl::Pause
+                });
+
+        let mut input_hook_ids = Vec::new();
+        let hovering_cp = hovering.clone();
+        let basalt_cp = basalt.clone();
+
+        input_hook_ids.push(owner.on_enter(move |_, window| {
+            *hovering_cp.lock() = Some(window.cursor_pos());
+            basalt_cp.interval_ref().start(intvl_hook_id);
+            Default::default()
+        }));
+
+        let hovering_cp = hovering.clone();
+
+        input_hook_ids.push(owner.on_cursor(move |_, window, _| {
+            let mut hovering = hovering_cp.lock();
+
+            if hovering.is_some() {
+                *hovering = Some(window.cursor_pos());
+            }
+
+            Default::default()
+        }));
+
+        let hide = {
+            let bin_wk = Arc::downgrade(&bin);
+            let basalt = basalt.clone();
+
+            move || {
+                let bin = match bin_wk.upgrade() {
+                    Some(some) => some,
+                    None => return InputHookCtrl::Remove,
+                };
+
+                let mut hovering = hovering.lock();
+                *hovering = None;
+                basalt.interval_ref().pause(intvl_hook_id);
+
+                if !bin.is_hidden() {
+                    bin.set_hidden(Some(true));
+                }
+
+                Default::default()
+            }
+        };
+
+        let hide_cp = hide.clone();
+        input_hook_ids.push(owner.on_leave(move |_, _| hide_cp()));
+
+        for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+            let hide_cp = hide.clone();
+            input_hook_ids.push(owner.on_press(button, move |_, _, _| hide_cp()));
+        }
+
+        Ok(Some(Self {
+            _bin: bin,
+            input_hook_ids,
+            intvl_hook_id,
+        }))
+    }
+
+    pub(crate) fn remove(self, owner: &Bin) {
+        for hook_id in self.input_hook_ids {
+            owner.basalt_ref().input_ref().remove_hook(hook_id);
+        }
+
+        owner.basalt_ref().interval_ref().remove(self.intvl_hook_id);
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::ops::{Deref, Range};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::interface::{Bin, BinPosition, BinStyle};
+
+/// The default amount of rows instantiated beyond each end of the visible range.
+const DEFAULT_OVERSCAN: usize = 2;
+
+type RowFactoryFn = Box<dyn FnMut(usize) -> Arc<Bin> + Send + 'static>;
+
+/// A list of rows where only the rows that are visible are instantiated.
+///
+/// Rows are created by a factory given the index of the row. As the container is scrolled, rows
+/// leaving the visible range are dropped and rows entering it are created. This makes lists with
+/// a large amount of rows feasible.
+///
+/// Each row is positioned with `BinPosition::Parent` at `index * row_height` within the container
+/// and is given the height of a row. When the width of a row isn't defined, it fills the width of
+/// the container.
+///
+/// Scrolling is driven by `BinStyle.scroll_y` of the container, so it can be used along with
+/// `Bin::add_scroll_events` or a `ScrollBar`.
+///
+/// ***Note:** The factory is called while the list is locked, so it must not call methods of the
+/// list itself.*
+pub struct VirtualList {
+    container: Arc<Bin>,
+    spacer: Arc<Bin>,
+    update_pending: AtomicBool,
+    state: Mutex<State>,
+}
+
+struct State {
+    count: usize,
+    row_height: f32,
+    overscan: usize,
+    factory: RowFactoryFn,
+    rows: BTreeMap<usize, Arc<Bin>>,
+}
+
+impl Deref for VirtualList {
+    type Target = Arc<Bin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.container
+    }
+}
+
+impl VirtualList {
+    /// Create a new `VirtualList` within the provided container.
+    ///
+    /// # Notes
+    /// - Panics if the container is not associated to a window.
+    pub fn new<F>(container: Arc<Bin>, count: usize, row_height: f32, factory: F) -> Arc<Self>
+    where
+        F: FnMut(usize) -> Arc<Bin> + Send + 'static,
+    {
+        let window = match container.window() {
+            Some(some) => some,
+            None => panic!("container bin is not associated to a window"),
+        };
+
+        // NOTE: The spacer spans the height of all rows, so that the overflow of the container
+        //       reflects the rows that aren't instantiated.
+        let spacer = window.new_bin();
+        container.add_child(spacer.clone());
+
+        let list = Arc::new(Self {
+            container,
+            spacer,
+            update_pending: AtomicBool::new(false),
+            state: Mutex::new(State {
+                count,
+                row_height,
+                overscan: DEFAULT_OVERSCAN,
+                factory: Box::new(factory),
+                rows: BTreeMap::new(),
+            }),
+        });
+
+        list.update_spacer();
+        let list_wk = Arc::downgrade(&list);
+
+        // NOTE: Children can't be added to the container within its own update hooks, so this
+        //       is deferred to the update of the spacer.
+        list.container.on_update(move |_, _| {
+            if let Some(list) = list_wk.upgrade() {
+                list.update_pending.store(true, atomic::Ordering::SeqCst);
+                list.spacer.trigger_update();
+            }
+        });
+
+        let list_wk = Arc::downgrade(&list);
+
+        list.spacer.on_update(move |_, _| {
+            if let Some(list) = list_wk.upgrade() {
+                if list.update_pending.swap(false, atomic::Ordering::SeqCst) {
+                    list.update();
+                }
+            }
+        });
+
+        list.update();
+        list
+    }
+
+    /// The amount of rows.
+    pub fn count(&self) -> usize {
+        self.state.lock().count
+    }
+
+    /// Set the amount of rows.
+    ///
+    /// ***Note:** Existing rows are retained. Use `refresh` if their content has changed.*
+    pub fn set_count(&self, count: usize) {
+        self.state.lock().count = count;
+        self.update_spacer();
+        self.update();
+    }
+
+    /// The height of each row.
+    pub fn row_height(&self) -> f32 {
+        self.state.lock().row_height
+    }
+
+    /// Set the height of each row.
+    pub fn set_row_height(&self, row_height: f32) {
+        let mut state = self.state.lock();
+        state.row_height = row_height;
+
+        for (index, row) in state.rows.iter() {
+            row.style_update_batch(|style| {
+                style.pos_from_t = Some(*index as f32 * row_height);
+                style.height = Some(row_height);
+            })
+            .expect_valid();
+        }
+
+        drop(state);
+        self.update_spacer();
+        self.update();
+    }
+
+    /// Set the amount of rows instantiated beyond each end of the visible range.
+    ///
+    /// **Default:** `2`
+    pub fn set_overscan(&self, overscan: usize) {
+        self.state.lock().overscan = overscan;
+        self.update();
+    }
+
+    /// The range of rows that are currently instantiated.
+    pub fn instantiated_range(&self) -> Range<usize> {
+        let state = self.state.lock();
+
+        match (state.rows.first_key_value(), state.rows.last_key_value()) {
+            (Some((first, _)), Some((last, _))) => *first..(*last + 1),
+            _ => 0..0,
+        }
+    }
+
+    /// Obtain the `Bin` of a row if it is instantiated.
+    pub fn row(&self, index: usize) -> Option<Arc<Bin>> {
+        self.state.lock().rows.get(&index).cloned()
+    }
+
+    /// Drop all instantiated rows and create them again with the factory.
+    ///
+    /// Useful for when the data the rows represent has changed.
+    pub fn refresh(&self) {
+        self.state.lock().rows.clear();
+        self.update();
+    }
+
+    fn update_spacer(&self) {
+        let height = {
+            let state = self.state.lock();
+            state.count as f32 * state.row_height
+        };
+
+        self.spacer
+            .style_update(BinStyle {
+                position: Some(BinPosition::Parent),
+                pos_from_t: Some(0.0),
+                pos_from_l: Some(0.0),
+                width: Some(0.0),
+                height: Some(height),
+                ..BinStyle::default()
+            })
+            .expect_valid();
+    }
+
+    fn update(&self) {
+        let container_bpu = self.container.post_update();
+        let scroll_y = self
+            .container
+            .style_inspect(|style| style.scroll_y.unwrap_or(0.0));
+        let visible_height = container_bpu.bli[1] - container_bpu.tli[1];
+        let mut state = self.state.lock();
+
+        let range = visible_range(
+            scroll_y,
+            visible_height,
+            state.row_height,
+            state.overscan,
+            state.count,
+        );
+
+        state.rows.retain(|index, _| range.contains(index));
+        let row_height = state.row_height;
+        let mut new_rows = Vec::new();
+
+        for index in range {
+            if state.rows.contains_key(&index) {
+                continue;
+            }
+
+            let row = (state.factory)(index);
+            state.rows.insert(index, row.clone());
+            new_rows.push((index, row));
+        }
+
+        drop(state);
+
+        if new_rows.is_empty() {
+            return;
+        }
+
+        self.container
+            .add_children(new_rows.iter().map(|(_, row)| row.clone()).collect());
+
+        for (index, row) in new_rows {
+            row.style_update_batch(|style| {
+                style.position = Some(BinPosition::Parent);
+                style.pos_from_t = Some(index as f32 * row_height);
+                style.pos_from_b = None;
+                style.height = Some(row_height);
+
+                if style.width.is_none() && style.width_pct.is_none() {
+                    style.pos_from_l = Some(style.pos_from_l.unwrap_or(0.0));
+                    style.pos_from_r = Some(style.pos_from_r.unwrap_or(0.0));
+                }
+            })
+            .expect_valid();
+        }
+    }
+}
+
+// NOTE: Returns the range of rows within the visible height extended by the overscan.
+fn visible_range(
+    scroll_y: f32,
+    visible_height: f32,
+    row_height: f32,
+    overscan: usize,
+    count: usize,
+) -> Range<usize> {
+    if row_height <= 0.0 || visible_height <= 0.0 {
+        return 0..0;
+    }
+
+    let first = (scroll_y / row_height).floor().max(0.0) as usize;
+    let last = ((scroll_y + visible_height) / row_height).ceil() as usize;
+    let end = (last + overscan).min(count);
+    first.saturating_sub(overscan).min(end)..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::visible_range;
+
+    #[test]
+    fn range_at_top() {
+        assert_eq!(visible_range(0.0, 100.0, 20.0, 0, 100), 0..5);
+        assert_eq!(visible_range(0.0, 100.0, 20.0, 2, 100), 0..7);
+    }
+
+    #[test]
+    fn range_scrolled() {
+        assert_eq!(visible_range(50.0, 100.0, 20.0, 0, 100), 2..8);
+        assert_eq!(visible_range(50.0, 100.0, 20.0, 2, 100), 0..10);
+        assert_eq!(visible_range(400.0, 100.0, 20.0, 2, 100), 18..27);
+    }
+
+    #[test]
+    fn range_clamped_to_count() {
+        assert_eq!(visible_range(1900.0, 100.0, 20.0, 2, 100), 93..100);
+        assert_eq!(visible_range(0.0, 100.0, 20.0, 2, 3), 0..3);
+        assert_eq!(visible_range(5000.0, 100.0, 20.0, 2, 100), 100..100);
+    }
+
+    #[test]
+    fn range_empty() {
+        assert_eq!(visible_range(0.0, 100.0, 20.0, 2, 0), 0..0);
+        assert_eq!(visible_range(0.0, 0.0, 20.0, 2, 100), 0..0);
+        assert_eq!(visible_range(0.0, 100.0, 0.0, 2, 100), 0..0);
+    }
+}
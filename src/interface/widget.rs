@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use crate::interface::{Bin, BinPosition, BinStyle, Theme};
+use crate::window::Window;
+
+/// Create the root `Bin` of a widget and apply the provided style to it.
+///
+/// The `Bin` is added to the parent and positioned relative to it, or to the window if no parent
+/// is provided.
+///
+/// # Notes
+/// - Panics if parent bin is not associated to the window provided.
+pub(crate) fn new_widget_bin(
+    window: &Arc<Window>,
+    parent: Option<Arc<Bin>>,
+    style: BinStyle,
+) -> Arc<Bin> {
+    if let Some(parent) = parent.as_ref() {
+        match parent.window() {
+            Some(parent_window) => {
+                if *window != parent_window {
+                    panic!("parent bin is not associated to the window provided");
+                }
+            },
+            None => {
+                panic!("parent bin is not associated to a window");
+            },
+        }
+    }
+
+    let bin = window.new_bin();
+
+    let position = match parent {
+        Some(parent) => {
+            parent.add_child(bin.clone());
+            BinPosition::Parent
+        },
+        None => BinPosition::Window,
+    };
+
+    bin.style_update(BinStyle {
+        position: Some(position),
+        ..style
+    })
+    .expect_valid();
+
+    bin
+}
+
+/// Style a `Bin` of a widget from the current `Theme`.
+///
+/// The method is provided the current style of the `Bin`, so that the themed fields are applied
+/// over it and changes to positioning are retained when the theme changes.
+pub(crate) fn set_widget_theme_style<F: FnMut(&Theme, BinStyle) -> BinStyle + Send + 'static>(
+    bin: &Arc<Bin>,
+    mut method: F,
+) {
+    let bin_wk = Arc::downgrade(bin);
+
+    bin.set_theme_style(move |theme| {
+        match bin_wk.upgrade() {
+            Some(bin) => method(theme, bin.style_copy()),
+            None => BinStyle::default(),
+        }
+    })
+    .expect_valid();
+}
+
+/// Set the radius of all corners of the provided style.
+pub(crate) fn with_border_radius(style: BinStyle, radius: f32) -> BinStyle {
+    BinStyle {
+        border_radius_tl: Some(radius),
+        border_radius_tr: Some(radius),
+        border_radius_bl: Some(radius),
+        border_radius_br: Some(radius),
+        ..style
+    }
+}
@@ -39,6 +39,7 @@ enum IntvlEvent {
     Pause(IntvlHookID),
     Start(IntvlHookID),
     Remove(IntvlHookID),
+    SetEvery(IntvlHookID, Duration),
 }
 
 /// The main struct for the interval system.
@@ -77,6 +78,12 @@ impl Interval {
                         },
                         IntvlEvent::Start(id) => {
                             if let Some(hook) = hooks.get_mut(&id) {
+                                // NOTE: The delay begins when the start event is received rather
+                                //       than the next iteration, so that delays stay accurate.
+                                if hook.paused && hook.delay.is_some() {
+                                    hook.delay_start = Some(Instant::now());
+                                }
+
                                 hook.paused = false;
                             }
                         },
@@ -87,6 +94,11 @@ impl Interval {
                                 hook.delay_start = None;
                             }
                         },
+                        IntvlEvent::SetEvery(id, every) => {
+                            if let Some(hook) = hooks.get_mut(&id) {
+                                hook.every = every;
+                            }
+                        },
                     }
                 }
 
@@ -154,14 +166,14 @@ impl Interval {
     /// Takes a `Fn(last_call: Option<Duration>) -> IntvlHookCtrl`.
     /// - `last_call`: Duration since the last method was called.
     /// - `delay`: is the duration that has to elapsed after `Interval::start(...)` before
-    /// the hook method is called.
+    ///   the hook method is called.
     /// - `IntvlHookCtrl`: controls how the hook is handled after the method is called.
     ///
     /// # Notes
     /// - Hooks are paused to begin with. They must be started with `Interval::start(...)`.
     /// - `last_call` will only be `Some` if the method is called continuously. Returning
-    /// `InputHookCtrl::Pause` or using `Interval::pause(...)` will cause the next call to
-    /// be `None`.
+    ///   `InputHookCtrl::Pause` or using `Interval::pause(...)` will cause the next call to
+    ///   be `None`.
     pub fn do_every<F: FnMut(Option<Duration>) -> IntvlHookCtrl + Send + 'static>(
         &self,
         every: Duration,
@@ -201,6 +213,16 @@ impl Interval {
     pub fn remove(&self, id: IntvlHookID) {
         self.event_send.send(IntvlEvent::Remove(id)).unwrap();
     }
+
+    /// Change the interval a hook is called at.
+    ///
+    /// # Notes
+    /// - If hook doesn't exist this does nothing.
+    pub fn set_every(&self, id: IntvlHookID, every: Duration) {
+        self.event_send
+            .send(IntvlEvent::SetEvery(id, every))
+            .unwrap();
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -12,6 +12,7 @@ pub mod window;
 
 use std::collections::BTreeMap;
 use std::num::NonZeroUsize;
+use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::Arc;
 use std::thread::available_parallelism;
@@ -22,22 +23,37 @@ use vulkano::device::{
     self, Device, DeviceCreateInfo, DeviceExtensions, Features as DeviceFeatures, QueueCreateInfo,
     QueueFlags,
 };
+use vulkano::instance::debug::{
+    DebugUtilsMessageSeverity, DebugUtilsMessageType, DebugUtilsMessenger,
+    DebugUtilsMessengerCallback, DebugUtilsMessengerCreateInfo,
+};
 use vulkano::instance::{
     Instance, InstanceCreateFlags, InstanceCreateInfo, InstanceExtensions, Version,
 };
-use vulkano::VulkanLibrary;
+use vulkano::{DeviceSize, VulkanLibrary};
 
 use crate::image_cache::ImageCache;
-use crate::input::Input;
+use crate::input::{Input, KeyRepeat};
 use crate::interval::Interval;
-use crate::render::{VSync, MSAA};
+use crate::render::{MemoryHeapStats, RenderMemory, RendererColorSpace, VSync, MSAA};
 use crate::window::WindowManager;
 
+const VALIDATION_LAYER: &str = "VK_LAYER_KHRONOS_validation";
+
+/// A method called with messages from the validation layers.
+///
+/// See `BasaltOptions::validation_debug_callback`.
+pub type ValidationCallback =
+    Arc<dyn Fn(DebugUtilsMessageSeverity, DebugUtilsMessageType, &str) + Send + Sync + 'static>;
+
 /// Options for Basalt's creation and operation.
 pub struct BasaltOptions {
     // Instance Options
+    application: Option<(String, Version)>,
     require_instance_extensions: InstanceExtensions,
     prefer_instance_extensions: InstanceExtensions,
+    validation: bool,
+    validation_callback: Option<ValidationCallback>,
     // Physical Device Selection
     portability_subset: bool,
     prefer_integrated_gpu: bool,
@@ -50,11 +66,22 @@ pub struct BasaltOptions {
     winit_force_x11: bool,
     window_ignore_dpi: bool,
     window_default_scale: f32,
+    window_default_scroll_speed: f32,
     // Render Options
     render_default_msaa: MSAA,
     render_default_vsync: VSync,
+    render_default_image_count: u32,
     render_default_consv_draw: bool,
+    render_default_color_space: RendererColorSpace,
     render_default_worker_threads: NonZeroUsize,
+    render_default_fps_limit: Option<u32>,
+    render_atlas_initial_size: u32,
+    render_atlas_large_threshold: u32,
+    render_dedicated_image_threshold: u32,
+    render_memory_block_size: Option<DeviceSize>,
+    render_memory_always_allocate: bool,
+    // Input Options
+    input_key_repeat: Option<KeyRepeat>,
     // Interface Options
     binary_fonts: Vec<Arc<dyn AsRef<[u8]> + Sync + Send>>,
 }
@@ -62,6 +89,7 @@ pub struct BasaltOptions {
 impl Default for BasaltOptions {
     fn default() -> Self {
         Self {
+            application: None,
             require_instance_extensions: InstanceExtensions {
                 khr_surface: true,
                 ..InstanceExtensions::empty()
@@ -80,11 +108,14 @@ impl Default for BasaltOptions {
                 ext_swapchain_colorspace: true,
                 ..InstanceExtensions::empty()
             },
+            validation: false,
+            validation_callback: None,
             portability_subset: false,
             prefer_integrated_gpu: true,
             require_device_extensions: DeviceExtensions::empty(),
             prefer_device_extensions: DeviceExtensions {
                 ext_swapchain_maintenance1: true,
+                khr_incremental_present: true,
                 ..DeviceExtensions::empty()
             },
             require_device_features: DeviceFeatures {
@@ -98,9 +129,12 @@ impl Default for BasaltOptions {
             winit_force_x11: false,
             window_ignore_dpi: false,
             window_default_scale: 1.0,
+            window_default_scroll_speed: 1.0,
             render_default_msaa: MSAA::X1,
             render_default_vsync: VSync::Enable,
+            render_default_image_count: 2,
             render_default_consv_draw: false,
+            render_default_color_space: RendererColorSpace::Srgb,
             render_default_worker_threads: NonZeroUsize::new(
                 (available_parallelism()
                     .unwrap_or(NonZeroUsize::new(4).unwrap())
@@ -109,12 +143,37 @@ impl Default for BasaltOptions {
                     .ceil() as usize,
             )
             .unwrap(),
+            render_default_fps_limit: None,
+            render_atlas_initial_size: 4096,
+            render_atlas_large_threshold: 512,
+            render_dedicated_image_threshold: 512,
+            render_memory_block_size: None,
+            render_memory_always_allocate: false,
+            input_key_repeat: None,
             binary_fonts: Vec::new(),
         }
     }
 }
 
 impl BasaltOptions {
+    /// Set the application name and version provided to the Vulkan instance.
+    ///
+    /// Drivers and tools, such as capture tools or driver profiles, may use these to identify the
+    /// application.
+    ///
+    /// **Default:** `None`
+    pub fn application<N: Into<String>>(mut self, name: N, version: (u16, u16, u16)) -> Self {
+        self.application = Some((
+            name.into(),
+            Version {
+                major: version.0 as u32,
+                minor: version.1 as u32,
+                patch: version.2 as u32,
+            },
+        ));
+        self
+    }
+
     /// Add required instance extensions
     ///
     /// ***Note:** This will cause an error if an extension is not supported. If this is not desired
@@ -130,6 +189,29 @@ impl BasaltOptions {
         self
     }
 
+    /// Enable the `VK_LAYER_KHRONOS_validation` layer.
+    ///
+    /// Messages from the layer are printed unless a callback is provided with
+    /// `validation_debug_callback`.
+    ///
+    /// **Default:** `false`
+    ///
+    /// ***Note:** If the layer isn't installed, a warning is printed and initialization continues
+    /// without it.*
+    pub fn enable_validation(mut self, enable: bool) -> Self {
+        self.validation = enable;
+        self
+    }
+
+    /// Set the method called with messages from the validation layers.
+    ///
+    /// ***Note:** This has no effect unless validation is enabled with `enable_validation`. The
+    /// method must not make any calls to the Vulkan API.*
+    pub fn validation_debug_callback(mut self, callback: ValidationCallback) -> Self {
+        self.validation_callback = Some(callback);
+        self
+    }
+
     /// Allow a portability subset device to be selected when enumerating `PhysicalDevice`'s.
     pub fn allow_portability_subset(mut self) -> Self {
         self.portability_subset = true;
@@ -202,6 +284,16 @@ impl BasaltOptions {
         self
     }
 
+    /// Set the default scroll speed multiplier used when a window is created.
+    ///
+    /// **Default:** `1.0`
+    ///
+    /// *See `Window::set_scroll_speed` for more information.*
+    pub fn window_default_scroll_speed(mut self, speed: f32) -> Self {
+        self.window_default_scroll_speed = speed;
+        self
+    }
+
     /// Set the default `MSAA` used for rendering the interface when a `Renderer` is created.
     ///
     /// **Default:** `MSAA::X1`
@@ -218,6 +310,17 @@ impl BasaltOptions {
         self
     }
 
+    /// Set the default preferred minimum swapchain image count used for rendering when a
+    /// `Renderer` is created. A value of `2` is double buffering and `3` is triple buffering.
+    ///
+    /// **Default:** `2`
+    ///
+    /// ***Note:** The count is clamped to what the surface supports.*
+    pub fn render_default_image_count(mut self, count: u32) -> Self {
+        self.render_default_image_count = count;
+        self
+    }
+
     /// Set the default value used when creating a `Renderer` for conservative draw feature.
     ///
     /// **Default:** `false`
@@ -229,6 +332,16 @@ impl BasaltOptions {
         self
     }
 
+    /// Set the default `RendererColorSpace` used when a `Renderer` is created.
+    ///
+    /// **Default:** `RendererColorSpace::Srgb`
+    ///
+    /// ***Note:** If the color space isn't supported, `RendererColorSpace::Srgb` is used instead.*
+    pub fn render_default_color_space(mut self, color_space: RendererColorSpace) -> Self {
+        self.render_default_color_space = color_space;
+        self
+    }
+
     /// Set the default count of worker threads used for a `Renderer`.
     ///
     /// **Default:** 1/3 of available threads (rounded up)
@@ -237,6 +350,81 @@ impl BasaltOptions {
         self
     }
 
+    /// Set the default frame rate limit used when a `Renderer` is created.
+    ///
+    /// **Default:** `None`
+    ///
+    /// ***Note:** A limit of zero is treated as `None`.*
+    pub fn render_default_fps_limit(mut self, limit: Option<u32>) -> Self {
+        self.render_default_fps_limit = limit.filter(|limit| *limit != 0);
+        self
+    }
+
+    /// Set the initial size of the atlases used to store images for the interface.
+    ///
+    /// **Default:** `4096`
+    ///
+    /// ***Note:** This is clamped to the device's maximum image dimension and to be at least large
+    /// enough to contain an image at the dedicated image threshold.*
+    pub fn render_atlas_initial_size(mut self, size: u32) -> Self {
+        self.render_atlas_initial_size = size;
+        self
+    }
+
+    /// Set the size at which allocations within an atlas are considered large.
+    ///
+    /// **Default:** `512`
+    pub fn render_atlas_large_threshold(mut self, threshold: u32) -> Self {
+        self.render_atlas_large_threshold = threshold;
+        self
+    }
+
+    /// Set the size at which images will use a dedicated allocation instead of an atlas.
+    ///
+    /// **Default:** `512`
+    ///
+    /// ***Note:** This applies to either the width or height.*
+    pub fn render_dedicated_image_threshold(mut self, threshold: u32) -> Self {
+        self.render_dedicated_image_threshold = threshold;
+        self
+    }
+
+    /// Set the size of the memory blocks that resources used by the renderers' workers are
+    /// suballocated from.
+    ///
+    /// Larger blocks reduce the amount of allocations made with the device, which is limited by
+    /// `max_memory_allocation_count`. Resources larger than half of the block size are given their
+    /// own allocation.
+    ///
+    /// **Default:** `None` (256 MiB for heaps of at least 1 GiB, otherwise 64 MiB)
+    pub fn render_memory_block_size(mut self, block_size: Option<DeviceSize>) -> Self {
+        self.render_memory_block_size = block_size;
+        self
+    }
+
+    /// Give every long-lived resource used by the renderers' workers its own allocation instead
+    /// of suballocating them from larger blocks.
+    ///
+    /// **Default:** `false`
+    ///
+    /// ***Note:** This is intended for debugging. Many images may exceed the device's
+    /// `max_memory_allocation_count`.*
+    pub fn render_memory_always_allocate(mut self, always_allocate: bool) -> Self {
+        self.render_memory_always_allocate = always_allocate;
+        self
+    }
+
+    /// Repeat characters with the provided delay and interval instead of using the platform's
+    /// key repeat.
+    ///
+    /// **Default:** `None`
+    ///
+    /// *See `Input::set_key_repeat` for more information.*
+    pub fn input_key_repeat(mut self, key_repeat: Option<KeyRepeat>) -> Self {
+        self.input_key_repeat = key_repeat;
+        self
+    }
+
     /// Add a font from a binary source that can be used by the interface.
     ///
     /// This is intended to be used with `include_bytes!(...)`.
@@ -253,10 +441,17 @@ pub struct NonExhaustive(pub(crate) ());
 struct BasaltConfig {
     window_ignore_dpi: bool,
     window_default_scale: f32,
+    window_default_scroll_speed: f32,
     render_default_msaa: MSAA,
     render_default_vsync: VSync,
+    render_default_image_count: u32,
     render_default_consv_draw: bool,
+    render_default_color_space: RendererColorSpace,
     render_default_worker_threads: NonZeroUsize,
+    render_default_fps_limit: Option<u32>,
+    render_atlas_initial_size: u32,
+    render_atlas_large_threshold: u32,
+    render_dedicated_image_threshold: u32,
 }
 
 /// The main object of this crate.
@@ -280,6 +475,8 @@ pub struct Basalt {
     window_manager: Arc<WindowManager>,
     wants_exit: AtomicBool,
     config: BasaltConfig,
+    render_memory: RenderMemory,
+    _debug_messenger: Option<DebugUtilsMessenger>,
 }
 
 impl Basalt {
@@ -291,6 +488,9 @@ impl Basalt {
         mut result_fn: F,
     ) {
         let BasaltOptions {
+            application,
+            validation,
+            validation_callback,
             portability_subset,
             prefer_integrated_gpu,
             require_instance_extensions,
@@ -302,10 +502,20 @@ impl Basalt {
             winit_force_x11,
             window_ignore_dpi,
             window_default_scale,
+            window_default_scroll_speed,
             render_default_msaa,
             render_default_vsync,
+            render_default_image_count,
             render_default_consv_draw,
+            render_default_color_space,
             render_default_worker_threads,
+            render_default_fps_limit,
+            render_atlas_initial_size,
+            render_atlas_large_threshold,
+            render_dedicated_image_threshold,
+            render_memory_block_size,
+            render_memory_always_allocate,
+            input_key_repeat,
             binary_fonts,
         } = options;
 
@@ -318,11 +528,75 @@ impl Basalt {
             Err(e) => return result_fn(Err(format!("Failed to load vulkan library: {}", e))),
         };
 
-        let instance_extensions = vulkan_library
-            .supported_extensions()
+        let mut enabled_layers = Vec::new();
+
+        if validation {
+            match vulkan_library.layer_properties() {
+                Ok(mut layers) => {
+                    if layers.any(|layer| layer.name() == VALIDATION_LAYER) {
+                        enabled_layers.push(String::from(VALIDATION_LAYER));
+                    } else {
+                        println!(
+                            "[Basalt]: Warning validation was requested, but {} isn't installed.",
+                            VALIDATION_LAYER
+                        );
+                    }
+                },
+                Err(e) => {
+                    println!(
+                        "[Basalt]: Warning validation was requested, but layers couldn't be \
+                         enumerated: {}",
+                        e
+                    );
+                },
+            }
+        }
+
+        let supported_instance_extensions = match vulkan_library
+            .supported_extensions_with_layers(enabled_layers.iter().map(String::as_str))
+        {
+            Ok(ok) => ok,
+            Err(e) => {
+                return result_fn(Err(format!(
+                    "Failed to enumerate instance extensions: {}",
+                    e
+                )))
+            },
+        };
+
+        let mut instance_extensions = supported_instance_extensions
             .intersection(&prefer_instance_extensions)
             .union(&require_instance_extensions);
 
+        let mut debug_messenger_info_op = None;
+
+        if !enabled_layers.is_empty() && supported_instance_extensions.ext_debug_utils {
+            instance_extensions.ext_debug_utils = true;
+
+            let callback = AssertUnwindSafe(validation_callback);
+
+            // SAFETY: The callback provided by the user is required to not make calls to the
+            //         Vulkan API as noted by `BasaltOptions::validation_debug_callback`.
+            let user_callback = unsafe {
+                DebugUtilsMessengerCallback::new(move |severity, ty, data| {
+                    match callback.as_ref() {
+                        Some(callback) => callback(severity, ty, data.message),
+                        None => println!("[Basalt][Validation]: {:?}: {}", severity, data.message),
+                    }
+                })
+            };
+
+            debug_messenger_info_op = Some(DebugUtilsMessengerCreateInfo {
+                message_severity: DebugUtilsMessageSeverity::ERROR
+                    | DebugUtilsMessageSeverity::WARNING
+                    | DebugUtilsMessageSeverity::INFO,
+                message_type: DebugUtilsMessageType::GENERAL
+                    | DebugUtilsMessageType::VALIDATION
+                    | DebugUtilsMessageType::PERFORMANCE,
+                ..DebugUtilsMessengerCreateInfo::user_callback(user_callback)
+            });
+        }
+
         let mut instance_create_flags = InstanceCreateFlags::empty();
 
         if portability_subset {
@@ -333,7 +607,11 @@ impl Basalt {
             vulkan_library,
             InstanceCreateInfo {
                 flags: instance_create_flags,
+                application_name: application.as_ref().map(|(name, _)| name.clone()),
+                application_version: application.map(|(_, version)| version).unwrap_or_default(),
+                enabled_layers,
                 enabled_extensions: instance_extensions,
+                debug_utils_messengers: debug_messenger_info_op.clone().into_iter().collect(),
                 engine_name: Some(String::from("Basalt")),
                 engine_version: Version {
                     major: 0,
@@ -353,6 +631,19 @@ impl Basalt {
             )));
         }
 
+        let mut debug_messenger = debug_messenger_info_op.and_then(|debug_messenger_info| {
+            match DebugUtilsMessenger::new(instance.clone(), debug_messenger_info) {
+                Ok(ok) => Some(ok),
+                Err(e) => {
+                    println!(
+                        "[Basalt]: Warning failed to create validation debug messenger: {}",
+                        e
+                    );
+                    None
+                },
+            }
+        });
+
         WindowManager::run(move |window_manager| {
             let mut physical_devices = match instance.enumerate_physical_devices() {
                 Ok(ok) => ok.collect::<Vec<_>>(),
@@ -606,7 +897,17 @@ impl Basalt {
 
             let interface = Interface::new(binary_fonts.clone());
             let interval = Arc::new(Interval::new());
-            let input = Input::new(interface.clone(), interval.clone());
+            let input = Input::new(interface.clone(), interval.clone(), input_key_repeat);
+
+            let render_memory = RenderMemory::new(
+                device.clone(),
+                render_memory_block_size,
+                render_memory_always_allocate,
+                [&graphics_queue, &transfer_queue]
+                    .into_iter()
+                    .chain(secondary_transfer_queue.as_ref())
+                    .map(|queue| queue.queue_family_index()),
+            );
 
             let basalt = Arc::new(Basalt {
                 device,
@@ -626,11 +927,20 @@ impl Basalt {
                 config: BasaltConfig {
                     window_ignore_dpi,
                     window_default_scale,
+                    window_default_scroll_speed,
                     render_default_msaa,
                     render_default_vsync,
+                    render_default_image_count,
                     render_default_consv_draw,
+                    render_default_color_space,
                     render_default_worker_threads,
+                    render_default_fps_limit,
+                    render_atlas_initial_size,
+                    render_atlas_large_threshold,
+                    render_dedicated_image_threshold,
                 },
+                render_memory,
+                _debug_messenger: debug_messenger.take(),
             });
 
             basalt.interface.associate_basalt(basalt.clone());
@@ -684,6 +994,18 @@ impl Basalt {
         &self.window_manager
     }
 
+    /// Retrieve the memory statistics of each heap.
+    ///
+    /// ***Note:** Only memory used by the renderers' workers for images and vertex data is
+    /// accounted for.*
+    pub fn memory_stats(&self) -> Vec<MemoryHeapStats> {
+        self.render_memory.stats()
+    }
+
+    pub(crate) fn render_memory(&self) -> &RenderMemory {
+        &self.render_memory
+    }
+
     /// Obtain a copy of `Arc<Instance>`
     pub fn instance(&self) -> Arc<Instance> {
         self.instance.clone()
@@ -738,7 +1060,7 @@ impl Basalt {
     ///
     /// # Notes:
     /// - This queue may be the same as the graphics queue in cases where the device only
-    /// has a single queue present.
+    ///   has a single queue present.
     pub fn compute_queue(&self) -> Arc<device::Queue> {
         self.compute_queue.clone()
     }
@@ -747,7 +1069,7 @@ impl Basalt {
     ///
     /// # Notes:
     /// - This queue may be the same as the graphics queue in cases where the device only
-    /// has a single queue present.
+    ///   has a single queue present.
     pub fn compute_queue_ref(&self) -> &Arc<device::Queue> {
         &self.compute_queue
     }
@@ -766,8 +1088,8 @@ impl Basalt {
     ///
     /// # Notes:
     /// - This queue may be the same as the compute queue in cases where the device only
-    /// has two queues present. In cases where there is only one queue the graphics, compute,
-    /// and transfer queues will all be the same queue.
+    ///   has two queues present. In cases where there is only one queue the graphics, compute,
+    ///   and transfer queues will all be the same queue.
     pub fn transfer_queue(&self) -> Arc<device::Queue> {
         self.transfer_queue.clone()
     }
@@ -776,8 +1098,8 @@ impl Basalt {
     ///
     /// # Notes:
     /// - This queue may be the same as the compute queue in cases where the device only
-    /// has two queues present. In cases where there is only one queue the graphics, compute,
-    /// and transfer queues will all be the same queue.
+    ///   has two queues present. In cases where there is only one queue the graphics, compute,
+    ///   and transfer queues will all be the same queue.
     pub fn transfer_queue_ref(&self) -> &Arc<device::Queue> {
         &self.transfer_queue
     }
@@ -1,3 +1,4 @@
+use std::ops::Range;
 use std::sync::Arc;
 
 use vulkano::buffer::Subbuffer;
@@ -11,6 +12,7 @@ use vulkano::descriptor_set::persistent::PersistentDescriptorSet;
 use vulkano::descriptor_set::WriteDescriptorSet;
 use vulkano::device::Device;
 use vulkano::format::{ClearColorValue, ClearValue, Format, NumericFormat};
+use vulkano::image::sampler::{Sampler, SamplerAddressMode, SamplerCreateInfo};
 use vulkano::image::view::ImageView;
 use vulkano::image::{Image, ImageCreateInfo, ImageType, ImageUsage, SampleCount};
 use vulkano::memory::allocator::{
@@ -18,13 +20,13 @@ use vulkano::memory::allocator::{
 };
 use vulkano::memory::MemoryPropertyFlags;
 use vulkano::pipeline::graphics::color_blend::{
-    AttachmentBlend, ColorBlendAttachmentState, ColorBlendState,
+    AttachmentBlend, BlendFactor, BlendOp, ColorBlendAttachmentState, ColorBlendState,
 };
 use vulkano::pipeline::graphics::input_assembly::InputAssemblyState;
 use vulkano::pipeline::graphics::multisample::MultisampleState;
 use vulkano::pipeline::graphics::rasterization::RasterizationState;
 use vulkano::pipeline::graphics::vertex_input::{Vertex, VertexDefinition, VertexInputState};
-use vulkano::pipeline::graphics::viewport::{Viewport, ViewportState};
+use vulkano::pipeline::graphics::viewport::{Scissor, Viewport, ViewportState};
 use vulkano::pipeline::graphics::GraphicsPipelineCreateInfo;
 use vulkano::pipeline::layout::PipelineDescriptorSetLayoutCreateInfo;
 use vulkano::pipeline::{
@@ -32,11 +34,21 @@ use vulkano::pipeline::{
     PipelineShaderStageCreateInfo,
 };
 use vulkano::render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass};
+use vulkano::DeviceSize;
 
-use crate::interface::ItfVertInfo;
-use crate::render::{shaders, UserRenderer, MSAA};
+use crate::interface::{Color, ItfVertInfo};
+use crate::render::{shaders, PostProcess, UserRenderer, MSAA};
 
-pub enum DrawState {
+/// The format of the layers isolated bins are drawn onto.
+const LAYER_FORMAT: Format = Format::R16G16B16A16_SFLOAT;
+
+pub struct DrawState {
+    kind: DrawKind,
+    layers: LayerState,
+    post_process: Option<PostProcessState>,
+}
+
+enum DrawKind {
     InterfaceOnly(InterfaceOnly),
     User(User),
 }
@@ -44,6 +56,7 @@ pub enum DrawState {
 #[derive(Default)]
 pub struct InterfaceOnly {
     msaa: Option<MSAA>,
+    clear_color: Color,
     render_pass: Option<Arc<RenderPass>>,
     pipeline: Option<Arc<GraphicsPipeline>>,
     framebuffers: Option<Vec<Arc<Framebuffer>>>,
@@ -193,22 +206,34 @@ impl InterfaceOnly {
     fn draw(
         &mut self,
         buffer: Subbuffer<[ItfVertInfo]>,
+        vertex_count: u32,
         desc_set: Arc<PersistentDescriptorSet>,
         swapchain_image_index: usize,
         viewport: Viewport,
+        damage_rect: Option<[u32; 4]>,
         cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
     ) {
-        let buffer_len = buffer.len();
+        let framebuffer = self.framebuffers.as_ref().unwrap()[swapchain_image_index].clone();
+
+        // NOTE: The clear load operation only affects the render area, so the rest of the
+        //       previous contents are retained.
+        let (render_area_offset, render_area_extent) = match damage_rect {
+            Some([x, y, width, height]) => ([x, y], [width, height]),
+            None => ([0; 2], framebuffer.extent()),
+        };
+
         let clear_values = match self.msaa.unwrap() {
             MSAA::X1 => {
                 vec![Some(clear_value_for_format(
                     self.framebuffers.as_ref().unwrap()[0].attachments()[0].format(),
+                    self.clear_color,
                 ))]
             },
             _ => {
                 vec![
                     Some(clear_value_for_format(
                         self.framebuffers.as_ref().unwrap()[0].attachments()[0].format(),
+                        self.clear_color,
                     )),
                     None,
                 ]
@@ -218,16 +243,26 @@ impl InterfaceOnly {
         cmd_builder
             .begin_render_pass(
                 RenderPassBeginInfo {
+                    render_area_offset,
+                    render_area_extent,
                     clear_values,
-                    ..RenderPassBeginInfo::framebuffer(
-                        self.framebuffers.as_ref().unwrap()[swapchain_image_index].clone(),
-                    )
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
                 },
                 SubpassBeginInfo::default(),
             )
             .unwrap()
             .set_viewport(0, [viewport].into_iter().collect())
             .unwrap()
+            .set_scissor(
+                0,
+                [Scissor {
+                    offset: render_area_offset,
+                    extent: render_area_extent,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
             .bind_pipeline_graphics(self.pipeline.clone().unwrap())
             .unwrap()
             .bind_descriptor_sets(
@@ -239,7 +274,7 @@ impl InterfaceOnly {
             .unwrap()
             .bind_vertex_buffers(0, buffer)
             .unwrap()
-            .draw(buffer_len as u32, 1, 0, 0)
+            .draw(vertex_count, 1, 0, 0)
             .unwrap()
             .end_render_pass(SubpassEndInfo::default())
             .unwrap();
@@ -592,13 +627,13 @@ impl User {
     fn draw(
         &mut self,
         buffer: Subbuffer<[ItfVertInfo]>,
+        vertex_count: u32,
         desc_set: Arc<PersistentDescriptorSet>,
         swapchain_image_index: usize,
         viewport: Viewport,
         cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
     ) {
         self.user_renderer.draw(cmd_builder);
-        let buffer_len = buffer.len();
 
         let clear_values = match self.msaa.unwrap() {
             MSAA::X1 => {
@@ -606,6 +641,7 @@ impl User {
                     None,
                     Some(clear_value_for_format(
                         self.framebuffers.as_ref().unwrap()[0].attachments()[1].format(),
+                        Color::default(),
                     )),
                     None,
                 ]
@@ -615,6 +651,7 @@ impl User {
                     None,
                     Some(clear_value_for_format(
                         self.framebuffers.as_ref().unwrap()[0].attachments()[1].format(),
+                        Color::default(),
                     )),
                     None,
                     None,
@@ -635,6 +672,8 @@ impl User {
             .unwrap()
             .set_viewport(0, [viewport.clone()].into_iter().collect())
             .unwrap()
+            .set_scissor(0, [Scissor::default()].into_iter().collect())
+            .unwrap()
             .bind_pipeline_graphics(self.pipeline_ui.clone().unwrap())
             .unwrap()
             .bind_descriptor_sets(
@@ -646,7 +685,7 @@ impl User {
             .unwrap()
             .bind_vertex_buffers(0, buffer)
             .unwrap()
-            .draw(buffer_len as u32, 1, 0, 0)
+            .draw(vertex_count, 1, 0, 0)
             .unwrap()
             .next_subpass(SubpassEndInfo::default(), SubpassBeginInfo::default())
             .unwrap()
@@ -722,11 +761,21 @@ fn create_ui_pipeline(
             color_blend_state: Some(ColorBlendState::with_attachment_states(
                 subpass.num_color_attachments(),
                 ColorBlendAttachmentState {
-                    blend: Some(AttachmentBlend::alpha()),
+                    // NOTE: The output of ui.fs is premultiplied.
+                    blend: Some(AttachmentBlend {
+                        src_color_blend_factor: BlendFactor::One,
+                        dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                        color_blend_op: BlendOp::Add,
+                        src_alpha_blend_factor: BlendFactor::One,
+                        dst_alpha_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                        alpha_blend_op: BlendOp::Add,
+                    }),
                     ..ColorBlendAttachmentState::default()
                 },
             )),
-            dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+            dynamic_state: [DynamicState::Viewport, DynamicState::Scissor]
+                .into_iter()
+                .collect(),
             subpass: Some(subpass.into()),
             ..GraphicsPipelineCreateInfo::layout(layout)
         },
@@ -734,17 +783,193 @@ fn create_ui_pipeline(
     .unwrap()
 }
 
+/// Offscreen layers that the subtrees of isolated bins are drawn onto.
+///
+/// The layers are sampled when drawing the interface, so they can't use the same descriptor set
+/// as is used when drawing onto them.
+struct LayerState {
+    render_pass: Arc<RenderPass>,
+    pipeline: Arc<GraphicsPipeline>,
+    extent: [u32; 2],
+    framebuffers: Vec<Arc<Framebuffer>>,
+    changed: bool,
+}
+
+impl LayerState {
+    fn new(device: Arc<Device>, image_capacity: u32) -> Self {
+        let render_pass = vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: LAYER_FORMAT,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                },
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {},
+            }
+        )
+        .unwrap();
+
+        let pipeline = Self::create_pipeline(device, render_pass.clone(), image_capacity);
+
+        Self {
+            render_pass,
+            pipeline,
+            extent: [0; 2],
+            framebuffers: Vec::new(),
+            changed: false,
+        }
+    }
+
+    fn create_pipeline(
+        device: Arc<Device>,
+        render_pass: Arc<RenderPass>,
+        image_capacity: u32,
+    ) -> Arc<GraphicsPipeline> {
+        create_ui_pipeline(
+            device,
+            image_capacity,
+            MSAA::X1,
+            Subpass::from(render_pass, 0).unwrap(),
+        )
+    }
+
+    fn create_framebuffer(&self, mem_alloc: &Arc<StandardMemoryAllocator>) -> Arc<Framebuffer> {
+        let view = ImageView::new_default(
+            Image::new(
+                mem_alloc.clone(),
+                ImageCreateInfo {
+                    image_type: ImageType::Dim2d,
+                    format: LAYER_FORMAT,
+                    extent: [self.extent[0], self.extent[1], 1],
+                    usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+                    ..ImageCreateInfo::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter {
+                        preferred_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+                        not_preferred_flags: MemoryPropertyFlags::HOST_CACHED,
+                        ..MemoryTypeFilter::empty()
+                    },
+                    allocate_preference: MemoryAllocatePreference::AlwaysAllocate,
+                    ..AllocationCreateInfo::default()
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        Framebuffer::new(
+            self.render_pass.clone(),
+            FramebufferCreateInfo {
+                attachments: vec![view],
+                ..FramebufferCreateInfo::default()
+            },
+        )
+        .unwrap()
+    }
+
+    fn update_extent(&mut self, mem_alloc: &Arc<StandardMemoryAllocator>, extent: [u32; 2]) {
+        if self.extent == extent {
+            return;
+        }
+
+        self.extent = extent;
+        let count = self.framebuffers.len();
+        self.framebuffers.clear();
+
+        for _ in 0..count {
+            self.framebuffers.push(self.create_framebuffer(mem_alloc));
+        }
+
+        self.changed = true;
+    }
+
+    fn update_count(&mut self, mem_alloc: &Arc<StandardMemoryAllocator>, count: usize) {
+        if self.framebuffers.len() == count {
+            return;
+        }
+
+        if self.framebuffers.len() > count {
+            self.framebuffers.truncate(count);
+        } else {
+            while self.framebuffers.len() < count {
+                self.framebuffers.push(self.create_framebuffer(mem_alloc));
+            }
+        }
+
+        self.changed = true;
+    }
+
+    fn draw(
+        &mut self,
+        buffer: Subbuffer<[ItfVertInfo]>,
+        ranges: &[Range<DeviceSize>],
+        desc_set: Arc<PersistentDescriptorSet>,
+        viewport: Viewport,
+        cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    ) {
+        for (framebuffer, range) in self.framebuffers.iter().zip(ranges.iter()) {
+            cmd_builder
+                .begin_render_pass(
+                    RenderPassBeginInfo {
+                        clear_values: vec![Some(ClearValue::Float([0.0; 4]))],
+                        ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
+                    },
+                    SubpassBeginInfo::default(),
+                )
+                .unwrap();
+
+            if !range.is_empty() {
+                cmd_builder
+                    .set_viewport(0, [viewport.clone()].into_iter().collect())
+                    .unwrap()
+                    .set_scissor(0, [Scissor::default()].into_iter().collect())
+                    .unwrap()
+                    .bind_pipeline_graphics(self.pipeline.clone())
+                    .unwrap()
+                    .bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        self.pipeline.layout().clone(),
+                        0,
+                        desc_set.clone(),
+                    )
+                    .unwrap()
+                    .bind_vertex_buffers(0, buffer.clone())
+                    .unwrap()
+                    .draw((range.end - range.start) as u32, 1, range.start as u32, 0)
+                    .unwrap();
+            }
+
+            cmd_builder
+                .end_render_pass(SubpassEndInfo::default())
+                .unwrap();
+        }
+    }
+}
+
 impl DrawState {
     pub fn interface_only(
         device: Arc<Device>,
         surface_format: Format,
         image_capacity: u32,
         msaa: MSAA,
+        post_process: Option<PostProcess>,
     ) -> Self {
         let mut state = InterfaceOnly::default();
         state.create_render_pass(device.clone(), surface_format, msaa);
-        state.create_pipeline(device, image_capacity);
-        Self::InterfaceOnly(state)
+        state.create_pipeline(device.clone(), image_capacity);
+
+        Self {
+            kind: DrawKind::InterfaceOnly(state),
+            layers: LayerState::new(device.clone(), image_capacity),
+            post_process: post_process
+                .map(|post_process| PostProcessState::new(device, surface_format, post_process)),
+        }
     }
 
     pub fn user<T: UserRenderer + Send + 'static>(
@@ -752,12 +977,29 @@ impl DrawState {
         surface_format: Format,
         image_capacity: u32,
         msaa: MSAA,
+        post_process: Option<PostProcess>,
         user_renderer: T,
     ) -> Self {
         let mut state = User::new(user_renderer);
         state.create_render_pass(device.clone(), surface_format, msaa);
-        state.create_pipeline(device, image_capacity);
-        Self::User(state)
+        state.create_pipeline(device.clone(), image_capacity);
+
+        Self {
+            kind: DrawKind::User(state),
+            layers: LayerState::new(device.clone(), image_capacity),
+            post_process: post_process
+                .map(|post_process| PostProcessState::new(device, surface_format, post_process)),
+        }
+    }
+
+    pub fn set_post_process(
+        &mut self,
+        device: Arc<Device>,
+        surface_format: Format,
+        post_process: Option<PostProcess>,
+    ) {
+        self.post_process = post_process
+            .map(|post_process| PostProcessState::new(device, surface_format, post_process));
     }
 
     pub fn update_framebuffers(
@@ -766,9 +1008,22 @@ impl DrawState {
         desc_alloc: &StandardDescriptorSetAllocator,
         swapchain_views: Vec<Arc<ImageView>>,
     ) {
-        match self {
-            Self::InterfaceOnly(state) => state.create_framebuffers(mem_alloc, swapchain_views),
-            Self::User(state) => state.create_framebuffers(mem_alloc, desc_alloc, swapchain_views),
+        // NOTE: When post processing is used, the interface is drawn onto intermediate images
+        //       instead of the swapchain images.
+
+        let extent = swapchain_views[0].image().extent();
+        self.layers.update_extent(mem_alloc, [extent[0], extent[1]]);
+
+        let target_views = match self.post_process.as_mut() {
+            Some(post_process) => {
+                post_process.create_framebuffers(mem_alloc, desc_alloc, swapchain_views)
+            },
+            None => swapchain_views,
+        };
+
+        match &mut self.kind {
+            DrawKind::InterfaceOnly(state) => state.create_framebuffers(mem_alloc, target_views),
+            DrawKind::User(state) => state.create_framebuffers(mem_alloc, desc_alloc, target_views),
         }
     }
 
@@ -779,75 +1034,368 @@ impl DrawState {
         image_capacity: u32,
         msaa: MSAA,
     ) {
-        match self {
-            Self::InterfaceOnly(state) => {
+        match &mut self.kind {
+            DrawKind::InterfaceOnly(state) => {
                 state.create_render_pass(device.clone(), surface_format, msaa);
                 state.create_pipeline(device.clone(), image_capacity);
             },
-            Self::User(state) => {
+            DrawKind::User(state) => {
                 state.create_render_pass(device.clone(), surface_format, msaa);
                 state.create_pipeline(device.clone(), image_capacity);
             },
         }
+
+        if let Some(post_process) = self.post_process.as_mut() {
+            *post_process =
+                PostProcessState::new(device, surface_format, post_process.post_process.clone());
+        }
     }
 
     pub fn update_image_capacity(&mut self, device: Arc<Device>, image_capacity: u32) {
-        match self {
-            Self::InterfaceOnly(state) => state.create_pipeline(device, image_capacity),
-            Self::User(state) => state.create_pipeline(device, image_capacity),
+        match &mut self.kind {
+            DrawKind::InterfaceOnly(state) => state.create_pipeline(device.clone(), image_capacity),
+            DrawKind::User(state) => state.create_pipeline(device.clone(), image_capacity),
+        }
+
+        self.layers.pipeline =
+            LayerState::create_pipeline(device, self.layers.render_pass.clone(), image_capacity);
+    }
+
+    /// Set the amount of layers returning `true` if the views of the layers have changed since
+    /// this was last called.
+    pub fn update_layers(
+        &mut self,
+        mem_alloc: &Arc<StandardMemoryAllocator>,
+        count: usize,
+    ) -> bool {
+        self.layers.update_count(mem_alloc, count);
+        std::mem::replace(&mut self.layers.changed, false)
+    }
+
+    /// The views of the layers, in the order of the layers.
+    pub fn layer_views(&self) -> Vec<Arc<ImageView>> {
+        self.layers
+            .framebuffers
+            .iter()
+            .map(|framebuffer| framebuffer.attachments()[0].clone())
+            .collect()
+    }
+
+    /// Set the color the interface is drawn on top of.
+    ///
+    /// ***Note:** When a user renderer is used, its output is the background instead.*
+    pub fn set_clear_color(&mut self, color: Color) {
+        if let DrawKind::InterfaceOnly(state) = &mut self.kind {
+            state.clear_color = color;
         }
     }
 
+    /// The color the interface is drawn on top of.
+    pub fn clear_color(&self) -> Color {
+        match &self.kind {
+            DrawKind::InterfaceOnly(state) => state.clear_color,
+            DrawKind::User(_) => Color::default(),
+        }
+    }
+
+    /// If only part of the previous contents of the swapchain image may be redrawn.
+    pub fn supports_partial_draw(&self) -> bool {
+        match &self.kind {
+            DrawKind::InterfaceOnly(state) => {
+                state.msaa == Some(MSAA::X1) && self.post_process.is_none()
+            },
+            DrawKind::User(_) => false,
+        }
+    }
+
+    /// Draw the interface.
+    ///
+    /// The vertexes of the layers follow the vertexes drawn directly. `layer_desc_set` is used
+    /// when drawing onto the layers and `desc_set` when drawing the rest.
     pub fn draw(
         &mut self,
         buffer: Subbuffer<[ItfVertInfo]>,
+        layers: &[Range<DeviceSize>],
         desc_set: Arc<PersistentDescriptorSet>,
+        layer_desc_set: Arc<PersistentDescriptorSet>,
         swapchain_image_index: usize,
         viewport: Viewport,
+        damage_rect: Option<[u32; 4]>,
         cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
     ) {
-        match self {
-            Self::InterfaceOnly(state) => {
+        let vertex_count = match layers.first() {
+            Some(range) => range.start,
+            None => buffer.len(),
+        } as u32;
+
+        // NOTE: Layers are always drawn in full, as only the composite is affected by the
+        //       render area.
+        self.layers.draw(
+            buffer.clone(),
+            layers,
+            layer_desc_set,
+            viewport.clone(),
+            cmd_builder,
+        );
+
+        match &mut self.kind {
+            DrawKind::InterfaceOnly(state) => {
                 state.draw(
                     buffer,
+                    vertex_count,
                     desc_set,
                     swapchain_image_index,
-                    viewport,
+                    viewport.clone(),
+                    damage_rect,
                     cmd_builder,
                 )
             },
-            Self::User(state) => {
+            DrawKind::User(state) => {
                 state.draw(
                     buffer,
+                    vertex_count,
                     desc_set,
                     swapchain_image_index,
-                    viewport,
+                    viewport.clone(),
                     cmd_builder,
                 )
             },
         }
+
+        if let Some(post_process) = self.post_process.as_mut() {
+            post_process.draw(swapchain_image_index, viewport, cmd_builder);
+        }
     }
 }
 
-pub fn clear_value_for_format(format: Format) -> ClearValue {
-    match format.numeric_format_color().unwrap() {
-        NumericFormat::SFLOAT
-        | NumericFormat::UFLOAT
-        | NumericFormat::SNORM
-        | NumericFormat::UNORM
-        | NumericFormat::SRGB => ClearValue::Float([0.0; 4]),
-        NumericFormat::SINT | NumericFormat::SSCALED => ClearValue::Int([0; 4]),
-        NumericFormat::UINT | NumericFormat::USCALED => ClearValue::Uint([0; 4]),
+struct PostProcessState {
+    post_process: PostProcess,
+    render_pass: Arc<RenderPass>,
+    pipeline: Arc<GraphicsPipeline>,
+    sampler: Arc<Sampler>,
+    framebuffers: Vec<Arc<Framebuffer>>,
+    desc_sets: Vec<Arc<PersistentDescriptorSet>>,
+}
+
+impl PostProcessState {
+    fn new(device: Arc<Device>, surface_format: Format, post_process: PostProcess) -> Self {
+        let render_pass = vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    format: surface_format,
+                    samples: 1,
+                    load_op: DontCare,
+                    store_op: Store,
+                },
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {},
+            }
+        )
+        .unwrap();
+
+        let final_vs = shaders::final_vs_sm(device.clone())
+            .entry_point("main")
+            .unwrap();
+
+        let post_fs = post_process.fragment_shader().entry_point("main").unwrap();
+
+        let stages = [
+            PipelineShaderStageCreateInfo::new(final_vs),
+            PipelineShaderStageCreateInfo::new(post_fs),
+        ];
+
+        let layout = PipelineLayout::new(
+            device.clone(),
+            PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+                .into_pipeline_layout_create_info(device.clone())
+                .unwrap(),
+        )
+        .unwrap();
+
+        let subpass = Subpass::from(render_pass.clone(), 0).unwrap();
+
+        let pipeline = GraphicsPipeline::new(
+            device.clone(),
+            None,
+            GraphicsPipelineCreateInfo {
+                stages: stages.into_iter().collect(),
+                vertex_input_state: Some(VertexInputState::new()),
+                input_assembly_state: Some(InputAssemblyState::default()),
+                viewport_state: Some(ViewportState::default()),
+                rasterization_state: Some(RasterizationState::default()),
+                multisample_state: Some(MultisampleState::default()),
+                color_blend_state: Some(ColorBlendState::with_attachment_states(
+                    subpass.num_color_attachments(),
+                    ColorBlendAttachmentState::default(),
+                )),
+                dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+                subpass: Some(subpass.into()),
+                ..GraphicsPipelineCreateInfo::layout(layout)
+            },
+        )
+        .unwrap();
+
+        let sampler = Sampler::new(
+            device,
+            SamplerCreateInfo {
+                address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                ..SamplerCreateInfo::simple_repeat_linear_no_mipmap()
+            },
+        )
+        .unwrap();
+
+        Self {
+            post_process,
+            render_pass,
+            pipeline,
+            sampler,
+            framebuffers: Vec::new(),
+            desc_sets: Vec::new(),
+        }
+    }
+
+    /// Create the framebuffers for the swapchain images returning the intermediate images.
+    fn create_framebuffers(
+        &mut self,
+        mem_alloc: &Arc<StandardMemoryAllocator>,
+        desc_alloc: &StandardDescriptorSetAllocator,
+        swapchain_views: Vec<Arc<ImageView>>,
+    ) -> Vec<Arc<ImageView>> {
+        let mut intermediate_views = Vec::with_capacity(swapchain_views.len());
+        self.framebuffers.clear();
+        self.desc_sets.clear();
+
+        for swapchain_view in swapchain_views {
+            let intermediate_view = ImageView::new_default(
+                Image::new(
+                    mem_alloc.clone(),
+                    ImageCreateInfo {
+                        image_type: ImageType::Dim2d,
+                        format: swapchain_view.format(),
+                        extent: swapchain_view.image().extent(),
+                        usage: ImageUsage::COLOR_ATTACHMENT
+                            | ImageUsage::SAMPLED
+                            | ImageUsage::TRANSFER_DST,
+                        ..ImageCreateInfo::default()
+                    },
+                    AllocationCreateInfo {
+                        memory_type_filter: MemoryTypeFilter {
+                            preferred_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+                            not_preferred_flags: MemoryPropertyFlags::HOST_CACHED,
+                            ..MemoryTypeFilter::empty()
+                        },
+                        allocate_preference: MemoryAllocatePreference::AlwaysAllocate,
+                        ..AllocationCreateInfo::default()
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+            self.framebuffers.push(
+                Framebuffer::new(
+                    self.render_pass.clone(),
+                    FramebufferCreateInfo {
+                        attachments: vec![swapchain_view],
+                        ..FramebufferCreateInfo::default()
+                    },
+                )
+                .unwrap(),
+            );
+
+            self.desc_sets.push(
+                PersistentDescriptorSet::new(
+                    desc_alloc,
+                    self.pipeline.layout().set_layouts()[0].clone(),
+                    [WriteDescriptorSet::image_view_sampler(
+                        0,
+                        intermediate_view.clone(),
+                        self.sampler.clone(),
+                    )],
+                    [],
+                )
+                .unwrap(),
+            );
+
+            intermediate_views.push(intermediate_view);
+        }
+
+        intermediate_views
+    }
+
+    fn draw(
+        &mut self,
+        swapchain_image_index: usize,
+        viewport: Viewport,
+        cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    ) {
+        cmd_builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(
+                        self.framebuffers[swapchain_image_index].clone(),
+                    )
+                },
+                SubpassBeginInfo::default(),
+            )
+            .unwrap()
+            .set_viewport(0, [viewport].into_iter().collect())
+            .unwrap()
+            .bind_pipeline_graphics(self.pipeline.clone())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline.layout().clone(),
+                0,
+                self.desc_sets[swapchain_image_index].clone(),
+            )
+            .unwrap();
+
+        if self.post_process.uses_params() {
+            cmd_builder
+                .push_constants(
+                    self.pipeline.layout().clone(),
+                    0,
+                    self.post_process.params(),
+                )
+                .unwrap();
+        }
+
+        cmd_builder
+            .draw(3, 1, 0, 0)
+            .unwrap()
+            .end_render_pass(SubpassEndInfo::default())
+            .unwrap();
     }
 }
 
-pub fn clear_color_value_for_format(format: Format) -> ClearColorValue {
+pub fn clear_value_for_format(format: Format, color: Color) -> ClearValue {
+    match clear_color_value_for_format(format, color) {
+        ClearColorValue::Float(value) => ClearValue::Float(value),
+        ClearColorValue::Int(value) => ClearValue::Int(value),
+        ClearColorValue::Uint(value) => ClearValue::Uint(value),
+    }
+}
+
+pub fn clear_color_value_for_format(format: Format, color: Color) -> ClearColorValue {
+    // NOTE: The output of the renderer is premultiplied.
     match format.numeric_format_color().unwrap() {
         NumericFormat::SFLOAT
         | NumericFormat::UFLOAT
         | NumericFormat::SNORM
         | NumericFormat::UNORM
-        | NumericFormat::SRGB => ClearColorValue::Float([0.0; 4]),
+        | NumericFormat::SRGB => {
+            ClearColorValue::Float([
+                color.r * color.a,
+                color.g * color.a,
+                color.b * color.a,
+                color.a,
+            ])
+        },
         NumericFormat::SINT | NumericFormat::SSCALED => ClearColorValue::Int([0; 4]),
         NumericFormat::UINT | NumericFormat::USCALED => ClearColorValue::Uint([0; 4]),
     }
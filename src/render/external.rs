@@ -0,0 +1,220 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use vulkano::buffer::Subbuffer;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, ClearColorImageInfo, CommandBufferUsage};
+use vulkano::descriptor_set::PersistentDescriptorSet;
+use vulkano::image::view::ImageView;
+use vulkano::image::Image;
+use vulkano::pipeline::graphics::viewport::Viewport;
+use vulkano::sync::future::{FenceSignalFuture, GpuFuture};
+use vulkano::DeviceSize;
+
+use crate::interface::ItfVertInfo;
+use crate::render::{draw, RenderEvent, Renderer};
+use crate::window::Window;
+
+/// A `Renderer` that draws into images provided by the user instead of a window's surface.
+///
+/// Created with `Renderer::into_external`.
+///
+/// # Synchronization
+///
+/// The target image is only accessed by basalt after the `after` future provided to `draw` and
+/// before the returned future is signaled. The user must not access the target image until the
+/// returned future is signaled, either by waiting on it or by chaining it to their own work.
+///
+/// ***Note:** The interface is laid out with the dimensions of the window. If the target image
+/// has a different extent, the interface will be stretched.*
+pub struct ExternalRenderer {
+    renderer: Renderer,
+    buffer_op: Option<Subbuffer<[ItfVertInfo]>>,
+    images_op: Option<Vec<Arc<Image>>>,
+    layers: Vec<Range<DeviceSize>>,
+    desc_sets_op: Option<[Arc<PersistentDescriptorSet>; 2]>,
+    target_op: Option<Arc<ImageView>>,
+    previous_frame_op: Option<Arc<FenceSignalFuture<Box<dyn GpuFuture + Send + Sync>>>>,
+}
+
+impl ExternalRenderer {
+    pub(super) fn new(renderer: Renderer) -> Self {
+        Self {
+            renderer,
+            buffer_op: None,
+            images_op: None,
+            layers: Vec::new(),
+            desc_sets_op: None,
+            target_op: None,
+            previous_frame_op: None,
+        }
+    }
+
+    /// The window whose interface is drawn.
+    pub fn window(&self) -> &Arc<Window> {
+        &self.renderer.window
+    }
+
+    /// Draw the interface into `target` once `after` is reached.
+    ///
+    /// `target` must have the format provided to `Renderer::into_external` and the usages
+    /// `COLOR_ATTACHMENT` & `TRANSFER_DST`.
+    ///
+    /// ***Note:** Pending updates to the interface are only applied when this method is called.
+    /// Until then, the renderer's worker will wait.*
+    pub fn draw(
+        &mut self,
+        target: Arc<ImageView>,
+        after: Box<dyn GpuFuture + Send + Sync>,
+    ) -> Result<Arc<FenceSignalFuture<Box<dyn GpuFuture + Send + Sync>>>, String> {
+        if target.format() != self.renderer.surface_format {
+            return Err(format!(
+                "The target's format must be {:?}.",
+                self.renderer.surface_format
+            ));
+        }
+
+        let mut update_framebuffers = match self.target_op.as_ref() {
+            Some(previous_target) => !Arc::ptr_eq(previous_target, &target),
+            None => true,
+        };
+
+        let render_events = self.renderer.render_event_recv.drain().collect::<Vec<_>>();
+
+        if render_events.is_empty() && self.renderer.render_event_recv.is_disconnected() {
+            self.renderer.worker_exit()?;
+            return Err(String::from("The window has been closed."));
+        }
+
+        for render_event in render_events {
+            match render_event {
+                RenderEvent::Update {
+                    buffer,
+                    images,
+                    layers,
+                    barrier,
+                    ..
+                } => {
+                    if let Some(previous_frame) = self.previous_frame_op.take() {
+                        previous_frame.wait(None).unwrap();
+                    }
+
+                    self.buffer_op = Some(buffer);
+                    self.images_op = Some(images);
+                    self.layers = layers;
+                    self.desc_sets_op = None;
+                    barrier.wait();
+                },
+                RenderEvent::SetMSAA(msaa) => {
+                    update_framebuffers |= self.renderer.set_msaa(msaa);
+                },
+                RenderEvent::SetPostProcess(post_process) => {
+                    self.renderer.draw_state.as_mut().unwrap().set_post_process(
+                        self.renderer.queue.device().clone(),
+                        self.renderer.surface_format,
+                        post_process,
+                    );
+
+                    update_framebuffers = true;
+                },
+                RenderEvent::SetClearColor(color) => {
+                    self.renderer
+                        .draw_state
+                        .as_mut()
+                        .unwrap()
+                        .set_clear_color(color);
+                },
+                // NOTE: Presentation is controlled by the user, so these are not applicable.
+                RenderEvent::Redraw
+                | RenderEvent::Resize
+                | RenderEvent::SetVSync(_)
+                | RenderEvent::SetImageCount(_)
+                | RenderEvent::SetFPSLimit(_)
+                | RenderEvent::SetMetrics(_)
+                | RenderEvent::WindowFullscreenEnabled
+                | RenderEvent::WindowFullscreenDisabled => (),
+            }
+        }
+
+        if update_framebuffers {
+            self.renderer
+                .draw_state
+                .as_mut()
+                .unwrap()
+                .update_framebuffers(
+                    &self.renderer.mem_alloc,
+                    &self.renderer.desc_alloc,
+                    vec![target.clone()],
+                );
+
+            self.target_op = Some(target.clone());
+        }
+
+        let extent = target.image().extent();
+
+        let viewport = Viewport {
+            offset: [0.0, 0.0],
+            extent: [extent[0] as f32, extent[1] as f32],
+            depth_range: 0.0..=1.0,
+        };
+
+        let mut cmd_builder = AutoCommandBufferBuilder::primary(
+            &self.renderer.cmd_alloc,
+            self.renderer.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        if self
+            .renderer
+            .draw_state
+            .as_mut()
+            .unwrap()
+            .update_layers(&self.renderer.mem_alloc, self.layers.len())
+        {
+            self.desc_sets_op = None;
+        }
+
+        if self.desc_sets_op.is_none() {
+            if let Some(images) = self.images_op.as_ref() {
+                self.desc_sets_op = Some(self.renderer.create_desc_sets(images));
+            }
+        }
+
+        match (self.buffer_op.clone(), self.desc_sets_op.clone()) {
+            (Some(buffer), Some([desc_set, layer_desc_set])) => {
+                self.renderer.draw_state.as_mut().unwrap().draw(
+                    buffer,
+                    &self.layers,
+                    desc_set,
+                    layer_desc_set,
+                    0,
+                    viewport,
+                    None,
+                    &mut cmd_builder,
+                );
+            },
+            _ => {
+                cmd_builder
+                    .clear_color_image(ClearColorImageInfo {
+                        clear_value: draw::clear_color_value_for_format(
+                            target.format(),
+                            self.renderer.draw_state.as_ref().unwrap().clear_color(),
+                        ),
+                        ..ClearColorImageInfo::image(target.image().clone())
+                    })
+                    .unwrap();
+            },
+        }
+
+        let future = after
+            .then_execute(self.renderer.queue.clone(), cmd_builder.build().unwrap())
+            .map_err(|e| format!("Failed to execute command buffer: {}", e))?
+            .boxed_send_sync()
+            .then_signal_fence_and_flush()
+            .map_err(|e| format!("Failed to flush command buffer: {}", e))?;
+
+        let future = Arc::new(future);
+        self.previous_frame_op = Some(future.clone());
+        Ok(future)
+    }
+}
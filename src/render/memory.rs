@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+use smallvec::SmallVec;
+use vulkano::buffer::{Buffer, BufferMemory};
+use vulkano::device::{Device, DeviceOwned};
+use vulkano::image::{Image, ImageMemory};
+use vulkano::memory::allocator::{
+    GenericMemoryAllocatorCreateInfo, MemoryAllocatePreference, StandardMemoryAllocator,
+};
+use vulkano::memory::{DeviceMemory, MemoryPropertyFlags, ResourceMemory};
+use vulkano::sync::Sharing;
+use vulkano::DeviceSize;
+
+/// Memory statistics of a heap.
+///
+/// Only memory used by the renderers' workers is accounted for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryHeapStats {
+    /// The index of the heap.
+    pub heap_index: u32,
+    /// The size of the heap.
+    pub heap_size: DeviceSize,
+    /// The amount of bytes used by resources.
+    pub used_bytes: DeviceSize,
+    /// The amount of bytes allocated by blocks that contain resources.
+    ///
+    /// ***Note:** This excludes empty blocks still retained by the allocator.*
+    pub reserved_bytes: DeviceSize,
+}
+
+enum TrackedResource {
+    Buffer(Weak<Buffer>),
+    Image(Weak<Image>),
+}
+
+/// Memory allocation used by the renderers' workers.
+pub(crate) struct RenderMemory {
+    allocator: Arc<StandardMemoryAllocator>,
+    preference: MemoryAllocatePreference,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
+    tracked: Mutex<Vec<TrackedResource>>,
+}
+
+impl RenderMemory {
+    pub fn new<F>(
+        device: Arc<Device>,
+        block_size: Option<DeviceSize>,
+        always_allocate: bool,
+        queue_family_indices: F,
+    ) -> Self
+    where
+        F: IntoIterator<Item = u32>,
+    {
+        let mut queue_family_indices: SmallVec<[u32; 4]> =
+            queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
+
+        // NOTE: Resources are used across queue families without ownership transfers.
+        let sharing = match queue_family_indices.len() {
+            0 | 1 => Sharing::Exclusive,
+            _ => Sharing::Concurrent(queue_family_indices),
+        };
+
+        let allocator = match block_size {
+            Some(block_size) => {
+                let memory_types = &device.physical_device().memory_properties().memory_types;
+                let block_sizes = vec![block_size; memory_types.len()];
+                let mut memory_type_bits = u32::MAX;
+
+                // NOTE: These are the same memory types excluded by
+                //       `StandardMemoryAllocator::new_default`.
+                for (index, memory_type) in memory_types.iter().enumerate() {
+                    if memory_type.property_flags.intersects(
+                        MemoryPropertyFlags::LAZILY_ALLOCATED
+                            | MemoryPropertyFlags::PROTECTED
+                            | MemoryPropertyFlags::DEVICE_COHERENT
+                            | MemoryPropertyFlags::RDMA_CAPABLE,
+                    ) {
+                        memory_type_bits &= !(1 << index);
+                    }
+                }
+
+                StandardMemoryAllocator::new(
+                    device,
+                    GenericMemoryAllocatorCreateInfo {
+                        block_sizes: &block_sizes,
+                        memory_type_bits,
+                        ..GenericMemoryAllocatorCreateInfo::default()
+                    },
+                )
+            },
+            None => StandardMemoryAllocator::new_default(device),
+        };
+
+        Self {
+            allocator: Arc::new(allocator),
+            preference: match always_allocate {
+                true => MemoryAllocatePreference::AlwaysAllocate,
+                false => MemoryAllocatePreference::Unknown,
+            },
+            sharing,
+            tracked: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn allocator(&self) -> &Arc<StandardMemoryAllocator> {
+        &self.allocator
+    }
+
+    /// The preference used for long-lived resources.
+    pub fn preference(&self) -> MemoryAllocatePreference {
+        self.preference
+    }
+
+    /// The sharing mode used for resources accessed by multiple queues.
+    pub fn sharing(&self) -> Sharing<SmallVec<[u32; 4]>> {
+        self.sharing.clone()
+    }
+
+    pub fn track_buffer(&self, buffer: &Arc<Buffer>) {
+        self.track(TrackedResource::Buffer(Arc::downgrade(buffer)));
+    }
+
+    pub fn track_image(&self, image: &Arc<Image>) {
+        self.track(TrackedResource::Image(Arc::downgrade(image)));
+    }
+
+    fn track(&self, resource: TrackedResource) {
+        let mut tracked = self.tracked.lock();
+
+        // NOTE: Dropped resources are removed before the list would grow, so that it doesn't
+        //       grow unbounded when stats are never retrieved.
+        if tracked.len() == tracked.capacity() {
+            tracked.retain(|resource| {
+                match resource {
+                    TrackedResource::Buffer(buffer_wk) => buffer_wk.strong_count() > 0,
+                    TrackedResource::Image(image_wk) => image_wk.strong_count() > 0,
+                }
+            });
+        }
+
+        tracked.push(resource);
+    }
+
+    pub fn stats(&self) -> Vec<MemoryHeapStats> {
+        let properties = self
+            .allocator
+            .device()
+            .physical_device()
+            .memory_properties();
+
+        let mut stats = properties
+            .memory_heaps
+            .iter()
+            .enumerate()
+            .map(|(heap_index, heap)| {
+                MemoryHeapStats {
+                    heap_index: heap_index as u32,
+                    heap_size: heap.size,
+                    ..MemoryHeapStats::default()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut blocks: HashMap<*const DeviceMemory, Arc<DeviceMemory>> = HashMap::new();
+
+        let mut account = |resource_memory: &ResourceMemory| {
+            let device_memory = resource_memory.device_memory();
+            let memory_type = &properties.memory_types[device_memory.memory_type_index() as usize];
+            stats[memory_type.heap_index as usize].used_bytes += resource_memory.size();
+            blocks.insert(Arc::as_ptr(device_memory), device_memory.clone());
+        };
+
+        self.tracked.lock().retain(|resource| {
+            match resource {
+                TrackedResource::Buffer(buffer_wk) => {
+                    match buffer_wk.upgrade() {
+                        Some(buffer) => {
+                            if let BufferMemory::Normal(resource_memory) = buffer.memory() {
+                                account(resource_memory);
+                            }
+
+                            true
+                        },
+                        None => false,
+                    }
+                },
+                TrackedResource::Image(image_wk) => {
+                    match image_wk.upgrade() {
+                        Some(image) => {
+                            if let ImageMemory::Normal(resource_memories) = image.memory() {
+                                resource_memories.iter().for_each(&mut account);
+                            }
+
+                            true
+                        },
+                        None => false,
+                    }
+                },
+            }
+        });
+
+        for device_memory in blocks.into_values() {
+            let memory_type = &properties.memory_types[device_memory.memory_type_index() as usize];
+            stats[memory_type.heap_index as usize].reserved_bytes +=
+                device_memory.allocation_size();
+        }
+
+        stats
+    }
+}
@@ -1,12 +1,18 @@
 //! Window rendering
 
 use std::collections::BTreeMap;
+use std::ops::Range;
 use std::sync::{Arc, Barrier};
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
 pub use amwr::AutoMultiWindowRenderer;
 use cosmic_text::{FontSystem, SwashCache};
+pub use external::ExternalRenderer;
 use flume::Receiver;
+pub use memory::MemoryHeapStats;
+pub(crate) use memory::RenderMemory;
+use parking_lot::Mutex;
 use vulkano::buffer::Subbuffer;
 use vulkano::command_buffer::allocator::{
     StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo,
@@ -18,33 +24,39 @@ use vulkano::command_buffer::{
 use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
 use vulkano::descriptor_set::layout::DescriptorSetLayout;
 use vulkano::descriptor_set::{PersistentDescriptorSet, WriteDescriptorSet};
-use vulkano::device::Queue;
+use vulkano::device::{Device, Queue};
 use vulkano::format::{Format, FormatFeatures, NumericFormat};
 use vulkano::image::sampler::{Sampler, SamplerAddressMode, SamplerCreateInfo};
 use vulkano::image::sys::ImageCreateInfo;
 use vulkano::image::view::ImageView;
-use vulkano::image::{Image, ImageUsage};
+use vulkano::image::{Image, ImageUsage, SampleCount};
 use vulkano::memory::allocator::{
     AllocationCreateInfo, MemoryAllocatePreference, MemoryTypeFilter, StandardMemoryAllocator,
 };
 use vulkano::memory::MemoryPropertyFlags;
 use vulkano::pipeline::graphics::viewport::Viewport;
+use vulkano::query::{QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType};
+use vulkano::shader::spirv::ExecutionModel;
+use vulkano::shader::{ShaderModule, ShaderModuleCreateInfo};
 use vulkano::swapchain::{
-    self, ColorSpace, FullScreenExclusive, PresentGravity, PresentGravityFlags, PresentMode,
-    PresentScaling, PresentScalingFlags, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo,
-    Win32Monitor,
+    self, ColorSpace, CompositeAlpha, FullScreenExclusive, PresentGravity, PresentGravityFlags,
+    PresentMode, PresentScaling, PresentScalingFlags, RectangleLayer, Swapchain,
+    SwapchainCreateInfo, SwapchainPresentInfo, Win32Monitor,
 };
 use vulkano::sync::future::{FenceSignalFuture, GpuFuture};
-use vulkano::VulkanError;
+use vulkano::sync::PipelineStage;
+use vulkano::{DeviceSize, VulkanError};
 pub use worker::WorkerPerfMetrics;
 
 use self::draw::DrawState;
 use crate::image_cache::ImageCacheKey;
-use crate::interface::{BinID, BinPlacement, DefaultFont, ItfVertInfo};
-use crate::window::Window;
+use crate::interface::{BinID, BinPlacement, Color, DefaultFont, ItfVertInfo};
+use crate::window::{SurfaceColorSpace, SurfaceFormat, Window};
 
 mod amwr;
 mod draw;
+mod external;
+mod memory;
 mod shaders;
 mod worker;
 
@@ -57,6 +69,29 @@ pub enum MSAA {
     X8,
 }
 
+impl MSAA {
+    fn sample_count(self) -> SampleCount {
+        match self {
+            Self::X1 => SampleCount::Sample1,
+            Self::X2 => SampleCount::Sample2,
+            Self::X4 => SampleCount::Sample4,
+            Self::X8 => SampleCount::Sample8,
+        }
+    }
+}
+
+/// Used to specify the color space of a `Renderer`'s output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RendererColorSpace {
+    /// Standard dynamic range sRGB.
+    #[default]
+    Srgb,
+    /// Extended sRGB (scRGB). Values greater than `1.0` are brighter than SDR white.
+    ///
+    /// ***Note:** This requires the `ext_swapchain_colorspace` instance extension.*
+    ExtendedSrgb,
+}
+
 /// Used to specify if VSync should be enabled.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VSync {
@@ -72,22 +107,193 @@ pub trait UserRenderer {
     fn draw(&mut self, cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>);
 }
 
+/// A user provided fullscreen pass that is ran after the interface is composited.
+///
+/// The fragment shader must have an entry point named `main`, must use `ui_color` and may use
+/// the push constant block:
+/// ```glsl
+/// // The composited output of the renderer.
+/// layout(set = 0, binding = 0) uniform sampler2D ui_color;
+/// // The values set with `PostProcess::set_params`.
+/// layout(push_constant) uniform PostProcessParams { vec4 params[4]; };
+/// layout(location = 0) out vec4 out_color;
+/// ```
+///
+/// ***Note:** The post process pass requires an additional fullscreen pass and an additional
+/// image the size of the window for each swapchain image. MSAA is resolved before the post
+/// process pass, so `ui_color` will always have a single sample.*
+#[derive(Clone)]
+pub struct PostProcess {
+    fragment_shader: Arc<ShaderModule>,
+    params: Arc<Mutex<[f32; 16]>>,
+}
+
+impl PostProcess {
+    /// Create a `PostProcess` from a fragment shader module.
+    pub fn new(fragment_shader: Arc<ShaderModule>) -> Result<Self, String> {
+        let entry_point = fragment_shader
+            .entry_point("main")
+            .ok_or_else(|| String::from("The shader must have an entry point named `main`."))?;
+
+        if entry_point.info().execution_model != ExecutionModel::Fragment {
+            return Err(String::from("The shader must be a fragment shader."));
+        }
+
+        let descriptor_bindings = &entry_point.info().descriptor_binding_requirements;
+
+        if descriptor_bindings.len() != 1 || !descriptor_bindings.contains_key(&(0, 0)) {
+            return Err(String::from(
+                "The shader must only use `ui_color` at set 0, binding 0.",
+            ));
+        }
+
+        if let Some(push_constant_range) = entry_point.info().push_constant_requirements {
+            if push_constant_range.offset != 0 || push_constant_range.size != 64 {
+                return Err(String::from(
+                    "The push constant block of the shader must be `vec4 params[4]`.",
+                ));
+            }
+        }
+
+        Ok(Self {
+            fragment_shader,
+            params: Arc::new(Mutex::new([0.0; 16])),
+        })
+    }
+
+    /// Create a `PostProcess` from the SPIR-V words of a fragment shader.
+    ///
+    /// # Safety
+    ///
+    /// See `ShaderModule::new`.
+    pub unsafe fn from_spirv(device: Arc<Device>, words: &[u32]) -> Result<Self, String> {
+        let fragment_shader = ShaderModule::new(device, ShaderModuleCreateInfo::new(words))
+            .map_err(|e| format!("Failed to create shader module: {}", e))?;
+
+        Self::new(fragment_shader)
+    }
+
+    /// Set the values provided to the shader's push constant block.
+    ///
+    /// ***Note:** This may be called at any time. The values are read when a frame is drawn.*
+    pub fn set_params(&self, params: [f32; 16]) {
+        *self.params.lock() = params;
+    }
+
+    /// Retrieve the values provided to the shader's push constant block.
+    pub fn params(&self) -> [f32; 16] {
+        *self.params.lock()
+    }
+
+    pub(crate) fn fragment_shader(&self) -> Arc<ShaderModule> {
+        self.fragment_shader.clone()
+    }
+
+    pub(crate) fn uses_params(&self) -> bool {
+        self.fragment_shader
+            .entry_point("main")
+            .unwrap()
+            .info()
+            .push_constant_requirements
+            .is_some()
+    }
+}
+
 pub(crate) struct UpdateContext {
     pub extent: [f32; 2],
     pub scale: f32,
     pub font_system: FontSystem,
     pub glyph_cache: SwashCache,
     pub default_font: DefaultFont,
+    pub fallback_fonts: Vec<String>,
     pub metrics_level: RendererMetricsLevel,
     pub placement_cache: BTreeMap<BinID, BinPlacement>,
 }
 
+/// The region of the window that changed with an update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Damage {
+    None,
+    /// Bounds in normalized device coordinates: `[min_x, min_y, max_x, max_y]`
+    Region([f32; 4]),
+    Full,
+}
+
+impl Damage {
+    pub fn add(&mut self, other: Self) {
+        *self = match (*self, other) {
+            (Self::Full, _) | (_, Self::Full) => Self::Full,
+            (Self::None, other) | (other, Self::None) => other,
+            (Self::Region(a), Self::Region(b)) => {
+                Self::Region([
+                    a[0].min(b[0]),
+                    a[1].min(b[1]),
+                    a[2].max(b[2]),
+                    a[3].max(b[3]),
+                ])
+            },
+        };
+    }
+
+    pub fn add_vertexes<'a, I: IntoIterator<Item = &'a ItfVertInfo>>(&mut self, vertexes: I) {
+        let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+
+        for vertex in vertexes {
+            bounds[0] = bounds[0].min(vertex.position[0]);
+            bounds[1] = bounds[1].min(vertex.position[1]);
+            bounds[2] = bounds[2].max(vertex.position[0]);
+            bounds[3] = bounds[3].max(vertex.position[1]);
+        }
+
+        if bounds[0] <= bounds[2] && bounds[1] <= bounds[3] {
+            self.add(Self::Region(bounds));
+        }
+    }
+
+    /// The region in pixels, `[x, y, width, height]`, that needs to be drawn. `None` is returned
+    /// when the whole extent should be drawn.
+    pub fn pixel_rect(self, extent: [u32; 2]) -> Option<[u32; 4]> {
+        let bounds = match self {
+            Self::Region(bounds) => bounds,
+            Self::None | Self::Full => return None,
+        };
+
+        let width = extent[0] as f32;
+        let height = extent[1] as f32;
+
+        // NOTE: The region is padded to account for antialiasing & filtering at the edges.
+        let min_x = (((bounds[0] + 1.0) / 2.0 * width).floor() - 2.0).clamp(0.0, width) as u32;
+        let min_y = (((bounds[1] + 1.0) / 2.0 * height).floor() - 2.0).clamp(0.0, height) as u32;
+        let max_x = (((bounds[2] + 1.0) / 2.0 * width).ceil() + 2.0).clamp(0.0, width) as u32;
+        let max_y = (((bounds[3] + 1.0) / 2.0 * height).ceil() + 2.0).clamp(0.0, height) as u32;
+
+        if max_x <= min_x || max_y <= min_y {
+            return None;
+        }
+
+        let rect = [min_x, min_y, max_x - min_x, max_y - min_y];
+
+        if (rect[2] as u64 * rect[3] as u64) as f32
+            > (extent[0] as u64 * extent[1] as u64) as f32 * PARTIAL_DRAW_MAX_AREA
+        {
+            return None;
+        }
+
+        Some(rect)
+    }
+}
+
+/// Ratio of the window's area at which a partial draw is replaced with a full draw.
+const PARTIAL_DRAW_MAX_AREA: f32 = 0.5;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub(crate) enum ImageSource {
     #[default]
     None,
     Cache(ImageCacheKey),
     Vulkano(Arc<Image>),
+    /// The layer of an isolated `Bin`, provided by the renderer.
+    Layer(BinID),
 }
 
 enum RenderEvent {
@@ -95,13 +301,19 @@ enum RenderEvent {
     Update {
         buffer: Subbuffer<[ItfVertInfo]>,
         images: Vec<Arc<Image>>,
+        layers: Vec<Range<DeviceSize>>,
         barrier: Arc<Barrier>,
-        metrics: Option<WorkerPerfMetrics>,
+        damage: Damage,
+        metrics: Option<Box<WorkerPerfMetrics>>,
     },
     Resize,
     SetMSAA(MSAA),
     SetVSync(VSync),
+    SetImageCount(u32),
+    SetFPSLimit(Option<u32>),
     SetMetrics(RendererMetricsLevel),
+    SetPostProcess(Option<PostProcess>),
+    SetClearColor(Color),
     WindowFullscreenEnabled,
     WindowFullscreenDisabled,
 }
@@ -112,9 +324,26 @@ pub struct RendererPerfMetrics {
     pub total_frames: usize,
     pub total_updates: usize,
     pub avg_cpu_time: f32,
+    pub avg_frame_time: f32,
     pub avg_frame_rate: f32,
     pub avg_update_rate: f32,
     pub avg_worker_metrics: Option<WorkerPerfMetrics>,
+    /// Average time in milliseconds the gpu spent executing a frame's commands.
+    ///
+    /// ***Note:** This is only tracked with `RendererMetricsLevel::Full` and when the device
+    /// supports timestamp queries.*
+    pub avg_gpu_time: Option<f32>,
+}
+
+/// A copy of an atlas image used by a `Renderer`.
+#[derive(Debug, Clone)]
+pub struct AtlasSnapshot {
+    /// The index of the image backing.
+    pub index: usize,
+    pub width: u32,
+    pub height: u32,
+    /// The data of the image as 8-bit RGBA.
+    pub data: Vec<u8>,
 }
 
 /// Defines the level of metrics tracked.
@@ -138,6 +367,7 @@ struct MetricsState {
     last_update: Instant,
     cpu_times: Vec<f32>,
     gpu_times: Vec<f32>,
+    gpu_exec_times: Vec<f32>,
     update_times: Vec<f32>,
     worker_metrics: Vec<WorkerPerfMetrics>,
 }
@@ -152,6 +382,7 @@ impl MetricsState {
             last_update: inst,
             cpu_times: Vec::new(),
             gpu_times: Vec::new(),
+            gpu_exec_times: Vec::new(),
             update_times: Vec::new(),
             worker_metrics: Vec::new(),
         }
@@ -168,13 +399,17 @@ impl MetricsState {
             .push(self.last_acquire.elapsed().as_micros() as f32 / 1000.0);
     }
 
-    fn track_update(&mut self, worker_metrics_op: Option<WorkerPerfMetrics>) {
+    fn track_gpu_exec(&mut self, gpu_exec_time: f32) {
+        self.gpu_exec_times.push(gpu_exec_time);
+    }
+
+    fn track_update(&mut self, worker_metrics_op: Option<Box<WorkerPerfMetrics>>) {
         self.update_times
             .push(self.last_update.elapsed().as_micros() as f32 / 1000.0);
         self.last_update = Instant::now();
 
         if let Some(worker_metrics) = worker_metrics_op {
-            self.worker_metrics.push(worker_metrics);
+            self.worker_metrics.push(*worker_metrics);
         }
     }
 
@@ -208,35 +443,159 @@ impl MetricsState {
             (0, 0.0, None)
         };
 
-        let (total_frames, avg_cpu_time, avg_frame_rate) = if !self.gpu_times.is_empty() {
-            (
-                self.gpu_times.len(),
-                self.cpu_times.iter().sum::<f32>() / self.cpu_times.len() as f32,
-                1000.0 / (self.gpu_times.iter().sum::<f32>() / self.gpu_times.len() as f32),
-            )
+        let (total_frames, avg_cpu_time, avg_frame_time, avg_frame_rate) =
+            if !self.gpu_times.is_empty() {
+                let avg_frame_time =
+                    self.gpu_times.iter().sum::<f32>() / self.gpu_times.len() as f32;
+
+                (
+                    self.gpu_times.len(),
+                    self.cpu_times.iter().sum::<f32>() / self.cpu_times.len() as f32,
+                    avg_frame_time,
+                    1000.0 / avg_frame_time,
+                )
+            } else {
+                (0, 0.0, 0.0, 0.0)
+            };
+
+        let avg_gpu_time = if !self.gpu_exec_times.is_empty() {
+            Some(self.gpu_exec_times.iter().sum::<f32>() / self.gpu_exec_times.len() as f32)
         } else {
-            (0, 0.0, 0.0)
+            None
         };
 
         *self = Self::new();
 
         RendererPerfMetrics {
+            avg_gpu_time,
             total_updates,
             avg_update_rate,
             avg_worker_metrics,
             total_frames,
             avg_cpu_time,
+            avg_frame_time,
             avg_frame_rate,
         }
     }
 }
 
+/// Measures the gpu execution time of frames with timestamp queries.
+struct GpuTimer {
+    query_pool: Arc<QueryPool>,
+    timestamp_period: f32,
+    timestamp_mask: u64,
+    written: Vec<bool>,
+}
+
+impl GpuTimer {
+    /// Returns `None` if the queue doesn't support timestamps.
+    fn new(queue: &Arc<Queue>, slots: usize) -> Option<Self> {
+        let physical_device = queue.device().physical_device();
+        let timestamp_period = physical_device.properties().timestamp_period;
+
+        let timestamp_valid_bits = physical_device.queue_family_properties()
+            [queue.queue_family_index() as usize]
+            .timestamp_valid_bits?;
+
+        if timestamp_period <= 0.0 {
+            return None;
+        }
+
+        let query_pool = QueryPool::new(
+            queue.device().clone(),
+            QueryPoolCreateInfo {
+                query_count: slots as u32 * 2,
+                ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+            },
+        )
+        .ok()?;
+
+        Some(Self {
+            query_pool,
+            timestamp_period,
+            timestamp_mask: match timestamp_valid_bits {
+                64.. => u64::MAX,
+                bits => (1 << bits) - 1,
+            },
+            written: vec![false; slots],
+        })
+    }
+
+    fn slots(&self) -> usize {
+        self.written.len()
+    }
+
+    /// The execution time in milliseconds of the previous commands written to this slot.
+    ///
+    /// ***Note:** This does not wait on the results to be available.*
+    fn result(&mut self, slot: usize) -> Option<f32> {
+        if !std::mem::replace(&mut self.written[slot], false) {
+            return None;
+        }
+
+        let mut timestamps = [0_u64; 2];
+        let first_query = slot as u32 * 2;
+
+        match self.query_pool.get_results(
+            first_query..(first_query + 2),
+            &mut timestamps,
+            QueryResultFlags::empty(),
+        ) {
+            Ok(true) => {
+                let ticks = timestamps[1].wrapping_sub(timestamps[0]) & self.timestamp_mask;
+                Some((ticks as f64 * self.timestamp_period as f64 / 1_000_000.0) as f32)
+            },
+            _ => None,
+        }
+    }
+
+    fn begin(
+        &mut self,
+        slot: usize,
+        cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    ) {
+        let first_query = slot as u32 * 2;
+
+        unsafe {
+            cmd_builder
+                .reset_query_pool(self.query_pool.clone(), first_query..(first_query + 2))
+                .unwrap()
+                .write_timestamp(
+                    self.query_pool.clone(),
+                    first_query,
+                    PipelineStage::TopOfPipe,
+                )
+                .unwrap();
+        }
+    }
+
+    fn end(
+        &mut self,
+        slot: usize,
+        cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    ) {
+        unsafe {
+            cmd_builder
+                .write_timestamp(
+                    self.query_pool.clone(),
+                    (slot as u32 * 2) + 1,
+                    PipelineStage::BottomOfPipe,
+                )
+                .unwrap();
+        }
+
+        self.written[slot] = true;
+    }
+}
+
 /// Provides rendering for a window.
 pub struct Renderer {
     window: Arc<Window>,
     render_event_recv: Receiver<RenderEvent>,
+    worker_handle: Option<JoinHandle<()>>,
     surface_format: Format,
     surface_colorspace: ColorSpace,
+    color_space: RendererColorSpace,
     fullscreen_mode: FullScreenExclusive,
     win32_monitor: Option<Win32Monitor>,
     queue: Arc<Queue>,
@@ -247,6 +606,7 @@ pub struct Renderer {
     desc_layout: Option<Arc<DescriptorSetLayout>>,
     sampler: Arc<Sampler>,
     default_image: Arc<ImageView>,
+    msaa: MSAA,
     draw_state: Option<DrawState>,
 }
 
@@ -268,43 +628,11 @@ impl Renderer {
             false => (FullScreenExclusive::Default, None),
         };
 
-        let mut surface_formats = window.surface_formats(fullscreen_mode);
-
-        /*let ext_swapchain_colorspace = window
-        .basalt_ref()
-        .instance_ref()
-        .enabled_extensions()
-        .ext_swapchain_colorspace;*/
-
-        surface_formats.retain(|(format, colorspace)| {
-            if !match colorspace {
-                ColorSpace::SrgbNonLinear => true,
-                // TODO: Support these properly, these are for hdr mainly. Typically the format
-                //       is a signed float where values are allowed to be less than zero or greater
-                //       one. The main problem currently is that anything that falls in the normal
-                //       range don't appear as bright as one would expect on a hdr display.
-                // ColorSpace::ExtendedSrgbLinear => ext_swapchain_colorspace,
-                // ColorSpace::ExtendedSrgbNonLinear => ext_swapchain_colorspace,
-                _ => false,
-            } {
-                return false;
-            }
-
-            // TODO: Support non SRGB formats properly. When writing to a non-SRGB format using the
-            //       SrgbNonLinear colorspace, colors written will be assumed to be SRGB. This
-            //       causes issues since everything is done with linear color.
-            if format.numeric_format_color() != Some(NumericFormat::SRGB) {
-                return false;
-            }
-
-            true
-        });
-
-        surface_formats.sort_by_key(|(format, _colorspace)| format.components()[0]);
-
-        let (surface_format, surface_colorspace) = surface_formats.pop().ok_or(String::from(
-            "Unable to find suitable format & colorspace for the swapchain.",
-        ))?;
+        let (surface_format, surface_colorspace, color_space) = select_surface_format(
+            &window,
+            fullscreen_mode,
+            window.basalt_ref().config.render_default_color_space,
+        )?;
 
         let image_format = if surface_format.components()[0] > 8 {
             vec![
@@ -360,7 +688,7 @@ impl Renderer {
 
         let (render_event_send, render_event_recv) = flume::unbounded();
 
-        worker::spawn(
+        let worker_handle = worker::spawn(
             window.clone(),
             window_event_recv,
             render_event_send,
@@ -368,6 +696,8 @@ impl Renderer {
         )?;
 
         let queue = window.basalt_ref().graphics_queue();
+        let msaa = find_msaa(queue.device(), window.renderer_msaa());
+        window.set_renderer_active_msaa(Some(msaa));
 
         let cmd_alloc = StandardCommandBufferAllocator::new(
             queue.device().clone(),
@@ -413,7 +743,7 @@ impl Renderer {
 
             cmd_builder
                 .clear_color_image(ClearColorImageInfo {
-                    clear_value: draw::clear_color_value_for_format(image_format),
+                    clear_value: draw::clear_color_value_for_format(image_format, Color::default()),
                     ..ClearColorImageInfo::image(image.clone())
                 })
                 .unwrap();
@@ -444,8 +774,10 @@ impl Renderer {
         Ok(Self {
             window,
             render_event_recv,
+            worker_handle: Some(worker_handle),
             surface_format,
             surface_colorspace,
+            color_space,
             fullscreen_mode,
             win32_monitor,
             queue,
@@ -456,11 +788,69 @@ impl Renderer {
             desc_layout: None,
             sampler,
             default_image,
+            msaa,
             draw_state: None,
         })
     }
 
-    fn create_desc_set(&mut self, images: Vec<Arc<Image>>) -> Arc<PersistentDescriptorSet> {
+    /// The MSAA currently used by this renderer.
+    ///
+    /// ***Note:** This differs from `Window::renderer_msaa` when its sample count isn't supported
+    /// by the device.*
+    pub fn current_msaa(&self) -> MSAA {
+        self.msaa
+    }
+
+    /// Change the MSAA returning `true` if the draw state was changed.
+    fn set_msaa(&mut self, msaa: MSAA) -> bool {
+        let msaa = find_msaa(self.queue.device(), msaa);
+
+        if msaa == self.msaa {
+            return false;
+        }
+
+        self.msaa = msaa;
+        self.window.set_renderer_active_msaa(Some(msaa));
+
+        match self.draw_state.as_mut() {
+            Some(draw_state) => {
+                draw_state.update_msaa(
+                    self.queue.device().clone(),
+                    self.surface_format,
+                    self.desc_image_capacity,
+                    msaa,
+                );
+
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Create the descriptor sets used to draw the interface & its layers.
+    ///
+    /// The images of the layers are placed after the provided images. As a layer can't be
+    /// sampled while it is drawn onto, the second set excludes them.
+    fn create_desc_sets(&mut self, images: &[Arc<Image>]) -> [Arc<PersistentDescriptorSet>; 2] {
+        let image_views = images
+            .iter()
+            .map(|image| ImageView::new_default(image.clone()).unwrap())
+            .collect::<Vec<_>>();
+
+        let layer_views = self.draw_state.as_ref().unwrap().layer_views();
+
+        if layer_views.is_empty() {
+            let desc_set = self.create_desc_set(image_views);
+            return [desc_set.clone(), desc_set];
+        }
+
+        let desc_set =
+            self.create_desc_set(image_views.iter().cloned().chain(layer_views).collect());
+
+        [desc_set, self.create_desc_set(image_views)]
+    }
+
+    fn create_desc_set(&mut self, images: Vec<Arc<ImageView>>) -> Arc<PersistentDescriptorSet> {
         if images.len() as u32 > self.desc_image_capacity {
             while self.desc_image_capacity < images.len() as u32 {
                 self.desc_image_capacity *= 2;
@@ -501,7 +891,6 @@ impl Renderer {
                     0,
                     images
                         .into_iter()
-                        .map(|image| ImageView::new_default(image).unwrap())
                         .chain((0..num_default_images).map(|_| self.default_image.clone())),
                 ),
             ],
@@ -510,15 +899,46 @@ impl Renderer {
         .unwrap()
     }
 
+    /// Set the color space of this renderer's output.
+    ///
+    /// ***Note:** If the color space isn't supported, `RendererColorSpace::Srgb` is used instead.*
+    pub fn with_color_space(mut self, color_space: RendererColorSpace) -> Result<Self, String> {
+        let (surface_format, surface_colorspace, color_space) =
+            select_surface_format(&self.window, self.fullscreen_mode, color_space)?;
+
+        self.surface_format = surface_format;
+        self.surface_colorspace = surface_colorspace;
+        self.color_space = color_space;
+
+        if let Some(draw_state) = self.draw_state.as_mut() {
+            draw_state.update_msaa(
+                self.queue.device().clone(),
+                self.surface_format,
+                self.desc_image_capacity,
+                self.msaa,
+            );
+        }
+
+        Ok(self)
+    }
+
+    /// The color space of this renderer's output.
+    pub fn color_space(&self) -> RendererColorSpace {
+        self.color_space
+    }
+
     /// This renderer will only render an interface.
     pub fn with_interface_only(mut self) -> Self {
-        self.draw_state = Some(DrawState::interface_only(
+        let mut draw_state = DrawState::interface_only(
             self.queue.device().clone(),
             self.surface_format,
             self.desc_image_capacity,
-            self.window.renderer_msaa(),
-        ));
+            self.msaa,
+            self.window.renderer_post_process(),
+        );
 
+        draw_state.set_clear_color(self.window.renderer_clear_color());
+        self.draw_state = Some(draw_state);
         self
     }
 
@@ -531,13 +951,71 @@ impl Renderer {
             self.queue.device().clone(),
             self.surface_format,
             self.desc_image_capacity,
-            self.window.renderer_msaa(),
+            self.msaa,
+            self.window.renderer_post_process(),
             user_renderer,
         ));
 
         self
     }
 
+    /// Draw into images provided by the user instead of the window's surface.
+    ///
+    /// `target_format` is the format of the images that will be provided to
+    /// `ExternalRenderer::draw`.
+    ///
+    /// ***Note:** Colors are written linearly. If the user expects sRGB encoded data, an SRGB
+    /// format should be used.*
+    pub fn into_external(mut self, target_format: Format) -> Result<ExternalRenderer, String> {
+        let msaa = self.msaa;
+
+        let draw_state = self.draw_state.as_mut().ok_or_else(|| {
+            String::from(
+                "One of the methods `with_interface_only` or `with_user_renderer` must be called \
+                 before this method.",
+            )
+        })?;
+
+        draw_state.set_post_process(
+            self.queue.device().clone(),
+            target_format,
+            self.window.renderer_post_process(),
+        );
+
+        draw_state.update_msaa(
+            self.queue.device().clone(),
+            target_format,
+            self.desc_image_capacity,
+            msaa,
+        );
+
+        self.surface_format = target_format;
+        Ok(ExternalRenderer::new(self))
+    }
+
+    /// Called once the worker has exited. If the worker panicked, the panic is reported to the
+    /// window and returned as an error.
+    fn worker_exit(&mut self) -> Result<(), String> {
+        let worker_handle = match self.worker_handle.take() {
+            Some(some) => some,
+            None => return Ok(()),
+        };
+
+        match worker_handle.join() {
+            Ok(_) => Ok(()),
+            Err(payload) => {
+                let error = format!(
+                    "The renderer's worker has panicked: {}",
+                    worker::panic_message(&*payload)
+                );
+
+                println!("[Basalt]: {:?}: {}", self.window.id(), error);
+                self.window.renderer_error(error.clone());
+                Err(error)
+            },
+        }
+    }
+
     /// Start running the the renderer.
     pub fn run(mut self) -> Result<(), String> {
         if self.draw_state.is_none() {
@@ -552,7 +1030,7 @@ impl Renderer {
             .enabled_extensions()
             .ext_swapchain_maintenance1
         {
-            let capabilities = self.window.surface_capabilities(self.fullscreen_mode);
+            let capabilities = self.window.vk_surface_capabilities(self.fullscreen_mode);
 
             let scaling = if capabilities
                 .supported_present_scaling
@@ -578,7 +1056,11 @@ impl Renderer {
         };
 
         let mut swapchain_create_info = SwapchainCreateInfo {
-            min_image_count: 2,
+            min_image_count: find_image_count(
+                &self.window,
+                self.fullscreen_mode,
+                self.window.renderer_image_count(),
+            ),
             image_format: self.surface_format,
             image_color_space: self.surface_colorspace,
             image_extent: self.window.surface_current_extent(self.fullscreen_mode),
@@ -588,6 +1070,7 @@ impl Renderer {
                 self.fullscreen_mode,
                 self.window.renderer_vsync(),
             ),
+            composite_alpha: find_composite_alpha(&self.window, self.fullscreen_mode),
             full_screen_exclusive: self.fullscreen_mode,
             win32_monitor: self.win32_monitor,
             scaling_behavior,
@@ -608,9 +1091,12 @@ impl Renderer {
         let mut swapchain_op: Option<Arc<Swapchain>> = None;
         let mut swapchain_views_op = None;
         let mut buffer_op = None;
-        let mut desc_set_op = None;
+        let mut images_op: Option<Vec<Arc<Image>>> = None;
+        let mut layers: Vec<Range<DeviceSize>> = Vec::new();
+        let mut desc_sets_op = None;
         let mut recreate_swapchain = true;
         let mut update_after_acquire_wait = None;
+        let mut swapchain_damage: Vec<Damage> = Vec::new();
         let conservative_draw = self.window.basalt_ref().config.render_default_consv_draw;
         let mut conservative_draw_ready = true;
         let mut exclusive_fullscreen_acquired = false;
@@ -618,6 +1104,9 @@ impl Renderer {
         let mut release_exclusive_fullscreen = false;
         let mut previous_frame_op: Option<FenceSignalFuture<Box<dyn GpuFuture>>> = None;
         let mut pending_render_events = Vec::new();
+        let mut pending_msaa = None;
+        let mut fps_limit = self.window.renderer_fps_limit();
+        let mut last_frame_inst = Instant::now();
 
         let mut metrics_state_op =
             if self.window.renderer_metrics_level() >= RendererMetricsLevel::Basic {
@@ -626,6 +1115,10 @@ impl Renderer {
                 None
             };
 
+        let mut track_gpu_exec = self.window.renderer_metrics_level() == RendererMetricsLevel::Full;
+        let mut gpu_timer_op: Option<GpuTimer> = None;
+        let mut gpu_timer_supported = true;
+
         'render_loop: loop {
             assert!(update_after_acquire_wait.is_none());
 
@@ -633,18 +1126,21 @@ impl Renderer {
                 pending_render_events.append(&mut self.render_event_recv.drain().collect());
 
                 if pending_render_events.is_empty() && self.render_event_recv.is_disconnected() {
-                    return Ok(());
+                    return self.worker_exit();
                 }
 
                 for render_event in pending_render_events.drain(..) {
                     match render_event {
                         RenderEvent::Redraw => {
+                            swapchain_damage.fill(Damage::Full);
                             conservative_draw_ready = true;
                         },
                         RenderEvent::Update {
                             buffer,
                             images,
+                            layers: update_layers,
                             barrier,
+                            damage,
                             metrics,
                         } => {
                             if swapchain_op.is_none()
@@ -655,28 +1151,35 @@ impl Renderer {
                                 }
 
                                 buffer_op = Some(buffer);
-                                desc_set_op = Some(self.create_desc_set(images));
+                                images_op = Some(images);
+                                layers = update_layers;
+                                desc_sets_op = None;
+                                swapchain_damage.iter_mut().for_each(|d| d.add(damage));
                                 barrier.wait();
                             } else {
-                                update_after_acquire_wait = Some((buffer, images, barrier));
+                                update_after_acquire_wait =
+                                    Some((buffer, images, update_layers, barrier, damage));
                             }
 
                             if let Some(metrics_state) = metrics_state_op.as_mut() {
+                                if let Some(worker_metrics) = metrics.as_ref() {
+                                    self.window.track_worker_metrics(worker_metrics);
+                                }
+
                                 metrics_state.track_update(metrics);
                             }
 
                             conservative_draw_ready = true;
                         },
-                        RenderEvent::Resize {
-                            ..
-                        } => {
+                        RenderEvent::Resize => {
                             recreate_swapchain = true;
-                            swapchain_create_info.image_extent =
-                                self.window.surface_current_extent(self.fullscreen_mode);
-                            viewport.extent = [
-                                swapchain_create_info.image_extent[0] as f32,
-                                swapchain_create_info.image_extent[1] as f32,
-                            ];
+
+                            update_extent(
+                                self.window.surface_current_extent(self.fullscreen_mode),
+                                &mut swapchain_create_info,
+                                &mut viewport,
+                            );
+
                             conservative_draw_ready = true;
                         },
                         RenderEvent::SetVSync(vsync) => {
@@ -689,14 +1192,30 @@ impl Renderer {
                                 conservative_draw_ready = true;
                             }
                         },
+                        RenderEvent::SetImageCount(count) => {
+                            let min_image_count =
+                                find_image_count(&self.window, self.fullscreen_mode, count);
+
+                            if swapchain_create_info.min_image_count != min_image_count {
+                                swapchain_create_info.min_image_count = min_image_count;
+                                recreate_swapchain = true;
+                                conservative_draw_ready = true;
+                            }
+                        },
+                        RenderEvent::SetFPSLimit(limit) => {
+                            fps_limit = limit;
+                        },
                         RenderEvent::SetMSAA(msaa) => {
+                            pending_msaa = Some(msaa);
+                            conservative_draw_ready = true;
+                        },
+                        RenderEvent::SetPostProcess(post_process) => {
                             let draw_state = self.draw_state.as_mut().unwrap();
 
-                            draw_state.update_msaa(
+                            draw_state.set_post_process(
                                 self.queue.device().clone(),
                                 self.surface_format,
-                                self.desc_image_capacity,
-                                msaa,
+                                post_process,
                             );
 
                             if let Some(swapchain_views) = swapchain_views_op.clone() {
@@ -707,6 +1226,12 @@ impl Renderer {
                                 );
                             }
 
+                            swapchain_damage.fill(Damage::Full);
+                            conservative_draw_ready = true;
+                        },
+                        RenderEvent::SetClearColor(color) => {
+                            self.draw_state.as_mut().unwrap().set_clear_color(color);
+                            swapchain_damage.fill(Damage::Full);
                             conservative_draw_ready = true;
                         },
                         RenderEvent::SetMetrics(level) => {
@@ -717,20 +1242,44 @@ impl Renderer {
                             } else {
                                 metrics_state_op = None;
                             }
+
+                            track_gpu_exec = level == RendererMetricsLevel::Full;
+
+                            if !track_gpu_exec {
+                                gpu_timer_op = None;
+                            }
                         },
                         RenderEvent::WindowFullscreenEnabled => {
                             if self.fullscreen_mode == FullScreenExclusive::ApplicationControlled {
                                 acquire_exclusive_fullscreen = true;
                                 release_exclusive_fullscreen = false;
-                                conservative_draw_ready = true;
                             }
+
+                            // NOTE: The display mode may have changed, so the extent is checked
+                            //       instead of waiting for the resize event.
+                            recreate_swapchain |= update_extent(
+                                self.window.surface_current_extent(self.fullscreen_mode),
+                                &mut swapchain_create_info,
+                                &mut viewport,
+                            );
+
+                            conservative_draw_ready = true;
                         },
                         RenderEvent::WindowFullscreenDisabled => {
                             if self.fullscreen_mode == FullScreenExclusive::ApplicationControlled {
                                 acquire_exclusive_fullscreen = false;
                                 release_exclusive_fullscreen = true;
-                                conservative_draw_ready = true;
                             }
+
+                            // NOTE: The display mode may have changed, so the extent is checked
+                            //       instead of waiting for the resize event.
+                            recreate_swapchain |= update_extent(
+                                self.window.surface_current_extent(self.fullscreen_mode),
+                                &mut swapchain_create_info,
+                                &mut viewport,
+                            );
+
+                            conservative_draw_ready = true;
                         },
                     }
                 }
@@ -741,13 +1290,43 @@ impl Renderer {
                 {
                     match self.render_event_recv.recv() {
                         Ok(ok) => pending_render_events.push(ok),
-                        Err(_) => return Ok(()),
+                        Err(_) => return self.worker_exit(),
                     }
                 } else {
                     break;
                 }
             }
 
+            if let Some(limit) = fps_limit {
+                let frame_interval = Duration::from_secs(1) / limit;
+                let elapsed = last_frame_inst.elapsed();
+
+                if elapsed < frame_interval {
+                    thread::sleep(frame_interval - elapsed);
+                }
+            }
+
+            last_frame_inst = Instant::now();
+
+            // NOTE: Changes to MSAA are applied here instead of when received, so that several
+            //       changes before a frame, e.g. from a slider, only recreate the attachments once
+            //       and the next frame is fully drawn with the new attachments.
+            if let Some(msaa) = pending_msaa.take() {
+                if self.set_msaa(msaa) {
+                    if !recreate_swapchain {
+                        if let Some(swapchain_views) = swapchain_views_op.clone() {
+                            self.draw_state.as_mut().unwrap().update_framebuffers(
+                                &self.mem_alloc,
+                                &self.desc_alloc,
+                                swapchain_views,
+                            );
+                        }
+                    }
+
+                    swapchain_damage.fill(Damage::Full);
+                }
+            }
+
             if recreate_swapchain {
                 loop {
                     if let Some(previous_frame) = previous_frame_op.take() {
@@ -786,6 +1365,13 @@ impl Renderer {
                         Err(e) => panic!("Unhandled error: {:?}", e),
                     };
 
+                    self.window
+                        .set_renderer_swapchain_image_count(Some(swapchain.image_count()));
+
+                    self.window.set_surface_format(Some(SurfaceFormat {
+                        format: swapchain.image_format(),
+                        color_space: SurfaceColorSpace::from_vk(swapchain.image_color_space()),
+                    }));
                     swapchain_op = Some(swapchain);
                     swapchain_views_op = Some(
                         swapchain_images
@@ -800,6 +1386,8 @@ impl Renderer {
                         swapchain_views_op.clone().unwrap(),
                     );
 
+                    swapchain_damage =
+                        vec![Damage::Full; swapchain_views_op.as_ref().unwrap().len()];
                     recreate_swapchain = false;
                     break;
                 }
@@ -853,9 +1441,14 @@ impl Renderer {
                         previous_frame.wait(None).unwrap();
                     }
 
-                    if let Some((buffer, images, barrier)) = update_after_acquire_wait.take() {
+                    if let Some((buffer, images, update_layers, barrier, damage)) =
+                        update_after_acquire_wait.take()
+                    {
                         buffer_op = Some(buffer);
-                        desc_set_op = Some(self.create_desc_set(images));
+                        images_op = Some(images);
+                        layers = update_layers;
+                        desc_sets_op = None;
+                        swapchain_damage.iter_mut().for_each(|d| d.add(damage));
                         barrier.wait();
                     }
 
@@ -873,9 +1466,14 @@ impl Renderer {
                 metrics_state.track_acquire();
             }
 
-            if let Some((buffer, images, barrier)) = update_after_acquire_wait.take() {
+            if let Some((buffer, images, update_layers, barrier, damage)) =
+                update_after_acquire_wait.take()
+            {
                 buffer_op = Some(buffer);
-                desc_set_op = Some(self.create_desc_set(images));
+                images_op = Some(images);
+                layers = update_layers;
+                desc_sets_op = None;
+                swapchain_damage.iter_mut().for_each(|d| d.add(damage));
                 barrier.wait();
             }
 
@@ -886,14 +1484,87 @@ impl Renderer {
             )
             .unwrap();
 
-            self.draw_state.as_mut().unwrap().draw(
+            // NOTE: Partial draws rely on the swapchain image retaining the previous contents, so
+            //       each swapchain image tracks the damage since it was last drawn.
+
+            let damage = std::mem::replace(&mut swapchain_damage[image_num as usize], Damage::None);
+
+            if track_gpu_exec && gpu_timer_supported {
+                if gpu_timer_op.as_ref().map(|gpu_timer| gpu_timer.slots())
+                    != Some(swapchain_damage.len())
+                {
+                    gpu_timer_op = GpuTimer::new(&self.queue, swapchain_damage.len());
+                    gpu_timer_supported = gpu_timer_op.is_some();
+                }
+
+                if let Some(gpu_timer) = gpu_timer_op.as_mut() {
+                    if let Some(gpu_exec_time) = gpu_timer.result(image_num as usize) {
+                        if let Some(metrics_state) = metrics_state_op.as_mut() {
+                            metrics_state.track_gpu_exec(gpu_exec_time);
+                        }
+                    }
+
+                    gpu_timer.begin(image_num as usize, &mut cmd_builder);
+                }
+            }
+
+            if self
+                .draw_state
+                .as_mut()
+                .unwrap()
+                .update_layers(&self.mem_alloc, layers.len())
+            {
+                desc_sets_op = None;
+            }
+
+            if desc_sets_op.is_none() {
+                desc_sets_op = Some(self.create_desc_sets(images_op.as_ref().unwrap()));
+            }
+
+            let [desc_set, layer_desc_set] = desc_sets_op.clone().unwrap();
+            let draw_state = self.draw_state.as_mut().unwrap();
+
+            let damage_rect = if conservative_draw && draw_state.supports_partial_draw() {
+                damage.pixel_rect(swapchain_create_info.image_extent)
+            } else {
+                None
+            };
+
+            draw_state.draw(
                 buffer_op.as_ref().unwrap().clone(),
-                desc_set_op.as_ref().unwrap().clone(),
+                &layers,
+                desc_set,
+                layer_desc_set,
                 image_num as usize,
                 viewport.clone(),
+                damage_rect,
                 &mut cmd_builder,
             );
 
+            if let Some(gpu_timer) = gpu_timer_op.as_mut() {
+                gpu_timer.end(image_num as usize, &mut cmd_builder);
+            }
+
+            let mut present_info = SwapchainPresentInfo::swapchain_image_index(
+                swapchain_op.clone().unwrap(),
+                image_num,
+            );
+
+            if let Some([x, y, width, height]) = damage_rect {
+                if self
+                    .queue
+                    .device()
+                    .enabled_extensions()
+                    .khr_incremental_present
+                {
+                    present_info.present_regions = vec![RectangleLayer {
+                        offset: [x, y],
+                        extent: [width, height],
+                        layer: 0,
+                    }];
+                }
+            }
+
             let cmd_buffer = cmd_builder.build().unwrap();
 
             if let Some(metrics_state) = metrics_state_op.as_mut() {
@@ -910,13 +1581,7 @@ impl Renderer {
                         .join(acquire_future)
                         .then_execute(self.queue.clone(), cmd_buffer)
                         .unwrap()
-                        .then_swapchain_present(
-                            self.queue.clone(),
-                            SwapchainPresentInfo::swapchain_image_index(
-                                swapchain_op.as_ref().unwrap().clone(),
-                                image_num,
-                            ),
-                        )
+                        .then_swapchain_present(self.queue.clone(), present_info)
                         .boxed()
                         .then_signal_fence_and_flush()
                         .map_err(|e| e.unwrap())
@@ -925,13 +1590,7 @@ impl Renderer {
                     acquire_future
                         .then_execute(self.queue.clone(), cmd_buffer)
                         .unwrap()
-                        .then_swapchain_present(
-                            self.queue.clone(),
-                            SwapchainPresentInfo::swapchain_image_index(
-                                swapchain_op.as_ref().unwrap().clone(),
-                                image_num,
-                            ),
-                        )
+                        .then_swapchain_present(self.queue.clone(), present_info)
                         .boxed()
                         .then_signal_fence_and_flush()
                         .map_err(|e| e.unwrap())
@@ -940,6 +1599,7 @@ impl Renderer {
                 Ok(future) => {
                     conservative_draw_ready = false;
                     previous_frame_op = Some(future);
+                    self.window.frame_presented();
                 },
                 Err(VulkanError::OutOfDate) => recreate_swapchain = true,
                 Err(e) => panic!("Unhandled error: {:?}", e),
@@ -948,6 +1608,139 @@ impl Renderer {
     }
 }
 
+fn select_surface_format(
+    window: &Arc<Window>,
+    fullscreen_mode: FullScreenExclusive,
+    color_space: RendererColorSpace,
+) -> Result<(Format, ColorSpace, RendererColorSpace), String> {
+    let mut surface_formats = window.surface_formats(fullscreen_mode);
+
+    if color_space == RendererColorSpace::ExtendedSrgb {
+        let ext_swapchain_colorspace = window
+            .basalt_ref()
+            .instance_ref()
+            .enabled_extensions()
+            .ext_swapchain_colorspace;
+
+        // NOTE: Extended sRGB requires a signed float format.
+        let extended_op = surface_formats
+            .iter()
+            .filter(|(format, colorspace)| {
+                ext_swapchain_colorspace
+                    && *colorspace == ColorSpace::ExtendedSrgbLinear
+                    && format.numeric_format_color() == Some(NumericFormat::SFLOAT)
+            })
+            .max_by_key(|(format, _colorspace)| format.components()[0]);
+
+        if let Some((format, colorspace)) = extended_op {
+            return Ok((*format, *colorspace, RendererColorSpace::ExtendedSrgb));
+        }
+
+        println!(
+            "[Basalt]: Extended sRGB color space isn't supported by the surface, falling back to \
+             sRGB."
+        );
+    }
+
+    surface_formats.retain(|(format, colorspace)| {
+        if *colorspace != ColorSpace::SrgbNonLinear {
+            return false;
+        }
+
+        // TODO: Support non SRGB formats properly. When writing to a non-SRGB format using the
+        //       SrgbNonLinear colorspace, colors written will be assumed to be SRGB. This
+        //       causes issues since everything is done with linear color.
+        if format.numeric_format_color() != Some(NumericFormat::SRGB) {
+            return false;
+        }
+
+        true
+    });
+
+    surface_formats.sort_by_key(|(format, _colorspace)| format.components()[0]);
+
+    let (surface_format, surface_colorspace) = surface_formats.pop().ok_or(String::from(
+        "Unable to find suitable format & colorspace for the swapchain.",
+    ))?;
+
+    Ok((surface_format, surface_colorspace, RendererColorSpace::Srgb))
+}
+
+/// Find the MSAA supported by the device nearest to the one provided, preferring lower sample
+/// counts.
+fn find_msaa(device: &Arc<Device>, msaa: MSAA) -> MSAA {
+    let supported = device
+        .physical_device()
+        .properties()
+        .framebuffer_color_sample_counts;
+
+    let found = [MSAA::X8, MSAA::X4, MSAA::X2]
+        .into_iter()
+        .skip_while(|candidate| *candidate != msaa)
+        .find(|candidate| supported.contains_enum(candidate.sample_count()))
+        .unwrap_or(MSAA::X1);
+
+    if found != msaa {
+        println!(
+            "[Basalt]: MSAA::{:?} isn't supported by the device, using MSAA::{:?} instead.",
+            msaa, found
+        );
+    }
+
+    found
+}
+
+// NOTE: Returns true if the extent changed, in which case the swapchain must be recreated.
+fn update_extent(
+    extent: [u32; 2],
+    swapchain_create_info: &mut SwapchainCreateInfo,
+    viewport: &mut Viewport,
+) -> bool {
+    let changed = swapchain_create_info.image_extent != extent;
+    swapchain_create_info.image_extent = extent;
+    viewport.extent = [extent[0] as f32, extent[1] as f32];
+    changed
+}
+
+fn find_image_count(window: &Arc<Window>, fullscreen_mode: FullScreenExclusive, count: u32) -> u32 {
+    let capabilities = window.vk_surface_capabilities(fullscreen_mode);
+    let mut count = count.max(capabilities.min_image_count);
+
+    if let Some(max_image_count) = capabilities.max_image_count {
+        count = count.min(max_image_count);
+    }
+
+    count
+}
+
+fn find_composite_alpha(
+    window: &Arc<Window>,
+    fullscreen_mode: FullScreenExclusive,
+) -> CompositeAlpha {
+    let supported = window
+        .vk_surface_capabilities(fullscreen_mode)
+        .supported_composite_alpha;
+
+    // NOTE: The output of the renderer is premultiplied.
+    let preferred: &[CompositeAlpha] = match window.is_transparent() {
+        true => {
+            &[
+                CompositeAlpha::PreMultiplied,
+                CompositeAlpha::PostMultiplied,
+                CompositeAlpha::Inherit,
+                CompositeAlpha::Opaque,
+            ]
+        },
+        false => &[CompositeAlpha::Opaque, CompositeAlpha::Inherit],
+    };
+
+    preferred
+        .iter()
+        .copied()
+        .find(|composite_alpha| supported.contains_enum(*composite_alpha))
+        .unwrap_or_else(|| supported.into_iter().next().unwrap())
+}
+
 fn find_present_mode(
     window: &Arc<Window>,
     fullscreen_mode: FullScreenExclusive,
@@ -990,3 +1783,50 @@ fn find_present_mode(
 
     present_modes.pop().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use vulkano::pipeline::graphics::viewport::Viewport;
+    use vulkano::swapchain::SwapchainCreateInfo;
+
+    use super::update_extent;
+
+    #[test]
+    fn fullscreen_extent_recreates() {
+        let mut swapchain_create_info = SwapchainCreateInfo {
+            image_extent: [800, 600],
+            ..SwapchainCreateInfo::default()
+        };
+
+        let mut viewport = Viewport {
+            offset: [0.0, 0.0],
+            extent: [800.0, 600.0],
+            depth_range: 0.0..=1.0,
+        };
+
+        // NOTE: Entering fullscreen changes the extent of the surface.
+        assert!(update_extent(
+            [1920, 1080],
+            &mut swapchain_create_info,
+            &mut viewport
+        ));
+
+        assert_eq!(swapchain_create_info.image_extent, [1920, 1080]);
+        assert_eq!(viewport.extent, [1920.0, 1080.0]);
+
+        // NOTE: A transition that doesn't change the extent doesn't recreate the swapchain.
+        assert!(!update_extent(
+            [1920, 1080],
+            &mut swapchain_create_info,
+            &mut viewport
+        ));
+
+        assert!(update_extent(
+            [800, 600],
+            &mut swapchain_create_info,
+            &mut viewport
+        ));
+
+        assert_eq!(swapchain_create_info.image_extent, [800, 600]);
+    }
+}
@@ -6,6 +6,7 @@ layout(location = 0) in vec2 coords;
 layout(location = 1) in vec4 color;
 layout(location = 2) in flat int type;
 layout(location = 3) in flat uint tex_i;
+layout(location = 4) in float opacity;
 
 layout(location = 0) out vec4 out_color;
 
@@ -79,4 +80,17 @@ void main() {
             textureLod(sampler2D(images[nonuniformEXT(tex_i)], image_sampler), coords, 0).r
         );
     }
+    else if(type == 109) { // LayerComposite
+        // The layer is premultiplied and has the same extent as the target.
+        out_color = texelFetch(
+            sampler2D(images[nonuniformEXT(tex_i)], image_sampler),
+            ivec2(gl_FragCoord.xy),
+            0
+        ) * (color.a * opacity);
+
+        return;
+    }
+
+    out_color.a *= opacity;
+    out_color.rgb *= out_color.a;
 }
\ No newline at end of file
@@ -6,16 +6,19 @@ layout(location = 1) in vec2 coords;
 layout(location = 2) in vec4 color;
 layout(location = 3) in int ty;
 layout(location = 4) in uint tex_i;
+layout(location = 5) in float opacity;
 
 layout(location = 0) out vec2 out_coords;
 layout(location = 1) out vec4 out_color;
 layout(location = 2) out flat int out_type;
 layout(location = 3) out flat uint out_tex_i;
+layout(location = 4) out float out_opacity;
 
 void main() {
     out_coords = coords;
     out_color = color;
     out_type = ty;
     out_tex_i = tex_i;
+    out_opacity = opacity;
     gl_Position = vec4(position, 1.0);
 }
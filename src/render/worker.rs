@@ -2,9 +2,11 @@
 // This should be solved in a future vulkano release.
 #![allow(clippy::mutable_key_type)]
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::{AddAssign, DivAssign, Range};
 use std::sync::{Arc, Barrier, Weak};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
 use cosmic_text::fontdb::Source as FontSource;
@@ -23,20 +25,25 @@ use vulkano::command_buffer::allocator::{
 use vulkano::command_buffer::auto::AutoCommandBufferBuilder;
 use vulkano::command_buffer::{
     BufferCopy, BufferImageCopy, CommandBufferUsage, CopyBufferInfoTyped, CopyBufferToImageInfo,
-    CopyImageInfo, PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract,
+    CopyImageInfo, CopyImageToBufferInfo, ImageCopy, PrimaryAutoCommandBuffer,
+    PrimaryCommandBufferAbstract,
 };
 use vulkano::format::Format as VkFormat;
 use vulkano::image::sys::ImageCreateInfo;
 use vulkano::image::{Image, ImageSubresourceLayers, ImageType, ImageUsage};
 use vulkano::memory::allocator::{
-    AllocationCreateInfo, MemoryAllocatePreference, MemoryTypeFilter, StandardMemoryAllocator,
+    AllocationCreateInfo, MemoryAllocatePreference, MemoryTypeFilter,
 };
 use vulkano::memory::MemoryPropertyFlags;
 use vulkano::sync::GpuFuture;
 use vulkano::DeviceSize;
 
-use crate::interface::{Bin, BinID, DefaultFont, ItfVertInfo, OVDPerfMetrics};
-use crate::render::{ImageCacheKey, ImageSource, RenderEvent, RendererMetricsLevel, UpdateContext};
+use crate::image_cache::convert::vulkan_format_to_rgba8;
+use crate::interface::{Bin, BinID, BinPlacement, DefaultFont, ItfVertInfo, OVDPerfMetrics};
+use crate::render::{
+    AtlasSnapshot, Damage, ImageCacheKey, ImageSource, RenderEvent, RenderMemory,
+    RendererMetricsLevel, UpdateContext,
+};
 use crate::window::{Window, WindowEvent};
 
 /// Performance metrics of a `Renderer`'s worker.
@@ -44,16 +51,30 @@ use crate::window::{Window, WindowEvent};
 pub struct WorkerPerfMetrics {
     pub total: f32,
     pub bins_changed: usize,
+    /// Count of changed bins that reused their previous vertex data.
+    pub bins_unchanged: usize,
     pub bin_data_remove: f32,
+    pub bin_placement: f32,
     pub bin_data_obtain: f32,
     pub image_ref_count: f32,
     pub cmd_buf_allocate: f32,
     pub clear_atlas_regions: f32,
+    pub atlas_compact: f32,
     pub images_remove: f32,
     pub images_obtain: f32,
     pub vertex_count: f32,
     pub vertex_update: f32,
     pub cmd_buf_execute: f32,
+    /// The size in bytes of the vertex & staging buffers.
+    pub vertex_buffer_bytes: DeviceSize,
+    pub atlas_count: usize,
+    pub atlas_total_bytes: DeviceSize,
+    pub atlas_used_bytes: DeviceSize,
+    /// Fragmentation ratio of each atlas.
+    ///
+    /// This is the portion of free space that is not part of the largest free region. A value
+    /// near `1.0` indicates that free space is spread into many small regions.
+    pub atlas_fragmentation: Vec<f32>,
     pub ovd_metrics: Option<OVDPerfMetrics>,
 }
 
@@ -61,16 +82,30 @@ impl AddAssign for WorkerPerfMetrics {
     fn add_assign(&mut self, mut rhs: Self) {
         self.total += rhs.total;
         self.bins_changed += rhs.bins_changed;
+        self.bins_unchanged += rhs.bins_unchanged;
         self.bin_data_remove += rhs.bin_data_remove;
+        self.bin_placement += rhs.bin_placement;
         self.bin_data_obtain += rhs.bin_data_obtain;
         self.image_ref_count += rhs.image_ref_count;
         self.cmd_buf_allocate += rhs.cmd_buf_allocate;
         self.clear_atlas_regions += rhs.clear_atlas_regions;
+        self.atlas_compact += rhs.atlas_compact;
         self.images_remove += rhs.images_remove;
         self.images_obtain += rhs.images_obtain;
         self.vertex_count += rhs.vertex_count;
         self.vertex_update += rhs.vertex_update;
         self.cmd_buf_execute += rhs.cmd_buf_execute;
+        self.vertex_buffer_bytes += rhs.vertex_buffer_bytes;
+        self.atlas_count += rhs.atlas_count;
+        self.atlas_total_bytes += rhs.atlas_total_bytes;
+        self.atlas_used_bytes += rhs.atlas_used_bytes;
+
+        for (i, fragmentation) in rhs.atlas_fragmentation.into_iter().enumerate() {
+            match self.atlas_fragmentation.get_mut(i) {
+                Some(self_fragmentation) => *self_fragmentation += fragmentation,
+                None => self.atlas_fragmentation.push(fragmentation),
+            }
+        }
 
         if let Some(rhs_ovd_metrics) = rhs.ovd_metrics.take() {
             match self.ovd_metrics.as_mut() {
@@ -89,16 +124,27 @@ impl DivAssign<f32> for WorkerPerfMetrics {
     fn div_assign(&mut self, rhs: f32) {
         self.total /= rhs;
         self.bins_changed = (self.bins_changed as f32 / rhs).trunc() as usize;
+        self.bins_unchanged = (self.bins_unchanged as f32 / rhs).trunc() as usize;
         self.bin_data_remove /= rhs;
+        self.bin_placement /= rhs;
         self.bin_data_obtain /= rhs;
         self.image_ref_count /= rhs;
         self.cmd_buf_allocate /= rhs;
         self.clear_atlas_regions /= rhs;
+        self.atlas_compact /= rhs;
         self.images_remove /= rhs;
         self.images_obtain /= rhs;
         self.vertex_count /= rhs;
         self.vertex_update /= rhs;
         self.cmd_buf_execute /= rhs;
+        self.vertex_buffer_bytes = (self.vertex_buffer_bytes as f32 / rhs).trunc() as DeviceSize;
+        self.atlas_count = (self.atlas_count as f32 / rhs).trunc() as usize;
+        self.atlas_total_bytes = (self.atlas_total_bytes as f32 / rhs).trunc() as DeviceSize;
+        self.atlas_used_bytes = (self.atlas_used_bytes as f32 / rhs).trunc() as DeviceSize;
+
+        for fragmentation in self.atlas_fragmentation.iter_mut() {
+            *fragmentation /= rhs;
+        }
 
         if let Some(ovd_metrics) = self.ovd_metrics.as_mut() {
             *ovd_metrics /= rhs;
@@ -109,7 +155,26 @@ impl DivAssign<f32> for WorkerPerfMetrics {
 struct BinState {
     weak: Weak<Bin>,
     image_sources: Vec<ImageSource>,
-    vertex_data: Option<BTreeMap<OrderedFloat<f32>, BinZData>>,
+    vertex_data: Option<BTreeMap<DrawKey, BinZData>>,
+    fingerprint: Option<BinFingerprint>,
+}
+
+/// The inputs used to obtain the vertex data of a bin.
+#[derive(PartialEq)]
+struct BinFingerprint {
+    version: u64,
+    placement: BinPlacement,
+    extent: [u32; 2],
+    scale: f32,
+}
+
+/// The order vertex data is placed in the vertex buffer.
+///
+/// Data drawn directly is placed first followed by the data of each layer, each sorted by z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct DrawKey {
+    layer: Option<BinID>,
+    z: OrderedFloat<f32>,
 }
 
 struct BinZData {
@@ -142,13 +207,388 @@ enum ImageBacking {
     },
 }
 
+impl ImageBacking {
+    fn resident_sources(&self) -> impl Iterator<Item = &ImageSource> {
+        let (contains_op, source_op) = match self {
+            Self::Atlas {
+                contains, ..
+            } => (Some(contains.keys()), None),
+            Self::Dedicated {
+                source, ..
+            }
+            | Self::UserProvided {
+                source, ..
+            } => (None, Some(source)),
+        };
+
+        contains_op.into_iter().flatten().chain(source_op)
+    }
+
+    fn use_count_mut(&mut self, image_source: &ImageSource) -> Option<&mut usize> {
+        match self {
+            Self::Atlas {
+                contains, ..
+            } => {
+                contains
+                    .get_mut(image_source)
+                    .map(|contained| &mut contained.use_count)
+            },
+            Self::Dedicated {
+                source,
+                contains,
+                ..
+            }
+            | Self::UserProvided {
+                source,
+                contains,
+                ..
+            } => (*source == *image_source).then_some(&mut contains.use_count),
+        }
+    }
+
+    // NOTE: The offset of the coordinates of a source within this backing.
+    fn coords_offset(&self, image_source: &ImageSource) -> Option<[f32; 2]> {
+        match self {
+            Self::Atlas {
+                contains, ..
+            } => {
+                contains.get(image_source).map(|contained| {
+                    [
+                        contained.data.rectangle.min.x as f32 + 1.0,
+                        contained.data.rectangle.min.y as f32 + 1.0,
+                    ]
+                })
+            },
+            Self::Dedicated {
+                source, ..
+            }
+            | Self::UserProvided {
+                source, ..
+            } => (*source == *image_source).then_some([0.0; 2]),
+        }
+    }
+}
+
+/// Fragmentation ratio at which an atlas will be compacted.
+///
+/// Compaction is only considered for atlases that had allocations removed during the update.
+const ATLAS_COMPACT_THRESHOLD: f32 = 0.5;
+
+/// The minimum length of the vertex & staging buffers.
+const MIN_VERTEX_BUFFER_LEN: DeviceSize = 32768;
+
+/// The amount of consecutive updates with low usage before the vertex buffers are shrunk.
+///
+/// Usage is considered low when less than a quarter of the capacity is used.
+const VERTEX_BUFFER_SHRINK_UPDATES: usize = 120;
+
+#[derive(Clone)]
 enum OVDEvent {
     AddBinaryFont(Arc<dyn AsRef<[u8]> + Sync + Send>),
     SetDefaultFont(DefaultFont),
+    SetFallbackFonts(Vec<String>),
     SetExtent([u32; 2]),
     SetScale(f32),
     SetMetrics(RendererMetricsLevel),
-    PerformOVD,
+    PerformOVD(Arc<BTreeMap<BinID, BinPlacement>>),
+}
+
+type OVDData = (
+    BinID,
+    HashSet<ImageSource>,
+    BTreeMap<DrawKey, BinZData>,
+    Option<OVDPerfMetrics>,
+);
+
+struct OVDThread {
+    event_send: Sender<OVDEvent>,
+    handle: JoinHandle<()>,
+}
+
+/// The threads used to obtain the vertex data of bins.
+///
+/// The state needed to create an `UpdateContext` is kept, so that threads can be added after
+/// fonts have been loaded or settings have been changed.
+struct OVDPool {
+    font_system: FontSystem,
+    default_font: DefaultFont,
+    fallback_fonts: Vec<String>,
+    extent: [f32; 2],
+    scale: f32,
+    metrics_level: RendererMetricsLevel,
+    data_send: Sender<OVDData>,
+    data_recv: Receiver<OVDData>,
+    bin_send: Sender<Option<Arc<Bin>>>,
+    bin_recv: Receiver<Option<Arc<Bin>>>,
+    threads: Vec<OVDThread>,
+}
+
+impl OVDPool {
+    fn new(
+        window: &Arc<Window>,
+        extent: [f32; 2],
+        scale: f32,
+        metrics_level: RendererMetricsLevel,
+    ) -> Self {
+        let interface = window.basalt_ref().interface_ref();
+        let mut font_system = FontSystem::new();
+
+        for binary_font in interface.binary_fonts() {
+            font_system
+                .db_mut()
+                .load_font_source(FontSource::Binary(binary_font));
+        }
+
+        let (data_send, data_recv) = flume::unbounded();
+        let (bin_send, bin_recv) = flume::unbounded();
+
+        Self {
+            font_system,
+            default_font: interface.default_font(),
+            fallback_fonts: interface.fallback_fonts(),
+            extent,
+            scale,
+            metrics_level,
+            data_send,
+            data_recv,
+            bin_send,
+            bin_recv,
+            threads: Vec::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Panic if any thread has exited, forwarding the panic message of that thread.
+    fn check_panicked(&mut self) {
+        let index = match self
+            .threads
+            .iter()
+            .position(|thread| thread.handle.is_finished())
+        {
+            Some(some) => some,
+            None => return,
+        };
+
+        match self.threads.swap_remove(index).handle.join() {
+            Ok(_) => panic!("an ovd thread has exited unexpectedly."),
+            Err(payload) => panic!("an ovd thread has panicked: {}", panic_message(&*payload)),
+        }
+    }
+
+    /// Grow or shrink the amount of threads.
+    ///
+    /// ***Note:** This must not be called while vertex data is being obtained.*
+    fn resize(&mut self, count: usize) {
+        let count = count.max(1);
+
+        while self.threads.len() > count {
+            let OVDThread {
+                event_send,
+                handle,
+            } = self.threads.pop().unwrap();
+
+            // NOTE: Dropping the sender causes the thread to exit once it has finished any
+            //       remaining work.
+            drop(event_send);
+
+            if let Err(payload) = handle.join() {
+                panic!("an ovd thread has panicked: {}", panic_message(&*payload));
+            }
+        }
+
+        while self.threads.len() < count {
+            let locale = self.font_system.locale().to_string();
+            let db = self.font_system.db().clone();
+
+            self.threads.push(spawn_ovd_thread(
+                UpdateContext {
+                    extent: self.extent,
+                    scale: self.scale,
+                    font_system: FontSystem::new_with_locale_and_db(locale, db),
+                    glyph_cache: SwashCache::new(),
+                    default_font: self.default_font.clone(),
+                    fallback_fonts: self.fallback_fonts.clone(),
+                    metrics_level: self.metrics_level,
+                    placement_cache: BTreeMap::new(),
+                },
+                self.data_send.clone(),
+                self.bin_recv.clone(),
+            ));
+        }
+    }
+
+    /// Send an event to every thread.
+    fn broadcast(&mut self, event: OVDEvent) {
+        match &event {
+            OVDEvent::AddBinaryFont(binary_font) => {
+                self.font_system
+                    .db_mut()
+                    .load_font_source(FontSource::Binary(binary_font.clone()));
+            },
+            OVDEvent::SetDefaultFont(default_font) => {
+                self.default_font = default_font.clone();
+            },
+            OVDEvent::SetFallbackFonts(fallback_fonts) => {
+                self.fallback_fonts = fallback_fonts.clone();
+            },
+            OVDEvent::SetExtent(extent) => {
+                self.extent = [extent[0] as f32, extent[1] as f32];
+            },
+            OVDEvent::SetScale(scale) => {
+                self.scale = *scale;
+            },
+            OVDEvent::SetMetrics(level) => {
+                self.metrics_level = *level;
+            },
+            OVDEvent::PerformOVD(_) => (),
+        }
+
+        for thread in self.threads.iter() {
+            if thread.event_send.send(event.clone()).is_err() {
+                self.check_panicked();
+                panic!("an ovd thread has panicked.");
+            }
+        }
+    }
+}
+
+fn spawn_ovd_thread(
+    mut update_context: UpdateContext,
+    data_send: Sender<OVDData>,
+    bin_recv: Receiver<Option<Arc<Bin>>>,
+) -> OVDThread {
+    let (event_send, event_recv) = flume::unbounded();
+
+    let handle = std::thread::spawn(move || {
+        while let Ok(ovd_event) = event_recv.recv() {
+            match ovd_event {
+                OVDEvent::AddBinaryFont(binary_font) => {
+                    update_context
+                        .font_system
+                        .db_mut()
+                        .load_font_source(FontSource::Binary(binary_font));
+                },
+                OVDEvent::SetDefaultFont(default_font) => {
+                    update_context.default_font = default_font;
+                },
+                OVDEvent::SetFallbackFonts(fallback_fonts) => {
+                    update_context.fallback_fonts = fallback_fonts;
+                },
+                OVDEvent::SetScale(scale) => {
+                    update_context.scale = scale;
+                },
+                OVDEvent::SetExtent(extent) => {
+                    update_context.extent = [extent[0] as f32, extent[1] as f32];
+                },
+                OVDEvent::SetMetrics(level) => {
+                    update_context.metrics_level = level;
+                },
+                OVDEvent::PerformOVD(placements) => {
+                    update_context.placement_cache = BTreeMap::clone(&placements);
+
+                    while let Ok(Some(bin)) = bin_recv.recv() {
+                        let id = bin.id();
+                        let (obtained_data, ovd_metrics_op) =
+                            bin.obtain_vertex_data(&mut update_context);
+                        let mut image_sources = HashSet::new();
+
+                        for (image_source, _) in obtained_data.iter() {
+                            if !matches!(image_source, ImageSource::None | ImageSource::Layer(_)) {
+                                image_sources.insert(image_source.clone());
+                            }
+                        }
+
+                        let bin_layer = update_context
+                            .placement_cache
+                            .get(&id)
+                            .and_then(BinPlacement::layer);
+
+                        let mut vertex_data = BTreeMap::new();
+                        let mut tmp_vertexes = Vec::new();
+                        let mut tmp_z = OrderedFloat::<f32>::from(0.0);
+
+                        for (image_source, vertexes) in obtained_data {
+                            // NOTE: The composite of a layer is drawn directly, while the rest of
+                            //       the data of the bin is drawn onto the layer.
+                            let layer = match image_source {
+                                ImageSource::Layer(_) => None,
+                                _ => bin_layer,
+                            };
+
+                            let mut vertex_iter = vertexes.into_iter();
+
+                            while let (Some(a), Some(b), Some(c)) =
+                                (vertex_iter.next(), vertex_iter.next(), vertex_iter.next())
+                            {
+                                let z = OrderedFloat::<f32>::from(a.position[2]);
+
+                                if tmp_z != z {
+                                    if !tmp_vertexes.is_empty() {
+                                        vertex_data
+                                            .entry(DrawKey {
+                                                layer,
+                                                z: tmp_z,
+                                            })
+                                            .or_insert_with(|| {
+                                                BinZData {
+                                                    range: None,
+                                                    data: HashMap::new(),
+                                                }
+                                            })
+                                            .data
+                                            .entry(image_source.clone())
+                                            .or_insert_with(Vec::new)
+                                            .append(&mut tmp_vertexes);
+                                    }
+
+                                    tmp_z = z;
+                                }
+
+                                tmp_vertexes.push(a);
+                                tmp_vertexes.push(b);
+                                tmp_vertexes.push(c);
+                            }
+
+                            if !tmp_vertexes.is_empty() {
+                                vertex_data
+                                    .entry(DrawKey {
+                                        layer,
+                                        z: tmp_z,
+                                    })
+                                    .or_insert_with(|| {
+                                        BinZData {
+                                            range: None,
+                                            data: HashMap::new(),
+                                        }
+                                    })
+                                    .data
+                                    .entry(image_source.clone())
+                                    .or_insert_with(Vec::new)
+                                    .append(&mut tmp_vertexes);
+                            }
+                        }
+
+                        if data_send
+                            .send((id, image_sources, vertex_data, ovd_metrics_op))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+
+                    update_context.placement_cache.clear();
+                },
+            }
+        }
+    });
+
+    OVDThread {
+        event_send,
+        handle,
+    }
 }
 
 pub fn spawn(
@@ -156,11 +596,10 @@ pub fn spawn(
     window_event_recv: Receiver<WindowEvent>,
     render_event_send: Sender<RenderEvent>,
     image_format: VkFormat,
-) -> Result<(), String> {
-    std::thread::spawn(move || {
-        let mem_alloc = Arc::new(StandardMemoryAllocator::new_default(
-            window.basalt_ref().device(),
-        ));
+) -> Result<JoinHandle<()>, String> {
+    Ok(std::thread::spawn(move || {
+        let basalt = window.basalt();
+        let mem_alloc = basalt.render_memory();
 
         let cmd_alloc = StandardCommandBufferAllocator::new(
             window.basalt_ref().device(),
@@ -172,12 +611,41 @@ pub fn spawn(
         );
 
         let queue = window.basalt_ref().transfer_queue();
+
+        // NOTE: Prefer the secondary transfer queue so images and vertexes upload in parallel.
+        let image_queue = window
+            .basalt_ref()
+            .secondary_transfer_queue()
+            .unwrap_or_else(|| queue.clone());
         let max_image_dimension2_d = window
             .basalt_ref()
             .physical_device()
             .properties()
             .max_image_dimension2_d;
 
+        let dedicated_image_threshold = window
+            .basalt_ref()
+            .config
+            .render_dedicated_image_threshold
+            .clamp(1, max_image_dimension2_d - 2);
+
+        let atlas_initial_size = window.basalt_ref().config.render_atlas_initial_size.clamp(
+            dedicated_image_threshold.max(14) + 2,
+            max_image_dimension2_d,
+        );
+
+        let atlas_large_threshold = window
+            .basalt_ref()
+            .config
+            .render_atlas_large_threshold
+            .clamp(16, atlas_initial_size);
+
+        let atlas_allocator_options = AtlasAllocatorOptions {
+            alignment: AtlasSize::new(16, 16),
+            small_size_threshold: 16,
+            large_size_threshold: atlas_large_threshold as i32,
+        };
+
         let mut window_size = window.inner_dimensions();
         let mut effective_scale = window.effective_interface_scale();
         let mut bin_states: BTreeMap<BinID, BinState> = BTreeMap::new();
@@ -189,6 +657,7 @@ pub fn spawn(
                     weak: Arc::downgrade(&bin),
                     image_sources: Vec::new(),
                     vertex_data: None,
+                    fingerprint: None,
                 },
             );
         }
@@ -197,159 +666,33 @@ pub fn spawn(
         let mut update_bins: HashSet<BinID> = HashSet::new();
         let mut remove_bins: HashSet<BinID> = HashSet::new();
         let (mut staging_buffers, mut vertex_buffers) =
-            create_buffers(&mem_alloc as &Arc<_>, 32768);
+            create_buffers(mem_alloc, MIN_VERTEX_BUFFER_LEN);
+        let mut low_usage_updates = 0;
         let mut vertex_buffer_offset = false;
         let mut zeroing_buffer: Option<Subbuffer<[u8]>> = None;
         let mut image_backings: Vec<ImageBacking> = Vec::new();
+        let mut image_source_index: HashMap<ImageSource, usize> = HashMap::new();
+        let mut layers: Vec<BinID> = Vec::new();
+        let mut layer_tex_base: u32 = 0;
         let mut metrics_level = window.renderer_metrics_level();
 
-        let ovd_num_threads = window
-            .basalt_ref()
-            .config
-            .render_default_worker_threads
-            .get();
-        let mut ovd_font_systems = vec![FontSystem::new()];
-
-        for binary_font in window.basalt_ref().interface_ref().binary_fonts() {
-            ovd_font_systems[0]
-                .db_mut()
-                .load_font_source(FontSource::Binary(binary_font));
-        }
-
-        while ovd_font_systems.len() < ovd_num_threads {
-            let locale = ovd_font_systems[0].locale().to_string();
-            let db = ovd_font_systems[0].db().clone();
-            ovd_font_systems.push(FontSystem::new_with_locale_and_db(locale, db));
-        }
-
-        let default_font = window.basalt_ref().interface_ref().default_font();
-        let mut ovd_event_sends = Vec::with_capacity(ovd_num_threads);
-        let (ovd_data_send, ovd_data_recv) = flume::unbounded();
-        let (ovd_bin_send, ovd_bin_recv) = flume::unbounded::<Option<Arc<Bin>>>();
-        let mut ovd_threads = Vec::with_capacity(ovd_num_threads);
-
-        for font_system in ovd_font_systems {
-            let (ovd_event_send, event_recv) = flume::unbounded();
-            ovd_event_sends.push(ovd_event_send);
-
-            let mut update_context = UpdateContext {
-                extent: [window_size[0] as f32, window_size[1] as f32],
-                scale: effective_scale,
-                font_system,
-                glyph_cache: SwashCache::new(),
-                default_font: default_font.clone(),
-                metrics_level,
-                placement_cache: BTreeMap::new(),
-            };
-
-            let data_send = ovd_data_send.clone();
-            let bin_recv = ovd_bin_recv.clone();
-
-            ovd_threads.push(std::thread::spawn(move || {
-                while let Ok(ovd_event) = event_recv.recv() {
-                    match ovd_event {
-                        OVDEvent::AddBinaryFont(binary_font) => {
-                            update_context
-                                .font_system
-                                .db_mut()
-                                .load_font_source(FontSource::Binary(binary_font));
-                        },
-                        OVDEvent::SetDefaultFont(default_font) => {
-                            update_context.default_font = default_font;
-                        },
-                        OVDEvent::SetScale(scale) => {
-                            update_context.scale = scale;
-                        },
-                        OVDEvent::SetExtent(extent) => {
-                            update_context.extent = [extent[0] as f32, extent[1] as f32];
-                        },
-                        OVDEvent::SetMetrics(level) => {
-                            update_context.metrics_level = level;
-                        },
-                        OVDEvent::PerformOVD => {
-                            while let Ok(Some(bin)) = bin_recv.recv() {
-                                let id = bin.id();
-                                let (obtained_data, ovd_metrics_op) =
-                                    bin.obtain_vertex_data(&mut update_context);
-                                let mut image_sources = HashSet::new();
-
-                                for (image_source, _) in obtained_data.iter() {
-                                    if *image_source != ImageSource::None {
-                                        image_sources.insert(image_source.clone());
-                                    }
-                                }
-
-                                let mut vertex_data = BTreeMap::new();
-                                let mut tmp_vertexes = Vec::new();
-                                let mut tmp_z = OrderedFloat::<f32>::from(0.0);
-
-                                for (image_source, vertexes) in obtained_data {
-                                    let mut vertex_iter = vertexes.into_iter();
-
-                                    while let (Some(a), Some(b), Some(c)) =
-                                        (vertex_iter.next(), vertex_iter.next(), vertex_iter.next())
-                                    {
-                                        let z = OrderedFloat::<f32>::from(a.position[2]);
-
-                                        if tmp_z != z {
-                                            if !tmp_vertexes.is_empty() {
-                                                vertex_data
-                                                    .entry(tmp_z)
-                                                    .or_insert_with(|| {
-                                                        BinZData {
-                                                            range: None,
-                                                            data: HashMap::new(),
-                                                        }
-                                                    })
-                                                    .data
-                                                    .entry(image_source.clone())
-                                                    .or_insert_with(Vec::new)
-                                                    .append(&mut tmp_vertexes);
-                                            }
-
-                                            tmp_z = z;
-                                        }
-
-                                        tmp_vertexes.push(a);
-                                        tmp_vertexes.push(b);
-                                        tmp_vertexes.push(c);
-                                    }
-
-                                    if !tmp_vertexes.is_empty() {
-                                        vertex_data
-                                            .entry(tmp_z)
-                                            .or_insert_with(|| {
-                                                BinZData {
-                                                    range: None,
-                                                    data: HashMap::new(),
-                                                }
-                                            })
-                                            .data
-                                            .entry(image_source.clone())
-                                            .or_insert_with(Vec::new)
-                                            .append(&mut tmp_vertexes);
-                                    }
-                                }
-
-                                if data_send
-                                    .send((id, image_sources, vertex_data, ovd_metrics_op))
-                                    .is_err()
-                                {
-                                    return;
-                                }
-                            }
+        let mut ovd_pool = OVDPool::new(
+            &window,
+            [window_size[0] as f32, window_size[1] as f32],
+            effective_scale,
+            metrics_level,
+        );
 
-                            update_context.placement_cache.clear();
-                        },
-                    }
-                }
-            }));
-        }
+        ovd_pool.resize(window.renderer_worker_threads());
+        window.set_renderer_active_worker_threads(Some(ovd_pool.len()));
 
         let mut next_cmd_builder_op = None;
+        let mut next_vertex_cmd_builder_op = None;
         let mut active_index = 0;
         let mut inactive_index = 1;
         let mut pending_window_events = Vec::new();
+        let mut pump_complete_sends: Vec<flume::Sender<()>> = Vec::new();
+        let mut damage = Damage::None;
 
         'main_loop: loop {
             loop {
@@ -366,14 +709,7 @@ pub fn spawn(
                             if [width, height] != window_size {
                                 window_size = [width, height];
 
-                                for ovd_event_send in ovd_event_sends.iter() {
-                                    if ovd_event_send
-                                        .send(OVDEvent::SetExtent(window_size))
-                                        .is_err()
-                                    {
-                                        panic!("an ovd thread has panicked.");
-                                    }
-                                }
+                                ovd_pool.broadcast(OVDEvent::SetExtent(window_size));
 
                                 update_all = true;
 
@@ -386,14 +722,7 @@ pub fn spawn(
                             if new_scale != effective_scale {
                                 effective_scale = new_scale;
 
-                                for ovd_event_send in ovd_event_sends.iter() {
-                                    if ovd_event_send
-                                        .send(OVDEvent::SetScale(effective_scale))
-                                        .is_err()
-                                    {
-                                        panic!("an ovd thread has panicked.");
-                                    }
-                                }
+                                ovd_pool.broadcast(OVDEvent::SetScale(effective_scale));
 
                                 update_all = true;
                             }
@@ -428,6 +757,7 @@ pub fn spawn(
                                     weak: Arc::downgrade(&bin),
                                     image_sources: Vec::new(),
                                     vertex_data: None,
+                                    fingerprint: None,
                                 },
                             );
 
@@ -438,37 +768,32 @@ pub fn spawn(
                             update_bins.remove(&bin_id);
                             remove_bins.insert(bin_id);
                         },
-                        WindowEvent::UpdateBin(bin_id) => {
-                            remove_bins.remove(&bin_id);
-                            update_bins.insert(bin_id);
+                        WindowEvent::UpdateBins => {
+                            for bin_id in window.take_pending_updates() {
+                                remove_bins.remove(&bin_id);
+                                update_bins.insert(bin_id);
+                            }
                         },
-                        WindowEvent::UpdateBinBatch(bin_ids) => {
-                            for bin_id in bin_ids {
+                        WindowEvent::PumpUpdates(complete_send) => {
+                            for bin_id in window.take_pending_updates() {
                                 remove_bins.remove(&bin_id);
                                 update_bins.insert(bin_id);
                             }
+
+                            pump_complete_sends.push(complete_send);
                         },
                         WindowEvent::AddBinaryFont(binary_font) => {
-                            for ovd_event_send in ovd_event_sends.iter() {
-                                if ovd_event_send
-                                    .send(OVDEvent::AddBinaryFont(binary_font.clone()))
-                                    .is_err()
-                                {
-                                    panic!("an ovd thread has panicked.");
-                                }
-                            }
+                            ovd_pool.broadcast(OVDEvent::AddBinaryFont(binary_font));
 
                             update_all = true;
                         },
                         WindowEvent::SetDefaultFont(default_font) => {
-                            for ovd_event_send in ovd_event_sends.iter() {
-                                if ovd_event_send
-                                    .send(OVDEvent::SetDefaultFont(default_font.clone()))
-                                    .is_err()
-                                {
-                                    panic!("an ovd thread has panicked.");
-                                }
-                            }
+                            ovd_pool.broadcast(OVDEvent::SetDefaultFont(default_font));
+
+                            update_all = true;
+                        },
+                        WindowEvent::SetFallbackFonts(fallback_fonts) => {
+                            ovd_pool.broadcast(OVDEvent::SetFallbackFonts(fallback_fonts));
 
                             update_all = true;
                         },
@@ -485,12 +810,30 @@ pub fn spawn(
                                 break 'main_loop;
                             }
                         },
-                        WindowEvent::SetMetrics(level) => {
-                            for ovd_event_send in ovd_event_sends.iter() {
-                                if ovd_event_send.send(OVDEvent::SetMetrics(level)).is_err() {
-                                    panic!("an ovd thread has panicked.");
-                                }
+                        WindowEvent::SetImageCount(count) => {
+                            if render_event_send
+                                .send(RenderEvent::SetImageCount(count))
+                                .is_err()
+                            {
+                                break 'main_loop;
+                            }
+                        },
+                        WindowEvent::SetFPSLimit(limit) => {
+                            if render_event_send
+                                .send(RenderEvent::SetFPSLimit(limit))
+                                .is_err()
+                            {
+                                break 'main_loop;
                             }
+                        },
+                        WindowEvent::SetWorkerThreads(count) => {
+                            // NOTE: Vertex data isn't being obtained while events are processed,
+                            //       so threads removed here have no work remaining.
+                            ovd_pool.resize(count);
+                            window.set_renderer_active_worker_threads(Some(ovd_pool.len()));
+                        },
+                        WindowEvent::SetMetrics(level) => {
+                            ovd_pool.broadcast(OVDEvent::SetMetrics(level));
 
                             if render_event_send
                                 .send(RenderEvent::SetMetrics(level))
@@ -501,10 +844,108 @@ pub fn spawn(
 
                             metrics_level = level;
                         },
+                        WindowEvent::SetPostProcess(post_process) => {
+                            if render_event_send
+                                .send(RenderEvent::SetPostProcess(post_process))
+                                .is_err()
+                            {
+                                break 'main_loop;
+                            }
+                        },
+                        WindowEvent::SetClearColor(color) => {
+                            if render_event_send
+                                .send(RenderEvent::SetClearColor(color))
+                                .is_err()
+                            {
+                                break 'main_loop;
+                            }
+                        },
+                        WindowEvent::DebugAtlasSnapshot {
+                            index,
+                            result_send,
+                        } => {
+                            let result = match image_backings.get(index) {
+                                Some(ImageBacking::Atlas {
+                                    images, ..
+                                }) => {
+                                    // NOTE: Reuse the pending builder to keep command order.
+                                    let mut cmd_builder = match next_cmd_builder_op.take() {
+                                        Some(some) => some,
+                                        None => {
+                                            AutoCommandBufferBuilder::primary(
+                                                &cmd_alloc,
+                                                image_queue.queue_family_index(),
+                                                CommandBufferUsage::OneTimeSubmit,
+                                            )
+                                            .unwrap()
+                                        },
+                                    };
+
+                                    let image = images[active_index].clone();
+                                    let [width, height, _] = image.extent();
+
+                                    let buffer = Buffer::new_slice::<u8>(
+                                        mem_alloc.allocator().clone(),
+                                        BufferCreateInfo {
+                                            usage: BufferUsage::TRANSFER_DST,
+                                            ..BufferCreateInfo::default()
+                                        },
+                                        AllocationCreateInfo {
+                                            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                                                | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                                            ..AllocationCreateInfo::default()
+                                        },
+                                        image_format.block_size()
+                                            * width as DeviceSize
+                                            * height as DeviceSize,
+                                    )
+                                    .unwrap();
+
+                                    cmd_builder
+                                        .copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+                                            image,
+                                            buffer.clone(),
+                                        ))
+                                        .unwrap();
+
+                                    cmd_builder
+                                        .build()
+                                        .unwrap()
+                                        .execute(image_queue.clone())
+                                        .unwrap()
+                                        .then_signal_fence_and_flush()
+                                        .unwrap()
+                                        .wait(None)
+                                        .unwrap();
+
+                                    let data_result = vulkan_format_to_rgba8(
+                                        image_format,
+                                        &buffer.read().unwrap(),
+                                    );
+
+                                    data_result.map(|data| {
+                                        Some(AtlasSnapshot {
+                                            index,
+                                            width,
+                                            height,
+                                            data,
+                                        })
+                                    })
+                                },
+                                Some(_) => Ok(None),
+                                None => Err(format!("There is no image backing at {}.", index)),
+                            };
+
+                            let _ = result_send.send(result);
+                        },
                     }
                 }
 
-                if !update_all && update_bins.is_empty() && remove_bins.is_empty() {
+                if !update_all
+                    && update_bins.is_empty()
+                    && remove_bins.is_empty()
+                    && pump_complete_sends.is_empty()
+                {
                     match window_event_recv.recv() {
                         Ok(ok) => pending_window_events.push(ok),
                         Err(_) => break 'main_loop,
@@ -531,12 +972,18 @@ pub fn spawn(
 
             // --- Remove Bin States --- //
 
+            if update_all {
+                damage = Damage::Full;
+            }
+
             let mut modified_vertexes = false;
             let mut remove_image_sources: HashMap<ImageSource, usize> = HashMap::new();
 
             for bin_id in remove_bins.drain() {
                 if let Some(mut state) = bin_states.remove(&bin_id) {
                     if let Some(vertex_data) = state.vertex_data.take() {
+                        add_vertex_data_damage(&mut damage, &vertex_data);
+
                         modified_vertexes |= vertex_data
                             .into_values()
                             .any(|z_data| z_data.range.is_some());
@@ -551,7 +998,8 @@ pub fn spawn(
             }
 
             if update_all {
-                for bin_id in bin_states.keys() {
+                for (bin_id, state) in bin_states.iter_mut() {
+                    state.fingerprint = None;
                     update_bins.insert(*bin_id);
                 }
 
@@ -569,23 +1017,20 @@ pub fn spawn(
             let mut add_image_sources: HashMap<ImageSource, usize> = HashMap::new();
 
             if !update_bins.is_empty() {
-                if ovd_threads.iter().any(|handle| handle.is_finished()) {
-                    panic!("one or more ovd threads has panicked.");
-                }
+                ovd_pool.check_panicked();
 
-                for ovd_event_send in ovd_event_sends.iter() {
-                    ovd_event_send.send(OVDEvent::PerformOVD).unwrap();
-                }
+                // NOTE: Placements are calculated once here, so that the ovd threads don't each
+                //       recalculate the placements of shared ancestors.
 
-                let mut update_count = update_bins.len();
+                let extent = [window_size[0] as f32, window_size[1] as f32];
+                let mut placements = BTreeMap::new();
+                let mut bins = Vec::with_capacity(update_bins.len());
+                let mut unchanged_bins = Vec::new();
 
                 for bin_id in update_bins.drain() {
                     let state = match bin_states.get_mut(&bin_id) {
                         Some(some) => some,
-                        None => {
-                            update_count -= 1;
-                            continue;
-                        },
+                        None => continue,
                     };
 
                     let bin = match state.weak.upgrade() {
@@ -593,12 +1038,41 @@ pub fn spawn(
                         None => {
                             // TODO: Instead of deferring removal do now?
                             remove_bins.insert(bin_id);
-                            update_count -= 1;
                             continue;
                         },
                     };
 
+                    let fingerprint = BinFingerprint {
+                        version: bin.update_version(),
+                        placement: bin.calc_placement_cached(
+                            extent,
+                            effective_scale,
+                            &mut placements,
+                        ),
+                        extent: window_size,
+                        scale: effective_scale,
+                    };
+
+                    // NOTE: Recursive updates invalidate entire subtrees. Bins whose inputs are
+                    //       the same as the last update keep their existing vertex data, but
+                    //       their update hooks are still called.
+
+                    if state.vertex_data.is_some()
+                        && state.fingerprint.as_ref() == Some(&fingerprint)
+                    {
+                        if let Some((_, _, ref mut metrics)) = metrics_op.as_mut() {
+                            metrics.bins_unchanged += 1;
+                        }
+
+                        unchanged_bins.push(bin);
+                        continue;
+                    }
+
+                    state.fingerprint = Some(fingerprint);
+
                     if let Some(vertex_data) = state.vertex_data.take() {
+                        add_vertex_data_damage(&mut damage, &vertex_data);
+
                         modified_vertexes |= vertex_data
                             .into_values()
                             .any(|z_data| z_data.range.is_some());
@@ -610,17 +1084,46 @@ pub fn spawn(
                             .or_insert_with(|| 0) += 1;
                     }
 
-                    if ovd_bin_send.send(Some(bin)).is_err() {
+                    bins.push(bin);
+                }
+
+                let placements = Arc::new(placements);
+
+                if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
+                    metrics.bin_placement = inst.elapsed().as_micros() as f32 / 1000.0;
+                    *inst = Instant::now();
+                }
+
+                ovd_pool.broadcast(OVDEvent::PerformOVD(placements));
+
+                // NOTE: The overflow of a parent depends on the post update of its children, so it
+                //       is checked once per parent after all of the bins have been updated.
+                let mut overflow_parents = BTreeMap::new();
+
+                for bin in bins.iter().chain(unchanged_bins.iter()) {
+                    if let Some(parent) = bin.parent() {
+                        overflow_parents.entry(parent.id()).or_insert(parent);
+                    }
+                }
+
+                let update_count = bins.len();
+
+                for bin in bins {
+                    if ovd_pool.bin_send.send(Some(bin)).is_err() {
                         panic!("all ovd threads have panicked");
                     }
                 }
 
-                for _ in 0..ovd_num_threads {
-                    if ovd_bin_send.send(None).is_err() {
+                for _ in 0..ovd_pool.len() {
+                    if ovd_pool.bin_send.send(None).is_err() {
                         panic!("all ovd threads have panicked");
                     }
                 }
 
+                for bin in unchanged_bins {
+                    bin.complete_unchanged_update();
+                }
+
                 let mut update_recv_count = 0;
 
                 let mut total_ovd_metrics_op = if metrics_level == RendererMetricsLevel::Full {
@@ -629,15 +1132,15 @@ pub fn spawn(
                     None
                 };
 
-                // TODO: what happens if a thread panics before all data is received?
                 while update_recv_count < update_count {
                     let (bin_id, image_sources, vertex_data, ovd_metrics_op) =
-                        match ovd_data_recv.recv_timeout(Duration::from_secs(1)) {
+                        match ovd_pool.data_recv.recv_timeout(Duration::from_secs(1)) {
                             Ok(ok) => ok,
                             Err(RecvTimeoutError::Disconnected) => {
                                 panic!("all ovd threads have panicked")
                             },
                             Err(RecvTimeoutError::Timeout) => {
+                                ovd_pool.check_panicked();
                                 println!("ovd perform stalled");
                                 continue;
                             },
@@ -659,12 +1162,17 @@ pub fn spawn(
                         *metrics_total += metrics;
                     }
 
+                    add_vertex_data_damage(&mut damage, &vertex_data);
                     let state = bin_states.get_mut(&bin_id).unwrap();
                     state.vertex_data = Some(vertex_data);
                     state.image_sources = image_sources.into_iter().collect();
                     update_recv_count += 1;
                 }
 
+                for parent in overflow_parents.into_values() {
+                    parent.call_overflow_changed_hooks();
+                }
+
                 if let (Some(total_ovd_metrics), Some((_, _, ref mut metrics))) =
                     (total_ovd_metrics_op, metrics_op.as_mut())
                 {
@@ -677,96 +1185,36 @@ pub fn spawn(
                 *inst = Instant::now();
             }
 
+            for complete_send in pump_complete_sends.drain(..) {
+                let _ = complete_send.send(());
+            }
+
             // -- Decrease Image Use Counters -- //
 
             for (image_source, count) in remove_image_sources {
-                for image_backing in image_backings.iter_mut() {
-                    match image_backing {
-                        ImageBacking::Atlas {
-                            contains, ..
-                        } => {
-                            if let Some(contained_image) = contains.get_mut(&image_source) {
-                                contained_image.use_count -= count;
-                                break;
-                            }
-                        },
-                        ImageBacking::Dedicated {
-                            source,
-                            contains,
-                            ..
-                        } => {
-                            if *source == image_source {
-                                contains.use_count -= count;
-                                break;
-                            }
-                        },
-                        ImageBacking::UserProvided {
-                            source,
-                            contains,
-                            ..
-                        } => {
-                            if *source == image_source {
-                                contains.use_count -= count;
-                                break;
-                            }
-                        },
-                    }
+                if let Some(image_index) = image_source_index.get(&image_source) {
+                    *image_backings[*image_index]
+                        .use_count_mut(&image_source)
+                        .unwrap() -= count;
                 }
             }
 
             // -- Increase Image Use Counters -- //
 
-            let mut obtain_image_sources: HashMap<ImageSource, usize> = HashMap::new();
-
-            for (image_source, count) in add_image_sources {
-                let mut obtain_image_source = true;
-
-                for image_backing in image_backings.iter_mut() {
-                    match image_backing {
-                        ImageBacking::Atlas {
-                            contains, ..
-                        } => {
-                            if let Some(contained_image) = contains.get_mut(&image_source) {
-                                contained_image.use_count += count;
-                                obtain_image_source = false;
-                                break;
-                            }
-                        },
-                        ImageBacking::Dedicated {
-                            source,
-                            contains,
-                            ..
-                        } => {
-                            if *source == image_source {
-                                contains.use_count += count;
-                                obtain_image_source = false;
-                                break;
-                            }
-                        },
-                        ImageBacking::UserProvided {
-                            source,
-                            contains,
-                            ..
-                        } => {
-                            if *source == image_source {
-                                contains.use_count += count;
-                                obtain_image_source = false;
-                                break;
-                            }
-                        },
-                    }
-                }
-
-                if obtain_image_source {
-                    *obtain_image_sources
-                        .entry(image_source)
-                        .or_insert_with(|| 0) += count;
-                }
-            }
+            let obtain_image_sources = queue_image_sources(
+                add_image_sources,
+                &image_source_index,
+                |image_index, image_source, count| {
+                    *image_backings[image_index]
+                        .use_count_mut(image_source)
+                        .unwrap() += count;
+                },
+            );
 
             // -- Deref Image Cache Keys & Remove Image Backings -- //
 
             let mut remove_image_backings = Vec::new();
+            let mut compact_image_backings = Vec::new();
             let mut deref_image_cache_keys: Vec<ImageCacheKey> = Vec::new();
             let mut active_atlas_clear_regions: HashMap<Arc<Image>, Vec<BufferImageCopy>> =
                 HashMap::new();
@@ -784,11 +1232,14 @@ pub fn spawn(
                         // NOTE: Atlas's that are empty are kept as it assumed that if the user
                         //       execeeded the capacity of the other atlas's they will do so again.
 
+                        let mut deallocated = false;
+
                         contains.retain(|image_source, contains| {
                             if contains.use_count == 0 {
                                 if let ImageSource::Cache(image_cache_key) = &image_source {
                                     deref_image_cache_keys.push(image_cache_key.clone());
                                     allocator.deallocate(contains.data.id);
+                                    deallocated = true;
 
                                     let clear_region_info = BufferImageCopy {
                                         image_offset: [
@@ -826,6 +1277,13 @@ pub fn spawn(
                                 true
                             }
                         });
+
+                        if deallocated
+                            && !contains.is_empty()
+                            && atlas_fragmentation(allocator) >= ATLAS_COMPACT_THRESHOLD
+                        {
+                            compact_image_backings.push(i);
+                        }
                     },
                     ImageBacking::Dedicated {
                         source,
@@ -865,7 +1323,7 @@ pub fn spawn(
                         false,
                         AutoCommandBufferBuilder::primary(
                             &cmd_alloc,
-                            queue.queue_family_index(),
+                            image_queue.queue_family_index(),
                             CommandBufferUsage::OneTimeSubmit,
                         )
                         .unwrap(),
@@ -873,7 +1331,30 @@ pub fn spawn(
                 },
             };
 
-            let mut next_cmd_builder = AutoCommandBufferBuilder::primary(
+            let mut next_cmd_builder = AutoCommandBufferBuilder::primary(
+                &cmd_alloc,
+                image_queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap();
+
+            let (exec_prev_vertex_cmds, mut active_vertex_cmd_builder) =
+                match next_vertex_cmd_builder_op.take() {
+                    Some(some) => (true, some),
+                    None => {
+                        (
+                            false,
+                            AutoCommandBufferBuilder::primary(
+                                &cmd_alloc,
+                                queue.queue_family_index(),
+                                CommandBufferUsage::OneTimeSubmit,
+                            )
+                            .unwrap(),
+                        )
+                    },
+                };
+
+            let mut next_vertex_cmd_builder = AutoCommandBufferBuilder::primary(
                 &cmd_alloc,
                 queue.queue_family_index(),
                 CommandBufferUsage::OneTimeSubmit,
@@ -913,7 +1394,7 @@ pub fn spawn(
 
                 check_resize_zeroing_buffer(
                     &mut active_cmd_builder,
-                    &mem_alloc,
+                    mem_alloc,
                     &mut zeroing_buffer,
                     required_buffer_len,
                 );
@@ -944,6 +1425,146 @@ pub fn spawn(
                 *inst = Instant::now();
             }
 
+            // -- Compact Fragmented Atlases -- //
+
+            if !compact_image_backings.is_empty() {
+                let mut image_sources_effected = HashSet::new();
+
+                for i in compact_image_backings {
+                    if let ImageBacking::Atlas {
+                        contains,
+                        staging_buffers,
+                        staging_buffer_index,
+                        images,
+                        allocator,
+                    } = &mut image_backings[i]
+                    {
+                        let mut new_allocator = AtlasAllocator::with_options(
+                            allocator.size(),
+                            &atlas_allocator_options,
+                        );
+
+                        // Allocating the largest first results in a tighter packing.
+                        let mut reallocate = contains.iter_mut().collect::<Vec<_>>();
+
+                        reallocate.sort_by_key(|(_, contained)| {
+                            std::cmp::Reverse(contained.data.rectangle.area())
+                        });
+
+                        let mut new_allocations = Vec::with_capacity(reallocate.len());
+                        let mut copy_regions = Vec::with_capacity(reallocate.len());
+
+                        for (image_source, contained) in reallocate {
+                            let rectangle = contained.data.rectangle;
+
+                            let allocation = match new_allocator.allocate(rectangle.size()) {
+                                Some(some) => some,
+                                None => break,
+                            };
+
+                            copy_regions.push(ImageCopy {
+                                src_subresource: ImageSubresourceLayers::from_parameters(
+                                    image_format,
+                                    1,
+                                ),
+                                src_offset: [rectangle.min.x as u32, rectangle.min.y as u32, 0],
+                                dst_subresource: ImageSubresourceLayers::from_parameters(
+                                    image_format,
+                                    1,
+                                ),
+                                dst_offset: [
+                                    allocation.rectangle.min.x as u32,
+                                    allocation.rectangle.min.y as u32,
+                                    0,
+                                ],
+                                extent: [rectangle.width() as u32, rectangle.height() as u32, 1],
+                                ..ImageCopy::default()
+                            });
+
+                            new_allocations.push((image_source.clone(), allocation));
+                        }
+
+                        // NOTE: Should the new allocator not be able to fit everything, the atlas
+                        //       is left as is since it is no worse than before.
+                        if new_allocations.len() != contains.len() {
+                            continue;
+                        }
+
+                        let (new_images, new_staging_buffers) = create_images_with_buffers(
+                            mem_alloc,
+                            image_format,
+                            allocator.size().width as u32,
+                            allocator.size().height as u32,
+                            true,
+                        );
+
+                        clear_image(
+                            &mut active_cmd_builder,
+                            mem_alloc,
+                            &mut zeroing_buffer,
+                            new_images[active_index].clone(),
+                        );
+
+                        active_cmd_builder
+                            .copy_image(CopyImageInfo {
+                                regions: copy_regions.clone().into(),
+                                ..CopyImageInfo::images(
+                                    images[active_index].clone(),
+                                    new_images[active_index].clone(),
+                                )
+                            })
+                            .unwrap();
+
+                        clear_image(
+                            &mut next_cmd_builder,
+                            mem_alloc,
+                            &mut zeroing_buffer,
+                            new_images[inactive_index].clone(),
+                        );
+
+                        next_cmd_builder
+                            .copy_image(CopyImageInfo {
+                                regions: copy_regions.into(),
+                                ..CopyImageInfo::images(
+                                    images[inactive_index].clone(),
+                                    new_images[inactive_index].clone(),
+                                )
+                            })
+                            .unwrap();
+
+                        for (image_source, allocation) in new_allocations {
+                            contains.get_mut(&image_source).unwrap().data = allocation;
+                            image_sources_effected.insert(image_source);
+                        }
+
+                        *staging_buffer_index = 0;
+                        *images = new_images;
+                        *staging_buffers = new_staging_buffers;
+                        *allocator = new_allocator;
+                    }
+                }
+
+                for state in bin_states.values_mut() {
+                    if let Some(vertex_data) = &mut state.vertex_data {
+                        for z_data in vertex_data.values_mut() {
+                            if z_data.range.is_some()
+                                && image_sources_effected
+                                    .iter()
+                                    .any(|image_source| z_data.data.contains_key(image_source))
+                            {
+                                z_data.range = None;
+                                modified_vertexes = true;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
+                metrics.atlas_compact = inst.elapsed().as_micros() as f32 / 1000.0;
+                *inst = Instant::now();
+            }
+
             // -- Remove Unused Image Backings & Set Vertex Range to None for Effected Data -- //
 
             let mut next_staging_index: DeviceSize = 0;
@@ -1012,8 +1633,8 @@ pub fn spawn(
 
             if !obtain_image_sources.is_empty() || !deref_image_cache_keys.is_empty() {
                 let obtain_image_cache_keys = obtain_image_sources
-                    .iter()
-                    .filter_map(|(image_source, _)| {
+                    .keys()
+                    .filter_map(|image_source| {
                         match image_source {
                             ImageSource::Cache(image_cache_key) => Some(image_cache_key.clone()),
                             _ => None,
@@ -1044,7 +1665,7 @@ pub fn spawn(
 
                 for (image_source, uses) in obtain_image_sources {
                     match image_source.clone() {
-                        ImageSource::None => unreachable!(),
+                        ImageSource::None | ImageSource::Layer(_) => unreachable!(),
                         ImageSource::Vulkano(image) => {
                             image_backings.push(ImageBacking::UserProvided {
                                 source: image_source,
@@ -1059,9 +1680,11 @@ pub fn spawn(
                             let obtained_image = obtained_images.get(&image_cache_key).unwrap();
 
                             // Large images will use a dedicated allocation
-                            if obtained_image.width > 512 || obtained_image.height > 512 {
+                            if obtained_image.width > dedicated_image_threshold
+                                || obtained_image.height > dedicated_image_threshold
+                            {
                                 let (image, buffer) = create_image_with_buffer(
-                                    &mem_alloc,
+                                    mem_alloc,
                                     image_format,
                                     obtained_image.width,
                                     obtained_image.height,
@@ -1070,7 +1693,7 @@ pub fn spawn(
 
                                 {
                                     let mut buffer_write = buffer.write().unwrap();
-                                    buffer_write.copy_from_slice(&obtained_image.data);
+                                    obtained_image.write_data(&mut buffer_write);
                                 }
 
                                 active_cmd_builder
@@ -1106,10 +1729,11 @@ pub fn spawn(
                                     {
                                         // Try allocation without resizing
                                         if let Some(allocation) = allocator.allocate(alloc_size) {
-                                            staging_buffers[active_index].write().unwrap()
-                                                [*staging_buffer_index..]
-                                                [..obtained_image.data.len()]
-                                                .copy_from_slice(&obtained_image.data);
+                                            obtained_image.write_data(
+                                                &mut staging_buffers[active_index].write().unwrap()
+                                                    [*staging_buffer_index..]
+                                                    [..obtained_image.data_len()],
+                                            );
 
                                             active_atlas_copy_infos
                                                 .entry((
@@ -1165,7 +1789,7 @@ pub fn spawn(
                                                     ..BufferImageCopy::default()
                                                 });
 
-                                            *staging_buffer_index += obtained_image.data.len();
+                                            *staging_buffer_index += obtained_image.data_len();
                                             image_allocated = true;
 
                                             contains.insert(
@@ -1190,7 +1814,7 @@ pub fn spawn(
 
                                             let (new_images, new_staging_buffers) =
                                                 create_images_with_buffers(
-                                                    &mem_alloc,
+                                                    mem_alloc,
                                                     image_format,
                                                     allocator.size().width as u32,
                                                     allocator.size().height as u32,
@@ -1199,7 +1823,7 @@ pub fn spawn(
 
                                             clear_image(
                                                 &mut active_cmd_builder,
-                                                &mem_alloc,
+                                                mem_alloc,
                                                 &mut zeroing_buffer,
                                                 new_images[active_index].clone(),
                                             );
@@ -1213,7 +1837,7 @@ pub fn spawn(
 
                                             clear_image(
                                                 &mut next_cmd_builder,
-                                                &mem_alloc,
+                                                mem_alloc,
                                                 &mut zeroing_buffer,
                                                 new_images[inactive_index].clone(),
                                             );
@@ -1232,10 +1856,11 @@ pub fn spawn(
                                             let allocation =
                                                 allocator.allocate(alloc_size).unwrap();
 
-                                            staging_buffers[active_index].write().unwrap()
-                                                [*staging_buffer_index..]
-                                                [..obtained_image.data.len()]
-                                                .copy_from_slice(&obtained_image.data);
+                                            obtained_image.write_data(
+                                                &mut staging_buffers[active_index].write().unwrap()
+                                                    [*staging_buffer_index..]
+                                                    [..obtained_image.data_len()],
+                                            );
 
                                             active_atlas_copy_infos
                                                 .entry((
@@ -1291,7 +1916,7 @@ pub fn spawn(
                                                     ..BufferImageCopy::default()
                                                 });
 
-                                            *staging_buffer_index += obtained_image.data.len();
+                                            *staging_buffer_index += obtained_image.data_len();
                                             image_allocated = true;
 
                                             contains.insert(
@@ -1310,16 +1935,15 @@ pub fn spawn(
                                 // no suitable atlas found, create a new one
                                 if !image_allocated {
                                     let mut allocator = AtlasAllocator::with_options(
-                                        AtlasSize::new(4096, 4096),
-                                        &AtlasAllocatorOptions {
-                                            alignment: AtlasSize::new(16, 16),
-                                            small_size_threshold: 16,
-                                            large_size_threshold: 512,
-                                        },
+                                        AtlasSize::new(
+                                            atlas_initial_size as i32,
+                                            atlas_initial_size as i32,
+                                        ),
+                                        &atlas_allocator_options,
                                     );
 
                                     let (images, staging_buffers) = create_images_with_buffers(
-                                        &mem_alloc,
+                                        mem_alloc,
                                         image_format,
                                         allocator.size().width as u32,
                                         allocator.size().height as u32,
@@ -1328,14 +1952,14 @@ pub fn spawn(
 
                                     clear_image(
                                         &mut active_cmd_builder,
-                                        &mem_alloc,
+                                        mem_alloc,
                                         &mut zeroing_buffer,
                                         images[active_index].clone(),
                                     );
 
                                     clear_image(
                                         &mut next_cmd_builder,
-                                        &mem_alloc,
+                                        mem_alloc,
                                         &mut zeroing_buffer,
                                         images[inactive_index].clone(),
                                     );
@@ -1344,9 +1968,10 @@ pub fn spawn(
                                     let mut staging_buffer_index = 0;
 
                                     let allocation = allocator.allocate(alloc_size).unwrap();
-                                    staging_buffers[active_index].write().unwrap()
-                                        [staging_buffer_index..][..obtained_image.data.len()]
-                                        .copy_from_slice(&obtained_image.data);
+                                    obtained_image.write_data(
+                                        &mut staging_buffers[active_index].write().unwrap()
+                                            [staging_buffer_index..][..obtained_image.data_len()],
+                                    );
 
                                     active_atlas_copy_infos
                                         .entry((
@@ -1400,7 +2025,7 @@ pub fn spawn(
                                             ..BufferImageCopy::default()
                                         });
 
-                                    staging_buffer_index += obtained_image.data.len();
+                                    staging_buffer_index += obtained_image.data_len();
                                     contains.insert(
                                         image_source,
                                         ContainedImage {
@@ -1441,14 +2066,50 @@ pub fn spawn(
                 }
             }
 
+            if modified_images {
+                image_source_index = image_source_index_of(
+                    image_backings
+                        .iter()
+                        .map(|image_backing| image_backing.resident_sources()),
+                );
+            }
+
             if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
                 metrics.images_obtain = inst.elapsed().as_micros() as f32 / 1000.0;
+                let block_size = image_format.block_size();
+
+                for image_backing in image_backings.iter() {
+                    if let ImageBacking::Atlas {
+                        allocator, ..
+                    } = image_backing
+                    {
+                        let mut used_area: DeviceSize = 0;
+
+                        allocator.for_each_allocated_rectangle(|_, rectangle| {
+                            used_area += rectangle.area() as DeviceSize;
+                        });
+
+                        let total_area = allocator.size().width as DeviceSize
+                            * allocator.size().height as DeviceSize;
+
+                        // NOTE: Atlases are double buffered, so each allocation exists twice.
+                        metrics.atlas_count += 1;
+                        metrics.atlas_total_bytes += total_area * block_size * 2;
+                        metrics.atlas_used_bytes += used_area * block_size * 2;
+
+                        metrics
+                            .atlas_fragmentation
+                            .push(atlas_fragmentation(allocator));
+                    }
+                }
+
                 *inst = Instant::now();
             }
 
             // -- Count Vertexes -- //
 
-            let mut z_count: BTreeMap<OrderedFloat<f32>, DeviceSize> = BTreeMap::new();
+            let mut z_count: BTreeMap<DrawKey, DeviceSize> = BTreeMap::new();
+            let mut next_layers: BTreeSet<BinID> = BTreeSet::new();
 
             for state in bin_states.values() {
                 let vertex_data = match &state.vertex_data {
@@ -1457,6 +2118,19 @@ pub fn spawn(
                 };
 
                 for (z, z_data) in vertex_data.iter() {
+                    match z.layer {
+                        Some(layer) => {
+                            next_layers.insert(layer);
+                        },
+                        None => {
+                            for image_source in z_data.data.keys() {
+                                if let ImageSource::Layer(layer) = image_source {
+                                    next_layers.insert(*layer);
+                                }
+                            }
+                        },
+                    }
+
                     *z_count.entry(*z).or_insert(0) += match z_data.range.as_ref() {
                         Some(range) => range.end - range.start,
                         None => {
@@ -1472,6 +2146,55 @@ pub fn spawn(
 
             let total_vertexes = z_count.values().sum();
 
+            // NOTE: The images of layers are placed after the worker's images. When the index of
+            //       a layer changes, the data of its composite has to be uploaded again.
+
+            let next_layers = next_layers.into_iter().collect::<Vec<_>>();
+            let next_layer_tex_base = image_backings.len() as u32;
+
+            if next_layers != layers || next_layer_tex_base != layer_tex_base {
+                for state in bin_states.values_mut() {
+                    if let Some(vertex_data) = &mut state.vertex_data {
+                        for (z, z_data) in vertex_data.iter_mut() {
+                            if z.layer.is_none()
+                                && z_data.range.is_some()
+                                && z_data.data.keys().any(|image_source| {
+                                    matches!(image_source, ImageSource::Layer(_))
+                                })
+                            {
+                                z_data.range = None;
+                                modified_vertexes = true;
+                            }
+                        }
+                    }
+                }
+
+                layers = next_layers;
+                layer_tex_base = next_layer_tex_base;
+            }
+
+            let layer_ranges = {
+                let mut layer_counts: HashMap<BinID, DeviceSize> = HashMap::new();
+                let mut range_start: DeviceSize = 0;
+
+                for (z, count) in z_count.iter() {
+                    match z.layer {
+                        Some(layer) => *layer_counts.entry(layer).or_insert(0) += count,
+                        None => range_start += count,
+                    }
+                }
+
+                layers
+                    .iter()
+                    .map(|layer| {
+                        let count = layer_counts.get(layer).copied().unwrap_or(0);
+                        let range = range_start..(range_start + count);
+                        range_start += count;
+                        range
+                    })
+                    .collect::<Vec<_>>()
+            };
+
             if let Some((ref mut inst, _, ref mut metrics)) = metrics_op.as_mut() {
                 metrics.vertex_count = inst.elapsed().as_micros() as f32 / 1000.0;
                 *inst = Instant::now();
@@ -1480,27 +2203,65 @@ pub fn spawn(
             // -- Check Buffer Size -- //
 
             let mut old_vertex_buffers = None;
+            let buffer_capacity = vertex_buffers[active_index].len() / 2;
+
+            if total_vertexes * 4 < buffer_capacity
+                && vertex_buffers[active_index].len() > MIN_VERTEX_BUFFER_LEN
+            {
+                low_usage_updates += 1;
+            } else {
+                low_usage_updates = 0;
+            }
 
-            if vertex_buffers[active_index].len() / 2 < total_vertexes {
-                let mut new_buffer_size = vertex_buffers[active_index].len() / 2;
+            let new_buffer_len_op = if buffer_capacity < total_vertexes {
+                let mut new_buffer_size = buffer_capacity;
 
                 while new_buffer_size < total_vertexes {
                     new_buffer_size *= 2;
                 }
 
+                Some(new_buffer_size)
+            } else if low_usage_updates >= VERTEX_BUFFER_SHRINK_UPDATES {
+                // NOTE: Leave headroom of double the current usage, so that the buffers don't
+                //       immediately need to grow again.
+                let mut new_buffer_len = vertex_buffers[active_index].len();
+
+                while new_buffer_len > MIN_VERTEX_BUFFER_LEN && total_vertexes * 4 < new_buffer_len
+                {
+                    new_buffer_len /= 2;
+                }
+
+                Some(new_buffer_len.max(MIN_VERTEX_BUFFER_LEN))
+            } else {
+                None
+            };
+
+            if let Some(new_buffer_len) = new_buffer_len_op {
+                // NOTE: The old buffers are kept until the end of this update, so that existing
+                //       data can be moved. The renderer keeps its own reference to the buffer
+                //       it is using until it receives the new one.
                 let (new_staging_buffers, new_vertex_buffers) =
-                    create_buffers(&mem_alloc, new_buffer_size);
+                    create_buffers(mem_alloc, new_buffer_len);
 
                 old_vertex_buffers = Some(vertex_buffers.split_off(0));
                 staging_buffers = new_staging_buffers;
                 vertex_buffers = new_vertex_buffers;
                 modified_vertexes = true;
+                low_usage_updates = 0;
+            }
+
+            if let Some((_, _, ref mut metrics)) = metrics_op.as_mut() {
+                metrics.vertex_buffer_bytes = vertex_buffers
+                    .iter()
+                    .chain(staging_buffers.iter())
+                    .map(|buffer| buffer.size())
+                    .sum();
             }
 
             // -- Move & Upload Vertex Data -- //
 
             if modified_vertexes {
-                let mut z_next_index: BTreeMap<OrderedFloat<f32>, DeviceSize> = BTreeMap::new();
+                let mut z_next_index: BTreeMap<DrawKey, DeviceSize> = BTreeMap::new();
 
                 if old_vertex_buffers.is_none() {
                     vertex_buffer_offset ^= true;
@@ -1521,116 +2282,79 @@ pub fn spawn(
                 let mut upload_regions = Vec::new();
                 let mut staging_buffer_write = staging_buffers[active_index].write().unwrap();
 
-                for state in bin_states.values_mut() {
-                    let vertex_data = match state.vertex_data.as_mut() {
-                        Some(some) => some,
-                        None => continue,
-                    };
-
-                    for (z, z_data) in vertex_data.iter_mut() {
-                        match z_data.range.clone() {
-                            Some(src_range) => {
-                                let next_index = z_next_index.get_mut(z).unwrap();
-                                let range_len = src_range.end - src_range.start;
-                                let dst_range = *next_index..(*next_index + range_len);
-                                *next_index += range_len;
+                place_vertex_data(&mut bin_states, z_next_index, |z_data, dst_start| {
+                    match z_data.range.clone() {
+                        Some(src_range) => {
+                            let range_len = src_range.end - src_range.start;
+                            let dst_range = dst_start..(dst_start + range_len);
 
-                                if dst_range == src_range && old_vertex_buffers.is_none() {
-                                    continue;
-                                }
+                            if dst_range == src_range && old_vertex_buffers.is_none() {
+                                return range_len;
+                            }
 
-                                move_regions.push(BufferCopy {
-                                    src_offset: src_range.start,
-                                    dst_offset: dst_range.start,
-                                    size: range_len,
-                                    ..BufferCopy::default()
-                                });
+                            move_regions.push(BufferCopy {
+                                src_offset: src_range.start,
+                                dst_offset: dst_range.start,
+                                size: range_len,
+                                ..BufferCopy::default()
+                            });
 
-                                z_data.range = Some(dst_range.start..(dst_range.start + range_len));
-                            },
-                            None => {
-                                let mut z_vertexes = Vec::new();
+                            z_data.range = Some(dst_range);
+                            range_len
+                        },
+                        None => {
+                            let mut z_vertexes = Vec::new();
 
-                                for (image_source, vertexes) in z_data.data.iter() {
-                                    let mut vertexes = vertexes.clone();
+                            for (image_source, vertexes) in z_sources(&z_data.data) {
+                                let mut vertexes = vertexes.clone();
 
-                                    if *image_source != ImageSource::None {
-                                        let mut tex_i_op = None;
-                                        let mut coords_offset = [0.0; 2];
+                                match image_source {
+                                    ImageSource::None => (),
+                                    ImageSource::Layer(layer) => {
+                                        let tex_i = layer_tex_base
+                                            + layers.binary_search(layer).unwrap() as u32;
 
-                                        for (image_index, image_backing) in
-                                            image_backings.iter().enumerate()
-                                        {
-                                            match image_backing {
-                                                ImageBacking::Atlas {
-                                                    contains, ..
-                                                } => {
-                                                    if let Some(contained) =
-                                                        contains.get(image_source)
-                                                    {
-                                                        coords_offset = [
-                                                            contained.data.rectangle.min.x as f32
-                                                                + 1.0,
-                                                            contained.data.rectangle.min.y as f32
-                                                                + 1.0,
-                                                        ];
-
-                                                        tex_i_op = Some(image_index);
-                                                        break;
-                                                    }
-                                                },
-                                                ImageBacking::Dedicated {
-                                                    source, ..
-                                                } => {
-                                                    if *source == *image_source {
-                                                        tex_i_op = Some(image_index);
-                                                        break;
-                                                    }
-                                                },
-                                                ImageBacking::UserProvided {
-                                                    source, ..
-                                                } => {
-                                                    if *source == *image_source {
-                                                        tex_i_op = Some(image_index);
-                                                        break;
-                                                    }
-                                                },
-                                            }
+                                        for vertex in vertexes.iter_mut() {
+                                            vertex.tex_i = tex_i;
                                         }
-
-                                        let tex_i = tex_i_op.unwrap() as u32;
+                                    },
+                                    _ => {
+                                        let image_index =
+                                            *image_source_index.get(image_source).unwrap();
+                                        let coords_offset = image_backings[image_index]
+                                            .coords_offset(image_source)
+                                            .unwrap();
 
                                         for vertex in vertexes.iter_mut() {
-                                            vertex.tex_i = tex_i;
+                                            vertex.tex_i = image_index as u32;
                                             vertex.coords[0] += coords_offset[0];
                                             vertex.coords[1] += coords_offset[1];
                                         }
-                                    }
-
-                                    z_vertexes.append(&mut vertexes);
+                                    },
                                 }
 
-                                let range_len = z_vertexes.len() as DeviceSize;
-                                let next_index = z_next_index.get_mut(z).unwrap();
+                                z_vertexes.append(&mut vertexes);
+                            }
+
+                            let range_len = z_vertexes.len() as DeviceSize;
 
-                                (*staging_buffer_write)[(next_staging_index as usize)..]
-                                    [..z_vertexes.len()]
-                                    .clone_from_slice(&z_vertexes);
+                            (*staging_buffer_write)[(next_staging_index as usize)..]
+                                [..z_vertexes.len()]
+                                .clone_from_slice(&z_vertexes);
 
-                                upload_regions.push(BufferCopy {
-                                    src_offset: next_staging_index,
-                                    dst_offset: *next_index,
-                                    size: range_len,
-                                    ..BufferCopy::default()
-                                });
+                            upload_regions.push(BufferCopy {
+                                src_offset: next_staging_index,
+                                dst_offset: dst_start,
+                                size: range_len,
+                                ..BufferCopy::default()
+                            });
 
-                                z_data.range = Some(*next_index..(*next_index + range_len));
-                                next_staging_index += range_len;
-                                *next_index += range_len;
-                            },
-                        }
+                            z_data.range = Some(dst_start..(dst_start + range_len));
+                            next_staging_index += range_len;
+                            range_len
+                        },
                     }
-                }
+                });
 
                 if !move_regions.is_empty() {
                     move_regions.sort_by_key(|region| region.src_offset);
@@ -1657,7 +2381,7 @@ pub fn spawn(
                         None => &vertex_buffers,
                     };
 
-                    active_cmd_builder
+                    active_vertex_cmd_builder
                         .copy_buffer(CopyBufferInfoTyped {
                             regions: merged_move_regions.clone().into(),
                             ..CopyBufferInfoTyped::buffers(
@@ -1667,7 +2391,7 @@ pub fn spawn(
                         })
                         .unwrap();
 
-                    next_cmd_builder
+                    next_vertex_cmd_builder
                         .copy_buffer(CopyBufferInfoTyped {
                             regions: merged_move_regions.into(),
                             ..CopyBufferInfoTyped::buffers(
@@ -1698,7 +2422,7 @@ pub fn spawn(
                         }
                     }
 
-                    active_cmd_builder
+                    active_vertex_cmd_builder
                         .copy_buffer(CopyBufferInfoTyped {
                             regions: merged_upload_regions.clone().into(),
                             ..CopyBufferInfoTyped::buffers(
@@ -1708,7 +2432,7 @@ pub fn spawn(
                         })
                         .unwrap();
 
-                    next_cmd_builder
+                    next_vertex_cmd_builder
                         .copy_buffer(CopyBufferInfoTyped {
                             regions: merged_upload_regions.into(),
                             ..CopyBufferInfoTyped::buffers(
@@ -1725,17 +2449,26 @@ pub fn spawn(
                 *inst = Instant::now();
             }
 
-            // active cmd builder has something to execute
-            if exec_prev_cmds || modified_vertexes || modified_images {
-                active_cmd_builder
+            // active cmd builders have something to execute
+            if exec_prev_cmds || exec_prev_vertex_cmds || modified_vertexes || modified_images {
+                let image_future = active_cmd_builder
                     .build()
                     .unwrap()
-                    .execute(queue.clone())
+                    .execute(image_queue.clone())
                     .unwrap()
                     .then_signal_fence_and_flush()
+                    .unwrap();
+
+                let vertex_future = active_vertex_cmd_builder
+                    .build()
                     .unwrap()
-                    .wait(None)
+                    .execute(queue.clone())
+                    .unwrap()
+                    .then_signal_fence_and_flush()
                     .unwrap();
+
+                image_future.wait(None).unwrap();
+                vertex_future.wait(None).unwrap();
             }
 
             // next cmd builder has commands to execute perform a swap
@@ -1745,6 +2478,7 @@ pub fn spawn(
                 }
 
                 next_cmd_builder_op = Some(next_cmd_builder);
+                next_vertex_cmd_builder_op = Some(next_vertex_cmd_builder);
                 let barrier = Arc::new(Barrier::new(2));
 
                 let images = image_backings
@@ -1767,7 +2501,7 @@ pub fn spawn(
                 let metrics_op = metrics_op.map(|(inst, inst_total, mut metrics)| {
                     metrics.cmd_buf_execute = inst.elapsed().as_micros() as f32 / 1000.0;
                     metrics.total = inst_total.elapsed().as_micros() as f32 / 1000.0;
-                    metrics
+                    Box::new(metrics)
                 });
 
                 let vertex_range = if vertex_buffer_offset {
@@ -1781,7 +2515,9 @@ pub fn spawn(
                     .send(RenderEvent::Update {
                         buffer: vertex_buffers[active_index].clone().slice(vertex_range),
                         images,
+                        layers: layer_ranges,
                         barrier: barrier.clone(),
+                        damage,
                         metrics: metrics_op,
                     })
                     .is_err()
@@ -1790,17 +2526,27 @@ pub fn spawn(
                 }
 
                 barrier.wait();
+                damage = Damage::None;
                 active_index ^= 1;
                 inactive_index ^= 1;
             }
         }
-    });
+    }))
+}
 
-    Ok(())
+/// Obtain the message of a panic's payload.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic payload")
+    }
 }
 
 fn create_buffers(
-    mem_alloc: &Arc<StandardMemoryAllocator>,
+    mem_alloc: &RenderMemory,
     len: DeviceSize,
 ) -> (Vec<Subbuffer<[ItfVertInfo]>>, Vec<Subbuffer<[ItfVertInfo]>>) {
     let mut staging_buffers = Vec::with_capacity(2);
@@ -1809,7 +2555,7 @@ fn create_buffers(
     for _ in 0..2 {
         staging_buffers.push(
             Buffer::new_slice::<ItfVertInfo>(
-                mem_alloc.clone(),
+                mem_alloc.allocator().clone(),
                 BufferCreateInfo {
                     usage: BufferUsage::TRANSFER_SRC,
                     ..BufferCreateInfo::default()
@@ -1821,7 +2567,7 @@ fn create_buffers(
                             | MemoryPropertyFlags::DEVICE_COHERENT,
                         ..MemoryTypeFilter::empty()
                     },
-                    allocate_preference: MemoryAllocatePreference::AlwaysAllocate,
+                    allocate_preference: mem_alloc.preference(),
                     ..AllocationCreateInfo::default()
                 },
                 len,
@@ -1831,11 +2577,12 @@ fn create_buffers(
 
         vertex_buffers.push(
             Buffer::new_slice::<ItfVertInfo>(
-                mem_alloc.clone(),
+                mem_alloc.allocator().clone(),
                 BufferCreateInfo {
                     usage: BufferUsage::TRANSFER_SRC
                         | BufferUsage::TRANSFER_DST
                         | BufferUsage::VERTEX_BUFFER,
+                    sharing: mem_alloc.sharing(),
                     ..BufferCreateInfo::default()
                 },
                 AllocationCreateInfo {
@@ -1844,7 +2591,7 @@ fn create_buffers(
                         not_preferred_flags: MemoryPropertyFlags::HOST_CACHED,
                         ..MemoryTypeFilter::empty()
                     },
-                    allocate_preference: MemoryAllocatePreference::AlwaysAllocate,
+                    allocate_preference: mem_alloc.preference(),
                     ..AllocationCreateInfo::default()
                 },
                 len * 2,
@@ -1853,12 +2600,112 @@ fn create_buffers(
         );
     }
 
+    for buffer in staging_buffers.iter().chain(vertex_buffers.iter()) {
+        mem_alloc.track_buffer(buffer.buffer());
+    }
+
     (staging_buffers, vertex_buffers)
 }
 
+// NOTE: The tex_i of a source is the index of the first backing that contains it.
+fn image_source_index_of<'a, B, S>(backings: B) -> HashMap<ImageSource, usize>
+where
+    B: IntoIterator<Item = S>,
+    S: IntoIterator<Item = &'a ImageSource>,
+{
+    let mut index = HashMap::new();
+
+    for (image_index, sources) in backings.into_iter().enumerate() {
+        for image_source in sources {
+            index.entry(image_source.clone()).or_insert(image_index);
+        }
+    }
+
+    index
+}
+
+// NOTE: Sources that are already resident only have their use count increased, the rest are
+//       returned to be obtained.
+fn queue_image_sources<F>(
+    add_image_sources: HashMap<ImageSource, usize>,
+    image_source_index: &HashMap<ImageSource, usize>,
+    mut add_uses: F,
+) -> HashMap<ImageSource, usize>
+where
+    F: FnMut(usize, &ImageSource, usize),
+{
+    let mut obtain_image_sources = HashMap::new();
+
+    for (image_source, count) in add_image_sources {
+        match image_source_index.get(&image_source) {
+            Some(image_index) => add_uses(*image_index, &image_source, count),
+            None => *obtain_image_sources.entry(image_source).or_insert(0) += count,
+        }
+    }
+
+    obtain_image_sources
+}
+
+// NOTE: Bins are iterated in order of their `BinID`, so that within a z the data of bins is always
+//       placed in the same order. This is the tiebreak for bins that share a z, where later
+//       created bins are drawn above.
+fn place_vertex_data<F>(
+    bin_states: &mut BTreeMap<BinID, BinState>,
+    mut z_next_index: BTreeMap<DrawKey, DeviceSize>,
+    mut place: F,
+) where
+    F: FnMut(&mut BinZData, DeviceSize) -> DeviceSize,
+{
+    for state in bin_states.values_mut() {
+        let vertex_data = match state.vertex_data.as_mut() {
+            Some(some) => some,
+            None => continue,
+        };
+
+        for (z, z_data) in vertex_data.iter_mut() {
+            let next_index = z_next_index.get_mut(z).unwrap();
+            *next_index += place(z_data, *next_index);
+        }
+    }
+}
+
+// NOTE: Untextured vertexes are placed first, so that a background or custom vertexes are always
+//       below textured ones.
+fn z_sources(
+    data: &HashMap<ImageSource, Vec<ItfVertInfo>>,
+) -> Vec<(&ImageSource, &Vec<ItfVertInfo>)> {
+    let mut z_sources = data.iter().collect::<Vec<_>>();
+    z_sources.sort_by_key(|(image_source, _)| **image_source != ImageSource::None);
+    z_sources
+}
+
+fn add_vertex_data_damage(damage: &mut Damage, vertex_data: &BTreeMap<DrawKey, BinZData>) {
+    damage.add_vertexes(
+        vertex_data
+            .values()
+            .flat_map(|z_data| z_data.data.values().flatten()),
+    );
+}
+
+fn atlas_fragmentation(allocator: &AtlasAllocator) -> f32 {
+    let mut free_area: DeviceSize = 0;
+    let mut largest_free_area: DeviceSize = 0;
+
+    allocator.for_each_free_rectangle(|rectangle| {
+        free_area += rectangle.area() as DeviceSize;
+        largest_free_area = largest_free_area.max(rectangle.area() as DeviceSize);
+    });
+
+    if free_area == 0 {
+        0.0
+    } else {
+        1.0 - (largest_free_area as f32 / free_area as f32)
+    }
+}
+
 fn check_resize_zeroing_buffer(
     cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
-    mem_alloc: &Arc<StandardMemoryAllocator>,
+    mem_alloc: &RenderMemory,
     zeroing_buffer: &mut Option<Subbuffer<[u8]>>,
     required_len: DeviceSize,
 ) {
@@ -1866,7 +2713,7 @@ fn check_resize_zeroing_buffer(
 
     if zeroing_buffer.is_none() || zeroing_buffer.as_ref().unwrap().size() < buffer_len {
         let buffer = Buffer::new_slice::<u8>(
-            mem_alloc.clone(),
+            mem_alloc.allocator().clone(),
             BufferCreateInfo {
                 usage: BufferUsage::TRANSFER_SRC | BufferUsage::TRANSFER_DST,
                 ..BufferCreateInfo::default()
@@ -1877,7 +2724,7 @@ fn check_resize_zeroing_buffer(
                     not_preferred_flags: MemoryPropertyFlags::HOST_CACHED,
                     ..MemoryTypeFilter::empty()
                 },
-                allocate_preference: MemoryAllocatePreference::AlwaysAllocate,
+                allocate_preference: mem_alloc.preference(),
                 ..AllocationCreateInfo::default()
             },
             buffer_len,
@@ -1888,13 +2735,14 @@ fn check_resize_zeroing_buffer(
             .fill_buffer(buffer.clone().reinterpret(), 0)
             .unwrap();
 
+        mem_alloc.track_buffer(buffer.buffer());
         *zeroing_buffer = Some(buffer);
     }
 }
 
 fn clear_image(
     cmd_builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
-    mem_alloc: &Arc<StandardMemoryAllocator>,
+    mem_alloc: &RenderMemory,
     zeroing_buffer: &mut Option<Subbuffer<[u8]>>,
     image: Arc<Image>,
 ) {
@@ -1913,20 +2761,20 @@ fn clear_image(
 }
 
 fn create_buffer_for_image(
-    mem_alloc: &Arc<StandardMemoryAllocator>,
+    mem_alloc: &RenderMemory,
     image_format: VkFormat,
     width: u32,
     height: u32,
     buffer_long_lived: bool,
 ) -> Subbuffer<[u8]> {
     let buffer_alloc_preference = if buffer_long_lived {
-        MemoryAllocatePreference::AlwaysAllocate
+        mem_alloc.preference()
     } else {
         MemoryAllocatePreference::Unknown
     };
 
-    Buffer::new_slice::<u8>(
-        mem_alloc.clone(),
+    let buffer = Buffer::new_slice::<u8>(
+        mem_alloc.allocator().clone(),
         BufferCreateInfo {
             usage: BufferUsage::TRANSFER_SRC,
             ..BufferCreateInfo::default()
@@ -1943,43 +2791,51 @@ fn create_buffer_for_image(
         },
         image_format.block_size() * width as DeviceSize * height as DeviceSize,
     )
-    .unwrap()
+    .unwrap();
+
+    mem_alloc.track_buffer(buffer.buffer());
+    buffer
 }
 
 fn create_image_with_buffer(
-    mem_alloc: &Arc<StandardMemoryAllocator>,
+    mem_alloc: &RenderMemory,
     image_format: VkFormat,
     width: u32,
     height: u32,
     buffer_long_lived: bool,
 ) -> (Arc<Image>, Subbuffer<[u8]>) {
-    (
-        Image::new(
-            mem_alloc.clone(),
-            ImageCreateInfo {
-                image_type: ImageType::Dim2d,
-                format: image_format,
-                extent: [width, height, 1],
-                usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
-                ..ImageCreateInfo::default()
-            },
-            AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter {
-                    preferred_flags: MemoryPropertyFlags::DEVICE_LOCAL,
-                    not_preferred_flags: MemoryPropertyFlags::HOST_CACHED,
-                    ..MemoryTypeFilter::empty()
-                },
-                allocate_preference: MemoryAllocatePreference::AlwaysAllocate,
-                ..AllocationCreateInfo::default()
+    let image = Image::new(
+        mem_alloc.allocator().clone(),
+        ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format: image_format,
+            extent: [width, height, 1],
+            usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+            sharing: mem_alloc.sharing(),
+            ..ImageCreateInfo::default()
+        },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter {
+                preferred_flags: MemoryPropertyFlags::DEVICE_LOCAL,
+                not_preferred_flags: MemoryPropertyFlags::HOST_CACHED,
+                ..MemoryTypeFilter::empty()
             },
-        )
-        .unwrap(),
+            allocate_preference: mem_alloc.preference(),
+            ..AllocationCreateInfo::default()
+        },
+    )
+    .unwrap();
+
+    mem_alloc.track_image(&image);
+
+    (
+        image,
         create_buffer_for_image(mem_alloc, image_format, width, height, buffer_long_lived),
     )
 }
 
 fn create_images_with_buffers(
-    mem_alloc: &Arc<StandardMemoryAllocator>,
+    mem_alloc: &RenderMemory,
     image_format: VkFormat,
     width: u32,
     height: u32,
@@ -1991,3 +2847,151 @@ fn create_images_with_buffers(
         create_image_with_buffer(mem_alloc, image_format, width, height, buffer_long_lived);
     (vec![image1, image2], vec![buffer1, buffer2])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Weak;
+
+    use ordered_float::OrderedFloat;
+
+    use super::{
+        image_source_index_of, place_vertex_data, queue_image_sources, z_sources, BinState,
+        BinZData, DrawKey,
+    };
+    use crate::interface::{BinID, ItfVertInfo};
+    use crate::render::{ImageCacheKey, ImageSource};
+
+    fn overlapping_bin(z: DrawKey) -> BinState {
+        BinState {
+            weak: Weak::new(),
+            image_sources: Vec::new(),
+            vertex_data: Some(BTreeMap::from([(
+                z,
+                BinZData {
+                    range: None,
+                    data: HashMap::from([(ImageSource::None, vec![ItfVertInfo::default(); 6])]),
+                },
+            )])),
+            fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn image_source_indexes() {
+        let [a, b, c] = [0, 1, 2].map(|i| ImageSource::Cache(ImageCacheKey::Encoded(i)));
+        let index = image_source_index_of([vec![&a, &b], vec![], vec![&c]]);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index[&a], 0);
+        assert_eq!(index[&b], 0);
+        assert_eq!(index[&c], 2);
+    }
+
+    #[test]
+    fn image_source_first_backing() {
+        let a = ImageSource::Cache(ImageCacheKey::Encoded(0));
+        let index = image_source_index_of([vec![&a], vec![&a]]);
+        assert_eq!(index[&a], 0);
+    }
+
+    #[test]
+    fn resident_source_reused() {
+        let a = ImageSource::Cache(ImageCacheKey::Encoded(0));
+        let mut use_counts: HashMap<ImageSource, usize> = HashMap::new();
+        let mut index = HashMap::new();
+
+        // The first bin referencing the source has it obtained.
+        let obtain = queue_image_sources(
+            HashMap::from([(a.clone(), 1)]),
+            &index,
+            |_, _, _| unreachable!(),
+        );
+
+        assert_eq!(obtain, HashMap::from([(a.clone(), 1)]));
+        use_counts.insert(a.clone(), 1);
+        index = image_source_index_of([vec![&a]]);
+
+        // A second bin referencing it reuses the resident source.
+        let obtain = queue_image_sources(
+            HashMap::from([(a.clone(), 1)]),
+            &index,
+            |image_index, image_source, count| {
+                assert_eq!(image_index, 0);
+                *use_counts.get_mut(image_source).unwrap() += count;
+            },
+        );
+
+        assert!(obtain.is_empty());
+        assert_eq!(use_counts[&a], 2);
+    }
+
+    #[test]
+    fn equal_z_draw_order() {
+        let z = DrawKey {
+            layer: None,
+            z: OrderedFloat(0.5),
+        };
+
+        let mut bin_states = BTreeMap::new();
+
+        // NOTE: Inserted out of order, so that the order isn't from insertion.
+        for bin_id in [BinID(2), BinID(1)] {
+            bin_states.insert(bin_id, overlapping_bin(z));
+        }
+
+        for _ in 0..2 {
+            for state in bin_states.values_mut() {
+                state
+                    .vertex_data
+                    .as_mut()
+                    .unwrap()
+                    .get_mut(&z)
+                    .unwrap()
+                    .range = None;
+            }
+
+            let mut placed = Vec::new();
+
+            place_vertex_data(
+                &mut bin_states,
+                BTreeMap::from([(z, 0)]),
+                |z_data, dst_start| {
+                    let range_len = z_data.data[&ImageSource::None].len() as u64;
+                    placed.push(dst_start);
+                    z_data.range = Some(dst_start..(dst_start + range_len));
+                    range_len
+                },
+            );
+
+            assert_eq!(placed, vec![0, 6]);
+
+            // NOTE: The bin created later is placed after, so it is drawn above.
+            let range = |bin_id| {
+                bin_states[&bin_id].vertex_data.as_ref().unwrap()[&z]
+                    .range
+                    .clone()
+                    .unwrap()
+            };
+
+            assert_eq!(range(BinID(1)), 0..6);
+            assert_eq!(range(BinID(2)), 6..12);
+        }
+    }
+
+    #[test]
+    fn untextured_placed_first() {
+        let data = HashMap::from([
+            (
+                ImageSource::Cache(ImageCacheKey::Encoded(0)),
+                vec![ItfVertInfo::default(); 3],
+            ),
+            (ImageSource::None, vec![ItfVertInfo::default(); 6]),
+            (
+                ImageSource::Cache(ImageCacheKey::Encoded(1)),
+                vec![ItfVertInfo::default(); 3],
+            ),
+        ]);
+
+        assert_eq!(*z_sources(&data)[0].0, ImageSource::None);
+    }
+}
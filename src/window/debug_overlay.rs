@@ -0,0 +1,239 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::input::InputHookID;
+use crate::interface::{Bin, BinID, BinPosition, BinStyle, BinVert, Color, TextWrap};
+use crate::interval::{IntvlHookCtrl, IntvlHookID};
+use crate::window::Window;
+use crate::Basalt;
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+const LINE_WIDTH: f32 = 1.0;
+const LABEL_WIDTH: f32 = 40.0;
+const LABEL_HEIGHT: f32 = 14.0;
+
+/// Bins associated to a window drawn over with their bounds & z-index.
+///
+/// The overlay is made of a root `Bin` in the top-most layer, which draws the bounds with
+/// `custom_verts`, and a label `Bin` for each visible `Bin`. These are excluded from
+/// `Window::bins_at`, so that they don't interfere with input.
+pub(crate) struct DebugOverlay {
+    root: Arc<Bin>,
+    input_hook_id: InputHookID,
+    intvl_hook_id: IntvlHookID,
+}
+
+impl DebugOverlay {
+    pub(crate) fn new(window: &Arc<Window>) -> Self {
+        let root = window.new_bin();
+
+        root.style_update(BinStyle {
+            position: Some(BinPosition::Window),
+            z_layer: Some(i8::MAX),
+            pos_from_t: Some(0.0),
+            pos_from_b: Some(0.0),
+            pos_from_l: Some(0.0),
+            pos_from_r: Some(0.0),
+            ..BinStyle::default()
+        })
+        .expect_valid();
+
+        let cursor: Arc<Mutex<Option<[f32; 2]>>> = Arc::new(Mutex::new(None));
+        let cursor_cp = cursor.clone();
+
+        let input_hook_id = window.on_cursor(move |_, window_state, _| {
+            *cursor_cp.lock() = Some(window_state.cursor_pos());
+            Default::default()
+        });
+
+        let root_wk = Arc::downgrade(&root);
+        let window_wk = Arc::downgrade(window);
+        let mut labels: Vec<Arc<Bin>> = Vec::new();
+
+        let intvl_hook_id =
+            window
+                .basalt_ref()
+                .interval_ref()
+                .do_every(REFRESH_INTERVAL, None, move |_| {
+                    let (root, window) = match (root_wk.upgrade(), window_wk.upgrade()) {
+                        (Some(root), Some(window)) => (root, window),
+                        _ => return IntvlHookCtrl::Remove,
+                    };
+
+                    let cursor = *cursor.lock();
+                    refresh(&window, &root, &mut labels, cursor);
+                    IntvlHookCtrl::Continue
+                });
+
+        window.basalt_ref().interval_ref().start(intvl_hook_id);
+
+        Self {
+            root,
+            input_hook_id,
+            intvl_hook_id,
+        }
+    }
+
+    pub(crate) fn root_id(&self) -> BinID {
+        self.root.id()
+    }
+
+    pub(crate) fn remove(self, basalt: &Basalt) {
+        basalt.input_ref().remove_hook(self.input_hook_id);
+        basalt.interval_ref().remove(self.intvl_hook_id);
+    }
+}
+
+/// Check if the `Bin` is the root of the overlay or one of its labels.
+pub(crate) fn is_overlay_bin(root_id: BinID, bin: &Bin) -> bool {
+    bin.id() == root_id
+        || bin
+            .parent()
+            .map(|parent| parent.id() == root_id)
+            .unwrap_or(false)
+}
+
+fn refresh(
+    window: &Arc<Window>,
+    root: &Arc<Bin>,
+    labels: &mut Vec<Arc<Bin>>,
+    cursor: Option<[f32; 2]>,
+) {
+    let root_id = root.id();
+
+    let mut bins = window
+        .associated_bins()
+        .into_iter()
+        .filter(|bin| !is_overlay_bin(root_id, bin))
+        .map(|bin| (bin.id(), bin.post_update()))
+        .filter(|(_, post_update)| post_update.visible)
+        .collect::<Vec<_>>();
+
+    bins.sort_by_key(|(bin_id, _)| *bin_id);
+
+    let hovered_id = cursor
+        .and_then(|[x, y]| window.bin_at(x, y))
+        .map(|bin| bin.id());
+
+    let mut verts = Vec::new();
+
+    for (bin_id, post_update) in bins.iter() {
+        let bounds = [
+            post_update.tlo[0],
+            post_update.tro[0],
+            post_update.tlo[1],
+            post_update.blo[1],
+        ];
+
+        if Some(*bin_id) == hovered_id {
+            push_rect(&mut verts, bounds, Color::shex("ffff0040"));
+        }
+
+        push_outline(
+            &mut verts,
+            post_update.optimal_outer_bounds,
+            Color::shex("ff800080"),
+        );
+
+        push_outline(&mut verts, bounds, Color::shex("0080ffc0"));
+
+        push_outline(
+            &mut verts,
+            post_update.optimal_inner_bounds,
+            Color::shex("00ffffc0"),
+        );
+
+        push_outline(
+            &mut verts,
+            post_update.optimal_content_bounds,
+            Color::shex("00ff0080"),
+        );
+    }
+
+    if root.style_inspect(|style| style.custom_verts != verts) {
+        root.style_update_batch(move |style| style.custom_verts = verts)
+            .expect_valid();
+    }
+
+    while labels.len() < bins.len() {
+        let label = window.new_bin();
+        root.add_child(label.clone());
+        labels.push(label);
+    }
+
+    for (i, label) in labels.iter().enumerate() {
+        let (text, top, left, hidden) = match bins.get(i) {
+            Some((_, post_update)) => {
+                (
+                    post_update.z_index.to_string(),
+                    post_update.tlo[1],
+                    post_update.tlo[0],
+                    false,
+                )
+            },
+            None => (String::new(), 0.0, 0.0, true),
+        };
+
+        let unchanged = label.style_inspect(|style| {
+            style.text == text
+                && style.pos_from_t == Some(top)
+                && style.pos_from_l == Some(left)
+                && style.hidden == Some(hidden)
+        });
+
+        if unchanged {
+            continue;
+        }
+
+        label
+            .style_update(BinStyle {
+                position: Some(BinPosition::Parent),
+                hidden: Some(hidden),
+                pos_from_t: Some(top),
+                pos_from_l: Some(left),
+                width: Some(LABEL_WIDTH),
+                height: Some(LABEL_HEIGHT),
+                pad_l: Some(2.0),
+                back_color: Some(Color::shex("000000a0")),
+                text,
+                text_color: Some(Color::shex("ffffff")),
+                text_height: Some(10.0),
+                text_wrap: Some(TextWrap::None),
+                ..BinStyle::default()
+            })
+            .expect_valid();
+    }
+}
+
+fn push_rect(verts: &mut Vec<BinVert>, [min_x, max_x, min_y, max_y]: [f32; 4], color: Color) {
+    if max_x <= min_x || max_y <= min_y {
+        return;
+    }
+
+    for (x, y) in [
+        (max_x, min_y),
+        (min_x, min_y),
+        (min_x, max_y),
+        (max_x, min_y),
+        (min_x, max_y),
+        (max_x, max_y),
+    ] {
+        verts.push(BinVert {
+            position: (x, y, 0),
+            color,
+        });
+    }
+}
+
+fn push_outline(verts: &mut Vec<BinVert>, [min_x, max_x, min_y, max_y]: [f32; 4], color: Color) {
+    if max_x <= min_x || max_y <= min_y {
+        return;
+    }
+
+    push_rect(verts, [min_x, max_x, min_y, min_y + LINE_WIDTH], color);
+    push_rect(verts, [min_x, max_x, max_y - LINE_WIDTH, max_y], color);
+    push_rect(verts, [min_x, min_x + LINE_WIDTH, min_y, max_y], color);
+    push_rect(verts, [max_x - LINE_WIDTH, max_x, min_y, max_y], color);
+}
@@ -1,5 +1,6 @@
 //! Window creation and management.
 
+mod debug_overlay;
 mod key;
 mod monitor;
 mod window;
@@ -12,6 +13,8 @@ use std::thread;
 use flume::{Receiver, Sender};
 pub use monitor::{FullScreenBehavior, FullScreenError, Monitor, MonitorMode};
 use parking_lot::{Condvar, FairMutex, FairMutexGuard, Mutex};
+use vulkano::format::Format as VkFormat;
+use vulkano::swapchain::{ColorSpace as VkColorSpace, PresentMode};
 pub use window::Window;
 use winit::dpi::PhysicalSize;
 use winit::event::{
@@ -20,16 +23,25 @@ use winit::event::{
 };
 use winit::event_loop::{EventLoopBuilder, EventLoopProxy};
 use winit::window::WindowBuilder;
+pub use winit::window::{CursorIcon, ResizeDirection};
 
-use crate::input::{InputEvent, MouseButton};
-use crate::interface::{Bin, BinID, DefaultFont};
-use crate::render::{RendererMetricsLevel, VSync, MSAA};
+use crate::input::{InputEvent, MouseButton, ScrollSource, SCROLL_STEP_PIXELS};
+use crate::interface::{Bin, BinID, Color, DefaultFont};
+use crate::render::{AtlasSnapshot, PostProcess, RendererMetricsLevel, VSync, MSAA};
 use crate::{Basalt, NonExhaustive};
 
 /// An ID that is used to identify a `Window`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindowID(u64);
 
+impl WindowID {
+    #[cfg(test)]
+    pub(crate) fn from_raw(id: u64) -> Self {
+        Self(id)
+    }
+}
+
 /// An ID that is used to identify a hook on `WindowManager`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct WMHookID(u64);
@@ -81,6 +93,17 @@ pub struct WindowOptions {
     ///
     /// Default: `true`
     pub decorations: bool,
+    /// If the window should be transparent.
+    ///
+    /// When enabled, areas of the window not covered by bins or a user renderer are transparent
+    /// allowing what is behind the window to be visible. The alpha of `back_color` on root bins
+    /// then determines how much of what is behind the window is visible.
+    ///
+    /// ***Note:** Support depends on the platform & compositor. When the surface doesn't support
+    /// a transparent composite alpha the window will be opaque.*
+    ///
+    /// Default: `false`
+    pub transparent: bool,
     pub _ne: NonExhaustive,
 }
 
@@ -97,6 +120,7 @@ impl Default for WindowOptions {
             minimized: false,
             fullscreen: None,
             decorations: true,
+            transparent: false,
             _ne: NonExhaustive(()),
         }
     }
@@ -106,20 +130,33 @@ impl Default for WindowOptions {
 pub(crate) enum WindowEvent {
     Opened,
     Closed,
-    Resized { width: u32, height: u32 },
+    Resized {
+        width: u32,
+        height: u32,
+    },
     ScaleChanged(f32),
     RedrawRequested,
     EnabledFullscreen,
     DisabledFullscreen,
     AssociateBin(Arc<Bin>),
     DissociateBin(BinID),
-    UpdateBin(BinID),
-    UpdateBinBatch(Vec<BinID>),
+    UpdateBins,
     AddBinaryFont(Arc<dyn AsRef<[u8]> + Sync + Send>),
     SetDefaultFont(DefaultFont),
+    SetFallbackFonts(Vec<String>),
     SetMSAA(MSAA),
     SetVSync(VSync),
+    SetImageCount(u32),
+    SetFPSLimit(Option<u32>),
+    SetWorkerThreads(usize),
     SetMetrics(RendererMetricsLevel),
+    SetPostProcess(Option<PostProcess>),
+    SetClearColor(Color),
+    PumpUpdates(flume::Sender<()>),
+    DebugAtlasSnapshot {
+        index: usize,
+        result_send: flume::Sender<Result<Option<AtlasSnapshot>, String>>,
+    },
 }
 
 /// An enum that specifies the backend that a window uses.
@@ -136,6 +173,89 @@ pub enum WindowType {
     Xlib,
 }
 
+/// The format of the swapchain images of a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SurfaceFormat {
+    pub format: VkFormat,
+    pub color_space: SurfaceColorSpace,
+}
+
+/// The color space of a surface format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SurfaceColorSpace {
+    SrgbNonLinear,
+    ExtendedSrgbLinear,
+    ExtendedSrgbNonLinear,
+    DisplayP3NonLinear,
+    Bt2020Linear,
+    Hdr10St2084,
+    /// A color space that isn't listed above.
+    Other,
+}
+
+impl SurfaceColorSpace {
+    pub(crate) fn from_vk(color_space: VkColorSpace) -> Self {
+        match color_space {
+            VkColorSpace::SrgbNonLinear => Self::SrgbNonLinear,
+            VkColorSpace::ExtendedSrgbLinear => Self::ExtendedSrgbLinear,
+            VkColorSpace::ExtendedSrgbNonLinear => Self::ExtendedSrgbNonLinear,
+            VkColorSpace::DisplayP3NonLinear => Self::DisplayP3NonLinear,
+            VkColorSpace::Bt2020Linear => Self::Bt2020Linear,
+            VkColorSpace::Hdr10St2084 => Self::Hdr10St2084,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A mode of presenting swapchain images to the surface of a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SurfacePresentMode {
+    Immediate,
+    Mailbox,
+    Fifo,
+    FifoRelaxed,
+    /// A present mode that isn't listed above.
+    Other,
+}
+
+impl SurfacePresentMode {
+    pub(crate) fn from_vk(present_mode: PresentMode) -> Self {
+        match present_mode {
+            PresentMode::Immediate => Self::Immediate,
+            PresentMode::Mailbox => Self::Mailbox,
+            PresentMode::Fifo => Self::Fifo,
+            PresentMode::FifoRelaxed => Self::FifoRelaxed,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// An error that is returned by various `Window` related methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowError {
+    /// Querying the surface of the window failed.
+    SurfaceQuery(String),
+}
+
+/// The capabilities of the surface of a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurfaceCapabilities {
+    /// The minimum amount of swapchain images.
+    pub min_image_count: u32,
+    /// The maximum amount of swapchain images. `None` if there is no limit.
+    pub max_image_count: Option<u32>,
+    /// The current extent of the surface. `None` if it is determined by the swapchain.
+    pub current_extent: Option<[u32; 2]>,
+    /// The minimum extent of the swapchain images.
+    pub min_image_extent: [u32; 2],
+    /// The maximum extent of the swapchain images.
+    pub max_image_extent: [u32; 2],
+    /// The formats supported by the surface.
+    pub formats: Vec<SurfaceFormat>,
+    /// The present modes supported by the surface.
+    pub present_modes: Vec<SurfacePresentMode>,
+}
+
 enum WMEvent {
     AssociateBasalt(Arc<Basalt>),
     OnOpen {
@@ -157,7 +277,7 @@ enum WMEvent {
         result: Arc<Mutex<Option<Option<Receiver<WindowEvent>>>>>,
     },
     CreateWindow {
-        options: WindowOptions,
+        options: Box<WindowOptions>,
         cond: Arc<Condvar>,
         result: Arc<Mutex<Option<Result<Arc<Window>, String>>>>,
     },
@@ -171,6 +291,7 @@ enum WMEvent {
     },
     AddBinaryFont(Arc<dyn AsRef<[u8]> + Sync + Send>),
     SetDefaultFont(DefaultFont),
+    SetFallbackFonts(Vec<String>),
     Exit,
 }
 
@@ -202,6 +323,7 @@ impl std::fmt::Debug for WMEvent {
             } => write!(f, "GetMonitors"),
             Self::AddBinaryFont(_) => write!(f, "AddBinaryFont"),
             Self::SetDefaultFont(_) => write!(f, "SetDefaultFont"),
+            Self::SetFallbackFonts(_) => write!(f, "SetFallbackFonts"),
             Self::Exit => write!(f, "Exit"),
         }
     }
@@ -228,7 +350,7 @@ impl WindowManager {
 
         self.event_proxy
             .send_event(WMEvent::CreateWindow {
-                options,
+                options: Box::new(options),
                 result: result.clone(),
                 cond: cond.clone(),
             })
@@ -347,7 +469,7 @@ impl WindowManager {
         result_guard.take().unwrap()
     }
 
-    pub(crate) fn request_draw(&self) -> DrawGuard {
+    pub(crate) fn request_draw(&self) -> DrawGuard<'_> {
         DrawGuard {
             inner: self.draw_lock.lock(),
         }
@@ -361,6 +483,10 @@ impl WindowManager {
         self.send_event(WMEvent::SetDefaultFont(default_font));
     }
 
+    pub(crate) fn set_fallback_fonts(&self, fallback_fonts: Vec<String>) {
+        self.send_event(WMEvent::SetFallbackFonts(fallback_fonts));
+    }
+
     fn send_window_event(&self, id: WindowID, event: WindowEvent) {
         self.send_event(WMEvent::WindowEvent {
             id,
@@ -477,10 +603,12 @@ impl WindowManager {
                                 cond.notify_one();
                             },
                             WMEvent::CreateWindow {
-                                mut options,
+                                options,
                                 cond,
                                 result,
                             } => {
+                                let mut options = *options;
+
                                 if basalt_op.is_none() {
                                     *result.lock() = Some(Err(String::from(
                                         "Failed to create window: basalt is not associated.",
@@ -496,7 +624,8 @@ impl WindowManager {
                                     .with_resizable(options.resizeable)
                                     .with_maximized(options.maximized)
                                     .with_visible(!options.minimized)
-                                    .with_decorations(options.decorations);
+                                    .with_decorations(options.decorations)
+                                    .with_transparent(options.transparent);
 
                                 if let Some(inner_size) = options.inner_size.take() {
                                     window_builder = window_builder.with_inner_size(
@@ -569,11 +698,18 @@ impl WindowManager {
                                 let winit_window_id = winit_window.id();
                                 let window_id = WindowID(next_window_id);
 
+                                if let Some(winit_theme) = winit_window.theme() {
+                                    basalt
+                                        .interface_ref()
+                                        .set_system_color_scheme(winit_theme.into());
+                                }
+
                                 let window = match Window::new(
                                     basalt.clone(),
                                     wm.clone(),
                                     window_id,
                                     winit_window,
+                                    options.transparent,
                                 ) {
                                     Ok(ok) => ok,
                                     Err(e) => {
@@ -644,6 +780,13 @@ impl WindowManager {
                                         .send(WindowEvent::SetDefaultFont(default_font.clone()));
                                 }
                             },
+                            WMEvent::SetFallbackFonts(fallback_fonts) => {
+                                for window_event_sender in window_event_senders.values() {
+                                    let _ = window_event_sender.send(
+                                        WindowEvent::SetFallbackFonts(fallback_fonts.clone()),
+                                    );
+                                }
+                            },
                             WMEvent::Exit => {
                                 elwt.exit();
                             },
@@ -697,6 +840,12 @@ impl WindowManager {
                             } => {
                                 match event.state {
                                     ElementState::Pressed => {
+                                        // NOTE: Repeats are generated by input when overridden.
+                                        if event.repeat && basalt.input_ref().key_repeat().is_some()
+                                        {
+                                            return;
+                                        }
+
                                         if let Some(qwerty) = key::event_to_qwerty(&event) {
                                             basalt.input_ref().send_event(InputEvent::Press {
                                                 win: *window_id,
@@ -751,17 +900,31 @@ impl WindowManager {
                             WinitWindowEvent::MouseWheel {
                                 delta, ..
                             } => {
-                                let [v, h] = match delta {
-                                    MouseScrollDelta::LineDelta(x, y) => [-y, x],
+                                // NOTE: Pixel deltas are converted to logical fractional steps.
+                                let (source, [v, h]) = match delta {
+                                    MouseScrollDelta::LineDelta(x, y) => {
+                                        (
+                                            ScrollSource::Line,
+                                            [(-y).clamp(-1.0, 1.0), x.clamp(-1.0, 1.0)],
+                                        )
+                                    },
                                     MouseScrollDelta::PixelDelta(position) => {
-                                        [-position.y as f32, position.x as f32]
+                                        let step = SCROLL_STEP_PIXELS * window.dpi_scale();
+
+                                        (
+                                            ScrollSource::Pixel,
+                                            [-position.y as f32 / step, position.x as f32 / step],
+                                        )
                                     },
                                 };
 
+                                let scroll_speed = window.scroll_speed();
+
                                 basalt.input_ref().send_event(InputEvent::Scroll {
                                     win: *window_id,
-                                    v: v.clamp(-1.0, 1.0),
-                                    h: h.clamp(-1.0, 1.0),
+                                    v: v * scroll_speed,
+                                    h: h * scroll_speed,
+                                    source,
                                 });
                             },
                             WinitWindowEvent::MouseInput {
@@ -801,6 +964,10 @@ impl WindowManager {
                                 }
 
                                 window.set_dpi_scale(scale_factor as f32);
+                                window.check_monitor_changed();
+                            },
+                            WinitWindowEvent::Moved(_) => {
+                                window.check_monitor_changed();
                             },
                             WinitWindowEvent::RedrawRequested => {
                                 wm.send_event(WMEvent::WindowEvent {
@@ -808,6 +975,11 @@ impl WindowManager {
                                     event: WindowEvent::RedrawRequested,
                                 });
                             },
+                            WinitWindowEvent::ThemeChanged(winit_theme) => {
+                                basalt
+                                    .interface_ref()
+                                    .set_system_color_scheme(winit_theme.into());
+                            },
                             _ => (),
                         }
                     },
@@ -820,26 +992,35 @@ impl WindowManager {
                             None => return,
                         };
 
-                        if let DeviceEvent::Motion {
-                            axis,
-                            value,
-                        } = device_event
-                        {
-                            basalt.input_ref().send_event(match axis {
-                                0 => {
-                                    InputEvent::Motion {
-                                        x: -value as f32,
-                                        y: 0.0,
-                                    }
-                                },
-                                1 => {
-                                    InputEvent::Motion {
-                                        x: 0.0,
-                                        y: -value as f32,
-                                    }
-                                },
-                                _ => return,
-                            });
+                        match device_event {
+                            DeviceEvent::Motion {
+                                axis,
+                                value,
+                            } => {
+                                basalt.input_ref().send_event(match axis {
+                                    0 => {
+                                        InputEvent::Motion {
+                                            x: -value as f32,
+                                            y: 0.0,
+                                        }
+                                    },
+                                    1 => {
+                                        InputEvent::Motion {
+                                            x: 0.0,
+                                            y: -value as f32,
+                                        }
+                                    },
+                                    _ => return,
+                                });
+                            },
+                            DeviceEvent::MouseMotion {
+                                delta,
+                            } => {
+                                for window in windows.values() {
+                                    window.raw_mouse_motion(delta.0 as f32, delta.1 as f32);
+                                }
+                            },
+                            _ => (),
                         }
                     },
                     _ => (),
@@ -53,6 +53,7 @@ pub struct Monitor {
     pub(crate) position: [i32; 2],
     pub(crate) refresh_rate: OrderedFloat<f32>,
     pub(crate) bit_depth: u16,
+    pub(crate) scale_factor: OrderedFloat<f32>,
     pub(crate) is_current: bool,
     pub(crate) is_primary: bool,
     pub(crate) modes: Vec<MonitorMode>,
@@ -66,6 +67,7 @@ impl std::fmt::Debug for Monitor {
             .field("resolution", &self.resolution)
             .field("bit_depth", &self.bit_depth)
             .field("refresh_rate", &self.refresh_rate.into_inner())
+            .field("scale_factor", &self.scale_factor.into_inner())
             .field("is_current", &self.is_current)
             .field("is_primary", &self.is_primary)
             .field("modes", &self.modes)
@@ -99,6 +101,11 @@ impl Monitor {
         self.refresh_rate.into_inner()
     }
 
+    /// The scale factor of this monitor used for dpi scaling.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor.into_inner()
+    }
+
     /// Returns a list of `MonitorMode`'s supported by this monitor.
     pub fn modes(&self) -> Vec<MonitorMode> {
         self.modes.clone()
@@ -182,10 +189,7 @@ impl Monitor {
 
     pub(crate) fn from_winit(winit_monitor: WinitMonitorHandle) -> Option<Self> {
         // Should always be some, "Returns None if the monitor doesn’t exist anymore."
-        let name = match winit_monitor.name() {
-            Some(some) => some,
-            None => return None,
-        };
+        let name = winit_monitor.name()?;
 
         let physical_size = winit_monitor.size();
         let resolution = [physical_size.width, physical_size.height];
@@ -240,6 +244,7 @@ impl Monitor {
             position,
             refresh_rate,
             bit_depth,
+            scale_factor: OrderedFloat::from(winit_monitor.scale_factor() as f32),
             is_current: false,
             is_primary: false,
             modes,
@@ -250,6 +255,7 @@ impl Monitor {
 
 /// Determines how the application should go into full screen.
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum FullScreenBehavior {
     #[default]
     /// **Default**
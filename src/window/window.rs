@@ -1,8 +1,9 @@
 use std::any::Any;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::{Arc, Weak};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
 use raw_window_handle::{
@@ -10,22 +11,35 @@ use raw_window_handle::{
 };
 use vulkano::format::Format as VkFormat;
 use vulkano::swapchain::{
-    ColorSpace as VkColorSpace, FullScreenExclusive, PresentMode, Surface, SurfaceCapabilities,
-    SurfaceInfo, Win32Monitor,
+    ColorSpace as VkColorSpace, FullScreenExclusive, PresentMode, Surface,
+    SurfaceCapabilities as VkSurfaceCapabilities, SurfaceInfo, Win32Monitor,
+};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle as WinitMonitorHandle;
+use winit::window::{
+    CursorGrabMode, CursorIcon, ResizeDirection, Window as WinitWindow, WindowId as WinitWindowId,
 };
-use winit::dpi::PhysicalSize;
-use winit::window::{CursorGrabMode, Window as WinitWindow, WindowId as WinitWindowId};
 
 use crate::input::{
     Char, InputEvent, InputHookCtrl, InputHookID, InputHookTarget, KeyCombo, LocalCursorState,
-    LocalKeyState, WindowState,
+    LocalKeyState, Modifiers, Qwerty, WindowState,
+};
+use crate::interface::{Bin, BinBuilder, BinID, BinPostUpdate, BinStyle, Color, TextMetrics};
+use crate::render::{
+    AtlasSnapshot, PostProcess, RendererMetricsLevel, RendererPerfMetrics, VSync,
+    WorkerPerfMetrics, MSAA,
+};
+use crate::window::debug_overlay::{self, DebugOverlay};
+use crate::window::monitor::{FullScreenBehavior, FullScreenError, Monitor, MonitorMode};
+use crate::window::{
+    SurfaceCapabilities, SurfaceColorSpace, SurfaceFormat, SurfacePresentMode, WindowError,
+    WindowEvent, WindowID, WindowManager, WindowType,
 };
-use crate::interface::{Bin, BinID};
-use crate::render::{RendererMetricsLevel, RendererPerfMetrics, VSync, MSAA};
-use crate::window::monitor::{FullScreenBehavior, FullScreenError, Monitor};
-use crate::window::{WindowEvent, WindowID, WindowManager, WindowType};
 use crate::Basalt;
 
+const MIN_INTERFACE_SCALE: f32 = 0.25;
+const MAX_INTERFACE_SCALE: f32 = 8.0;
+
 /// Object that represents a window.
 ///
 /// This object is generally passed around as it allows accessing mosts things within the crate.
@@ -36,23 +50,47 @@ pub struct Window {
     wm: Arc<WindowManager>,
     surface: Arc<Surface>,
     window_type: WindowType,
+    transparent: bool,
     state: Mutex<State>,
+    pending_updates: Mutex<HashSet<BinID>>,
     close_requested: AtomicBool,
 }
 
 struct State {
     cursor_captured: bool,
+    cursor_icon: CursorIcon,
+    raw_mouse: bool,
+    raw_mouse_recenter: bool,
     ignore_dpi: bool,
     dpi_scale: f32,
     interface_scale: f32,
+    scroll_speed: f32,
     msaa: MSAA,
     vsync: VSync,
+    image_count: u32,
+    swapchain_image_count: Option<u32>,
+    surface_format: Option<SurfaceFormat>,
+    fps_limit: Option<u32>,
+    worker_threads: usize,
+    active_worker_threads: Option<usize>,
+    active_msaa: Option<MSAA>,
     metrics: RendererPerfMetrics,
     metrics_level: RendererMetricsLevel,
+    metrics_history_len: usize,
+    metrics_history: VecDeque<WorkerPerfMetrics>,
+    post_process: Option<PostProcess>,
+    clear_color: Color,
     on_metrics_update: Vec<Box<dyn FnMut(WindowID, RendererPerfMetrics) + Send + Sync + 'static>>,
+    on_renderer_error: Vec<Box<dyn FnMut(WindowID, String) + Send + Sync + 'static>>,
+    frame_index: u64,
+    last_frame: Option<Instant>,
+    on_frame: Vec<Box<dyn FnMut(WindowID, u64, Duration) + Send + Sync + 'static>>,
+    current_monitor: Option<WinitMonitorHandle>,
+    on_monitor_changed: Vec<Box<dyn FnMut(WindowID, Monitor) + Send + Sync + 'static>>,
     associated_bins: HashMap<BinID, Weak<Bin>>,
     attached_input_hooks: Vec<InputHookID>,
     keep_alive_objects: Vec<Box<dyn Any + Send + Sync + 'static>>,
+    debug_overlay: Option<DebugOverlay>,
 }
 
 impl std::fmt::Debug for Window {
@@ -78,6 +116,7 @@ impl Window {
         wm: Arc<WindowManager>,
         id: WindowID,
         winit: Arc<WinitWindow>,
+        transparent: bool,
     ) -> Result<Arc<Self>, String> {
         // NOTE: Although it may seem the winit window doesn't need to be in an Arc. This allows
         //       vulkano to keep the window alive longer than the surface. It may be possible to
@@ -104,29 +143,74 @@ impl Window {
 
         let state = State {
             cursor_captured: false,
+            cursor_icon: CursorIcon::Default,
+            raw_mouse: false,
+            raw_mouse_recenter: false,
             ignore_dpi,
             dpi_scale,
             msaa: basalt.config.render_default_msaa,
             vsync: basalt.config.render_default_vsync,
+            image_count: basalt.config.render_default_image_count,
+            swapchain_image_count: None,
+            surface_format: None,
+            fps_limit: basalt.config.render_default_fps_limit,
+            worker_threads: basalt.config.render_default_worker_threads.get(),
+            active_worker_threads: None,
+            active_msaa: None,
             metrics: RendererPerfMetrics::default(),
             metrics_level: RendererMetricsLevel::None,
+            metrics_history_len: 0,
+            metrics_history: VecDeque::new(),
+            post_process: None,
+            clear_color: Color::default(),
             on_metrics_update: Vec::new(),
+            on_renderer_error: Vec::new(),
+            frame_index: 0,
+            last_frame: None,
+            on_frame: Vec::new(),
+            current_monitor: winit.current_monitor(),
+            on_monitor_changed: Vec::new(),
             interface_scale: basalt.config.window_default_scale,
+            scroll_speed: basalt.config.window_default_scroll_speed,
             associated_bins: HashMap::new(),
             attached_input_hooks: Vec::new(),
             keep_alive_objects: Vec::new(),
+            debug_overlay: None,
         };
 
-        Ok(Arc::new(Self {
+        let window = Arc::new(Self {
             id,
             inner: winit,
             basalt,
             wm,
             surface,
             window_type,
+            transparent,
             state: Mutex::new(state),
+            pending_updates: Mutex::new(HashSet::new()),
             close_requested: AtomicBool::new(false),
-        }))
+        });
+
+        window.on_press(Qwerty::Tab, move |target, window_state, _| {
+            let window = target.into_window().unwrap();
+
+            if window
+                .focused_bin()
+                .map(|focused| focused.accepts_tab())
+                .unwrap_or(false)
+            {
+                return Default::default();
+            }
+
+            let reverse = window_state.is_key_pressed(Qwerty::LShift)
+                || window_state.is_key_pressed(Qwerty::RShift);
+
+            window.focus_traverse(window_state.focused_bin_id(), reverse);
+
+            Default::default()
+        });
+
+        Ok(window)
     }
 
     pub(crate) fn winit_id(&self) -> WinitWindowId {
@@ -145,18 +229,30 @@ impl Window {
 
     pub(crate) fn dissociate_bin(&self, bin_id: BinID) {
         self.state.lock().associated_bins.remove(&bin_id);
+        self.pending_updates.lock().remove(&bin_id);
         self.wm
             .send_window_event(self.id, WindowEvent::DissociateBin(bin_id));
     }
 
     pub(crate) fn update_bin(&self, bin_id: BinID) {
-        self.wm
-            .send_window_event(self.id, WindowEvent::UpdateBin(bin_id));
+        self.update_bin_batch([bin_id]);
     }
 
-    pub(crate) fn update_bin_batch(&self, bin_ids: Vec<BinID>) {
-        self.wm
-            .send_window_event(self.id, WindowEvent::UpdateBinBatch(bin_ids));
+    pub(crate) fn update_bin_batch<I: IntoIterator<Item = BinID>>(&self, bin_ids: I) {
+        let mut pending_updates = self.pending_updates.lock();
+        let notify = pending_updates.is_empty();
+        pending_updates.extend(bin_ids);
+
+        // NOTE: Updates are coalesced until the renderer's worker takes them, so only the first
+        //       pending update sends an event. The event is sent while the lock is held, so that
+        //       it is always ordered before events that are sent afterwards.
+        if notify && !pending_updates.is_empty() {
+            self.wm.send_window_event(self.id, WindowEvent::UpdateBins);
+        }
+    }
+
+    pub(crate) fn take_pending_updates(&self) -> HashSet<BinID> {
+        std::mem::take(&mut *self.pending_updates.lock())
     }
 
     /// The window id of this window.
@@ -201,6 +297,11 @@ impl Window {
         bin
     }
 
+    /// Create a `Bin` associated with this window with a builder.
+    pub fn bin_builder(self: &Arc<Self>) -> BinBuilder<'_> {
+        self.basalt.interface_ref().bin_builder().window(self)
+    }
+
     /// Create new `Bin`'s associated with this window.
     pub fn new_bins(self: &Arc<Self>, count: usize) -> Vec<Arc<Bin>> {
         let bins = self.basalt.interface_ref().new_bins(count);
@@ -222,11 +323,83 @@ impl Window {
             .collect()
     }
 
+    /// Retrieve the `Bin`'s associated to this window for which the predicate returns `true`.
+    ///
+    /// ***Note:** This is sorted by `BinID`. Bins of the debug overlay are excluded.*
+    pub fn find_bins<F: FnMut(&Arc<Bin>) -> bool>(&self, mut predicate: F) -> Vec<Arc<Bin>> {
+        let mut bins = self
+            .interface_bins()
+            .into_iter()
+            .filter(|bin| predicate(bin))
+            .collect::<Vec<_>>();
+
+        bins.sort_by_key(|bin| bin.id());
+        bins
+    }
+
+    /// Retrieve the `Bin`'s associated to this window with the provided tag.
+    ///
+    /// See `Bin::set_tag`.
+    ///
+    /// ***Note:** This is sorted by `BinID`.*
+    pub fn find_bins_by_tag(&self, tag: &str) -> Vec<Arc<Bin>> {
+        self.find_bins(|bin| bin.has_tag(tag))
+    }
+
+    /// Wait for the renderer to perform all pending updates of `Bin`'s associated to this window.
+    ///
+    /// Returns the resulting `BinPostUpdate` of each `Bin` ordered by `BinID`.
+    ///
+    /// This is intended for testing, where the asynchronous updates performed by the renderer
+    /// make asserting state difficult.
+    ///
+    /// ***Note:** Updates triggered by update hooks during these updates may not be performed.
+    /// An error is returned if the renderer isn't running.*
+    pub fn pump_updates(&self) -> Result<Vec<(BinID, BinPostUpdate)>, String> {
+        let (complete_send, complete_recv) = flume::bounded(1);
+        self.wm
+            .send_window_event(self.id, WindowEvent::PumpUpdates(complete_send));
+
+        if complete_recv.recv().is_err() {
+            return Err(String::from("The renderer is not running."));
+        }
+
+        let mut bins = self.associated_bins();
+        bins.sort_by_key(|bin| bin.id());
+
+        Ok(bins
+            .into_iter()
+            .map(|bin| (bin.id(), bin.post_update()))
+            .collect())
+    }
+
     /// Retrieve a list of `BinID`'s associated to this window.
     pub fn associated_bin_ids(&self) -> Vec<BinID> {
         self.state.lock().associated_bins.keys().copied().collect()
     }
 
+    /// Retrieve the top-most `Bin` at the provided position.
+    ///
+    /// ***Note:** The position is in window coordinates, the same as `WindowState::cursor_pos`.*
+    pub fn bin_at(&self, x: f32, y: f32) -> Option<Arc<Bin>> {
+        self.bins_at(x, y).into_iter().next()
+    }
+
+    /// Retrieve all `Bin`'s at the provided position.
+    ///
+    /// ***Note:** This is sorted where the top-most is first and the bottom-most is last.*
+    pub fn bins_at(&self, x: f32, y: f32) -> Vec<Arc<Bin>> {
+        let effective_scale = self.effective_interface_scale();
+        let [x, y] = [x / effective_scale, y / effective_scale];
+
+        top_most_first(
+            self.interface_bins()
+                .into_iter()
+                .filter_map(|bin| bin.hit_test(x, y).map(|z| (z, bin)))
+                .collect(),
+        )
+    }
+
     /// Hides and captures cursor.
     pub fn capture_cursor(&self) {
         let mut state = self.state.lock();
@@ -245,9 +418,13 @@ impl Window {
     }
 
     /// Shows and releases cursor.
+    ///
+    /// ***Note:** This will also disable raw mouse if it is enabled.*
     pub fn release_cursor(&self) {
         let mut state = self.state.lock();
         state.cursor_captured = false;
+        state.raw_mouse = false;
+        state.raw_mouse_recenter = false;
 
         self.inner.set_cursor_visible(true);
         self.inner.set_cursor_grab(CursorGrabMode::None).unwrap();
@@ -265,6 +442,127 @@ impl Window {
         self.state.lock().cursor_captured
     }
 
+    /// Enable or disable raw mouse motion.
+    ///
+    /// When enabled the cursor is hidden and locked to the window. Unaccelerated mouse motion is
+    /// then delivered to hooks created with `on_raw_motion`.
+    ///
+    /// ***Note:** If the platform is unable to lock the cursor in place, it will instead be confined
+    /// to the window and recentered after each motion event.*
+    pub fn enable_raw_mouse(&self, enable: bool) {
+        let mut state = self.state.lock();
+
+        if state.raw_mouse == enable {
+            return;
+        }
+
+        state.raw_mouse = enable;
+        state.cursor_captured = enable;
+        self.inner.set_cursor_visible(!enable);
+
+        if enable {
+            state.raw_mouse_recenter = match self.inner.set_cursor_grab(CursorGrabMode::Locked) {
+                Ok(_) => false,
+                Err(_) => {
+                    self.inner
+                        .set_cursor_grab(CursorGrabMode::Confined)
+                        .unwrap();
+                    true
+                },
+            };
+        } else {
+            state.raw_mouse_recenter = false;
+            self.inner.set_cursor_grab(CursorGrabMode::None).unwrap();
+        }
+
+        self.basalt
+            .input_ref()
+            .send_event(InputEvent::CursorCapture {
+                win: self.id,
+                captured: enable,
+            });
+    }
+
+    /// Checks if raw mouse motion is currently enabled.
+    pub fn raw_mouse_enabled(&self) -> bool {
+        self.state.lock().raw_mouse
+    }
+
+    pub(crate) fn raw_mouse_motion(&self, dx: f32, dy: f32) {
+        let state = self.state.lock();
+
+        if !state.raw_mouse || !self.inner.has_focus() {
+            return;
+        }
+
+        if state.raw_mouse_recenter {
+            let [width, height] = self.inner_dimensions();
+
+            let _ = self
+                .inner
+                .set_cursor_position(PhysicalPosition::new(width / 2, height / 2));
+        }
+
+        self.basalt.input_ref().send_event(InputEvent::RawMotion {
+            win: self.id,
+            dx,
+            dy,
+        });
+    }
+
+    /// Set the cursor icon to one of the standard system cursors.
+    ///
+    /// ***Note:** The icon is retained while the cursor is captured and will be shown again once
+    /// the cursor is released.*
+    pub fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.state.lock().cursor_icon = icon;
+        self.inner.set_cursor_icon(icon);
+    }
+
+    /// Retrieve the cursor icon currently used.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.state.lock().cursor_icon
+    }
+
+    /// Set a custom cursor from an image.
+    ///
+    /// - `rgba`: image data in the RGBA8 format with a length of `width * height * 4`.
+    /// - `size`: the `[width, height]` of the image.
+    /// - `hotspot`: the position within the image that represents the cursor's position.
+    /// - `fallback`: the nearest standard icon, used if the platform can't do custom cursors.
+    ///
+    /// Returns an error if the image is invalid, in which case the cursor is left unchanged.
+    ///
+    /// ***Note:** Custom cursors are not supported by `winit` 0.29, so `fallback` is currently
+    /// used on all platforms.*
+    pub fn set_cursor_image(
+        &self,
+        rgba: Vec<u8>,
+        size: [u32; 2],
+        hotspot: [u32; 2],
+        fallback: CursorIcon,
+    ) -> Result<(), String> {
+        if size[0] == 0 || size[1] == 0 {
+            return Err(String::from("Cursor image dimensions must be non-zero."));
+        }
+
+        if rgba.len() != size[0] as usize * size[1] as usize * 4 {
+            return Err(format!(
+                "Cursor image data length of {} doesn't match the expected length of {}.",
+                rgba.len(),
+                size[0] as usize * size[1] as usize * 4
+            ));
+        }
+
+        if hotspot[0] >= size[0] || hotspot[1] >= size[1] {
+            return Err(String::from("Cursor hotspot must be within the image."));
+        }
+
+        // TODO: Use custom cursors once winit is upgraded to a version that supports them.
+        self.set_cursor_icon(fallback);
+        Ok(())
+    }
+
     /// Return a list of active monitors on the system.
     pub fn monitors(&self) -> Vec<Monitor> {
         let current_op = self.inner.current_monitor();
@@ -321,6 +619,46 @@ impl Window {
         })
     }
 
+    /// When the current monitor of this window changes call the provided method.
+    ///
+    /// ***Note:** This method will be kept for the lifetime of the window.*
+    pub fn on_monitor_changed<F: FnMut(WindowID, Monitor) + Send + Sync + 'static>(
+        &self,
+        method: F,
+    ) {
+        self.state.lock().on_monitor_changed.push(Box::new(method));
+    }
+
+    pub(crate) fn check_monitor_changed(&self) {
+        let winit_monitor_op = self.inner.current_monitor();
+        let mut state = self.state.lock();
+
+        if state.current_monitor == winit_monitor_op {
+            return;
+        }
+
+        state.current_monitor = winit_monitor_op;
+
+        if state.on_monitor_changed.is_empty() {
+            return;
+        }
+
+        // NOTE: The methods are taken out of the state so that the lock isn't held while they're
+        //       called. Methods added while they're being called are retained.
+        let mut methods = std::mem::take(&mut state.on_monitor_changed);
+        drop(state);
+
+        if let Some(monitor) = self.current_monitor() {
+            for method in methods.iter_mut() {
+                method(self.id, monitor.clone());
+            }
+        }
+
+        let mut state = self.state.lock();
+        methods.append(&mut state.on_monitor_changed);
+        state.on_monitor_changed = methods;
+    }
+
     /// Enable fullscreen with the provided behavior.
     ///
     /// If `fallback_borderless` is set to `true` and am exclusive behavior is used when it isn't
@@ -330,12 +668,25 @@ impl Window {
         fallback_borderless: bool,
         behavior: FullScreenBehavior,
     ) -> Result<(), FullScreenError> {
-        let winit_fullscreen = behavior.determine_winit_fullscreen(
+        self.apply_fullscreen(
             fallback_borderless,
+            behavior,
             self.basalt
                 .device_ref()
                 .enabled_extensions()
                 .ext_full_screen_exclusive,
+        )
+    }
+
+    fn apply_fullscreen(
+        &self,
+        fallback_borderless: bool,
+        behavior: FullScreenBehavior,
+        exclusive_supported: bool,
+    ) -> Result<(), FullScreenError> {
+        let winit_fullscreen = behavior.determine_winit_fullscreen(
+            fallback_borderless,
+            exclusive_supported,
             self.current_monitor(),
             self.primary_monitor(),
             self.monitors(),
@@ -347,6 +698,41 @@ impl Window {
         Ok(())
     }
 
+    /// Enable exclusive fullscreen on the provided monitor using the provided mode.
+    ///
+    /// This changes the display mode of the monitor, allowing a specific resolution, bit depth
+    /// & refresh rate to be used. Use `Monitor::modes` to retrieve the supported modes. The
+    /// display mode is restored when fullscreen is disabled.
+    ///
+    /// ***Note:** On Windows this requires the `ext_full_screen_exclusive` device extension. On
+    /// macOS & X11 the display mode is changed by the windowing system. Other platforms don't
+    /// support changing the display mode, so borderless fullscreen on the monitor is used if
+    /// `fallback_borderless` is `true`, otherwise `FullScreenError::ExclusiveNotSupported` is
+    /// returned.*
+    pub fn enter_exclusive_fullscreen(
+        &self,
+        monitor: Monitor,
+        mode: MonitorMode,
+        fallback_borderless: bool,
+    ) -> Result<(), FullScreenError> {
+        let exclusive_supported = match self.window_type {
+            WindowType::Windows => {
+                self.basalt
+                    .device_ref()
+                    .enabled_extensions()
+                    .ext_full_screen_exclusive
+            },
+            WindowType::Macos | WindowType::Xcb | WindowType::Xlib => true,
+            _ => false,
+        };
+
+        self.apply_fullscreen(
+            fallback_borderless,
+            FullScreenBehavior::Exclusive(monitor, mode),
+            exclusive_supported,
+        )
+    }
+
     /// Disable fullscreen.
     ///
     /// ***Note:** This is a no-op if this window isn't fullscreen.*
@@ -412,6 +798,46 @@ impl Window {
         }
     }
 
+    /// Enable or disable the decorations of this window.
+    ///
+    /// ***Note:** This is only a request. The platform may ignore it, for example a wayland
+    /// compositor that forces server-side decorations. Use `is_decorated` to check the result.*
+    pub fn set_decorations(&self, decorations: bool) {
+        self.inner.set_decorations(decorations);
+    }
+
+    /// Check if this window currently has decorations.
+    ///
+    /// On wayland, decorations are provided by the compositor when it supports server-side
+    /// decorations. Otherwise client-side decorations are drawn on the application's behalf, so
+    /// applications never need to draw their own to have a title bar. If the compositor forces
+    /// server-side decorations this will return `true` even after decorations were disabled.
+    ///
+    /// ***Note:** Always returns `true` on iOS, Android & web.*
+    pub fn is_decorated(&self) -> bool {
+        self.inner.is_decorated()
+    }
+
+    /// Begin an interactive move of this window. Intended to be called from a press hook.
+    ///
+    /// ***Note:** The release of the button may not be received.*
+    pub fn start_drag_move(&self) -> Result<(), String> {
+        self.inner
+            .drag_window()
+            .map_err(|e| format!("Failed to start drag move: {}", e))
+    }
+
+    /// Begin an interactive resize of this window from the provided edge. Intended to be called
+    /// from a press hook.
+    ///
+    /// ***Note:** The release of the button may not be received. Not supported on macOS, iOS,
+    /// Android & web.*
+    pub fn start_drag_resize(&self, edge: ResizeDirection) -> Result<(), String> {
+        self.inner
+            .drag_resize_window(edge)
+            .map_err(|e| format!("Failed to start drag resize: {}", e))
+    }
+
     /// Return the dimensions of the client area of this window.
     pub fn inner_dimensions(&self) -> [u32; 2] {
         self.inner.inner_size().into()
@@ -422,6 +848,11 @@ impl Window {
         self.window_type
     }
 
+    /// Check if this window was created with `WindowOptions::transparent`.
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
     /// DPI scaling used on this window.
     pub fn dpi_scale(&self) -> f32 {
         self.state.lock().dpi_scale
@@ -460,10 +891,33 @@ impl Window {
         state.interface_scale * state.dpi_scale
     }
 
+    /// Measure text as it would be laid out within a `Bin` of this window.
+    ///
+    /// This is the same as `Interface::measure_text`, but the text is shaped at the effective
+    /// interface scale of this window. The metrics are still in logical units.
+    pub fn measure_text(
+        &self,
+        text: &str,
+        style: &BinStyle,
+        max_width: Option<f32>,
+    ) -> TextMetrics {
+        self.basalt.interface_ref().measure_text_scaled(
+            text,
+            style,
+            max_width,
+            self.effective_interface_scale(),
+        )
+    }
+
     /// Set the scale of the interface. This does not include dpi scaling.
+    ///
+    /// The effective scale is this scale multiplied by the dpi scale. When the dpi scale of the
+    /// window changes, such as when moved to another monitor, this scale is retained.
+    ///
+    /// ***Note:** The scale is clamped to `0.25..=8.0`.*
     pub fn set_interface_scale(&self, set_scale: f32) {
         let mut state = self.state.lock();
-        state.interface_scale = set_scale;
+        state.interface_scale = set_scale.clamp(MIN_INTERFACE_SCALE, MAX_INTERFACE_SCALE);
 
         self.wm.send_window_event(
             self.id,
@@ -472,9 +926,13 @@ impl Window {
     }
 
     /// Set the scale of the interface. This includes dpi scaling.
+    ///
+    /// ***Note:** The scale excluding dpi scaling is clamped to `0.25..=8.0`. See
+    /// `set_interface_scale` for more information.*
     pub fn set_effective_interface_scale(&self, set_scale: f32) {
         let mut state = self.state.lock();
-        state.interface_scale = set_scale / state.dpi_scale;
+        state.interface_scale =
+            (set_scale / state.dpi_scale).clamp(MIN_INTERFACE_SCALE, MAX_INTERFACE_SCALE);
 
         self.wm.send_window_event(
             self.id,
@@ -482,12 +940,33 @@ impl Window {
         );
     }
 
+    /// Get the multiplier applied to scroll deltas of this window.
+    pub fn scroll_speed(&self) -> f32 {
+        self.state.lock().scroll_speed
+    }
+
+    /// Set the multiplier applied to scroll deltas of this window.
+    ///
+    /// Scroll deltas are normalized into steps, where a step is 100 logical pixels. Line based
+    /// devices, such as a mouse wheel, report a step per line. Pixel based devices, such as a
+    /// trackpad, report fractional steps. This multiplier is applied after normalization.
+    ///
+    /// **Default:** `1.0`
+    ///
+    /// ***Note:** This can be set upon basalt's creation via its options.*
+    pub fn set_scroll_speed(&self, speed: f32) {
+        self.state.lock().scroll_speed = speed;
+    }
+
     /// Get the current MSAA used for rendering.
     pub fn renderer_msaa(&self) -> MSAA {
         self.state.lock().msaa
     }
 
     /// Set the current MSAA used for rendering.
+    ///
+    /// ***Note:** When the sample count isn't supported by the device, the nearest lower one is
+    /// used instead. Use `renderer_active_msaa` to retrieve the MSAA actually used.*
     pub fn set_renderer_msaa(&self, msaa: MSAA) {
         self.state.lock().msaa = msaa;
 
@@ -495,6 +974,17 @@ impl Window {
             .send_window_event(self.id, WindowEvent::SetMSAA(msaa));
     }
 
+    /// Get the MSAA actually used by the renderer.
+    ///
+    /// ***Note:** This will be `None` if a renderer hasn't been created for this window.*
+    pub fn renderer_active_msaa(&self) -> Option<MSAA> {
+        self.state.lock().active_msaa
+    }
+
+    pub(crate) fn set_renderer_active_msaa(&self, msaa: Option<MSAA>) {
+        self.state.lock().active_msaa = msaa;
+    }
+
     /// Increase the current MSAA used for rendering returning the new value.
     pub fn incr_renderer_msaa(&self) -> MSAA {
         let mut state = self.state.lock();
@@ -560,6 +1050,174 @@ impl Window {
         vsync
     }
 
+    /// Get the current preferred minimum swapchain image count used for rendering.
+    pub fn renderer_image_count(&self) -> u32 {
+        self.state.lock().image_count
+    }
+
+    /// Set the current preferred minimum swapchain image count used for rendering.
+    ///
+    /// ***Note:** The count is clamped to what the surface supports. Use
+    /// `renderer_swapchain_image_count` to retrieve the actual count.*
+    pub fn set_renderer_image_count(&self, count: u32) {
+        self.state.lock().image_count = count;
+
+        self.wm
+            .send_window_event(self.id, WindowEvent::SetImageCount(count));
+    }
+
+    /// Get the count of images of the swapchain currently used by the renderer.
+    ///
+    /// ***Note:** This will be `None` if the renderer hasn't created a swapchain.*
+    pub fn renderer_swapchain_image_count(&self) -> Option<u32> {
+        self.state.lock().swapchain_image_count
+    }
+
+    pub(crate) fn set_renderer_swapchain_image_count(&self, count: Option<u32>) {
+        self.state.lock().swapchain_image_count = count;
+    }
+
+    /// Get the format of the swapchain currently used by the renderer.
+    ///
+    /// ***Note:** This will be `None` if the renderer hasn't created a swapchain.*
+    pub fn surface_format(&self) -> Option<SurfaceFormat> {
+        self.state.lock().surface_format
+    }
+
+    pub(crate) fn set_surface_format(&self, format: Option<SurfaceFormat>) {
+        self.state.lock().surface_format = format;
+    }
+
+    /// Get the present modes supported by the surface of this window.
+    pub fn supported_present_modes(&self) -> Result<Vec<SurfacePresentMode>, WindowError> {
+        Ok(self
+            .basalt
+            .physical_device_ref()
+            .surface_present_modes(
+                &self.surface,
+                self.surface_info(FullScreenExclusive::Default),
+            )
+            .map_err(|e| WindowError::SurfaceQuery(format!("{:?}", e)))?
+            .map(SurfacePresentMode::from_vk)
+            .collect())
+    }
+
+    /// Get the capabilities of the surface of this window.
+    ///
+    /// ***Note:** This doesn't reflect exclusive fullscreen, which may differ.*
+    pub fn surface_capabilities(&self) -> Result<SurfaceCapabilities, WindowError> {
+        let physical_device = self.basalt.physical_device_ref();
+        let surface_info = self.surface_info(FullScreenExclusive::Default);
+
+        let capabilities = physical_device
+            .surface_capabilities(&self.surface, surface_info.clone())
+            .map_err(|e| WindowError::SurfaceQuery(format!("{:?}", e)))?;
+
+        let formats = physical_device
+            .surface_formats(&self.surface, surface_info)
+            .map_err(|e| WindowError::SurfaceQuery(format!("{:?}", e)))?;
+
+        Ok(SurfaceCapabilities {
+            min_image_count: capabilities.min_image_count,
+            max_image_count: capabilities.max_image_count,
+            current_extent: capabilities.current_extent,
+            min_image_extent: capabilities.min_image_extent,
+            max_image_extent: capabilities.max_image_extent,
+            formats: formats
+                .into_iter()
+                .map(|(format, color_space)| {
+                    SurfaceFormat {
+                        format,
+                        color_space: SurfaceColorSpace::from_vk(color_space),
+                    }
+                })
+                .collect(),
+            present_modes: self.supported_present_modes()?,
+        })
+    }
+
+    /// Get the current frame rate limit used for rendering.
+    pub fn renderer_fps_limit(&self) -> Option<u32> {
+        self.state.lock().fps_limit
+    }
+
+    /// Set the current frame rate limit used for rendering.
+    ///
+    /// ***Note:** A limit of zero is treated as `None`.*
+    pub fn set_renderer_fps_limit(&self, limit: Option<u32>) {
+        let limit = limit.filter(|limit| *limit != 0);
+        self.state.lock().fps_limit = limit;
+
+        self.wm
+            .send_window_event(self.id, WindowEvent::SetFPSLimit(limit));
+    }
+
+    /// Get the current amount of threads used by the renderer's worker to update bins.
+    pub fn renderer_worker_threads(&self) -> usize {
+        self.state.lock().worker_threads
+    }
+
+    /// Set the amount of threads used by the renderer's worker to update bins.
+    ///
+    /// Threads are added or removed between updates, so lowering the amount never interrupts an
+    /// update in progress.
+    ///
+    /// ***Note:** A value of zero is treated as one. Use `renderer_active_worker_threads` to
+    /// retrieve the amount of threads currently running.*
+    pub fn set_renderer_worker_threads(&self, threads: usize) {
+        let threads = threads.max(1);
+        self.state.lock().worker_threads = threads;
+
+        self.wm
+            .send_window_event(self.id, WindowEvent::SetWorkerThreads(threads));
+    }
+
+    /// Get the amount of threads currently running in the renderer's worker to update bins.
+    ///
+    /// ***Note:** This will be `None` if the renderer's worker hasn't started.*
+    pub fn renderer_active_worker_threads(&self) -> Option<usize> {
+        self.state.lock().active_worker_threads
+    }
+
+    pub(crate) fn set_renderer_active_worker_threads(&self, threads: Option<usize>) {
+        self.state.lock().active_worker_threads = threads;
+    }
+
+    /// Get the current `PostProcess` used for rendering.
+    pub fn renderer_post_process(&self) -> Option<PostProcess> {
+        self.state.lock().post_process.clone()
+    }
+
+    /// Set the current `PostProcess` used for rendering. `None` disables post processing.
+    pub fn set_renderer_post_process(&self, post_process: Option<PostProcess>) {
+        self.state.lock().post_process = post_process.clone();
+
+        self.wm
+            .send_window_event(self.id, WindowEvent::SetPostProcess(post_process));
+    }
+
+    /// Get the current color the interface is drawn on top of.
+    pub fn renderer_clear_color(&self) -> Color {
+        self.state.lock().clear_color
+    }
+
+    /// Set the color the interface is drawn on top of.
+    ///
+    /// The alpha component is only respected when the window was created with
+    /// `WindowOptions::transparent`, otherwise the compositor treats the window as opaque. A
+    /// transparent window can use a transparent color to show what is behind it.
+    ///
+    /// **Default:** `Color::rgba(0.0, 0.0, 0.0, 0.0)`
+    ///
+    /// ***Note:** This has no effect when a user renderer is used, as its output is drawn beneath
+    /// the interface instead.*
+    pub fn set_renderer_clear_color(&self, color: Color) {
+        self.state.lock().clear_color = color;
+
+        self.wm
+            .send_window_event(self.id, WindowEvent::SetClearColor(color));
+    }
+
     /// Get the current renderer metrics level used.
     pub fn renderer_metrics_level(&self) -> RendererMetricsLevel {
         self.state.lock().metrics_level
@@ -616,6 +1274,242 @@ impl Window {
         state.metrics = metrics;
     }
 
+    /// Get the amount of worker metrics samples kept in the history.
+    pub fn renderer_metrics_history_len(&self) -> usize {
+        self.state.lock().metrics_history_len
+    }
+
+    /// Set the amount of worker metrics samples kept in the history. `0` disables the history.
+    ///
+    /// A sample is recorded every time the worker updates.
+    ///
+    /// **Default:** `0`
+    ///
+    /// ***Note:** Worker metrics are only tracked with `RendererMetricsLevel::Extended` or
+    /// higher.*
+    pub fn set_renderer_metrics_history_len(&self, len: usize) {
+        let mut state = self.state.lock();
+        state.metrics_history_len = len;
+
+        while state.metrics_history.len() > len {
+            state.metrics_history.pop_front();
+        }
+
+        state.metrics_history.shrink_to(len);
+    }
+
+    /// Get the worker metrics samples in the history, oldest first.
+    ///
+    /// *See `set_renderer_metrics_history_len` for more information.*
+    pub fn renderer_metrics_history(&self) -> Vec<WorkerPerfMetrics> {
+        self.state.lock().metrics_history.iter().cloned().collect()
+    }
+
+    /// Get the average of the worker metrics samples in the history.
+    ///
+    /// Returns `None` if the history is empty.
+    pub fn renderer_metrics_history_avg(&self) -> Option<WorkerPerfMetrics> {
+        let state = self.state.lock();
+
+        if state.metrics_history.is_empty() {
+            return None;
+        }
+
+        let mut avg_metrics = WorkerPerfMetrics::default();
+
+        for metrics in state.metrics_history.iter() {
+            avg_metrics += metrics.clone();
+        }
+
+        avg_metrics /= state.metrics_history.len() as f32;
+        Some(avg_metrics)
+    }
+
+    pub(crate) fn track_worker_metrics(&self, metrics: &WorkerPerfMetrics) {
+        let mut state = self.state.lock();
+
+        if state.metrics_history_len == 0 {
+            return;
+        }
+
+        if state.metrics_history.len() == state.metrics_history_len {
+            state.metrics_history.pop_front();
+        }
+
+        state.metrics_history.push_back(metrics.clone());
+    }
+
+    /// When the renderer exits because of an error call the provided method.
+    ///
+    /// This includes a panic of the renderer's worker, in which case the panic message is
+    /// provided. The renderer will no longer update the window after this is called.
+    ///
+    /// ***Note:** This method will be kept for the lifetime of the window.*
+    pub fn on_renderer_error<F: FnMut(WindowID, String) + Send + Sync + 'static>(&self, method: F) {
+        self.state.lock().on_renderer_error.push(Box::new(method));
+    }
+
+    pub(crate) fn renderer_error(&self, error: String) {
+        // NOTE: The methods are taken out of the state so that the lock isn't held while they're
+        //       called. Methods added while they're being called are retained.
+        let mut methods = std::mem::take(&mut self.state.lock().on_renderer_error);
+
+        for method in methods.iter_mut() {
+            method(self.id, error.clone());
+        }
+
+        let mut state = self.state.lock();
+        methods.append(&mut state.on_renderer_error);
+        state.on_renderer_error = methods;
+    }
+
+    /// When the renderer presents a frame call the provided method.
+    ///
+    /// The method is provided the index of the frame and the time since the previous frame was
+    /// presented. The index starts at zero and increases by one every frame. For the first frame
+    /// the time is zero.
+    ///
+    /// The method is called on the renderer's thread after the frame is submitted, so it should
+    /// return quickly as it delays the next frame. The window's lock isn't held while it is
+    /// called, so all methods of the window and bins are safe to call, including adding more
+    /// methods with `on_frame`. Changes made to bins will be shown in a later frame.
+    ///
+    /// ***Note:** The renderer only draws a frame when the interface changes or a redraw is
+    /// requested. Frames drawn with an `ExternalRenderer` are not reported. This method will be
+    /// kept for the lifetime of the window.*
+    pub fn on_frame<F: FnMut(WindowID, u64, Duration) + Send + Sync + 'static>(&self, method: F) {
+        self.state.lock().on_frame.push(Box::new(method));
+    }
+
+    pub(crate) fn frame_presented(&self) {
+        let mut state = self.state.lock();
+        let frame_index = state.frame_index;
+        let now = Instant::now();
+
+        let delta = match state.last_frame.replace(now) {
+            Some(last_frame) => now.duration_since(last_frame),
+            None => Duration::ZERO,
+        };
+
+        state.frame_index += 1;
+
+        if state.on_frame.is_empty() {
+            return;
+        }
+
+        // NOTE: The methods are taken out of the state so that the lock isn't held while they're
+        //       called. Methods added while they're being called are retained.
+        let mut methods = std::mem::take(&mut state.on_frame);
+        drop(state);
+
+        for method in methods.iter_mut() {
+            method(self.id, frame_index, delta);
+        }
+
+        let mut state = self.state.lock();
+        methods.append(&mut state.on_frame);
+        state.on_frame = methods;
+    }
+
+    /// Retrieve a copy of an atlas image used by the renderer. This is intended for debugging.
+    ///
+    /// Returns `None` if the image backing at `index` isn't an atlas, or an error if there is no
+    /// image backing at `index`.
+    ///
+    /// ***Note:** This blocks until the worker has copied the image.*
+    pub fn debug_atlas_snapshot(&self, index: usize) -> Result<Option<AtlasSnapshot>, String> {
+        let (result_send, result_recv) = flume::bounded(1);
+
+        self.wm.send_window_event(
+            self.id,
+            WindowEvent::DebugAtlasSnapshot {
+                index,
+                result_send,
+            },
+        );
+
+        result_recv
+            .recv()
+            .unwrap_or_else(|_| Err(String::from("The renderer is not running.")))
+    }
+
+    /// Enable or disable the debug overlay.
+    ///
+    /// The overlay draws over every visible `Bin` associated to this window:
+    /// - The bounds including the border in blue.
+    /// - `optimal_outer_bounds`, which includes the margin, in orange.
+    /// - `optimal_inner_bounds` in cyan.
+    /// - `optimal_content_bounds`, which excludes the padding, in green.
+    /// - A label with the z-index at the top left.
+    ///
+    /// The `Bin` under the cursor is also highlighted. This is intended for debugging.
+    ///
+    /// ***Note:** The overlay is refreshed periodically, so it may lag behind the interface.*
+    pub fn set_debug_overlay(self: &Arc<Self>, enabled: bool) {
+        if self.debug_overlay_enabled() == enabled {
+            return;
+        }
+
+        if enabled {
+            // NOTE: The state can't be locked while creating the overlay as it creates bins.
+            let overlay = DebugOverlay::new(self);
+            let mut state = self.state.lock();
+
+            if state.debug_overlay.is_none() {
+                state.debug_overlay = Some(overlay);
+            } else {
+                drop(state);
+                overlay.remove(&self.basalt);
+            }
+        } else {
+            let overlay_op = self.state.lock().debug_overlay.take();
+
+            if let Some(overlay) = overlay_op {
+                overlay.remove(&self.basalt);
+            }
+        }
+    }
+
+    /// Check if the debug overlay is enabled.
+    pub fn debug_overlay_enabled(&self) -> bool {
+        self.state.lock().debug_overlay.is_some()
+    }
+
+    /// Toggle the debug overlay returning if it is now enabled.
+    pub fn toggle_debug_overlay(self: &Arc<Self>) -> bool {
+        let enabled = !self.debug_overlay_enabled();
+        self.set_debug_overlay(enabled);
+        enabled
+    }
+
+    /// Retrieve the `Bin`'s associated to this window excluding bins of the debug overlay.
+    pub(crate) fn interface_bins(&self) -> Vec<Arc<Bin>> {
+        let (bins, overlay_root_id) = {
+            let state = self.state.lock();
+
+            (
+                state
+                    .associated_bins
+                    .values()
+                    .filter_map(|wk| wk.upgrade())
+                    .collect::<Vec<_>>(),
+                state
+                    .debug_overlay
+                    .as_ref()
+                    .map(|overlay| overlay.root_id()),
+            )
+        };
+
+        match overlay_root_id {
+            Some(root_id) => {
+                bins.into_iter()
+                    .filter(|bin| !debug_overlay::is_overlay_bin(root_id, bin))
+                    .collect()
+            },
+            None => bins,
+        }
+    }
+
     /// Keep objects alive for the lifetime of the window.
     pub fn keep_alive<O, T>(&self, objects: O)
     where
@@ -660,27 +1554,31 @@ impl Window {
         }
     }
 
-    pub(crate) fn surface_capabilities(&self, fse: FullScreenExclusive) -> SurfaceCapabilities {
+    fn surface_info(&self, fse: FullScreenExclusive) -> SurfaceInfo {
+        match fse {
+            FullScreenExclusive::ApplicationControlled => {
+                SurfaceInfo {
+                    full_screen_exclusive: FullScreenExclusive::ApplicationControlled,
+                    win32_monitor: self.win32_monitor(),
+                    ..SurfaceInfo::default()
+                }
+            },
+            fse => {
+                SurfaceInfo {
+                    full_screen_exclusive: fse,
+                    ..SurfaceInfo::default()
+                }
+            },
+        }
+    }
+
+    pub(crate) fn vk_surface_capabilities(
+        &self,
+        fse: FullScreenExclusive,
+    ) -> VkSurfaceCapabilities {
         self.basalt
             .physical_device_ref()
-            .surface_capabilities(
-                &self.surface,
-                match fse {
-                    FullScreenExclusive::ApplicationControlled => {
-                        SurfaceInfo {
-                            full_screen_exclusive: FullScreenExclusive::ApplicationControlled,
-                            win32_monitor: self.win32_monitor(),
-                            ..SurfaceInfo::default()
-                        }
-                    },
-                    fse => {
-                        SurfaceInfo {
-                            full_screen_exclusive: fse,
-                            ..SurfaceInfo::default()
-                        }
-                    },
-                },
-            )
+            .surface_capabilities(&self.surface, self.surface_info(fse))
             .unwrap()
     }
 
@@ -690,58 +1588,116 @@ impl Window {
     ) -> Vec<(VkFormat, VkColorSpace)> {
         self.basalt
             .physical_device_ref()
-            .surface_formats(
-                &self.surface,
-                match fse {
-                    FullScreenExclusive::ApplicationControlled => {
-                        SurfaceInfo {
-                            full_screen_exclusive: FullScreenExclusive::ApplicationControlled,
-                            win32_monitor: self.win32_monitor(),
-                            ..SurfaceInfo::default()
-                        }
-                    },
-                    fse => {
-                        SurfaceInfo {
-                            full_screen_exclusive: fse,
-                            ..SurfaceInfo::default()
-                        }
-                    },
-                },
-            )
+            .surface_formats(&self.surface, self.surface_info(fse))
             .unwrap()
     }
 
     pub(crate) fn surface_present_modes(&self, fse: FullScreenExclusive) -> Vec<PresentMode> {
         self.basalt
             .physical_device_ref()
-            .surface_present_modes(
-                &self.surface,
-                match fse {
-                    FullScreenExclusive::ApplicationControlled => {
-                        SurfaceInfo {
-                            full_screen_exclusive: FullScreenExclusive::ApplicationControlled,
-                            win32_monitor: self.win32_monitor(),
-                            ..SurfaceInfo::default()
-                        }
-                    },
-                    fse => {
-                        SurfaceInfo {
-                            full_screen_exclusive: fse,
-                            ..SurfaceInfo::default()
-                        }
-                    },
-                },
-            )
+            .surface_present_modes(&self.surface, self.surface_info(fse))
             .unwrap()
             .collect()
     }
 
     pub(crate) fn surface_current_extent(&self, fse: FullScreenExclusive) -> [u32; 2] {
-        self.surface_capabilities(fse)
+        self.vk_surface_capabilities(fse)
             .current_extent
             .unwrap_or_else(|| self.inner_dimensions())
     }
 
+    /// Retrieve the `Bin` that is currently focused.
+    ///
+    /// ***Note:** Changes in focus are processed asynchronously, so this may not reflect a
+    /// change from `focus_next`, `focus_prev` or `Input::set_bin_focused` immediately.*
+    pub fn focused_bin(&self) -> Option<Arc<Bin>> {
+        self.basalt
+            .input_ref()
+            .focused_bin_id(self.id)
+            .and_then(|bin_id| self.basalt.interface_ref().get_bin(bin_id))
+    }
+
+    /// Retrieve the state of the modifier keys within this window.
+    ///
+    /// *See `Input::modifiers` for more information.*
+    pub fn modifiers(&self) -> Modifiers {
+        self.basalt.input_ref().modifiers(self.id)
+    }
+
+    /// Focus the next focusable `Bin` returning it.
+    ///
+    /// This is also performed when `Tab` is pressed.
+    ///
+    /// See `BinStyle.focusable`, `BinStyle.tab_index` & `BinStyle.focus_scope`.
+    pub fn focus_next(&self) -> Option<Arc<Bin>> {
+        self.focus_traverse(self.basalt.input_ref().focused_bin_id(self.id), false)
+    }
+
+    /// Focus the previous focusable `Bin` returning it.
+    ///
+    /// This is also performed when `Shift + Tab` is pressed.
+    ///
+    /// See `BinStyle.focusable`, `BinStyle.tab_index` & `BinStyle.focus_scope`.
+    pub fn focus_prev(&self) -> Option<Arc<Bin>> {
+        self.focus_traverse(self.basalt.input_ref().focused_bin_id(self.id), true)
+    }
+
+    fn focus_traverse(&self, current_id_op: Option<BinID>, reverse: bool) -> Option<Arc<Bin>> {
+        let current_op =
+            current_id_op.and_then(|bin_id| self.basalt.interface_ref().get_bin(bin_id));
+
+        let scope_op = current_op.as_ref().and_then(|current| {
+            current
+                .ancestors()
+                .into_iter()
+                .find(|ancestor| ancestor.style_inspect(|style| style.focus_scope == Some(true)))
+        });
+
+        let mut focusable = self
+            .associated_bins()
+            .into_iter()
+            .filter(|bin| {
+                bin.style_inspect(|style| style.focusable == Some(true))
+                    && !bin.is_hidden()
+                    && match scope_op.as_ref() {
+                        Some(scope) => {
+                            bin.ancestors()
+                                .iter()
+                                .any(|ancestor| ancestor.id() == scope.id())
+                        },
+                        None => true,
+                    }
+            })
+            .map(|bin| {
+                let tab_index = bin.style_inspect(|style| style.tab_index);
+                (tab_index.is_none(), tab_index, hierarchy_path(&bin), bin)
+            })
+            .collect::<Vec<_>>();
+
+        if focusable.is_empty() {
+            return None;
+        }
+
+        focusable.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+
+        let position_op = current_id_op.and_then(|current_id| {
+            focusable
+                .iter()
+                .position(|(.., bin)| bin.id() == current_id)
+        });
+
+        let next_index = match (position_op, reverse) {
+            (Some(position), false) => (position + 1) % focusable.len(),
+            (Some(position), true) => (position + focusable.len() - 1) % focusable.len(),
+            (None, false) => 0,
+            (None, true) => focusable.len() - 1,
+        };
+
+        let next = focusable.swap_remove(next_index).3;
+        self.basalt.input_ref().set_bin_focused(&next);
+        Some(next)
+    }
+
     /// Attach an input hook to this window. When the window closes, this hook will be
     /// automatically removed from `Input`.
     ///
@@ -882,6 +1838,20 @@ impl Window {
             .unwrap()
     }
 
+    pub fn on_raw_motion<F>(self: &Arc<Self>, method: F) -> InputHookID
+    where
+        F: FnMut(InputHookTarget, &WindowState, f32, f32) -> InputHookCtrl + Send + 'static,
+    {
+        self.basalt()
+            .input_ref()
+            .hook()
+            .window(self)
+            .on_raw_motion()
+            .call(method)
+            .finish()
+            .unwrap()
+    }
+
     pub fn on_cursor<F>(self: &Arc<Self>, method: F) -> InputHookID
     where
         F: FnMut(InputHookTarget, &WindowState, &LocalCursorState) -> InputHookCtrl
@@ -901,9 +1871,15 @@ impl Window {
 
 impl Drop for Window {
     fn drop(&mut self) {
-        for hook_id in self.state.lock().attached_input_hooks.drain(..) {
+        let mut state = self.state.lock();
+
+        for hook_id in state.attached_input_hooks.drain(..) {
             self.basalt.input_ref().remove_hook(hook_id);
         }
+
+        if let Some(overlay) = state.debug_overlay.take() {
+            overlay.remove(&self.basalt);
+        }
     }
 }
 
@@ -918,3 +1894,59 @@ unsafe impl HasRawDisplayHandle for Window {
         self.inner.raw_display_handle()
     }
 }
+
+/// The root's `BinID` and the index of the `Bin` within each of its ancestors' children.
+// NOTE: Hits are keyed by z-layer then z-index. Hits with equal keys retain their order.
+fn top_most_first<T>(mut hits: Vec<((i8, i16), T)>) -> Vec<T> {
+    hits.sort_by_key(|(z, _)| Reverse(*z));
+    hits.into_iter().map(|(_, hit)| hit).collect()
+}
+
+fn hierarchy_path(bin: &Arc<Bin>) -> (BinID, Vec<usize>) {
+    let mut path = Vec::new();
+    let mut child = bin.clone();
+
+    while let Some(parent) = child.parent() {
+        path.push(
+            parent
+                .children()
+                .iter()
+                .position(|sibling| sibling.id() == child.id())
+                .unwrap_or(0),
+        );
+
+        child = parent;
+    }
+
+    path.reverse();
+    (child.id(), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::top_most_first;
+
+    #[test]
+    fn hits_top_most_first() {
+        assert_eq!(
+            top_most_first(vec![((0, 1), 'a'), ((0, 5), 'b'), ((0, -2), 'c')]),
+            vec!['b', 'a', 'c'],
+        );
+    }
+
+    #[test]
+    fn hits_layer_precedence() {
+        assert_eq!(
+            top_most_first(vec![((0, 100), 'a'), ((1, 0), 'b'), ((-1, 200), 'c')]),
+            vec!['b', 'a', 'c'],
+        );
+    }
+
+    #[test]
+    fn hits_equal_retain_order() {
+        assert_eq!(
+            top_most_first(vec![((0, 0), 'a'), ((0, 0), 'b'), ((0, 0), 'c')]),
+            vec!['a', 'b', 'c'],
+        );
+    }
+}